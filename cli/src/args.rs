@@ -1,6 +1,10 @@
 use crate::instructions::{set_pair_status_permissionless::SetPairStatusPermissionlessParams, *};
+use anchor_client::solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+use anchor_client::solana_sdk::pubkey::Pubkey;
 use anchor_client::Cluster;
 use clap::*;
+use commons::dlmm::types::{ActivationType, PairStatus};
+use commons::{MAX_REWARD_DURATION, MIN_REWARD_DURATION};
 
 /// 全局配置覆盖选项
 #[derive(Parser, Debug)]
@@ -10,8 +14,39 @@ pub struct ConfigOverride {
     ///
     /// Values = mainnet, testnet, devnet, localnet.
     /// Default: mainnet
-    #[clap(global = true, long = "provider.cluster", default_value_t = Cluster::Mainnet)]
+    #[clap(
+        global = true,
+        long = "provider.cluster",
+        env = "DLMM_CLUSTER",
+        default_value_t = Cluster::Mainnet
+    )]
     pub cluster: Cluster,
+    /// RPC endpoint override, such as a private or dedicated RPC provider. Takes precedence over
+    /// `--provider.cluster`. The websocket endpoint is derived from it (https -> wss, port + 1) unless `--ws-url`
+    /// is given
+    /// RPC节点覆盖设置（如私有或专用RPC节点），优先于 `--provider.cluster`。
+    /// 未指定 `--ws-url` 时由其推导websocket节点（https -> wss，端口 + 1）
+    ///
+    /// Example: https://my-rpc.example.com
+    #[clap(
+        global = true,
+        long = "cluster-url",
+        env = "DLMM_CLUSTER_URL",
+        value_parser = parse_cluster_url
+    )]
+    pub cluster_url: Option<Cluster>,
+    /// Websocket endpoint override for `--cluster-url`, for providers serving websocket on a different host or path
+    /// `--cluster-url` 对应的websocket节点覆盖设置，用于websocket使用不同主机或路径的RPC服务商
+    ///
+    /// Example: wss://my-rpc.example.com/ws
+    #[clap(
+        global = true,
+        long = "ws-url",
+        env = "DLMM_WS_URL",
+        requires = "cluster_url",
+        value_parser = parse_ws_url
+    )]
+    pub ws_url: Option<String>,
     /// Wallet override
     /// 钱包覆盖设置
     ///
@@ -20,13 +55,184 @@ pub struct ConfigOverride {
     #[clap(
         global = true,
         long = "provider.wallet",
+        env = "DLMM_WALLET",
         default_value_t = String::from(shellexpand::tilde("~/.config/solana/id.json"))
     )]
     pub wallet: String,
     /// Priority fee
     /// 优先费用（用于加速交易）
-    #[clap(global = true, long = "priority-fee", default_value_t = 0)]
+    #[clap(
+        global = true,
+        long = "priority-fee",
+        env = "DLMM_PRIORITY_FEE",
+        default_value_t = 0
+    )]
     pub priority_fee: u64,
+    /// Maximum total priority fee in lamports willing to pay per transaction. The compute unit price is derived
+    /// from it and `--compute-unit-limit`, instead of specifying `--priority-fee` per compute unit
+    /// 每笔交易愿意支付的最大优先费用总额（lamports），计算单元价格由其与 `--compute-unit-limit` 推导
+    #[clap(
+        global = true,
+        long = "max-priority-fee-lamports",
+        alias = "compute-unit-price-auto",
+        conflicts_with = "priority_fee"
+    )]
+    pub max_priority_fee_lamports: Option<u64>,
+    /// Compute unit limit set on the transactions which don't set their own when `--max-priority-fee-lamports` is
+    /// provided. The compute unit price is derived from the compute unit limit of each transaction, so the total
+    /// priority fee never exceeds the maximum
+    /// 提供 `--max-priority-fee-lamports` 时，未自行设置计算单元上限的交易使用的上限。计算单元价格由每笔交易的计算单元上限推导，
+    /// 优先费用总额不会超过最大值
+    #[clap(
+        global = true,
+        long = "compute-unit-limit",
+        default_value_t = crate::MAX_COMPUTE_UNIT_LIMIT
+    )]
+    pub compute_unit_limit: u32,
+    /// Commitment used to read accounts and confirm transactions. Defaults to `confirmed`, and to `processed` for
+    /// read-only commands, which don't need confirmation-level finality
+    /// 读取账户及确认交易使用的确认级别。默认为 `confirmed`，只读命令默认为 `processed`，不需要等待确认级别的最终性
+    ///
+    /// Values = processed, confirmed, finalized.
+    #[clap(global = true, long = "commitment", env = "DLMM_COMMITMENT")]
+    pub commitment: Option<CommitmentLevel>,
+    /// Admin authority of admin commands when it isn't the wallet, such as a multisig controlled key. The wallet
+    /// partially signs the transaction as fee payer and the transaction is printed instead of sent, for the authority
+    /// to sign with `sign-transaction`. Submit it with `send-transaction`
+    /// 管理员命令的管理员地址（不是钱包时，如多签控制的密钥）。钱包作为付款人部分签名，打印交易而不发送，
+    /// 由管理员通过 `sign-transaction` 签名后使用 `send-transaction` 发送
+    #[clap(global = true, long = "authority")]
+    pub authority: Option<Pubkey>,
+    /// Durable nonce account used instead of the latest blockhash by the transactions printed with `--authority`, so
+    /// collecting the signatures isn't bound by the blockhash expiry. The nonce authority must sign the transaction,
+    /// the wallet signs when it is the nonce authority
+    /// 配合 `--authority` 打印的交易使用的持久随机数账户，代替最新区块哈希，收集签名不受区块哈希过期限制。
+    /// 随机数权限须为交易签名，为钱包时由钱包签名
+    #[clap(global = true, long = "nonce", requires = "authority")]
+    pub nonce: Option<Pubkey>,
+    /// Log level
+    /// 日志级别
+    ///
+    /// Values = error, warn, info, debug, trace.
+    /// Default: info
+    #[clap(global = true, long = "log-level", default_value_t = tracing::Level::INFO)]
+    pub log_level: tracing::Level,
+}
+
+impl ConfigOverride {
+    /// 返回实际使用的集群，`--cluster-url` 及 `--ws-url` 优先于 `--provider.cluster`
+    /// Returns the cluster to connect to. `--cluster-url` and `--ws-url` take precedence over `--provider.cluster`
+    pub fn resolve_cluster(&self) -> Cluster {
+        match (&self.cluster_url, &self.ws_url) {
+            (Some(cluster), Some(ws_url)) => {
+                Cluster::Custom(cluster.url().to_string(), ws_url.clone())
+            }
+            (Some(cluster), None) => cluster.clone(),
+            (None, _) => self.cluster.clone(),
+        }
+    }
+
+    /// 返回实际使用的确认级别：优先使用 `--commitment`，否则只读命令使用 `processed`，其他命令使用 `confirmed`
+    /// Returns the commitment to use. `--commitment` takes precedence, otherwise read-only commands use `processed`
+    /// and the other commands `confirmed`
+    pub fn resolve_commitment(&self, command: &DLMMCommand) -> CommitmentConfig {
+        match self.commitment {
+            Some(commitment) => CommitmentConfig { commitment },
+            None if command.is_read_only() => CommitmentConfig::processed(),
+            None => CommitmentConfig::confirmed(),
+        }
+    }
+}
+
+/// 解析RPC节点地址，只接受http及https地址，并推导websocket节点
+/// Parse an RPC endpoint. Only http and https urls are accepted, the websocket endpoint is derived from it
+pub fn parse_cluster_url(src: &str) -> Result<Cluster, String> {
+    if !(src.starts_with("http://") || src.starts_with("https://")) {
+        return Err(format!("{} is not an http or https url", src));
+    }
+
+    src.parse::<Cluster>().map_err(|e| e.to_string())
+}
+
+/// 解析websocket节点地址，只接受ws及wss地址
+/// Parse a websocket endpoint. Only ws and wss urls are accepted
+pub fn parse_ws_url(src: &str) -> Result<String, String> {
+    let valid = ["ws://", "wss://"].iter().any(|scheme| {
+        src.strip_prefix(scheme)
+            .is_some_and(|rest| !rest.is_empty() && !rest.starts_with('/'))
+    });
+
+    if !valid {
+        return Err(format!("{} is not a ws or wss url", src));
+    }
+
+    Ok(src.to_string())
+}
+
+/// 解析时长，接受秒数（如 `86400`）或带单位的时长（`s`、`m`、`h`、`d`、`w`，如 `30d`、`12h`、`1w2d`），返回秒数
+/// Parse a duration, either in seconds (e.g. `86400`) or with units (`s`, `m`, `h`, `d`, `w`, e.g. `30d`, `12h`,
+/// `1w2d`). Returns the duration in seconds
+pub fn parse_duration_seconds(src: &str) -> Result<u64, String> {
+    let src = src.trim();
+    if src.is_empty() {
+        return Err("Duration is empty".to_string());
+    }
+    if let Ok(seconds) = src.parse::<u64>() {
+        return Ok(seconds);
+    }
+
+    let mut seconds = 0u64;
+    let mut rest = src;
+    while !rest.is_empty() {
+        let digits_end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(|| format!("{} is missing a unit after {}", src, rest))?;
+        let (amount, unit_and_rest) = rest.split_at(digits_end);
+        let amount = amount.parse::<u64>().map_err(|_| {
+            format!(
+                "{} is not a valid duration, expected e.g. 30d, 12h or 1w",
+                src
+            )
+        })?;
+
+        let mut chars = unit_and_rest.chars();
+        let unit_seconds = match chars.next() {
+            Some('s') => 1,
+            Some('m') => 60,
+            Some('h') => 3_600,
+            Some('d') => 86_400,
+            Some('w') => 604_800,
+            _ => {
+                return Err(format!(
+                    "{} has an unknown unit, expected s, m, h, d or w",
+                    src
+                ))
+            }
+        };
+
+        seconds = amount
+            .checked_mul(unit_seconds)
+            .and_then(|component| seconds.checked_add(component))
+            .ok_or_else(|| format!("{} overflows", src))?;
+        rest = chars.as_str();
+    }
+
+    Ok(seconds)
+}
+
+/// 解析奖励持续时间（见 [`parse_duration_seconds`]），须在链上允许的范围内
+/// Parse a reward duration (see [`parse_duration_seconds`]), which must be within the range allowed on chain
+pub fn parse_reward_duration(src: &str) -> Result<u64, String> {
+    let reward_duration = parse_duration_seconds(src)?;
+
+    if !(MIN_REWARD_DURATION..=MAX_REWARD_DURATION).contains(&reward_duration) {
+        return Err(format!(
+            "Reward duration {} seconds is out of range, must be between {} and {} seconds (1 year)",
+            reward_duration, MIN_REWARD_DURATION, MAX_REWARD_DURATION
+        ));
+    }
+
+    Ok(reward_duration)
 }
 
 /// 解析流动性移除参数（bin_id, 移除百分比）
@@ -46,26 +252,31 @@ pub fn parse_bin_liquidity_removal(src: &str) -> Result<(i32, f64), Error> {
     Ok((bin_id, bps_to_remove))
 }
 
-/// 解析流动性分配参数（delta_id, X代币分配比例, Y代币分配比例）
-pub fn parse_bin_liquidity_distribution(src: &str) -> Result<(i32, f64, f64), Error> {
-    let mut parsed_str: Vec<&str> = src.split(',').collect();
+/// 解析流动性分配参数 `BIN_ID,DIST_X,DIST_Y`（bin ID, X代币分配比例, Y代币分配比例），分配比例须在0到1之间
+/// Parse a liquidity distribution entry `BIN_ID,DIST_X,DIST_Y` (bin id, token X share, token Y share). The shares
+/// must be between 0 and 1
+pub fn parse_bin_liquidity_distribution(src: &str) -> Result<(i32, f64, f64), String> {
+    let [bin_id, dist_x, dist_y] = src.split(',').map(str::trim).collect::<Vec<_>>()[..] else {
+        return Err(format!("{} is not a BIN_ID,DIST_X,DIST_Y entry", src));
+    };
 
-    let dist_y = parsed_str
-        .pop()
-        .and_then(|s| s.parse::<f64>().ok())
-        .ok_or_else(|| clap::error::Error::new(error::ErrorKind::InvalidValue))?;
+    let bin_id = bin_id
+        .parse::<i32>()
+        .map_err(|_| format!("BIN_ID {} of {} is not an integer", bin_id, src))?;
 
-    let dist_x = parsed_str
-        .pop()
-        .and_then(|s| s.parse::<f64>().ok())
-        .ok_or_else(|| clap::error::Error::new(error::ErrorKind::InvalidValue))?;
-
-    let delta_id = parsed_str
-        .pop()
-        .and_then(|s| s.parse::<i32>().ok())
-        .ok_or_else(|| clap::error::Error::new(error::ErrorKind::InvalidValue))?;
+    let parse_share = |name: &str, share: &str| {
+        share
+            .parse::<f64>()
+            .ok()
+            .filter(|share| (0.0..=1.0).contains(share))
+            .ok_or_else(|| format!("{} {} of {} must be between 0 and 1", name, share, src))
+    };
 
-    Ok((delta_id, dist_x, dist_y))
+    Ok((
+        bin_id,
+        parse_share("DIST_X", dist_x)?,
+        parse_share("DIST_Y", dist_y)?,
+    ))
 }
 
 /// 选择性舍入模式
@@ -76,6 +287,62 @@ pub enum SelectiveRounding {
     None,  // 不舍入
 }
 
+/// 交易对状态
+/// Pair status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PairStatusArg {
+    /// 启用，允许交易 / Enabled, swaps are allowed
+    Enabled,
+    /// 禁用，阻止交易 / Disabled, swaps are blocked
+    Disabled,
+}
+
+impl From<PairStatus> for PairStatusArg {
+    fn from(status: PairStatus) -> Self {
+        match status {
+            PairStatus::Enabled => PairStatusArg::Enabled,
+            PairStatus::Disabled => PairStatusArg::Disabled,
+        }
+    }
+}
+
+impl From<PairStatusArg> for u8 {
+    fn from(status: PairStatusArg) -> Self {
+        match status {
+            PairStatusArg::Enabled => 0,
+            PairStatusArg::Disabled => 1,
+        }
+    }
+}
+
+/// 交易对激活类型
+/// Pair activation type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ActivationTypeArg {
+    /// 按slot激活 / Activation point is a slot
+    Slot,
+    /// 按时间戳激活 / Activation point is a unix timestamp
+    Timestamp,
+}
+
+impl From<ActivationType> for ActivationTypeArg {
+    fn from(activation_type: ActivationType) -> Self {
+        match activation_type {
+            ActivationType::Slot => ActivationTypeArg::Slot,
+            ActivationType::Timestamp => ActivationTypeArg::Timestamp,
+        }
+    }
+}
+
+impl From<ActivationTypeArg> for u8 {
+    fn from(activation_type: ActivationTypeArg) -> Self {
+        match activation_type {
+            ActivationTypeArg::Slot => 0,
+            ActivationTypeArg::Timestamp => 1,
+        }
+    }
+}
+
 /// DLMM主要命令
 #[derive(Parser, Debug)]
 pub enum DLMMCommand {
@@ -103,6 +370,9 @@ pub enum DLMMCommand {
     /// Deposit liquidity to the position of the given liquidity pair.
     /// 向指定流动性对的仓位存入流动性
     AddLiquidity(AddLiquidityParams),
+    /// Initialize a new position for the given liquidity pair and deposit liquidity to it.
+    /// 为指定流动性对初始化新仓位并存入流动性
+    InitializePositionAndAddLiquidity(InitPositionAndAddLiquidityParams),
     /// Remove liquidity from the position of the given liquidity pair.
     /// 从指定流动性对的仓位移除流动性
     RemoveLiquidity(RemoveLiquidityParams),
@@ -113,12 +383,55 @@ pub enum DLMMCommand {
     SwapExactOut(SwapExactOutParams),
     /// 带价格影响的交易
     SwapWithPriceImpact(SwapWithPriceImpactParams),
+    /// Quote multiple exact input amounts of the given liquidity pair, fetching the quote accounts only once.
+    /// 批量计算指定流动性对多个输入数量的精确输入报价，报价所需账户只获取一次
+    QuoteExactInBatch(QuoteExactInBatchParams),
+    /// Quote a ladder of token X amounts in both directions off a single bin array fetch and print a two-sided
+    /// depth table.
+    /// 一次读取bin数组，双向报价一组X代币数量并输出双边深度表
+    ShowDepth(ShowDepthParams),
     /// Show information of the given liquidity pair.
     /// 显示指定流动性对的信息
     ShowPair(ShowPairParams),
+    /// Print per-bin token amounts, price and liquidity of the given bin range as JSON.
+    /// 以JSON输出指定bin范围内每个bin的代币数量、价格及流动性
+    GetBinReserves(GetBinReservesParams),
+    /// Write every bin with liquidity of the given liquidity pair (bin id, token amounts, price, liquidity) to a JSON snapshot file.
+    /// 将指定流动性对每个有流动性的bin（bin ID、代币数量、价格、流动性）写入JSON快照文件
+    SnapshotPair(SnapshotPairParams),
+    /// Show the bin array indexes flagged as initialized in the bin array bitmap extension of the given liquidity pair.
+    /// 显示指定流动性对的bin数组位图扩展中标记为已初始化的bin数组索引
+    ShowBitmapExtension(ShowBitmapExtensionParams),
+    /// Show the protocol fees accrued by the given liquidity pair. Read-only companion of `withdraw-protocol-fee`.
+    /// 显示指定流动性对累积的协议费用，`withdraw-protocol-fee` 的只读查询命令
+    GetProtocolFees(GetProtocolFeesParams),
+    /// Run read-only checks on the given liquidity pair (status, oracle length, bitmap extension, reserves, liquidity around the active bin) and print a health report.
+    /// 对指定流动性对运行只读检查（状态、预言机长度、位图扩展、储备金、活跃bin附近的流动性）并打印健康报告
+    PairHealth(PairHealthParams),
+    /// Show the oracle of the given liquidity pair: observation length, active size and the recent observation samples.
+    /// 显示指定流动性对的预言机：观测长度、已初始化的观测数量及最近的观测样本
+    ShowOracle(ShowOracleParams),
+    /// Compute the time weighted average price of the given liquidity pair over a lookback window from its oracle observations.
+    /// 根据预言机观测样本计算指定流动性对在回看窗口内的时间加权平均价格
+    GetTwap(GetTwapParams),
+    /// Compute the bin id of a UI price for the given bin step and token decimals. Offline, the chain is not accessed.
+    /// 根据bin步长及代币小数位数计算UI价格对应的bin ID，离线计算，不连接链
+    ComputeBinId(ComputeBinIdParams),
+    /// Compute the UI price of a bin id for the given bin step and token decimals. Offline, the chain is not accessed.
+    /// 根据bin步长及代币小数位数计算bin ID对应的UI价格，离线计算，不连接链
+    ComputePrice(ComputePriceParams),
+    /// Show the program id the CLI is built against, the IDL version and the CLI version. With `--on-chain`, also the upgrade authority and the last deployed slot of the program.
+    /// 显示CLI构建所针对的程序ID、IDL版本及CLI版本，指定 `--on-chain` 时同时显示程序的升级权限及最近部署的slot
+    Version(VersionParams),
+    /// Show the reward configuration of every reward index of the given liquidity pair. Read-only companion of `fund-reward` and `update-reward-duration`.
+    /// 显示指定流动性对每个奖励索引的奖励配置，`fund-reward` 及 `update-reward-duration` 的只读查询命令
+    ListRewards(ListRewardsParams),
     /// Show information of the given position.
     /// 显示指定仓位的信息
     ShowPosition(ShowPositionParams),
+    /// Show the current token composition of the given position and the composition it would hold if the active bin moved to the given price.
+    /// 显示指定仓位当前的代币组成，以及活跃bin移动到指定价格时的代币组成
+    ValuePositionAtPrice(ValuePositionAtPriceParams),
     /// 领取奖励
     ClaimReward(ClaimRewardParams),
     /// 更新奖励持续时间
@@ -128,16 +441,29 @@ pub enum DLMMCommand {
     /// Close liquidity position.
     /// 关闭流动性仓位
     ClosePosition(ClosePositionParams),
+    /// Migrate a legacy (v1) position to a new PositionV2 using the on-chain migration instruction. The payer must be the position owner.
+    /// 使用链上迁移指令将旧版本（v1）仓位迁移为新的PositionV2仓位，支付者必须是仓位所有者
+    MigratePosition(MigratePositionParams),
     /// Claim fee
     /// 领取手续费
     ClaimFee(ClaimFeeParams),
+    /// Claim fee of multiple positions of the given liquidity pair, packed into as few transactions as possible.
+    /// 批量领取指定流动性对多个仓位的手续费，打包为尽可能少的交易
+    ClaimFeeBatch(ClaimFeeBatchParams),
+    /// Claim fee and the rewards of every initialized reward index of the given position.
+    /// 领取指定仓位的手续费及所有已初始化奖励索引的奖励
+    ClaimFeeAndRewards(ClaimFeeAndRewardsParams),
+    /// Harvest the pending fees and rewards of all positions of the payer concurrently, skipping positions with nothing to claim.
+    /// 并发领取支付者所有仓位的待领取手续费及奖励，跳过没有可领取数量的仓位
+    HarvestAll(HarvestAllParams),
     /// Increase an oracle observation sample length
     /// 增加预言机观察样本长度
     IncreaseOracleLength(IncreaseOracleLengthParams),
     /// 显示预设参数
     ShowPresetParameter(ShowPresetAccountParams),
     /// 列出所有bin步长
-    ListAllBinStep,
+    /// List all bin steps. Use `--verbose` to show the fee envelope of each preset.
+    ListAllBinStep(ListAllBinStepParams),
     /// 初始化可自定义的无需许可流动性对（版本1）
     InitializeCustomizablePermissionlessLbPair(InitCustomizablePermissionlessLbPairParam),
     /// 初始化可自定义的无需许可流动性对（版本2）
@@ -147,16 +473,76 @@ pub enum DLMMCommand {
     SeedLiquidityByOperator(SeedLiquidityByOperatorParameters),
     /// 由操作员播种单个bin的流动性
     SeedLiquiditySingleBinByOperator(SeedLiquiditySingleBinByOperatorParameters),
+    /// Preview the bin distribution of seed liquidity by operator without sending any transaction.
+    /// 预览操作员播种流动性的bin分布，不发送任何交易
+    PreviewSeed(PreviewSeedParameters),
     /// 无需许可设置交易对状态
     SetPairStatusPermissionless(SetPairStatusPermissionlessParams),
     /// 获取某个所有者的所有仓位
     GetAllPositionsForAnOwner(GetAllPositionsParams),
     /// 同步价格
     SyncPrice(SyncPriceParams),
+    /// Create or extend an address lookup table with the pair accounts and bin arrays for the given bin range. Use with `--use-lut` on AddLiquidity / RemoveLiquidity.
+    /// 为指定bin范围创建或扩展包含流动性对账户及bin数组的地址查找表，配合AddLiquidity / RemoveLiquidity的`--use-lut`使用
+    CreateLookupTable(CreateLookupTableParams),
+    /// Add the wallet signature to a transaction partially signed by an admin command with `--authority`.
+    /// 使用钱包为管理员命令配合 `--authority` 部分签名的交易补充签名
+    SignTransaction(SignTransactionParams),
+    /// Submit a transaction once all its signers signed it with `sign-transaction`.
+    /// 所有签名者通过 `sign-transaction` 签名后发送交易
+    SendTransaction(SendTransactionParams),
     #[clap(flatten)]
     Admin(AdminCommand),
 }
 
+impl DLMMCommand {
+    /// 只读取账户、不发送交易的命令
+    /// Commands that only read accounts and never send a transaction
+    pub fn is_read_only(&self) -> bool {
+        matches!(
+            self,
+            DLMMCommand::QuoteExactInBatch(_)
+                | DLMMCommand::ShowDepth(_)
+                | DLMMCommand::ShowPair(_)
+                | DLMMCommand::GetBinReserves(_)
+                | DLMMCommand::SnapshotPair(_)
+                | DLMMCommand::ShowBitmapExtension(_)
+                | DLMMCommand::GetProtocolFees(_)
+                | DLMMCommand::PairHealth(_)
+                | DLMMCommand::ShowOracle(_)
+                | DLMMCommand::GetTwap(_)
+                | DLMMCommand::Version(_)
+                | DLMMCommand::ListRewards(_)
+                | DLMMCommand::ShowPosition(_)
+                | DLMMCommand::ValuePositionAtPrice(_)
+                | DLMMCommand::ShowPresetParameter(_)
+                | DLMMCommand::ListAllBinStep(_)
+                | DLMMCommand::PreviewSeed(_)
+                | DLMMCommand::GetAllPositionsForAnOwner(_)
+        )
+    }
+
+    /// 支持 `--authority` 指定钱包以外的管理员、只部分签名交易的命令
+    /// Commands supporting an admin other than the wallet with `--authority`, only partially signing the transaction
+    pub fn supports_external_authority(&self) -> bool {
+        matches!(
+            self,
+            DLMMCommand::Admin(
+                AdminCommand::InitializePermissionPair(_)
+                    | AdminCommand::SetPairStatus(_)
+                    | AdminCommand::SetActivationPoint(_)
+                    | AdminCommand::InitializeReward(_)
+                    | AdminCommand::InitializePresetParameter(_)
+                    | AdminCommand::ClosePresetParameter(_)
+                    | AdminCommand::InitializeTokenBadge(_)
+                    | AdminCommand::CreateClaimProtocolFeeOperator(_)
+                    | AdminCommand::CloseClaimProtocolFeeOperator(_)
+                    | AdminCommand::UpdateBaseFee(_)
+            )
+        )
+    }
+}
+
 #[derive(Parser, Debug)]
 #[clap(version, about, author)]
 pub struct Cli {
@@ -202,3 +588,55 @@ pub enum AdminCommand {
     /// 更新基础费率
     UpdateBaseFee(UpdateBaseFeeParams),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_seconds() {
+        assert_eq!(parse_duration_seconds("86400"), Ok(86_400));
+        assert_eq!(parse_duration_seconds("90s"), Ok(90));
+        assert_eq!(parse_duration_seconds("15m"), Ok(900));
+        assert_eq!(parse_duration_seconds("12h"), Ok(43_200));
+        assert_eq!(parse_duration_seconds("30d"), Ok(2_592_000));
+        assert_eq!(parse_duration_seconds("1w"), Ok(604_800));
+        assert_eq!(parse_duration_seconds("1w2d"), Ok(777_600));
+
+        assert!(parse_duration_seconds("").is_err());
+        assert!(parse_duration_seconds("30").is_ok());
+        assert!(parse_duration_seconds("30x").is_err());
+        assert!(parse_duration_seconds("d").is_err());
+        assert!(parse_duration_seconds("1d2").is_err());
+        assert!(parse_duration_seconds("-1d").is_err());
+        assert!(parse_duration_seconds("99999999999999999999w").is_err());
+    }
+
+    #[test]
+    fn test_parse_bin_liquidity_distribution() {
+        assert_eq!(
+            parse_bin_liquidity_distribution("5555,0.75,0.25"),
+            Ok((5555, 0.75, 0.25))
+        );
+        assert_eq!(
+            parse_bin_liquidity_distribution("-1, 0, 1"),
+            Ok((-1, 0.0, 1.0))
+        );
+
+        assert!(parse_bin_liquidity_distribution("5555,0.75").is_err());
+        assert!(parse_bin_liquidity_distribution("1,5555,0.75,0.25").is_err());
+        assert!(parse_bin_liquidity_distribution("5555.5,0.75,0.25").is_err());
+        assert!(parse_bin_liquidity_distribution("5555,1.5,0.25").is_err());
+        assert!(parse_bin_liquidity_distribution("5555,0.75,-0.25").is_err());
+        assert!(parse_bin_liquidity_distribution("5555,NaN,0.25").is_err());
+    }
+
+    #[test]
+    fn test_parse_reward_duration() {
+        assert_eq!(parse_reward_duration("30d"), Ok(2_592_000));
+        assert_eq!(parse_reward_duration("365d"), Ok(MAX_REWARD_DURATION));
+        assert!(parse_reward_duration("0").is_err());
+        assert!(parse_reward_duration("0d").is_err());
+        assert!(parse_reward_duration("366d").is_err());
+    }
+}
@@ -0,0 +1,1446 @@
+/// 面向库使用者的DLMM客户端
+/// DLMM client for library consumers
+///
+/// 封装常用操作（报价、交换、添加流动性、查询头寸），可返回构建好的指令或直接发送交易，命令行工具同样基于它实现
+/// Wraps the common operations (quote, swap, add liquidity, query positions). Either returns the built instructions
+/// or sends the transaction. The command line tool is implemented on top of it as well
+use crate::*;
+use anchor_client::solana_sdk::signature::Signature;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+
+/// 未指定时交换使用的滑点（基点）
+/// Slippage (bps) used by swaps when not specified
+pub const DEFAULT_SWAP_SLIPPAGE_BPS: u16 = 100;
+
+/// 未指定时报价从活跃bin沿交易方向获取的bin数组数量
+/// Number of bin arrays fetched from the active bin in the swap direction for quoting when not specified
+pub const DEFAULT_QUOTE_BIN_ARRAY_COUNT: u8 = 3;
+
+/// 精确输入交换的参数
+/// Arguments of an exact input swap
+#[derive(Debug, Clone)]
+pub struct SwapExactInArgs {
+    /// 流动性对地址 / Address of the liquidity pair
+    pub lb_pair: Pubkey,
+    /// 卖出的代币数量 / Amount of token to sell
+    pub amount_in: u64,
+    /// 交易方向：true = 用X代币买Y代币，false = 用Y代币买X代币 / Swap direction. true = buy token Y, false = buy
+    /// token X
+    pub swap_for_y: bool,
+    /// 相对报价输出数量的滑点（基点） / Slippage (bps) applied to the quoted amount out
+    pub slippage_bps: u16,
+    /// X代币的代币程序，未提供时由mint所有者推导 / Token program of token X. Derived from the mint owner when omitted
+    pub token_x_program: Option<Pubkey>,
+    /// Y代币的代币程序，未提供时由mint所有者推导 / Token program of token Y. Derived from the mint owner when omitted
+    pub token_y_program: Option<Pubkey>,
+    /// 读取报价账户时使用的确认级别，未提供时使用客户端的确认级别 / Commitment used to read the quote accounts. The
+    /// client commitment is used when omitted
+    pub commitment_for_quote: Option<CommitmentConfig>,
+    /// 限价，即以Y代币计价的X代币UI价格。成交价格劣于限价时不构建指令 / Price limit as the UI price of token X in
+    /// token Y. No instruction is built when the execution price is worse than the limit
+    pub price_limit: Option<f64>,
+}
+
+impl SwapExactInArgs {
+    /// 使用默认滑点、自动推导代币程序及客户端确认级别创建参数
+    /// Create the arguments with the default slippage, derived token programs and the client commitment
+    pub fn new(lb_pair: Pubkey, amount_in: u64, swap_for_y: bool) -> Self {
+        Self {
+            lb_pair,
+            amount_in,
+            swap_for_y,
+            slippage_bps: DEFAULT_SWAP_SLIPPAGE_BPS,
+            token_x_program: None,
+            token_y_program: None,
+            commitment_for_quote: None,
+            price_limit: None,
+        }
+    }
+}
+
+/// 构建好的精确输入交换指令及其报价
+/// Built exact input swap instruction with the quote it was built against
+pub struct SwapExactInBuild {
+    /// 交换指令，不包含计算预算指令 / Swap instruction, without compute budget instructions
+    pub instruction: Instruction,
+    /// 剩余账户切片信息 / Remaining accounts slices
+    pub remaining_accounts_info: RemainingAccountsInfo,
+    /// 报价 / Quote
+    pub quote: SwapExactInQuote,
+    /// 应用滑点后的最小输出数量 / Minimum amount out after slippage
+    pub min_amount_out: u64,
+    /// 用户的输出代币账户 / User token account receiving the output
+    pub user_token_out: Pubkey,
+    /// 报价使用的账户 / Accounts used for the quote
+    pub quote_accounts: SwapQuoteAccounts,
+}
+
+/// 精确输出交换的参数
+/// Arguments of an exact output swap
+#[derive(Debug, Clone)]
+pub struct SwapExactOutArgs {
+    /// 流动性对地址 / Address of the liquidity pair
+    pub lb_pair: Pubkey,
+    /// 买入的代币数量 / Amount of token to buy
+    pub amount_out: u64,
+    /// 交易方向：true = 用X代币买Y代币，false = 用Y代币买X代币 / Swap direction. true = buy token Y, false = buy
+    /// token X
+    pub swap_for_y: bool,
+    /// 相对报价输入数量（含手续费）的滑点（基点） / Slippage (bps) applied to the quoted amount in, fee included
+    pub slippage_bps: u16,
+    /// X代币的代币程序，未提供时由mint所有者推导 / Token program of token X. Derived from the mint owner when omitted
+    pub token_x_program: Option<Pubkey>,
+    /// Y代币的代币程序，未提供时由mint所有者推导 / Token program of token Y. Derived from the mint owner when omitted
+    pub token_y_program: Option<Pubkey>,
+    /// 读取报价账户时使用的确认级别，未提供时使用客户端的确认级别 / Commitment used to read the quote accounts. The
+    /// client commitment is used when omitted
+    pub commitment_for_quote: Option<CommitmentConfig>,
+    /// 限价，即以Y代币计价的X代币UI价格。成交价格劣于限价时不构建指令 / Price limit as the UI price of token X in
+    /// token Y. No instruction is built when the execution price is worse than the limit
+    pub price_limit: Option<f64>,
+}
+
+impl SwapExactOutArgs {
+    /// 使用默认滑点、自动推导代币程序及客户端确认级别创建参数
+    /// Create the arguments with the default slippage, derived token programs and the client commitment
+    pub fn new(lb_pair: Pubkey, amount_out: u64, swap_for_y: bool) -> Self {
+        Self {
+            lb_pair,
+            amount_out,
+            swap_for_y,
+            slippage_bps: DEFAULT_SWAP_SLIPPAGE_BPS,
+            token_x_program: None,
+            token_y_program: None,
+            commitment_for_quote: None,
+            price_limit: None,
+        }
+    }
+}
+
+/// 构建好的精确输出交换指令及其报价
+/// Built exact output swap instruction with the quote it was built against
+pub struct SwapExactOutBuild {
+    /// 交换指令，不包含计算预算指令 / Swap instruction, without compute budget instructions
+    pub instruction: Instruction,
+    /// 剩余账户切片信息 / Remaining accounts slices
+    pub remaining_accounts_info: RemainingAccountsInfo,
+    /// 报价 / Quote
+    pub quote: SwapExactOutQuote,
+    /// 应用滑点后的最大输入数量（含手续费） / Maximum amount in after slippage, fee included
+    pub max_amount_in: u64,
+    /// 用户的输出代币账户 / User token account receiving the output
+    pub user_token_out: Pubkey,
+    /// 报价使用的账户 / Accounts used for the quote
+    pub quote_accounts: SwapQuoteAccounts,
+}
+
+/// 带价格影响限制的交换的参数
+/// Arguments of a swap bounded by its price impact
+#[derive(Debug, Clone)]
+pub struct SwapWithPriceImpactArgs {
+    /// 流动性对地址 / Address of the liquidity pair
+    pub lb_pair: Pubkey,
+    /// 卖出的代币数量 / Amount of token to sell
+    pub amount_in: u64,
+    /// 交易方向：true = 用X代币买Y代币，false = 用Y代币买X代币 / Swap direction. true = buy token Y, false = buy
+    /// token X
+    pub swap_for_y: bool,
+    /// 相对活跃bin价格允许的价格影响（基点） / Allowed price impact (bps) relative to the active bin price
+    pub price_impact_bps: u16,
+    /// 最少获得的代币数量，换算为价格影响后与 `price_impact_bps` 取更严格的一个 / Minimum amount of token to
+    /// receive. Converted into price impact, the stricter of it and `price_impact_bps` is used
+    pub min_amount_out: Option<u64>,
+    /// X代币的代币程序，未提供时由mint所有者推导 / Token program of token X. Derived from the mint owner when omitted
+    pub token_x_program: Option<Pubkey>,
+    /// Y代币的代币程序，未提供时由mint所有者推导 / Token program of token Y. Derived from the mint owner when omitted
+    pub token_y_program: Option<Pubkey>,
+    /// 读取报价账户时使用的确认级别，未提供时使用客户端的确认级别 / Commitment used to read the quote accounts. The
+    /// client commitment is used when omitted
+    pub commitment_for_quote: Option<CommitmentConfig>,
+    /// 限价，即以Y代币计价的X代币UI价格。成交价格劣于限价时不构建指令 / Price limit as the UI price of token X in
+    /// token Y. No instruction is built when the execution price is worse than the limit
+    pub price_limit: Option<f64>,
+}
+
+impl SwapWithPriceImpactArgs {
+    /// 使用自动推导的代币程序及客户端确认级别创建参数
+    /// Create the arguments with derived token programs and the client commitment
+    pub fn new(lb_pair: Pubkey, amount_in: u64, swap_for_y: bool, price_impact_bps: u16) -> Self {
+        Self {
+            lb_pair,
+            amount_in,
+            swap_for_y,
+            price_impact_bps,
+            min_amount_out: None,
+            token_x_program: None,
+            token_y_program: None,
+            commitment_for_quote: None,
+            price_limit: None,
+        }
+    }
+}
+
+/// 构建好的带价格影响限制的交换指令及其报价
+/// Built swap with price impact instruction with the quote it was built against
+pub struct SwapWithPriceImpactBuild {
+    /// 交换指令，不包含计算预算指令 / Swap instruction, without compute budget instructions
+    pub instruction: Instruction,
+    /// 剩余账户切片信息 / Remaining accounts slices
+    pub remaining_accounts_info: RemainingAccountsInfo,
+    /// 报价 / Quote
+    pub quote: SwapExactInQuote,
+    /// 指令使用的最大价格影响（基点） / Maximum price impact (bps) of the instruction
+    pub max_price_impact_bps: u16,
+    /// 用户的输出代币账户 / User token account receiving the output
+    pub user_token_out: Pubkey,
+    /// 报价使用的账户 / Accounts used for the quote
+    pub quote_accounts: SwapQuoteAccounts,
+}
+
+/// 报价所需的账户及bin数组位图扩展
+/// Accounts required for quoting, with the bin array bitmap extension
+pub struct SwapQuoteState {
+    /// bin数组位图扩展地址 / Address of the bin array bitmap extension
+    pub bitmap_extension_key: Pubkey,
+    /// bin数组位图扩展，未初始化时为 `None` / Bin array bitmap extension, `None` when not initialized
+    pub bitmap_extension: Option<BinArrayBitmapExtension>,
+    /// 报价账户 / Quote accounts
+    pub accounts: SwapQuoteAccounts,
+}
+
+/// 添加流动性的参数
+/// Arguments of adding liquidity
+#[derive(Debug, Clone)]
+pub struct AddLiquidityArgs {
+    /// 流动性对地址 / Address of the liquidity pair
+    pub lb_pair: Pubkey,
+    /// 存入流动性的头寸 / Position for the deposit
+    pub position: Pubkey,
+    /// 存入的X代币数量 / Amount of token X to deposit
+    pub amount_x: u64,
+    /// 存入的Y代币数量 / Amount of token Y to deposit
+    pub amount_y: u64,
+    /// 流动性在各个bin中的分配（基点） / Liquidity distribution to the bins in basis points
+    pub bin_liquidity_distribution: Vec<BinLiquidityDistribution>,
+    /// 使用 add_liquidity_by_weight 存入，活跃bin相对 `expected_active_id` 移动超过该bin数时在链上失败 / Deposit with
+    /// add_liquidity_by_weight, which fails on chain when the active bin moved more than this number of bins from
+    /// `expected_active_id`
+    pub max_active_bin_slippage: Option<u16>,
+    /// 分配所基于的活跃bin ID，未指定时使用构建指令时读取的活跃bin ID / Active bin id the distribution is based on.
+    /// Defaults to the active bin id read when building the instructions
+    pub expected_active_id: Option<i32>,
+}
+
+/// DLMM客户端，持有程序客户端及发送交易的配置
+/// DLMM client holding the program client and the configuration used to send transactions
+pub struct DlmmClient<C> {
+    program: Program<C>,
+    transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
+}
+
+impl<C, S> DlmmClient<C>
+where
+    C: Deref<Target = S> + Clone,
+    S: Signer,
+{
+    /// 创建客户端，`program` 需为DLMM程序的客户端
+    /// Create the client. `program` must be the client of the DLMM program
+    pub fn new(program: Program<C>, transaction_config: RpcSendTransactionConfig) -> Self {
+        Self {
+            program,
+            transaction_config,
+            compute_unit_price: ComputeUnitPrice::default(),
+        }
+    }
+
+    /// 设置发送交易时的计算单元价格（优先费用）
+    /// Set the compute unit price (priority fee) of the sent transactions
+    pub fn with_compute_unit_price(mut self, compute_unit_price: ComputeUnitPrice) -> Self {
+        self.compute_unit_price = compute_unit_price;
+        self
+    }
+
+    /// 程序客户端 / Program client
+    pub fn program(&self) -> &Program<C> {
+        &self.program
+    }
+
+    /// 交易发送配置 / Transaction send configuration
+    pub fn transaction_config(&self) -> RpcSendTransactionConfig {
+        self.transaction_config
+    }
+
+    /// 计算单元价格设置 / Compute unit price setting
+    pub fn compute_unit_price(&self) -> ComputeUnitPrice {
+        self.compute_unit_price.clone()
+    }
+
+    /// 交易付款人 / Transaction payer
+    pub fn payer(&self) -> Pubkey {
+        self.program.payer()
+    }
+
+    /// 计算预算指令：最大计算单元上限及计算单元价格
+    /// Compute budget instructions: the maximum compute unit limit and the compute unit price
+    pub fn compute_budget_instructions(&self) -> Result<Vec<Instruction>> {
+        self.compute_unit_price
+            .instructions_with_limit(Some(MAX_COMPUTE_UNIT_LIMIT))
+    }
+
+    /// 以付款人签名发送指令
+    /// Send the instructions signed by the payer
+    pub async fn send(&self, instructions: Vec<Instruction>) -> Result<Signature> {
+        let mut request_builder = self.program.request();
+        for instruction in instructions {
+            request_builder = request_builder.instruction(instruction);
+        }
+
+        let signature = request_builder
+            .send_with_spinner_and_config(self.transaction_config)
+            .await?;
+
+        Ok(signature)
+    }
+
+    /// 获取流动性对状态
+    /// Fetch the lb pair state
+    pub async fn get_lb_pair(&self, lb_pair: Pubkey) -> Result<LbPair> {
+        self.program
+            .rpc()
+            .get_account_and_deserialize(&lb_pair, |account| {
+                Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
+            })
+            .await
+    }
+
+    /// 获取交易对的头寸，提供所有者时仅返回该所有者的头寸
+    /// Fetch the positions of the pair. Only the positions of the owner are returned when provided
+    pub async fn get_positions(
+        &self,
+        lb_pair: Pubkey,
+        owner: Option<Pubkey>,
+    ) -> Result<Vec<(Pubkey, PositionV2)>> {
+        let filters = match owner {
+            Some(owner) => position_filter_by_wallet_and_pair(owner, lb_pair),
+            None => position_filter_by_pair(lb_pair),
+        };
+
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let accounts = get_program_accounts_with_retry(&self.program.rpc(), config).await?;
+
+        accounts
+            .into_iter()
+            .map(|(key, account)| {
+                let position_state = deserialize_position_v2(&account.data)
+                    .with_context(|| format!("Failed to read position {}", key))?;
+                Ok((key, position_state))
+            })
+            .collect()
+    }
+
+    /// 获取仓位每个bin的待领取手续费明细，用于精确的手续费对账
+    /// Fetch the pending fee breakdown of every bin of the position, for precise fee reconciliation
+    pub async fn get_position_fee_breakdown(
+        &self,
+        position: Pubkey,
+    ) -> Result<Vec<BinFeeBreakdown>> {
+        let rpc_client = self.program.rpc();
+        let position_state = rpc_client
+            .get_account_and_deserialize(&position, |account| {
+                deserialize_position_v2(&account.data)
+            })
+            .await?;
+
+        get_position_fee_breakdown(&rpc_client, &position_state).await
+    }
+
+    /// 获取报价所需的账户，复用调用方已获取的流动性对状态
+    /// Fetch the accounts required for quoting, reusing the lb pair state already fetched by the caller
+    pub async fn get_swap_quote_state(
+        &self,
+        lb_pair: Pubkey,
+        lb_pair_state: LbPair,
+        swap_for_y: bool,
+        bin_array_count: u8,
+        commitment: Option<CommitmentConfig>,
+    ) -> Result<SwapQuoteState> {
+        let rpc_client = self.program.rpc();
+
+        let (bitmap_extension_key, _bump) = derive_bin_array_bitmap_extension(lb_pair);
+
+        let bitmap_extension: Option<BinArrayBitmapExtension> =
+            get_optional_account(&rpc_client, bitmap_extension_key)
+                .await?
+                .map(|account| bytemuck::pod_read_unaligned(&account.data[8..]));
+
+        let bin_arrays_for_swap = get_bin_array_pubkeys_for_swap(
+            lb_pair,
+            &lb_pair_state,
+            bitmap_extension.as_ref(),
+            swap_for_y,
+            bin_array_count,
+        )?;
+
+        let accounts = fetch_quote_required_accounts(
+            &rpc_client,
+            lb_pair_state,
+            bin_arrays_for_swap,
+            commitment,
+        )
+        .await?;
+
+        Ok(SwapQuoteState {
+            bitmap_extension_key,
+            bitmap_extension,
+            accounts,
+        })
+    }
+
+    /// 获取双向报价所需的账户，一次读取活跃bin两侧的bin数组，复用调用方已获取的流动性对状态
+    /// Fetch the accounts required for quoting both swap directions, reading the bin arrays on both sides of the
+    /// active bin in a single fetch and reusing the lb pair state already fetched by the caller
+    pub async fn get_two_sided_quote_state(
+        &self,
+        lb_pair: Pubkey,
+        lb_pair_state: LbPair,
+        bin_array_count: u8,
+        commitment: Option<CommitmentConfig>,
+    ) -> Result<SwapQuoteState> {
+        let rpc_client = self.program.rpc();
+
+        let (bitmap_extension_key, _bump) = derive_bin_array_bitmap_extension(lb_pair);
+
+        let bitmap_extension: Option<BinArrayBitmapExtension> =
+            get_optional_account(&rpc_client, bitmap_extension_key)
+                .await?
+                .map(|account| bytemuck::pod_read_unaligned(&account.data[8..]));
+
+        // 两个方向都包含活跃bin所在的bin数组，去重后再读取
+        // Both directions include the bin array of the active bin, dedup before reading
+        let mut bin_arrays_for_quote = vec![];
+        for swap_for_y in [true, false] {
+            for key in get_bin_array_pubkeys_for_swap(
+                lb_pair,
+                &lb_pair_state,
+                bitmap_extension.as_ref(),
+                swap_for_y,
+                bin_array_count,
+            )? {
+                if !bin_arrays_for_quote.contains(&key) {
+                    bin_arrays_for_quote.push(key);
+                }
+            }
+        }
+
+        let accounts = fetch_quote_required_accounts(
+            &rpc_client,
+            lb_pair_state,
+            bin_arrays_for_quote,
+            commitment,
+        )
+        .await?;
+
+        Ok(SwapQuoteState {
+            bitmap_extension_key,
+            bitmap_extension,
+            accounts,
+        })
+    }
+
+    /// 计算精确输入交换的报价
+    /// Quote an exact input swap
+    pub async fn quote_exact_in(
+        &self,
+        lb_pair: Pubkey,
+        amount_in: u64,
+        swap_for_y: bool,
+    ) -> Result<SwapExactInQuote> {
+        let lb_pair_state = self.get_lb_pair(lb_pair).await?;
+
+        let SwapQuoteState {
+            bitmap_extension,
+            accounts,
+            ..
+        } = self
+            .get_swap_quote_state(
+                lb_pair,
+                lb_pair_state,
+                swap_for_y,
+                DEFAULT_QUOTE_BIN_ARRAY_COUNT,
+                None,
+            )
+            .await?;
+
+        quote_exact_in(
+            lb_pair,
+            &accounts.lb_pair_state,
+            amount_in,
+            swap_for_y,
+            accounts.bin_arrays,
+            bitmap_extension.as_ref(),
+            &accounts.clock,
+            &accounts.mint_x_account,
+            &accounts.mint_y_account,
+        )
+    }
+
+    /// 构建精确输入交换指令，复用调用方已获取的流动性对状态
+    /// Build the exact input swap instruction, reusing the lb pair state already fetched by the caller
+    pub async fn build_swap_exact_in(
+        &self,
+        lb_pair_state: LbPair,
+        args: SwapExactInArgs,
+    ) -> Result<SwapExactInBuild> {
+        let SwapExactInArgs {
+            lb_pair,
+            amount_in,
+            swap_for_y,
+            slippage_bps,
+            token_x_program,
+            token_y_program,
+            commitment_for_quote,
+            price_limit,
+        } = args;
+
+        let payer = self.payer();
+
+        // 获取代币程序（支持Token和Token2022）
+        let [token_x_program, token_y_program] =
+            resolve_token_programs(&lb_pair_state, token_x_program, token_y_program)?;
+
+        let (user_token_in, user_token_out) = swap_user_token_accounts(
+            payer,
+            &lb_pair_state,
+            swap_for_y,
+            [token_x_program, token_y_program],
+        );
+
+        let SwapQuoteState {
+            bitmap_extension_key,
+            bitmap_extension,
+            accounts: quote_accounts,
+        } = self
+            .get_swap_quote_state(
+                lb_pair,
+                lb_pair_state,
+                swap_for_y,
+                DEFAULT_QUOTE_BIN_ARRAY_COUNT,
+                commitment_for_quote,
+            )
+            .await?;
+
+        let lb_pair_state = &quote_accounts.lb_pair_state;
+
+        let quote = quote_exact_in(
+            lb_pair,
+            lb_pair_state,
+            amount_in,
+            swap_for_y,
+            quote_accounts.bin_arrays.clone(),
+            bitmap_extension.as_ref(),
+            &quote_accounts.clock,
+            &quote_accounts.mint_x_account,
+            &quote_accounts.mint_y_account,
+        )?;
+
+        if let Some(price_limit) = price_limit {
+            ensure_swap_price_within_limit(
+                amount_in,
+                quote.amount_out,
+                swap_for_y,
+                &quote_accounts.mint_x_account,
+                &quote_accounts.mint_y_account,
+                price_limit,
+            )?;
+        }
+
+        let (event_authority, _bump) = derive_event_authority_pda();
+
+        let main_accounts = dlmm::client::accounts::Swap2 {
+            lb_pair,
+            bin_array_bitmap_extension: bitmap_extension
+                .map(|_| bitmap_extension_key)
+                .or(Some(dlmm::ID)),
+            reserve_x: lb_pair_state.reserve_x,
+            reserve_y: lb_pair_state.reserve_y,
+            token_x_mint: lb_pair_state.token_x_mint,
+            token_y_mint: lb_pair_state.token_y_mint,
+            token_x_program,
+            token_y_program,
+            user: payer,
+            user_token_in,
+            user_token_out,
+            oracle: lb_pair_state.oracle,
+            host_fee_in: Some(dlmm::ID),
+            event_authority,
+            program: dlmm::ID,
+            memo_program: spl_memo::ID,
+        }
+        .to_account_metas(None);
+
+        let (remaining_accounts_info, remaining_accounts) = self
+            .get_swap_remaining_accounts(
+                lb_pair_state,
+                [token_x_program, token_y_program],
+                &quote_accounts.bin_array_keys,
+            )
+            .await?;
+
+        let min_amount_out = get_min_amount_out_with_slippage(quote.amount_out, slippage_bps)?;
+
+        let data = dlmm::client::args::Swap2 {
+            amount_in,
+            min_amount_out,
+            remaining_accounts_info: remaining_accounts_info.clone(),
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: dlmm::ID,
+            accounts: [main_accounts, remaining_accounts].concat(),
+            data,
+        };
+
+        Ok(SwapExactInBuild {
+            instruction,
+            remaining_accounts_info,
+            quote,
+            min_amount_out,
+            user_token_out,
+            quote_accounts,
+        })
+    }
+
+    /// 构建并发送精确输入交换
+    /// Build and send an exact input swap
+    pub async fn swap_exact_in(&self, args: SwapExactInArgs) -> Result<Signature> {
+        let lb_pair_state = self.get_lb_pair(args.lb_pair).await?;
+        let swap = self.build_swap_exact_in(lb_pair_state, args).await?;
+
+        let instructions = [self.compute_budget_instructions()?, vec![swap.instruction]].concat();
+
+        self.send(instructions).await
+    }
+
+    /// 构建精确输出交换指令，复用调用方已获取的流动性对状态
+    /// Build the exact output swap instruction, reusing the lb pair state already fetched by the caller
+    pub async fn build_swap_exact_out(
+        &self,
+        lb_pair_state: LbPair,
+        args: SwapExactOutArgs,
+    ) -> Result<SwapExactOutBuild> {
+        let SwapExactOutArgs {
+            lb_pair,
+            amount_out,
+            swap_for_y,
+            slippage_bps,
+            token_x_program,
+            token_y_program,
+            commitment_for_quote,
+            price_limit,
+        } = args;
+
+        let payer = self.payer();
+
+        // 获取代币程序（支持Token和Token2022）
+        let [token_x_program, token_y_program] =
+            resolve_token_programs(&lb_pair_state, token_x_program, token_y_program)?;
+
+        let (user_token_in, user_token_out) = swap_user_token_accounts(
+            payer,
+            &lb_pair_state,
+            swap_for_y,
+            [token_x_program, token_y_program],
+        );
+
+        let SwapQuoteState {
+            bitmap_extension_key,
+            bitmap_extension,
+            accounts: quote_accounts,
+        } = self
+            .get_swap_quote_state(
+                lb_pair,
+                lb_pair_state,
+                swap_for_y,
+                DEFAULT_QUOTE_BIN_ARRAY_COUNT,
+                commitment_for_quote,
+            )
+            .await?;
+
+        let lb_pair_state = &quote_accounts.lb_pair_state;
+
+        let quote = quote_exact_out(
+            lb_pair,
+            lb_pair_state,
+            amount_out,
+            swap_for_y,
+            quote_accounts.bin_arrays.clone(),
+            bitmap_extension.as_ref(),
+            &quote_accounts.clock,
+            &quote_accounts.mint_x_account,
+            &quote_accounts.mint_y_account,
+        )?;
+
+        // 输入数量包含手续费
+        // The amount in includes the fee
+        let amount_in = quote
+            .amount_in
+            .checked_add(quote.fee)
+            .context("amount in with fee overflow")?;
+
+        if let Some(price_limit) = price_limit {
+            ensure_swap_price_within_limit(
+                amount_in,
+                amount_out,
+                swap_for_y,
+                &quote_accounts.mint_x_account,
+                &quote_accounts.mint_y_account,
+                price_limit,
+            )?;
+        }
+
+        let (event_authority, _bump) = derive_event_authority_pda();
+
+        let main_accounts = dlmm::client::accounts::SwapExactOut2 {
+            lb_pair,
+            bin_array_bitmap_extension: bitmap_extension
+                .map(|_| bitmap_extension_key)
+                .or(Some(dlmm::ID)),
+            reserve_x: lb_pair_state.reserve_x,
+            reserve_y: lb_pair_state.reserve_y,
+            token_x_mint: lb_pair_state.token_x_mint,
+            token_y_mint: lb_pair_state.token_y_mint,
+            token_x_program,
+            token_y_program,
+            user: payer,
+            user_token_in,
+            user_token_out,
+            oracle: lb_pair_state.oracle,
+            host_fee_in: Some(dlmm::ID),
+            event_authority,
+            program: dlmm::ID,
+            memo_program: spl_memo::ID,
+        }
+        .to_account_metas(None);
+
+        let (remaining_accounts_info, remaining_accounts) = self
+            .get_swap_remaining_accounts(
+                lb_pair_state,
+                [token_x_program, token_y_program],
+                &quote_accounts.bin_array_keys,
+            )
+            .await?;
+
+        let max_amount_in = get_max_amount_in_with_slippage(amount_in, slippage_bps)?;
+
+        let data = dlmm::client::args::SwapExactOut2 {
+            out_amount: amount_out,
+            max_in_amount: max_amount_in,
+            remaining_accounts_info: remaining_accounts_info.clone(),
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: dlmm::ID,
+            accounts: [main_accounts, remaining_accounts].concat(),
+            data,
+        };
+
+        Ok(SwapExactOutBuild {
+            instruction,
+            remaining_accounts_info,
+            quote,
+            max_amount_in,
+            user_token_out,
+            quote_accounts,
+        })
+    }
+
+    /// 构建并发送精确输出交换
+    /// Build and send an exact output swap
+    pub async fn swap_exact_out(&self, args: SwapExactOutArgs) -> Result<Signature> {
+        let lb_pair_state = self.get_lb_pair(args.lb_pair).await?;
+        let swap = self.build_swap_exact_out(lb_pair_state, args).await?;
+
+        let instructions = [self.compute_budget_instructions()?, vec![swap.instruction]].concat();
+
+        self.send(instructions).await
+    }
+
+    /// 构建带价格影响限制的交换指令，复用调用方已获取的流动性对状态
+    /// Build the swap with price impact instruction, reusing the lb pair state already fetched by the caller
+    pub async fn build_swap_with_price_impact(
+        &self,
+        lb_pair_state: LbPair,
+        args: SwapWithPriceImpactArgs,
+    ) -> Result<SwapWithPriceImpactBuild> {
+        let SwapWithPriceImpactArgs {
+            lb_pair,
+            amount_in,
+            swap_for_y,
+            price_impact_bps,
+            min_amount_out,
+            token_x_program,
+            token_y_program,
+            commitment_for_quote,
+            price_limit,
+        } = args;
+
+        let payer = self.payer();
+
+        // 获取代币程序（支持Token和Token2022）
+        let [token_x_program, token_y_program] =
+            resolve_token_programs(&lb_pair_state, token_x_program, token_y_program)?;
+
+        let (user_token_in, user_token_out) = swap_user_token_accounts(
+            payer,
+            &lb_pair_state,
+            swap_for_y,
+            [token_x_program, token_y_program],
+        );
+
+        let SwapQuoteState {
+            bitmap_extension_key,
+            bitmap_extension,
+            accounts: quote_accounts,
+        } = self
+            .get_swap_quote_state(
+                lb_pair,
+                lb_pair_state,
+                swap_for_y,
+                DEFAULT_QUOTE_BIN_ARRAY_COUNT,
+                commitment_for_quote,
+            )
+            .await?;
+
+        let lb_pair_state = &quote_accounts.lb_pair_state;
+
+        let quote = quote_exact_in(
+            lb_pair,
+            lb_pair_state,
+            amount_in,
+            swap_for_y,
+            quote_accounts.bin_arrays.clone(),
+            bitmap_extension.as_ref(),
+            &quote_accounts.clock,
+            &quote_accounts.mint_x_account,
+            &quote_accounts.mint_y_account,
+        )?;
+
+        if let Some(price_limit) = price_limit {
+            ensure_swap_price_within_limit(
+                amount_in,
+                quote.amount_out,
+                swap_for_y,
+                &quote_accounts.mint_x_account,
+                &quote_accounts.mint_y_account,
+                price_limit,
+            )?;
+        }
+
+        // 最少获得数量换算为价格影响基点后，与指定的价格影响取更严格的一个
+        // The minimum amount out is converted into price impact bps and the stricter one is used
+        let max_price_impact_bps = match min_amount_out {
+            Some(min_amount_out) => {
+                ensure!(
+                    quote.amount_out >= min_amount_out,
+                    "Quoted amount out {} is below min_amount_out {}",
+                    quote.amount_out,
+                    min_amount_out
+                );
+
+                let min_amount_out_bps = min_amount_out_to_price_impact_bps(
+                    lb_pair_state,
+                    amount_in,
+                    swap_for_y,
+                    min_amount_out,
+                )?;
+
+                price_impact_bps.min(min_amount_out_bps)
+            }
+            None => price_impact_bps,
+        };
+
+        let (event_authority, _bump) = derive_event_authority_pda();
+
+        let main_accounts = dlmm::client::accounts::SwapWithPriceImpact2 {
+            lb_pair,
+            bin_array_bitmap_extension: bitmap_extension
+                .map(|_| bitmap_extension_key)
+                .or(Some(dlmm::ID)),
+            reserve_x: lb_pair_state.reserve_x,
+            reserve_y: lb_pair_state.reserve_y,
+            token_x_mint: lb_pair_state.token_x_mint,
+            token_y_mint: lb_pair_state.token_y_mint,
+            token_x_program,
+            token_y_program,
+            user: payer,
+            user_token_in,
+            user_token_out,
+            oracle: lb_pair_state.oracle,
+            host_fee_in: Some(dlmm::ID),
+            event_authority,
+            program: dlmm::ID,
+            memo_program: spl_memo::ID,
+        }
+        .to_account_metas(None);
+
+        let (remaining_accounts_info, remaining_accounts) = self
+            .get_swap_remaining_accounts(
+                lb_pair_state,
+                [token_x_program, token_y_program],
+                &quote_accounts.bin_array_keys,
+            )
+            .await?;
+
+        let data = dlmm::client::args::SwapWithPriceImpact2 {
+            amount_in,
+            active_id: Some(lb_pair_state.active_id),
+            max_price_impact_bps,
+            remaining_accounts_info: remaining_accounts_info.clone(),
+        }
+        .data();
+
+        let instruction = Instruction {
+            program_id: dlmm::ID,
+            accounts: [main_accounts, remaining_accounts].concat(),
+            data,
+        };
+
+        Ok(SwapWithPriceImpactBuild {
+            instruction,
+            remaining_accounts_info,
+            quote,
+            max_price_impact_bps,
+            user_token_out,
+            quote_accounts,
+        })
+    }
+
+    /// 构建并发送带价格影响限制的交换
+    /// Build and send a swap bounded by its price impact
+    pub async fn swap_with_price_impact(&self, args: SwapWithPriceImpactArgs) -> Result<Signature> {
+        let lb_pair_state = self.get_lb_pair(args.lb_pair).await?;
+        let swap = self
+            .build_swap_with_price_impact(lb_pair_state, args)
+            .await?;
+
+        let instructions = [self.compute_budget_instructions()?, vec![swap.instruction]].concat();
+
+        self.send(instructions).await
+    }
+
+    /// 交换指令的剩余账户：转账钩子账户，之后为报价使用的bin数组
+    /// Remaining accounts of a swap instruction: the transfer hook accounts, then the bin arrays of the quote
+    async fn get_swap_remaining_accounts(
+        &self,
+        lb_pair_state: &LbPair,
+        token_programs: [Pubkey; 2],
+        bin_array_keys: &[Pubkey],
+    ) -> Result<(RemainingAccountsInfo, Vec<AccountMeta>)> {
+        let mut remaining_accounts_info = RemainingAccountsInfo { slices: vec![] };
+        let mut remaining_accounts = vec![];
+
+        if let Some((slices, transfer_hook_remaining_accounts)) =
+            get_liquidity_transfer_hook_accounts(lb_pair_state, self.program.rpc(), token_programs)
+                .await?
+        {
+            remaining_accounts_info.slices = slices;
+            remaining_accounts.extend(transfer_hook_remaining_accounts);
+        }
+
+        remaining_accounts.extend(
+            bin_array_keys
+                .iter()
+                .map(|&key| AccountMeta::new(key, false)),
+        );
+
+        Ok((remaining_accounts_info, remaining_accounts))
+    }
+
+    /// 构建添加流动性的指令，不包含计算预算指令。用户代币账户由返回的指令幂等创建，不发送任何交易
+    /// Build the add liquidity instructions, without compute budget instructions. The user token accounts are
+    /// created idempotently by the returned instructions, no transaction is sent
+    pub async fn build_add_liquidity(&self, args: AddLiquidityArgs) -> Result<Vec<Instruction>> {
+        let AddLiquidityArgs {
+            lb_pair,
+            position,
+            amount_x,
+            amount_y,
+            mut bin_liquidity_distribution,
+            max_active_bin_slippage,
+            expected_active_id,
+        } = args;
+
+        // 按bin ID排序，确保从低到高
+        bin_liquidity_distribution.sort_by(|a, b| a.bin_id.cmp(&b.bin_id));
+
+        let rpc_client = self.program.rpc();
+        let payer = self.payer();
+
+        let lb_pair_state = self.get_lb_pair(lb_pair).await?;
+
+        // 获取代币程序（支持Token和Token2022）
+        let [token_x_program, token_y_program] = lb_pair_state.get_token_programs()?;
+
+        // 获取仓位状态
+        let position_state: PositionV2 = rpc_client
+            .get_account_and_deserialize(&position, |account| {
+                deserialize_position_v2(&account.data)
+            })
+            .await?;
+
+        // 获取最小和最大bin ID，用于确定需要的bin数组范围
+        let min_bin_id = bin_liquidity_distribution
+            .first()
+            .map(|bld| bld.bin_id)
+            .context("No bin liquidity distribution provided")?;
+
+        let max_bin_id = bin_liquidity_distribution
+            .last()
+            .map(|bld| bld.bin_id)
+            .context("No bin liquidity distribution provided")?;
+
+        // 获取覆盖所需bin范围的bin数组账户元数据
+        let bin_arrays_account_meta =
+            position_state.get_bin_array_accounts_meta_coverage_by_chunk(min_bin_id, max_bin_id)?;
+
+        // 提前检查覆盖的bin数组均已初始化
+        let (lower_bin_array_index, upper_bin_array_index) =
+            position_state.get_bin_array_indexes_bound_by_chunk(min_bin_id, max_bin_id)?;
+        ensure_bin_arrays_initialized(
+            &rpc_client,
+            lb_pair,
+            lower_bin_array_index,
+            upper_bin_array_index,
+        )
+        .await?;
+
+        let (user_token_x, create_user_token_x_ix) =
+            get_ata_with_create_ix(&rpc_client, lb_pair_state.token_x_mint, payer, payer).await?;
+        let (user_token_y, create_user_token_y_ix) =
+            get_ata_with_create_ix(&rpc_client, lb_pair_state.token_y_mint, payer, payer).await?;
+
+        let (bin_array_bitmap_extension, _bump) = derive_bin_array_bitmap_extension(lb_pair);
+
+        let bin_array_bitmap_extension =
+            resolve_optional_account(&rpc_client, bin_array_bitmap_extension)
+                .await?
+                .or(Some(dlmm::ID));
+
+        let (event_authority, _bump) = derive_event_authority_pda();
+
+        let transfer_hook_accounts = get_potential_token_2022_related_ix_data_and_accounts(
+            &lb_pair_state,
+            self.program.rpc(),
+            ActionType::Liquidity,
+        )
+        .await?;
+
+        // 设置活跃bin滑点时使用 add_liquidity_by_weight，由程序在链上检查活跃bin的移动
+        // With an active bin slippage the deposit uses add_liquidity_by_weight, which checks the active bin movement
+        // on chain
+        let add_liquidity_ix = if let Some(max_active_bin_slippage) = max_active_bin_slippage {
+            ensure!(
+                transfer_hook_accounts.is_none(),
+                "Active bin slippage is not supported for token mints with transfer hooks"
+            );
+
+            let bin_liquidity_dist = to_weight_distribution(
+                amount_x,
+                amount_y,
+                &bin_liquidity_distribution,
+                lb_pair_state.bin_step,
+            )?;
+            ensure!(
+                !bin_liquidity_dist.is_empty(),
+                "No bin liquidity distribution provided"
+            );
+
+            let (bin_array_lower, _bump) =
+                derive_bin_array_pda(lb_pair, lower_bin_array_index.into());
+            let (bin_array_upper, _bump) =
+                derive_bin_array_pda(lb_pair, upper_bin_array_index.into());
+
+            let accounts = dlmm::client::accounts::AddLiquidityByWeight {
+                position,
+                lb_pair,
+                bin_array_bitmap_extension,
+                user_token_x,
+                user_token_y,
+                reserve_x: lb_pair_state.reserve_x,
+                reserve_y: lb_pair_state.reserve_y,
+                token_x_mint: lb_pair_state.token_x_mint,
+                token_y_mint: lb_pair_state.token_y_mint,
+                bin_array_lower,
+                bin_array_upper,
+                sender: payer,
+                token_x_program,
+                token_y_program,
+                event_authority,
+                program: dlmm::ID,
+            }
+            .to_account_metas(None);
+
+            let data = dlmm::client::args::AddLiquidityByWeight {
+                liquidity_parameter: LiquidityParameterByWeight {
+                    amount_x,
+                    amount_y,
+                    active_id: expected_active_id.unwrap_or(lb_pair_state.active_id),
+                    max_active_bin_slippage: max_active_bin_slippage.into(),
+                    bin_liquidity_dist,
+                },
+            }
+            .data();
+
+            Instruction {
+                program_id: dlmm::ID,
+                accounts,
+                data,
+            }
+        } else {
+            let main_accounts = dlmm::client::accounts::AddLiquidity2 {
+                lb_pair,
+                bin_array_bitmap_extension,
+                position,
+                reserve_x: lb_pair_state.reserve_x,
+                reserve_y: lb_pair_state.reserve_y,
+                token_x_mint: lb_pair_state.token_x_mint,
+                token_y_mint: lb_pair_state.token_y_mint,
+                sender: payer,
+                user_token_x,
+                user_token_y,
+                token_x_program,
+                token_y_program,
+                event_authority,
+                program: dlmm::ID,
+            }
+            .to_account_metas(None);
+
+            let mut remaining_accounts_info = RemainingAccountsInfo { slices: vec![] };
+            let mut remaining_accounts = vec![];
+
+            if let Some((slices, transfer_hook_remaining_accounts)) = transfer_hook_accounts {
+                remaining_accounts_info.slices = slices;
+                remaining_accounts.extend(transfer_hook_remaining_accounts);
+            };
+
+            remaining_accounts.extend(bin_arrays_account_meta);
+
+            let data = dlmm::client::args::AddLiquidity2 {
+                liquidity_parameter: LiquidityParameter {
+                    amount_x,
+                    amount_y,
+                    bin_liquidity_dist: bin_liquidity_distribution,
+                },
+                remaining_accounts_info,
+            }
+            .data();
+
+            Instruction {
+                program_id: dlmm::ID,
+                accounts: [main_accounts, remaining_accounts].concat(),
+                data,
+            }
+        };
+
+        Ok(vec![
+            create_user_token_x_ix,
+            create_user_token_y_ix,
+            add_liquidity_ix,
+        ])
+    }
+
+    /// 构建并发送添加流动性交易
+    /// Build and send the add liquidity transaction
+    pub async fn add_liquidity(&self, args: AddLiquidityArgs) -> Result<Signature> {
+        let instructions = self.build_add_liquidity(args).await?;
+        self.send([self.compute_budget_instructions()?, instructions].concat())
+            .await
+    }
+}
+
+/// 推导交换的用户输入及输出代币账户
+/// Derive the user input and output token accounts of a swap
+fn swap_user_token_accounts(
+    payer: Pubkey,
+    lb_pair_state: &LbPair,
+    swap_for_y: bool,
+    token_programs: [Pubkey; 2],
+) -> (Pubkey, Pubkey) {
+    let [token_x_program, token_y_program] = token_programs;
+
+    let user_token_x = get_associated_token_address_with_program_id(
+        &payer,
+        &lb_pair_state.token_x_mint,
+        &token_x_program,
+    );
+    let user_token_y = get_associated_token_address_with_program_id(
+        &payer,
+        &lb_pair_state.token_y_mint,
+        &token_y_program,
+    );
+
+    // 根据交易方向确定输入和输出代币账户
+    if swap_for_y {
+        (user_token_x, user_token_y)
+    } else {
+        (user_token_y, user_token_x)
+    }
+}
+
+/// 将最少获得数量换算为相对活跃bin价格的价格影响基点，向下取整使换算后的限制不会比最少获得数量宽松
+/// Convert the minimum amount out into price impact bps relative to the active bin price. Rounded down so the
+/// converted bound is never looser than the minimum amount out
+fn min_amount_out_to_price_impact_bps(
+    lb_pair_state: &LbPair,
+    amount_in: u64,
+    swap_for_y: bool,
+    min_amount_out: u64,
+) -> Result<u16> {
+    let active_price = get_price_from_id(lb_pair_state.active_id, lb_pair_state.bin_step)?;
+    let amount_out_at_active_price = Bin::get_amount_out(amount_in, active_price, swap_for_y)?;
+
+    ensure!(
+        min_amount_out <= amount_out_at_active_price,
+        "min_amount_out {} exceeds the amount out {} at the active bin price",
+        min_amount_out,
+        amount_out_at_active_price
+    );
+
+    if amount_out_at_active_price == 0 {
+        return Ok(0);
+    }
+
+    let bps = u128::from(amount_out_at_active_price - min_amount_out)
+        .checked_mul(BASIS_POINT_MAX as u128)
+        .and_then(|value| value.checked_div(amount_out_at_active_price.into()))
+        .context("overflow")?;
+
+    Ok(u16::try_from(bps)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+    use anchor_spl::token::spl_token;
+    use std::rc::Rc;
+
+    /// 连接到夹具JSON-RPC服务的客户端 / Client connected to the fixture JSON-RPC server
+    fn fixture_client() -> DlmmClient<Rc<Keypair>> {
+        let url = start_fixture_rpc_server();
+        let client = Client::new_with_options(
+            Cluster::Custom(url.clone(), url.replace("http", "ws")),
+            Rc::new(Keypair::new()),
+            CommitmentConfig::confirmed(),
+        );
+
+        DlmmClient::new(
+            client.program(dlmm::ID).unwrap(),
+            RpcSendTransactionConfig::default(),
+        )
+    }
+
+    fn fixture_user_token_accounts(payer: Pubkey) -> (Pubkey, Pubkey) {
+        let lb_pair_state = fixture_lb_pair_state();
+        (
+            get_associated_token_address_with_program_id(
+                &payer,
+                &lb_pair_state.token_x_mint,
+                &spl_token::ID,
+            ),
+            get_associated_token_address_with_program_id(
+                &payer,
+                &lb_pair_state.token_y_mint,
+                &spl_token::ID,
+            ),
+        )
+    }
+
+    #[test]
+    fn test_compute_budget_instructions() {
+        let client =
+            fixture_client().with_compute_unit_price(ComputeUnitPrice::MicroLamports(1_000));
+
+        assert_eq!(
+            client.compute_budget_instructions().unwrap(),
+            vec![
+                ComputeBudgetInstruction::set_compute_unit_limit(MAX_COMPUTE_UNIT_LIMIT),
+                ComputeBudgetInstruction::set_compute_unit_price(1_000),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_quote_exact_in() {
+        let client = fixture_client();
+        let quote_accounts = fixture_quote_accounts();
+
+        for swap_for_y in [true, false] {
+            let quote = client
+                .quote_exact_in(FIXTURE_LB_PAIR, 1_000_000, swap_for_y)
+                .await
+                .unwrap();
+            let expected = quote_exact_in(
+                FIXTURE_LB_PAIR,
+                &quote_accounts.lb_pair_state,
+                1_000_000,
+                swap_for_y,
+                quote_accounts.bin_arrays.clone(),
+                None,
+                &quote_accounts.clock,
+                &quote_accounts.mint_x_account,
+                &quote_accounts.mint_y_account,
+            )
+            .unwrap();
+
+            assert_eq!(quote.amount_out, expected.amount_out);
+            assert_eq!(quote.fee, expected.fee);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_build_swap_exact_in() {
+        let client = fixture_client();
+        let (user_token_x, user_token_y) = fixture_user_token_accounts(client.payer());
+
+        let swap = client
+            .build_swap_exact_in(
+                fixture_lb_pair_state(),
+                SwapExactInArgs::new(FIXTURE_LB_PAIR, 1_000_000, true),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(swap.user_token_out, user_token_y);
+        assert_eq!(
+            swap.min_amount_out,
+            get_min_amount_out_with_slippage(swap.quote.amount_out, DEFAULT_SWAP_SLIPPAGE_BPS)
+                .unwrap()
+        );
+
+        assert!(!swap.quote_accounts.bin_array_keys.is_empty());
+        let accounts = &swap.instruction.accounts;
+        assert_eq!(swap.instruction.program_id, dlmm::ID);
+        assert_eq!(accounts[0].pubkey, FIXTURE_LB_PAIR);
+        // 没有bin数组位图扩展时传入程序ID / The program id is passed without a bin array bitmap extension
+        assert_eq!(accounts[1].pubkey, dlmm::ID);
+        assert_eq!(accounts[4].pubkey, user_token_x);
+        assert_eq!(accounts[5].pubkey, user_token_y);
+        assert_eq!(accounts[10].pubkey, client.payer());
+        assert_eq!(
+            accounts[SWAP_ACCOUNT_LABELS.len()..]
+                .iter()
+                .map(|account| account.pubkey)
+                .collect::<Vec<_>>(),
+            swap.quote_accounts.bin_array_keys
+        );
+
+        assert!(client
+            .build_swap_exact_in(
+                fixture_lb_pair_state(),
+                SwapExactInArgs {
+                    price_limit: Some(f64::MAX),
+                    ..SwapExactInArgs::new(FIXTURE_LB_PAIR, 1_000_000, true)
+                },
+            )
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_build_swap_exact_out() {
+        let client = fixture_client();
+        let (user_token_x, user_token_y) = fixture_user_token_accounts(client.payer());
+
+        let swap = client
+            .build_swap_exact_out(
+                fixture_lb_pair_state(),
+                SwapExactOutArgs::new(FIXTURE_LB_PAIR, 1_000_000, false),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(swap.user_token_out, user_token_x);
+
+        let max_amount_in = get_max_amount_in_with_slippage(
+            swap.quote.amount_in + swap.quote.fee,
+            DEFAULT_SWAP_SLIPPAGE_BPS,
+        )
+        .unwrap();
+        assert_eq!(swap.max_amount_in, max_amount_in);
+        assert_eq!(
+            swap.instruction.data,
+            dlmm::client::args::SwapExactOut2 {
+                out_amount: 1_000_000,
+                max_in_amount: max_amount_in,
+                remaining_accounts_info: RemainingAccountsInfo { slices: vec![] },
+            }
+            .data()
+        );
+
+        let accounts = &swap.instruction.accounts;
+        assert_eq!(accounts[4].pubkey, user_token_y);
+        assert_eq!(accounts[5].pubkey, user_token_x);
+        assert_eq!(
+            accounts.len(),
+            SWAP_ACCOUNT_LABELS.len() + swap.quote_accounts.bin_array_keys.len()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_swap_with_price_impact() {
+        let client = fixture_client();
+
+        let swap = client
+            .build_swap_with_price_impact(
+                fixture_lb_pair_state(),
+                SwapWithPriceImpactArgs::new(FIXTURE_LB_PAIR, 1_000_000, true, 500),
+            )
+            .await
+            .unwrap();
+        assert_eq!(swap.max_price_impact_bps, 500);
+
+        // 最少获得数量为报价输出数量时，换算的价格影响比500基点更严格
+        // With the quoted amount out as the minimum amount out, the converted price impact is stricter than 500 bps
+        let args = SwapWithPriceImpactArgs {
+            min_amount_out: Some(swap.quote.amount_out),
+            ..SwapWithPriceImpactArgs::new(FIXTURE_LB_PAIR, 1_000_000, true, 500)
+        };
+        let strict_swap = client
+            .build_swap_with_price_impact(fixture_lb_pair_state(), args.clone())
+            .await
+            .unwrap();
+        assert!(strict_swap.max_price_impact_bps < 500);
+        assert_eq!(
+            strict_swap.instruction.data,
+            dlmm::client::args::SwapWithPriceImpact2 {
+                amount_in: 1_000_000,
+                active_id: Some(fixture_lb_pair_state().active_id),
+                max_price_impact_bps: strict_swap.max_price_impact_bps,
+                remaining_accounts_info: RemainingAccountsInfo { slices: vec![] },
+            }
+            .data()
+        );
+
+        let args = SwapWithPriceImpactArgs {
+            min_amount_out: Some(swap.quote.amount_out + 1),
+            ..args
+        };
+        assert!(client
+            .build_swap_with_price_impact(fixture_lb_pair_state(), args)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,206 @@
+use std::path::Path;
+
+use crate::*;
+use clap::parser::ValueSource;
+use serde_json::Value;
+
+/// 默认配置文件路径
+/// Default config file path
+pub const DEFAULT_CONFIG_PATH: &str = "~/.config/dlmm/config.json";
+
+/// 配置文件支持的键，与 `ConfigOverride` 的字段名相同
+/// Keys supported by the config file, same as the field names of `ConfigOverride`
+const CONFIG_KEYS: [&str; 9] = [
+    "cluster",
+    "cluster_url",
+    "ws_url",
+    "wallet",
+    "priority_fee",
+    "max_priority_fee_lamports",
+    "compute_unit_limit",
+    "commitment",
+    "log_level",
+];
+
+/// 从原始命令行参数中找出 `--config` 指定的路径。配置文件提供参数的默认值，因此需要在解析参数之前读取
+/// Find the path given by `--config` in the raw command line arguments. The config file provides the argument
+/// defaults, so it has to be read before the arguments are parsed
+fn find_config_path(args: &[String]) -> Option<String> {
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            return args.next().cloned();
+        }
+
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Some(path.to_string());
+        }
+    }
+
+    None
+}
+
+/// 读取配置文件，返回 (参数id, 值) 列表。未指定 `--config` 且默认配置文件不存在时返回空列表
+/// Read the config file, returning a list of (argument id, value). Returns an empty list when `--config` is not
+/// given and the default config file does not exist
+pub fn load_config_defaults(path: Option<String>) -> Result<Vec<(&'static str, String)>> {
+    let explicit = path.is_some();
+    let path = shellexpand::tilde(path.as_deref().unwrap_or(DEFAULT_CONFIG_PATH)).to_string();
+
+    if !explicit && !Path::new(&path).exists() {
+        return Ok(vec![]);
+    }
+
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read config file {}", path))?;
+    let config: Value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse config file {}", path))?;
+    let config = config
+        .as_object()
+        .with_context(|| format!("Config file {} must be a JSON object", path))?;
+
+    let mut defaults = vec![];
+
+    for (key, value) in config {
+        let id = CONFIG_KEYS
+            .iter()
+            .copied()
+            .find(|id| id == key)
+            .with_context(|| {
+                format!(
+                    "Unknown key {} in config file {}, supported keys are {}",
+                    key,
+                    path,
+                    CONFIG_KEYS.join(", ")
+                )
+            })?;
+
+        let value = match value {
+            Value::String(value) => value.clone(),
+            Value::Number(value) => value.to_string(),
+            _ => bail!(
+                "Value of {} in config file {} must be a string or a number",
+                key,
+                path
+            ),
+        };
+
+        defaults.push((id, value));
+    }
+
+    ensure!(
+        !(config.contains_key("priority_fee") && config.contains_key("max_priority_fee_lamports")),
+        "Config file {} cannot set both priority_fee and max_priority_fee_lamports",
+        path
+    );
+
+    Ok(defaults)
+}
+
+/// 解析命令行参数，配置文件中的值作为全局选项的默认值
+/// 优先级：命令行参数 > 环境变量 > 配置文件 > 内置默认值
+///
+/// Parse the command line arguments with the values of the config file as the defaults of the global options.
+/// Precedence: explicit flag > env var > config file > built-in default
+pub fn parse_cli() -> Result<Cli> {
+    let args = std::env::args().collect::<Vec<_>>();
+    let defaults = load_config_defaults(find_config_path(&args))?;
+
+    // `--config` 只在解析参数之前使用，因此不属于 `ConfigOverride`
+    // `--config` is only used before the arguments are parsed, so it is not part of `ConfigOverride`
+    let mut command = Cli::command().arg(
+        Arg::new("config")
+            .long("config")
+            .global(true)
+            .value_name("PATH")
+            .help(format!(
+                "Config file setting the defaults of the global options. Default: {}",
+                DEFAULT_CONFIG_PATH
+            )),
+    );
+    for (id, value) in defaults {
+        command = command.mut_arg(id, |arg| arg.default_value(value));
+    }
+
+    let matches = command.get_matches_from(args);
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|error| error.exit());
+
+    // 冲突检查不适用于默认值，配置文件的优先费用总额不能覆盖显式指定的优先费用
+    // Conflicts are not checked against defaults, so a total priority fee from the config file must not
+    // override an explicitly specified priority fee
+    if matches.value_source("max_priority_fee_lamports") == Some(ValueSource::DefaultValue)
+        && matches.value_source("priority_fee") != Some(ValueSource::DefaultValue)
+    {
+        cli.config_override.max_priority_fee_lamports = None;
+    }
+
+    Ok(cli)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 将内容写入临时目录中的配置文件并返回路径
+    /// Write the content to a config file in the temp directory and return its path
+    fn write_config(name: &str, content: &str) -> String {
+        let path = std::env::temp_dir().join(format!("dlmm-config-test-{}.json", name));
+        std::fs::write(&path, content).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_find_config_path() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            find_config_path(&args(&["cli", "--config", "a.json", "show-pair"])),
+            Some("a.json".to_string())
+        );
+        assert_eq!(
+            find_config_path(&args(&["cli", "show-pair", "--config=b.json"])),
+            Some("b.json".to_string())
+        );
+        assert_eq!(find_config_path(&args(&["cli", "show-pair"])), None);
+        // 缺少值时视为未指定 / A missing value is treated as not given
+        assert_eq!(find_config_path(&args(&["cli", "--config"])), None);
+    }
+
+    #[test]
+    fn test_load_config_defaults() {
+        let path = write_config(
+            "valid",
+            r#"{"cluster": "devnet", "priority_fee": 1000, "commitment": "finalized"}"#,
+        );
+        let mut defaults = load_config_defaults(Some(path)).unwrap();
+        defaults.sort();
+        assert_eq!(
+            defaults,
+            vec![
+                ("cluster", "devnet".to_string()),
+                ("commitment", "finalized".to_string()),
+                ("priority_fee", "1000".to_string()),
+            ]
+        );
+
+        // 显式指定的配置文件必须存在 / An explicitly given config file must exist
+        let missing = std::env::temp_dir().join("dlmm-config-test-missing.json");
+        assert!(load_config_defaults(Some(missing.to_string_lossy().to_string())).is_err());
+
+        let path = write_config("unknown-key", r#"{"clusterr": "devnet"}"#);
+        assert!(load_config_defaults(Some(path)).is_err());
+
+        let path = write_config("invalid-value", r#"{"priority_fee": [1000]}"#);
+        assert!(load_config_defaults(Some(path)).is_err());
+
+        let path = write_config("not-object", r#"["devnet"]"#);
+        assert!(load_config_defaults(Some(path)).is_err());
+
+        let path = write_config(
+            "conflict",
+            r#"{"priority_fee": 1000, "max_priority_fee_lamports": 5000}"#,
+        );
+        assert!(load_config_defaults(Some(path)).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+//! 测试夹具：commons集成测试中的SOL交易对账户，以及应答这些账户查询的本地JSON-RPC服务
+//! Test fixtures: the SOL pair accounts of the commons integration tests, and a local JSON-RPC server answering the
+//! account queries with them
+use crate::*;
+use anchor_spl::token::spl_token;
+use solana_sdk::{account::Account, program_pack::Pack, sysvar::clock::Clock};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+
+/// 夹具交易对地址 / Address of the fixture pair
+pub const FIXTURE_LB_PAIR: Pubkey =
+    Pubkey::from_str_const("EtAdVRLFH22rjWh3mcUasKFF27WtHhsaCvK27tPFFWig");
+
+/// 夹具bin数组地址及文件名 / Addresses and file names of the fixture bin arrays
+const FIXTURE_BIN_ARRAYS: [(&str, &str); 2] = [
+    (
+        "5Sm2ecMeqohRkNpFJPWSqHL1BkA7AEW4ck8TmdF1gD4t",
+        "bin_array_1.bin",
+    ),
+    (
+        "E6gur9Jw8675DCR7GpJVhoSrkruRgt8EdEVqLAc5RLUt",
+        "bin_array_2.bin",
+    ),
+];
+
+fn read_fixture(name: &str) -> Vec<u8> {
+    std::fs::read(format!(
+        "{}/../commons/tests/fixtures/{}/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        FIXTURE_LB_PAIR,
+        name
+    ))
+    .unwrap()
+}
+
+/// 夹具交易对状态 / State of the fixture pair
+pub fn fixture_lb_pair_state() -> LbPair {
+    bytemuck::pod_read_unaligned(&read_fixture("lb_pair.bin")[8..])
+}
+
+/// 夹具交易对的报价账户，包括bin数组、代币mint及时钟
+/// Quote accounts of the fixture pair: the bin arrays, the token mints and the clock
+pub fn fixture_quote_accounts() -> SwapQuoteAccounts {
+    let lb_pair_state = fixture_lb_pair_state();
+
+    let bin_arrays = FIXTURE_BIN_ARRAYS
+        .iter()
+        .map(|&(key, name)| {
+            let bin_array: BinArray = bytemuck::pod_read_unaligned(&read_fixture(name)[8..]);
+            (Pubkey::from_str_const(key), bin_array)
+        })
+        .collect::<HashMap<_, _>>();
+
+    // 交易对的Y代币为原生SOL，夹具中没有其mint账户
+    // Token Y of the pair is native SOL, whose mint account isn't part of the fixtures
+    let mut mint_y_data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        decimals: 9,
+        is_initialized: true,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut mint_y_data);
+
+    let clock = Clock {
+        unix_timestamp: lb_pair_state.v_parameters.last_update_timestamp,
+        ..Default::default()
+    };
+
+    SwapQuoteAccounts {
+        lb_pair_state,
+        context_slot: 0,
+        clock,
+        mint_x_account: Account {
+            data: read_fixture("token_x_mint.bin"),
+            owner: spl_token::ID,
+            ..Default::default()
+        },
+        mint_y_account: Account {
+            data: mint_y_data,
+            owner: spl_token::ID,
+            ..Default::default()
+        },
+        bin_array_keys: bin_arrays.keys().copied().collect(),
+        bin_arrays,
+    }
+}
+
+/// 夹具交易对的链上账户 / On chain accounts of the fixture pair
+fn fixture_accounts() -> HashMap<Pubkey, Account> {
+    let quote_accounts = fixture_quote_accounts();
+    let lb_pair_state = quote_accounts.lb_pair_state;
+
+    let mut accounts = HashMap::from([
+        (
+            FIXTURE_LB_PAIR,
+            Account {
+                data: read_fixture("lb_pair.bin"),
+                owner: dlmm::ID,
+                ..Default::default()
+            },
+        ),
+        (lb_pair_state.token_x_mint, quote_accounts.mint_x_account),
+        (lb_pair_state.token_y_mint, quote_accounts.mint_y_account),
+        (
+            solana_sdk::sysvar::clock::ID,
+            Account {
+                data: bincode::serialize(&quote_accounts.clock).unwrap(),
+                ..Default::default()
+            },
+        ),
+    ]);
+
+    for (key, name) in FIXTURE_BIN_ARRAYS {
+        accounts.insert(
+            Pubkey::from_str_const(key),
+            Account {
+                data: read_fixture(name),
+                owner: dlmm::ID,
+                ..Default::default()
+            },
+        );
+    }
+
+    accounts
+}
+
+/// 在本地端口启动JSON-RPC服务，以夹具账户应答 `getAccountInfo` 及 `getMultipleAccounts`，返回其URL
+/// Start a JSON-RPC server on a local port answering `getAccountInfo` and `getMultipleAccounts` with the fixture
+/// accounts. Returns its URL
+pub fn start_fixture_rpc_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let accounts = fixture_accounts();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Result::Ok(stream) = stream else {
+                continue;
+            };
+            let accounts = accounts.clone();
+            std::thread::spawn(move || serve_connection(stream, &accounts));
+        }
+    });
+
+    url
+}
+
+/// 应答同一连接上的所有请求，直到客户端关闭连接
+/// Answer every request of a connection until the client closes it
+fn serve_connection(stream: std::net::TcpStream, accounts: &HashMap<Pubkey, Account>) {
+    let mut writer = stream.try_clone().unwrap();
+    let mut reader = BufReader::new(stream);
+
+    loop {
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                return;
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+        }
+
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        let encode = |key: &serde_json::Value| {
+            let key = key.as_str().unwrap().parse::<Pubkey>().unwrap();
+            accounts.get(&key).map(|account| {
+                encode_ui_account(&key, account, UiAccountEncoding::Base64, None, None)
+            })
+        };
+
+        let value = match request["method"].as_str().unwrap() {
+            "getAccountInfo" => serde_json::json!(encode(&request["params"][0])),
+            "getMultipleAccounts" => serde_json::json!(request["params"][0]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(encode)
+                .collect::<Vec<_>>()),
+            method => panic!("Unexpected RPC method {}", method),
+        };
+
+        let response = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "result": { "context": { "slot": 1 }, "value": value },
+        })
+        .to_string();
+
+        write!(
+            writer,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            response.len(),
+            response
+        )
+        .unwrap();
+    }
+}
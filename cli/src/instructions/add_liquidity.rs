@@ -1,6 +1,4 @@
 use crate::*;
-use commons::dlmm::accounts::{LbPair, PositionV2};
-use instructions::*;
 
 /// 添加流动性参数
 #[derive(Debug, Parser)]
@@ -17,61 +15,83 @@ pub struct AddLiquidityParams {
     /// Amount of token Y to be deposited.
     /// 要存入的Y代币数量
     pub amount_y: u64,
-    /// Liquidity distribution to the bins. "<DELTA_ID,DIST_X,DIST_Y, DELTA_ID,DIST_X,DIST_Y, ...>" where
-    /// DELTA_ID = Number of bins surrounding the active bin. This decide which bin the token is going to deposit to. For example: if the current active id is 5555, delta_ids is 1, the user will be depositing to bin 5554, 5555, and 5556.
+    /// Liquidity distribution to the bins. "<BIN_ID,DIST_X,DIST_Y, BIN_ID,DIST_X,DIST_Y, ...>" where
+    /// BIN_ID = Absolute id of the bin to deposit to, not an offset from the active bin. Must be within the position range.
     /// DIST_X = Percentage of amount_x to be deposited to the bins. Must not > 1.0
     /// DIST_Y = Percentage of amount_y to be deposited to the bins. Must not > 1.0
-    /// For example: --bin-liquidity-distribution "-1,0.0,0.25 0,0.75,0.75 1,0.25,0.0"
+    /// For example: --bin-liquidity-distribution "5554,0.0,0.25 5555,0.75,0.75 5556,0.25,0.0"
     /// 
-    /// 流动性在各个bin中的分配。格式："<DELTA_ID,DIST_X,DIST_Y, ...>"
-    /// DELTA_ID = 相对于当前活跃bin的偏移。例如：当前活跃ID是5555，delta_id为1表示存入bin 5556
+    /// 流动性在各个bin中的分配。格式："<BIN_ID,DIST_X,DIST_Y, ...>"
+    /// BIN_ID = 存入的bin的绝对ID，而不是相对活跃bin的偏移，必须位于仓位范围内
     /// DIST_X = X代币分配到该bin的百分比（不得大于1.0）
     /// DIST_Y = Y代币分配到该bin的百分比（不得大于1.0）
-    /// 示例：--bin-liquidity-distribution "-1,0.0,0.25 0,0.75,0.75 1,0.25,0.0"
+    /// 示例：--bin-liquidity-distribution "5554,0.0,0.25 5555,0.75,0.75 5556,0.25,0.0"
     #[clap(long, value_parser = parse_bin_liquidity_distribution, value_delimiter = ' ', allow_hyphen_values = true)]
     pub bin_liquidity_distribution: Vec<(i32, f64, f64)>,
+    /// JSON or CSV file with the liquidity distribution, one `BIN_ID,DIST_X,DIST_Y` row per entry of
+    /// `--bin-liquidity-distribution`. JSON: `[[BIN_ID, DIST_X, DIST_Y], ...]`. CSV: one row per line, an optional
+    /// header line and `#` comments are skipped. The file extension selects the format.
+    /// 包含流动性分配的JSON或CSV文件，每行对应 `--bin-liquidity-distribution` 的一项。由文件扩展名决定格式
+    #[clap(long, conflicts_with = "bin_liquidity_distribution")]
+    pub distribution_file: Option<String>,
+    /// Address lookup table used to compile a v0 transaction. Required for wide bin ranges that exceed the legacy transaction account limit.
+    /// 用于编译v0交易的地址查找表，适用于超出传统交易账户数量限制的宽bin范围
+    #[clap(long)]
+    pub use_lut: Option<Pubkey>,
+    /// Print the built instructions as JSON (program id, accounts, base58 data) instead of sending them, to compose
+    /// them into other transactions. Missing user token accounts are created by idempotent instructions included in
+    /// the output. Compute budget instructions are left out.
+    /// 不发送交易，以JSON打印构建的指令（程序ID、账户、base58数据），用于组合到其他交易中。
+    /// 输出中包含幂等创建用户代币账户的指令，不包含计算预算指令
+    #[clap(long, conflicts_with = "use_lut")]
+    pub build_only: bool,
+    /// Fail on chain when the active bin moved more than this number of bins from `--expected-active-id`. The
+    /// deposit is sent with add_liquidity_by_weight, which converts the distribution to weights by value and doesn't
+    /// support transfer hook mints.
+    /// 活跃bin相对 `--expected-active-id` 移动超过该bin数时在链上失败。存入使用 add_liquidity_by_weight，
+    /// 分配按价值转换为权重，不支持带转账钩子的代币
+    #[clap(long)]
+    pub max_active_bin_slippage: Option<u16>,
+    /// Active bin id the distribution was computed from. Defaults to the active bin id read when building the
+    /// instructions.
+    /// 计算分配时所基于的活跃bin ID，默认为构建指令时读取的活跃bin ID
+    #[clap(long, requires = "max_active_bin_slippage", allow_hyphen_values = true)]
+    pub expected_active_id: Option<i32>,
 }
 
 /// 执行添加流动性操作
 /// 
 /// # 参数
 /// * `params` - 添加流动性参数
-/// * `program` - Anchor程序客户端
-/// * `transaction_config` - 交易配置
-/// * `compute_unit_price` - 计算单元价格指令（可选）
+/// * `client` - DLMM客户端
+/// * `payer` - 使用地址查找表时签名v0交易的付款人
 /// 
 /// # 功能
-/// 1. 验证参数并排序流动性分配
-/// 2. 获取必要的账户和状态
-/// 3. 构建并发送添加流动性交易
+/// 1. 将流动性分配转换为基点
+/// 2. 由客户端构建添加流动性指令
+/// 3. 发送交易，提供地址查找表时编译为v0交易
 pub async fn execute_add_liquidity<C: Deref<Target = impl Signer> + Clone>(
     params: AddLiquidityParams,
-    program: &Program<C>,
-    transaction_config: RpcSendTransactionConfig,
-    compute_unit_price: Option<Instruction>,
+    client: &DlmmClient<C>,
+    payer: &dyn Signer,
 ) -> Result<()> {
     let AddLiquidityParams {
         lb_pair,
         position,
         amount_x,
         amount_y,
-        mut bin_liquidity_distribution,
+        bin_liquidity_distribution,
+        distribution_file,
+        use_lut,
+        build_only,
+        max_active_bin_slippage,
+        expected_active_id,
     } = params;
 
-    // 按bin ID排序，确保从低到高
-    bin_liquidity_distribution.sort_by(|a, b| a.0.cmp(&b.0));
-
-    let rpc_client = program.rpc();
-
-    // 获取流动性对状态
-    let lb_pair_state: LbPair = rpc_client
-        .get_account_and_deserialize(&lb_pair, |account| {
-            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
-        })
-        .await?;
-
-    // 获取代币程序（支持Token和Token2022）
-    let [token_x_program, token_y_program] = lb_pair_state.get_token_programs()?;
+    let bin_liquidity_distribution = match distribution_file {
+        Some(path) => load_bin_liquidity_distribution_file(&path)?,
+        None => bin_liquidity_distribution,
+    };
 
     // 将百分比转换为基点（1 = 10000基点）
     let bin_liquidity_distribution = bin_liquidity_distribution
@@ -83,120 +103,38 @@ pub async fn execute_add_liquidity<C: Deref<Target = impl Signer> + Clone>(
         })
         .collect::<Vec<_>>();
 
-    // 获取仓位状态
-    let position_state: PositionV2 = rpc_client
-        .get_account_and_deserialize(&position, |account| {
-            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
+    let instructions = client
+        .build_add_liquidity(AddLiquidityArgs {
+            lb_pair,
+            position,
+            amount_x,
+            amount_y,
+            bin_liquidity_distribution,
+            max_active_bin_slippage,
+            expected_active_id,
         })
         .await?;
 
-    // 获取最小和最大bin ID，用于确定需要的bin数组范围
-    let min_bin_id = bin_liquidity_distribution
-        .first()
-        .map(|bld| bld.bin_id)
-        .context("No bin liquidity distribution provided")?;
-
-    let max_bin_id = bin_liquidity_distribution
-        .last()
-        .map(|bld| bld.bin_id)
-        .context("No bin liquidity distribution provided")?;
-
-    // 获取覆盖所需bin范围的bin数组账户元数据
-    let bin_arrays_account_meta =
-        position_state.get_bin_array_accounts_meta_coverage_by_chunk(min_bin_id, max_bin_id)?;
-
-    let user_token_x = get_or_create_ata(
-        program,
-        transaction_config,
-        lb_pair_state.token_x_mint,
-        program.payer(),
-        compute_unit_price.clone(),
-    )
-    .await?;
-
-    let user_token_y = get_or_create_ata(
-        program,
-        transaction_config,
-        lb_pair_state.token_y_mint,
-        program.payer(),
-        compute_unit_price.clone(),
-    )
-    .await?;
-
-    let (bin_array_bitmap_extension, _bump) = derive_bin_array_bitmap_extension(lb_pair);
-
-    let bin_array_bitmap_extension = rpc_client
-        .get_account(&bin_array_bitmap_extension)
-        .await
-        .map(|_| bin_array_bitmap_extension)
-        .ok()
-        .or(Some(dlmm::ID));
-
-    let (event_authority, _bump) = derive_event_authority_pda();
-
-    let main_accounts = dlmm::client::accounts::AddLiquidity2 {
-        lb_pair,
-        bin_array_bitmap_extension,
-        position,
-        reserve_x: lb_pair_state.reserve_x,
-        reserve_y: lb_pair_state.reserve_y,
-        token_x_mint: lb_pair_state.token_x_mint,
-        token_y_mint: lb_pair_state.token_y_mint,
-        sender: program.payer(),
-        user_token_x,
-        user_token_y,
-        token_x_program,
-        token_y_program,
-        event_authority,
-        program: dlmm::ID,
+    if build_only {
+        return print_instructions(&instructions);
     }
-    .to_account_metas(None);
 
-    let mut remaining_accounts_info = RemainingAccountsInfo { slices: vec![] };
-    let mut remaining_accounts = vec![];
+    let instructions = [client.compute_budget_instructions()?, instructions].concat();
 
-    if let Some((slices, transfer_hook_remaining_accounts)) =
-        get_potential_token_2022_related_ix_data_and_accounts(
-            &lb_pair_state,
-            program.rpc(),
-            ActionType::Liquidity,
+    let signature = if let Some(lookup_table) = use_lut {
+        send_versioned_transaction_with_lookup_table(
+            &client.program().rpc(),
+            payer,
+            &instructions,
+            lookup_table,
+            client.transaction_config(),
         )
-        .await?
-    {
-        remaining_accounts_info.slices = slices;
-        remaining_accounts.extend(transfer_hook_remaining_accounts);
-    };
-
-    remaining_accounts.extend(bin_arrays_account_meta);
-
-    let data = dlmm::client::args::AddLiquidity2 {
-        liquidity_parameter: LiquidityParameter {
-            amount_x,
-            amount_y,
-            bin_liquidity_dist: bin_liquidity_distribution,
-        },
-        remaining_accounts_info,
-    }
-    .data();
-
-    let accounts = [main_accounts.to_vec(), remaining_accounts].concat();
-
-    let add_liquidity_ix = Instruction {
-        program_id: dlmm::ID,
-        accounts,
-        data,
+        .await
+    } else {
+        client.send(instructions).await
     };
 
-    let compute_budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(1_400_000);
-
-    let request_builder = program.request();
-    let signature = request_builder
-        .instruction(compute_budget_ix)
-        .instruction(add_liquidity_ix)
-        .send_with_spinner_and_config(transaction_config)
-        .await;
-
-    println!("Add Liquidity. Signature: {:#?}", signature);
+    info!(?signature, "Add Liquidity");
 
     signature?;
 
@@ -9,6 +9,10 @@ pub struct CloseClaimFeeOperatorParams {
     /// 该操作员的领取权限将被撤销
     #[clap(long)]
     pub operator: Pubkey,
+    /// 接收回收租金的账户，默认为交易付款人
+    /// 可用于将租金归集到指定的资金账户
+    #[clap(long)]
+    pub rent_receiver: Option<Pubkey>,
 }
 
 /// 执行关闭协议手续费领取操作员操作
@@ -24,6 +28,7 @@ pub struct CloseClaimFeeOperatorParams {
 /// * `params` - 关闭参数，包含要关闭的操作员地址
 /// * `program` - Solana程序客户端，用于执行链上操作
 /// * `transaction_config` - 交易配置，包含确认级别等设置
+/// * `compute_unit_price` - 计算单元价格设置
 /// 
 /// # 返回值
 /// * `Result<()>` - 成功时返回空值，失败时返回错误
@@ -32,14 +37,20 @@ pub struct CloseClaimFeeOperatorParams {
 /// - 只有程序管理员可以执行此操作
 /// - 操作员一旦关闭就无法恢复，需要重新创建
 /// - 关闭后该操作员将无法领取任何协议手续费
-/// - 租金将返还给管理员账户
+/// - 租金默认返还给管理员账户，可通过 `--rent-receiver` 指定其他账户
 pub async fn execute_close_claim_protocol_fee_operator<C: Deref<Target = impl Signer> + Clone>(
     params: CloseClaimFeeOperatorParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
+    external_authority: Option<ExternalAuthority<'_>>,
 ) -> Result<()> {
     // 解构参数，获取要关闭的操作员地址
-    let CloseClaimFeeOperatorParams { operator } = params;
+    let CloseClaimFeeOperatorParams {
+        operator,
+        rent_receiver,
+    } = params;
+    let admin = external_authority.map_or(program.payer(), |external| external.authority);
 
     // 生成协议手续费领取操作员的PDA
     let (claim_fee_operator, _bump) = derive_claim_protocol_fee_operator_pda(operator);
@@ -47,8 +58,8 @@ pub async fn execute_close_claim_protocol_fee_operator<C: Deref<Target = impl Si
     // 构建关闭协议手续费领取操作员指令所需的账户列表
     let accounts = dlmm::client::accounts::CloseClaimProtocolFeeOperator {
         claim_fee_operator,                                         // 要关闭的操作员账户
-        admin: program.payer(),                                     // 管理员账户（交易付款人）
-        rent_receiver: program.payer(),                             // 租金接收者（通常是管理员）
+        admin,                                                      // 管理员账户
+        rent_receiver: rent_receiver.unwrap_or(program.payer()),    // 租金接收者（默认为管理员）
     }
     .to_account_metas(None);
 
@@ -62,14 +73,26 @@ pub async fn execute_close_claim_protocol_fee_operator<C: Deref<Target = impl Si
         data,                                                       // 指令数据
     };
 
+    // 管理员为钱包以外的密钥时，只部分签名并打印交易
+    if let Some(external_authority) = external_authority {
+        return external_authority
+            .print_partially_signed_transaction(
+                &program.rpc(),
+                &compute_unit_price,
+                &[instruction],
+                &[],
+            )
+            .await;
+    }
+
     // 构建并发送交易请求
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
     let signature = request_builder
         .instruction(instruction)                                   // 添加关闭操作员指令
         .send_with_spinner_and_config(transaction_config)          // 发送交易并等待确认
         .await;
 
-    println!("Close claim protocol fee operator. Signature: {signature:#?}");
+    info!(?signature, "Close claim protocol fee operator");
 
     // 检查交易是否成功执行
     signature?;
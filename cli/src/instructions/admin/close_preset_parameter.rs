@@ -9,6 +9,10 @@ pub struct ClosePresetAccountParams {
     /// 预设参数的公钥地址，可以通过ListAllBinStep命令获取
     /// 该参数必须是现有的且没有被使用的预设参数
     pub preset_parameter: Pubkey,
+    /// 接收回收租金的账户，默认为交易付款人
+    /// 可用于将租金归集到指定的资金账户
+    #[clap(long)]
+    pub rent_receiver: Option<Pubkey>,
 }
 
 /// 执行关闭预设参数操作
@@ -19,9 +23,16 @@ pub async fn execute_close_preset_parameter<C: Deref<Target = impl Signer> + Clo
     params: ClosePresetAccountParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
+    external_authority: Option<ExternalAuthority<'_>>,
 ) -> Result<Pubkey> {
     // 解构参数，获取要关闭的预设参数地址
-    let ClosePresetAccountParams { preset_parameter } = params;
+    let ClosePresetAccountParams {
+        preset_parameter,
+        rent_receiver,
+    } = params;
+    let admin = external_authority.map_or(program.payer(), |external| external.authority);
+    let rent_receiver = rent_receiver.unwrap_or(program.payer());
 
     let rpc_client = program.rpc();
     // 获取预设参数账户数据以确定其类型
@@ -34,8 +45,8 @@ pub async fn execute_close_preset_parameter<C: Deref<Target = impl Signer> + Clo
     let instruction = if disc == dlmm::accounts::PresetParameter::DISCRIMINATOR {
         // 处理第一版预设参数
         let accounts = dlmm::client::accounts::ClosePresetParameter {
-            admin: program.payer(),                                 // 管理员账户
-            rent_receiver: program.payer(),                         // 租金接收者
+            admin,                                                  // 管理员账户
+            rent_receiver,                                          // 租金接收者
             preset_parameter,                                       // 要关闭的预设参数
         }
         .to_account_metas(None);
@@ -50,8 +61,8 @@ pub async fn execute_close_preset_parameter<C: Deref<Target = impl Signer> + Clo
     } else if disc == dlmm::accounts::PresetParameter2::DISCRIMINATOR {
         // 处理第二版预设参数
         let accounts = dlmm::client::accounts::ClosePresetParameter2 {
-            admin: program.payer(),                                 // 管理员账户
-            rent_receiver: program.payer(),                         // 租金接收者
+            admin,                                                  // 管理员账户
+            rent_receiver,                                          // 租金接收者
             preset_parameter,                                       // 要关闭的预设参数
         }
         .to_account_metas(None);
@@ -67,17 +78,27 @@ pub async fn execute_close_preset_parameter<C: Deref<Target = impl Signer> + Clo
         bail!("Not a valid preset parameter account");              // 不是有效的预设参数账户
     };
 
+    // 管理员为钱包以外的密钥时，只部分签名并打印交易
+    if let Some(external_authority) = external_authority {
+        external_authority
+            .print_partially_signed_transaction(
+                &program.rpc(),
+                &compute_unit_price,
+                &[instruction],
+                &[],
+            )
+            .await?;
+        return Ok(preset_parameter);
+    }
+
     // 构建并发送交易请求
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
     let signature = request_builder
         .instruction(instruction)                                   // 添加关闭指令
         .send_with_spinner_and_config(transaction_config)          // 发送交易并等待确认
         .await;
 
-    println!(
-        "Close preset parameter {}. Signature: {signature:#?}",
-        preset_parameter
-    );
+    info!(%preset_parameter, ?signature, "Close preset parameter");
 
     // 检查交易是否成功执行
     signature?;
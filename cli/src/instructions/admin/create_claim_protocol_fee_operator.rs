@@ -24,6 +24,7 @@ pub struct CreateClaimFeeOperatorParams {
 /// * `params` - 创建参数，包含新操作员的地址
 /// * `program` - Solana程序客户端，用于执行链上操作
 /// * `transaction_config` - 交易配置，包含确认级别等设置
+/// * `compute_unit_price` - 计算单元价格设置
 /// 
 /// # 返回值
 /// * `Result<()>` - 成功时返回空值，失败时返回错误
@@ -37,9 +38,12 @@ pub async fn execute_create_claim_protocol_fee_operator<C: Deref<Target = impl S
     params: CreateClaimFeeOperatorParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
+    external_authority: Option<ExternalAuthority<'_>>,
 ) -> Result<()> {
     // 解构参数，获取新操作员的地址
     let CreateClaimFeeOperatorParams { operator } = params;
+    let admin = external_authority.map_or(program.payer(), |external| external.authority);
 
     // 生成协议手续费领取操作员的PDA
     let (claim_fee_operator, _bump) = derive_claim_protocol_fee_operator_pda(operator);
@@ -48,7 +52,7 @@ pub async fn execute_create_claim_protocol_fee_operator<C: Deref<Target = impl S
     let accounts = dlmm::client::accounts::CreateClaimProtocolFeeOperator {
         claim_fee_operator,                                         // 新创建的操作员账户
         operator,                                                   // 操作员地址
-        admin: program.payer(),                                     // 管理员账户（交易付款人）
+        admin,                                                      // 管理员账户
         system_program: anchor_lang::system_program::ID,            // 系统程序
     }
     .to_account_metas(None);
@@ -63,14 +67,26 @@ pub async fn execute_create_claim_protocol_fee_operator<C: Deref<Target = impl S
         data,                                                       // 指令数据
     };
 
+    // 管理员为钱包以外的密钥时，只部分签名并打印交易
+    if let Some(external_authority) = external_authority {
+        return external_authority
+            .print_partially_signed_transaction(
+                &program.rpc(),
+                &compute_unit_price,
+                &[instruction],
+                &[],
+            )
+            .await;
+    }
+
     // 构建并发送交易请求
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
     let signature = request_builder
         .instruction(instruction)                                   // 添加创建操作员指令
         .send_with_spinner_and_config(transaction_config)          // 发送交易并等待确认
         .await;
 
-    println!("Create claim protocol fee operator. Signature: {signature:#?}");
+    info!(?signature, "Create claim protocol fee operator");
 
     // 检查交易是否成功执行
     signature?;
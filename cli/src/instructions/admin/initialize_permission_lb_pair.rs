@@ -1,8 +1,6 @@
 use std::sync::Arc;
 
 use crate::*;
-use anchor_lang::AccountDeserialize;
-use anchor_spl::token_interface::Mint;
 use commons::dlmm::types::{InitPermissionPairIx, Rounding};
 
 /// 初始化权限流动性池对参数结构体
@@ -28,9 +26,14 @@ pub struct InitPermissionLbPairParameters {
     /// 基础手续费率（以基点为单位）
     /// 1基点 = 0.01%，用于计算交易手续费
     pub base_fee_bps: u16,
-    /// 激活类型
-    /// 决定池对的激活方式和权限控制级别
-    pub activation_type: u8,
+    /// 激活类型，决定激活点及预激活时长按slot还是时间戳计算
+    /// Activation type, decides whether the activation point and pre-activation duration are slots or timestamps
+    #[clap(long, value_enum)]
+    pub activation_type: ActivationTypeArg,
+    /// 活跃箱子对应的实际初始价格相对初始价格的最大偏差（基点）
+    /// 超过时不创建池对，用于发现配置错误的发行
+    #[clap(long)]
+    pub max_price_deviation_bps: Option<u16>,
 }
 
 /// 执行初始化权限流动性池对操作
@@ -42,6 +45,7 @@ pub struct InitPermissionLbPairParameters {
 /// * `params` - 初始化权限池对所需的参数
 /// * `program` - Solana程序客户端，用于执行链上操作
 /// * `transaction_config` - 交易配置，包含确认级别等设置
+/// * `compute_unit_price` - 计算单元价格设置
 /// 
 /// # 返回值
 /// * `Result<Pubkey>` - 成功时返回创建的池对地址，失败时返回错误
@@ -54,6 +58,8 @@ pub async fn execute_initialize_permission_lb_pair<C: Deref<Target = impl Signer
     params: InitPermissionLbPairParameters,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
+    external_authority: Option<ExternalAuthority<'_>>,
 ) -> Result<Pubkey> {
     // 解构参数结构体，获取所有必要的配置参数
     let InitPermissionLbPairParameters {
@@ -64,7 +70,11 @@ pub async fn execute_initialize_permission_lb_pair<C: Deref<Target = impl Signer
         base_keypair_path,
         base_fee_bps,
         activation_type,
+        max_price_deviation_bps,
     } = params;
+    let admin = external_authority.map_or(program.payer(), |external| external.authority);
+
+    validate_mint_pair(token_mint_x, token_mint_y)?;
 
     // 读取基础密钥对文件，这个密钥对用于生成池对地址和权限控制
     // 只有拥有此密钥对的管理员才能管理该池对
@@ -74,17 +84,10 @@ pub async fn execute_initialize_permission_lb_pair<C: Deref<Target = impl Signer
     let rpc_client = program.rpc();
 
     // 批量获取代币铸造账户信息，验证代币是否存在且有效
-    let mut accounts = rpc_client
-        .get_multiple_accounts(&[token_mint_x, token_mint_y])
-        .await?;
-
-    // 获取并验证基础代币和报价代币的账户数据
-    let token_mint_base_account = accounts[0].take().context("token_mint_base not found")?;
-    let token_mint_quote_account = accounts[1].take().context("token_mint_quote not found")?;
-
-    // 反序列化代币铸造账户数据，获取代币的精度等重要信息
-    let token_mint_base = Mint::try_deserialize(&mut token_mint_base_account.data.as_ref())?;
-    let token_mint_quote = Mint::try_deserialize(&mut token_mint_quote_account.data.as_ref())?;
+    let mut mint_cache = MintCache::new();
+    mint_cache.fetch(&rpc_client, &[token_mint_x, token_mint_y]).await?;
+    let token_mint_base = mint_cache.get_cached(&token_mint_x)?;
+    let token_mint_quote = mint_cache.get_cached(&token_mint_y)?;
 
     // 将初始价格转换为以lamport为单位的价格
     // 考虑两个代币的小数位数差异，确保价格计算的准确性
@@ -100,6 +103,15 @@ pub async fn execute_initialize_permission_lb_pair<C: Deref<Target = impl Signer
     let computed_active_id = get_id_from_price(bin_step, &price_per_lamport, Rounding::Up)
         .context("get_id_from_price overflow")?;
 
+    check_initial_price(
+        initial_price,
+        computed_active_id,
+        bin_step,
+        token_mint_base.decimals,
+        token_mint_quote.decimals,
+        max_price_deviation_bps,
+    )?;
+
     // 生成权限流动性池对的程序衍生地址(PDA)
     // 使用基础密钥对、代币铸造地址和箱子步长作为种子
     let (lb_pair, _bump) =
@@ -152,10 +164,10 @@ pub async fn execute_initialize_permission_lb_pair<C: Deref<Target = impl Signer
         token_mint_y,                                               // 代币Y铸造地址
         token_badge_x,                                              // 代币X徽章（如果存在）
         token_badge_y,                                              // 代币Y徽章（如果存在）
-        token_program_x: token_mint_base_account.owner,             // 代币X的程序ID
-        token_program_y: token_mint_quote_account.owner,            // 代币Y的程序ID
+        token_program_x: token_mint_base.program_owner,             // 代币X的程序ID
+        token_program_y: token_mint_quote.program_owner,            // 代币Y的程序ID
         oracle,                                                     // 预言机账户
-        admin: program.payer(),                                     // 管理员账户（交易付款人）
+        admin,                                                      // 管理员账户
         rent: solana_sdk::sysvar::rent::ID,                        // 租金系统变量
         system_program: solana_sdk::system_program::ID,            // 系统程序
         event_authority,                                            // 事件权限账户
@@ -175,7 +187,7 @@ pub async fn execute_initialize_permission_lb_pair<C: Deref<Target = impl Signer
             active_id: computed_active_id,                          // 计算得出的活跃箱子ID
             bin_step,                                               // 箱子步长
             base_factor,                                            // 基础因子
-            activation_type,                                        // 激活类型
+            activation_type: activation_type.into(),                // 激活类型
             base_fee_power_factor,                                  // 基础手续费幂因子
             protocol_share: ILM_PROTOCOL_SHARE,                     // 协议分成比例
         },
@@ -189,15 +201,28 @@ pub async fn execute_initialize_permission_lb_pair<C: Deref<Target = impl Signer
         data,
     };
 
+    // 管理员为钱包以外的密钥时，只部分签名并打印交易
+    if let Some(external_authority) = external_authority {
+        external_authority
+            .print_partially_signed_transaction(
+                &program.rpc(),
+                &compute_unit_price,
+                &[init_pair_ix],
+                &[base_keypair.as_ref()],
+            )
+            .await?;
+        return Ok(lb_pair);
+    }
+
     // 构建并发送交易请求
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
     let signature = request_builder
         .instruction(init_pair_ix)                                  // 添加初始化指令
         .signer(base_keypair)                                       // 添加基础密钥对签名
         .send_with_spinner_and_config(transaction_config)          // 发送交易并等待确认
         .await;
 
-    println!("Initialize Permission LB pair {lb_pair}. Signature: {signature:#?}");
+    info!(%lb_pair, ?signature, "Initialize Permission LB pair");
 
     // 检查交易是否成功执行
     signature?;
@@ -54,6 +54,7 @@ pub struct InitPresetParameters {
 /// * `params` - 预设参数配置，包含所有费用和特性设置
 /// * `program` - Solana程序客户端，用于执行链上操作
 /// * `transaction_config` - 交易配置，包含确认级别等设置
+/// * `compute_unit_price` - 计算单元价格设置
 /// 
 /// # 返回值
 /// * `Result<Pubkey>` - 成功时返回创建的预设参数地址，失败时返回错误
@@ -67,6 +68,8 @@ pub async fn execute_initialize_preset_parameter<C: Deref<Target = impl Signer>
     params: InitPresetParameters,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
+    external_authority: Option<ExternalAuthority<'_>>,
 ) -> Result<Pubkey> {
     // 解构预设参数配置，获取所有必要的费用和特性设置
     let InitPresetParameters {
@@ -80,6 +83,7 @@ pub async fn execute_initialize_preset_parameter<C: Deref<Target = impl Signer>
         variable_fee_control,
         base_fee_power_factor,
     } = params;
+    let admin = external_authority.map_or(program.payer(), |external| external.authority);
 
     let rpc_client = program.rpc();
 
@@ -117,7 +121,7 @@ pub async fn execute_initialize_preset_parameter<C: Deref<Target = impl Signer>
     // 构建初始化预设参数指令所需的账户列表
     let accounts = dlmm::client::accounts::InitializePresetParameter2 {
         preset_parameter,                                           // 新创建的预设参数账户
-        admin: program.payer(),                                     // 管理员账户（交易付款人）
+        admin,                                                      // 管理员账户
         system_program: solana_sdk::system_program::ID,            // 系统程序
     }
     .to_account_metas(None);
@@ -146,17 +150,27 @@ pub async fn execute_initialize_preset_parameter<C: Deref<Target = impl Signer>
         data,                                                       // 指令数据
     };
 
+    // 管理员为钱包以外的密钥时，只部分签名并打印交易
+    if let Some(external_authority) = external_authority {
+        external_authority
+            .print_partially_signed_transaction(
+                &program.rpc(),
+                &compute_unit_price,
+                &[init_preset_param_ix],
+                &[],
+            )
+            .await?;
+        return Ok(preset_parameter);
+    }
+
     // 构建并发送交易请求
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
     let signature = request_builder
         .instruction(init_preset_param_ix)                          // 添加初始化指令
         .send_with_spinner_and_config(transaction_config)          // 发送交易并等待确认
         .await;
 
-    println!(
-        "Initialize preset parameter {}. Signature: {signature:#?}",
-        preset_parameter
-    );
+    info!(%preset_parameter, ?signature, "Initialize preset parameter");
 
     // 检查交易是否成功执行
     signature?;
@@ -14,8 +14,10 @@ pub struct InitializeRewardParams {
     /// 奖励索引
     /// 用于区分同一池对中的不同奖励代币，通常从0开始
     pub reward_index: u64,
-    /// 奖励持续时间（以秒为单位）
+    /// 奖励持续时间，秒数或带单位的时长（如 `30d`、`12h`、`1w`）
     /// 决定了奖励的分发期限，超过该时间后将停止发放
+    /// Reward duration, in seconds or with units (e.g. `30d`, `12h`, `1w`)
+    #[clap(value_parser = parse_reward_duration)]
     pub reward_duration: u64,
     /// 奖励资助者的地址
     /// 只有该地址可以为奖励系统追加资金
@@ -35,6 +37,7 @@ pub struct InitializeRewardParams {
 /// * `params` - 奖励系统初始化参数
 /// * `program` - Solana程序客户端，用于执行链上操作
 /// * `transaction_config` - 交易配置，包含确认级别等设置
+/// * `compute_unit_price` - 计算单元价格设置
 /// 
 /// # 返回值
 /// * `Result<()>` - 成功时返回空值，失败时返回错误
@@ -48,6 +51,8 @@ pub async fn execute_initialize_reward<C: Deref<Target = impl Signer> + Clone>(
     params: InitializeRewardParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
+    external_authority: Option<ExternalAuthority<'_>>,
 ) -> Result<()> {
     // 解构初始化奖励参数
     let InitializeRewardParams {
@@ -57,6 +62,12 @@ pub async fn execute_initialize_reward<C: Deref<Target = impl Signer> + Clone>(
         reward_duration,
         funder,
     } = params;
+    let admin = external_authority.map_or(program.payer(), |external| external.authority);
+
+    validate_reward_index(reward_index)?;
+
+    // 发送前显示解析得到的秒数，便于核对
+    println!("Reward duration: {} seconds", reward_duration);
 
     // 生成奖励金库的PDA，用于存放奖励代币
     // 每个奖励索引都有对应的独立金库
@@ -73,11 +84,8 @@ pub async fn execute_initialize_reward<C: Deref<Target = impl Signer> + Clone>(
     let (token_badge, _bump) = derive_token_badge_pda(reward_mint);
     // 检查代币徽章是否存在，如果不存在则使用程序ID作为默认值
     // 这允许同时支持带有特殊标识的代币和普通代币
-    let token_badge = rpc_client
-        .get_account(&token_badge)
-        .await
-        .ok()
-        .map(|_| token_badge)
+    let token_badge = resolve_optional_account(&rpc_client, token_badge)
+        .await?
         .or(Some(dlmm::ID));
 
     // 构建初始化奖励系统所需的账户列表
@@ -85,7 +93,7 @@ pub async fn execute_initialize_reward<C: Deref<Target = impl Signer> + Clone>(
         lb_pair,                                                    // 流动性池对账户
         reward_vault,                                               // 奖励金库账户
         reward_mint,                                                // 奖励代币铸造地址
-        admin: program.payer(),                                     // 管理员账户（交易付款人）
+        admin,                                                      // 管理员账户
         token_program: reward_mint_account.owner,                   // 奖励代币的程序ID
         token_badge,                                                // 奖励代币徽章（如果存在）
         rent: solana_sdk::sysvar::rent::ID,                        // 租金系统变量
@@ -110,14 +118,26 @@ pub async fn execute_initialize_reward<C: Deref<Target = impl Signer> + Clone>(
         data,                                                       // 指令数据
     };
 
+    // 管理员为钱包以外的密钥时，只部分签名并打印交易
+    if let Some(external_authority) = external_authority {
+        return external_authority
+            .print_partially_signed_transaction(
+                &program.rpc(),
+                &compute_unit_price,
+                &[instruction],
+                &[],
+            )
+            .await;
+    }
+
     // 构建并发送交易请求
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
     let signature = request_builder
         .instruction(instruction)                                   // 添加初始化奖励指令
         .send_with_spinner_and_config(transaction_config)          // 发送交易并等待确认
         .await;
 
-    println!("Initialize reward. Signature: {signature:#?}");
+    info!(?signature, "Initialize reward");
 
     // 检查交易是否成功执行
     signature?;
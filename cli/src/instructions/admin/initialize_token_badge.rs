@@ -24,6 +24,7 @@ pub struct InitializeTokenBadgeParams {
 /// * `params` - 初始化参数，包含代币铸造地址
 /// * `program` - Solana程序客户端，用于执行链上操作
 /// * `transaction_config` - 交易配置，包含确认级别等设置
+/// * `compute_unit_price` - 计算单元价格设置
 /// 
 /// # 返回值
 /// * `Result<()>` - 成功时返回空值，失败时返回错误
@@ -37,16 +38,19 @@ pub async fn execute_initialize_token_badge<C: Deref<Target = impl Signer> + Clo
     params: InitializeTokenBadgeParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
+    external_authority: Option<ExternalAuthority<'_>>,
 ) -> Result<()> {
     // 解构参数，获取代币铸造地址
     let InitializeTokenBadgeParams { mint } = params;
+    let admin = external_authority.map_or(program.payer(), |external| external.authority);
 
     // 生成代币徽章的PDA
     let (token_badge, _bump) = derive_token_badge_pda(mint);
 
     // 构建初始化代币徽章指令所需的账户列表
     let accounts = dlmm::client::accounts::InitializeTokenBadge {
-        admin: program.payer(),                                     // 管理员账户（交易付款人）
+        admin,                                                      // 管理员账户
         token_mint: mint,                                           // 代币铸造地址
         system_program: system_program::ID,                        // 系统程序
         token_badge,                                                // 新创建的代币徽章账户
@@ -63,14 +67,26 @@ pub async fn execute_initialize_token_badge<C: Deref<Target = impl Signer> + Clo
         data,                                                       // 指令数据
     };
 
+    // 管理员为钱包以外的密钥时，只部分签名并打印交易
+    if let Some(external_authority) = external_authority {
+        return external_authority
+            .print_partially_signed_transaction(
+                &program.rpc(),
+                &compute_unit_price,
+                &[instruction],
+                &[],
+            )
+            .await;
+    }
+
     // 构建并发送交易请求
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
     let signature = request_builder
         .instruction(instruction)                                   // 添加初始化徽章指令
         .send_with_spinner_and_config(transaction_config)          // 发送交易并等待确认
         .await;
 
-    println!("Initialize token badge {}. Signature: {signature:#?}", mint);
+    info!(%mint, ?signature, "Initialize token badge");
 
     // 检查交易是否成功执行
     signature?;
@@ -26,6 +26,7 @@ pub struct SetActivationPointParam {
 /// * `params` - 包含池对地址和激活点的参数
 /// * `program` - Solana程序客户端，用于执行链上操作
 /// * `transaction_config` - 交易配置，包含确认级别等设置
+/// * `compute_unit_price` - 计算单元价格设置
 /// 
 /// # 返回值
 /// * `Result<()>` - 成功时返回空值，失败时返回错误
@@ -39,17 +40,20 @@ pub async fn execute_set_activation_point<C: Deref<Target = impl Signer> + Clone
     params: SetActivationPointParam,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
+    external_authority: Option<ExternalAuthority<'_>>,
 ) -> Result<()> {
     // 解构参数，获取池对地址和激活点
     let SetActivationPointParam {
         lb_pair,
         activation_point,
     } = params;
+    let admin = external_authority.map_or(program.payer(), |external| external.authority);
 
     // 构建设置激活点指令所需的账户列表
     // 只需要管理员账户和目标池对账户
     let accounts = dlmm::client::accounts::SetActivationPoint {
-        admin: program.payer(),                                     // 管理员账户（必须是交易付款人）
+        admin,                                                      // 管理员账户
         lb_pair,                                                    // 目标流动性池对账户
     }
     .to_account_metas(None);
@@ -66,14 +70,26 @@ pub async fn execute_set_activation_point<C: Deref<Target = impl Signer> + Clone
         program_id: dlmm::ID,                                       // DLMM程序ID
     };
 
+    // 管理员为钱包以外的密钥时，只部分签名并打印交易
+    if let Some(external_authority) = external_authority {
+        return external_authority
+            .print_partially_signed_transaction(
+                &program.rpc(),
+                &compute_unit_price,
+                &[set_activation_point_ix],
+                &[],
+            )
+            .await;
+    }
+
     // 构建并发送交易请求
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
     let signature = request_builder
         .instruction(set_activation_point_ix)                       // 添加设置激活点指令
         .send_with_spinner_and_config(transaction_config)          // 发送交易并等待确认
         .await;
 
-    println!("Set activation point. Signature: {:#?}", signature);
+    info!(?signature, "Set activation point");
 
     // 检查交易是否成功执行
     signature?;
@@ -25,6 +25,7 @@ pub async fn execute_set_pre_activation_duration<C: Deref<Target = impl Signer>
     params: SetPreactivationDurationParam,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<()> {
     // 解构参数
     let SetPreactivationDurationParam {
@@ -53,13 +54,13 @@ pub async fn execute_set_pre_activation_duration<C: Deref<Target = impl Signer>
     };
 
     // 构建并发送交易请求
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
     let signature = request_builder
         .instruction(set_pre_activation_slot_duration_ix)           // 添加设置指令
         .send_with_spinner_and_config(transaction_config)          // 发送交易并等待确认
         .await;
 
-    println!("Set pre activation duration. Signature: {:#?}", signature);
+    info!(?signature, "Set pre activation duration");
 
     // 检查交易是否成功执行
     signature?;
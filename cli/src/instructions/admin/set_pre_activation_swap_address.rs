@@ -18,6 +18,7 @@ pub async fn execute_set_pre_activation_swap_address<C: Deref<Target = impl Sign
     params: SetPreactivationSwapAddressParam,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<()> {
     // 解构参数
     let SetPreactivationSwapAddressParam {
@@ -46,16 +47,13 @@ pub async fn execute_set_pre_activation_swap_address<C: Deref<Target = impl Sign
     };
 
     // 构建并发送交易请求
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
     let signature = request_builder
         .instruction(set_pre_activation_swap_address_ix)            // 添加设置指令
         .send_with_spinner_and_config(transaction_config)          // 发送交易并等待确认
         .await;
 
-    println!(
-        "Set pre activation swap address. Signature: {:#?}",
-        signature
-    );
+    info!(?signature, "Set pre activation swap address");
 
     // 检查交易是否成功执行
     signature?;
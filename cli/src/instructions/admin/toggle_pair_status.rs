@@ -8,9 +8,10 @@ pub struct SetPairStatusParams {
     /// 流动性池对的地址
     /// 必须是有效的且已初始化的池对地址
     pub lb_pair: Pubkey,
-    /// 池对状态：0 表示启用，1 表示禁用
+    /// 池对状态：enabled 表示启用，disabled 表示禁用
     /// 启用状态允许所有正常交易操作，禁用状态会阻止新的交易
-    pub pair_status: u8,
+    #[clap(value_enum)]
+    pub pair_status: PairStatusArg,
 }
 
 /// 执行设置流动性池对状态操作
@@ -25,6 +26,7 @@ pub struct SetPairStatusParams {
 /// * `params` - 包含池对地址和目标状态的参数
 /// * `program` - Solana程序客户端，用于执行链上操作
 /// * `transaction_config` - 交易配置，包含确认级别等设置
+/// * `compute_unit_price` - 计算单元价格设置
 /// 
 /// # 返回值
 /// * `Result<()>` - 成功时返回空值，失败时返回错误
@@ -38,24 +40,30 @@ pub async fn execute_set_pair_status<C: Deref<Target = impl Signer> + Clone>(
     params: SetPairStatusParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
+    external_authority: Option<ExternalAuthority<'_>>,
 ) -> Result<()> {
     // 解构参数，获取池对地址和目标状态
     let SetPairStatusParams {
         lb_pair,
         pair_status,
     } = params;
+    let admin = external_authority.map_or(program.payer(), |external| external.authority);
+
+    // 打印当前状态及目标状态，便于确认状态变更方向
+    print_pair_status_change(&program.rpc(), lb_pair, pair_status).await?;
 
     // 构建设置池对状态指令所需的账户列表
     // 只需要管理员账户和目标池对账户
     let accounts = dlmm::client::accounts::SetPairStatus {
-        admin: program.payer(),                                     // 管理员账户（必须是交易付款人）
+        admin,                                                      // 管理员账户
         lb_pair,                                                    // 目标流动性池对账户
     }
     .to_account_metas(None);
 
     // 构建指令数据，包含新的状态值
     let data = dlmm::client::args::SetPairStatus {
-        status: pair_status,                                        // 新的池对状态（0启用，1禁用）
+        status: pair_status.into(),                                 // 新的池对状态（0启用，1禁用）
     }
     .data();
 
@@ -66,14 +74,26 @@ pub async fn execute_set_pair_status<C: Deref<Target = impl Signer> + Clone>(
         data,                                                       // 指令数据
     };
 
+    // 管理员为钱包以外的密钥时，只部分签名并打印交易
+    if let Some(external_authority) = external_authority {
+        return external_authority
+            .print_partially_signed_transaction(
+                &program.rpc(),
+                &compute_unit_price,
+                &[instruction],
+                &[],
+            )
+            .await;
+    }
+
     // 构建并发送交易请求
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
     let signature = request_builder
         .instruction(instruction)                                   // 添加设置状态指令
         .send_with_spinner_and_config(transaction_config)          // 发送交易并等待确认
         .await;
 
-    println!("Set pair status. Signature: {:#?}", signature);
+    info!(?signature, "Set pair status");
 
     // 检查交易是否成功执行
     signature?;
@@ -10,7 +10,17 @@ pub struct UpdateBaseFeeParams {
     pub lb_pair: Pubkey,
     /// 新的基础手续费率（以基点为单位）
     /// 1基点 = 0.01%，用于设置新的基础手续费水平
-    pub base_fee_bps: u16,
+    #[clap(required_unless_present = "base_factor")]
+    pub base_fee_bps: Option<u16>,
+    /// 高级模式：直接设置链上的基础因子，不从基点推导。适用于基点无法精确表示的bin步长
+    /// Advanced mode: set the on-chain base factor directly instead of deriving it from the bps. For bin steps
+    /// where the target bps is not exactly representable
+    #[clap(long, conflicts_with = "base_fee_bps")]
+    pub base_factor: Option<u16>,
+    /// 高级模式：直接设置链上的基础手续费幂因子，默认为0
+    /// Advanced mode: set the on-chain base fee power factor directly. Defaults to 0
+    #[clap(long, requires = "base_factor")]
+    pub base_fee_power_factor: Option<u8>,
 }
 
 /// 执行更新基础手续费操作
@@ -21,12 +31,17 @@ pub async fn execute_update_base_fee<C: Deref<Target = impl Signer> + Clone>(
     params: UpdateBaseFeeParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
+    external_authority: Option<ExternalAuthority<'_>>,
 ) -> Result<()> {
     // 解构参数
     let UpdateBaseFeeParams {
         lb_pair,
         base_fee_bps,
+        base_factor,
+        base_fee_power_factor,
     } = params;
+    let admin = external_authority.map_or(program.payer(), |external| external.authority);
 
     let rpc_client = program.rpc();
 
@@ -34,11 +49,23 @@ pub async fn execute_update_base_fee<C: Deref<Target = impl Signer> + Clone>(
     let pair_account = rpc_client.get_account(&lb_pair).await?;
 
     // 反序列化池对状态数据，获取当前的参数设置
-    let lb_pair_state = LbPair::try_deserialize(&mut pair_account.data.as_ref())?;
+    let lb_pair_state: LbPair = bytemuck::pod_read_unaligned(&pair_account.data[8..]);
 
-    // 根据新的基础手续费率计算相应的基础因子和幂因子
-    let (base_factor, base_fee_power_factor) =
-        compute_base_factor_from_fee_bps(lb_pair_state.bin_step, base_fee_bps)?;
+    // 直接使用指定的基础因子和幂因子，或根据新的基础手续费率计算
+    let (base_factor, base_fee_power_factor) = match (base_factor, base_fee_bps) {
+        (Some(base_factor), _) => (base_factor, base_fee_power_factor.unwrap_or_default()),
+        (None, Some(base_fee_bps)) => {
+            compute_base_factor_from_fee_bps(lb_pair_state.bin_step, base_fee_bps)?
+        }
+        (None, None) => bail!("Either base_fee_bps or base_factor is required"),
+    };
+
+    info!(
+        base_factor,
+        base_fee_power_factor,
+        bin_step = lb_pair_state.bin_step,
+        "Base fee parameters"
+    );
 
     // 构建更新基础手续费参数指令的数据
     let ix_data = dlmm::client::args::UpdateBaseFeeParameters {
@@ -56,7 +83,7 @@ pub async fn execute_update_base_fee<C: Deref<Target = impl Signer> + Clone>(
     // 构建更新基础手续费参数指令所需的账户列表
     let accounts = dlmm::client::accounts::UpdateBaseFeeParameters {
         lb_pair,                                                    // 流动性池对账户
-        admin: program.payer(),                                     // 管理员账户
+        admin,                                                      // 管理员账户
         event_authority,                                            // 事件权限账户
         program: dlmm::ID,                                          // DLMM程序ID
     }
@@ -69,14 +96,21 @@ pub async fn execute_update_base_fee<C: Deref<Target = impl Signer> + Clone>(
         accounts: accounts.to_vec(),                                // 账户列表
     };
 
+    // 管理员为钱包以外的密钥时，只部分签名并打印交易
+    if let Some(external_authority) = external_authority {
+        return external_authority
+            .print_partially_signed_transaction(&program.rpc(), &compute_unit_price, &[ix], &[])
+            .await;
+    }
+
     // 构建并发送交易请求
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
     let signature = request_builder
         .instruction(ix)                                            // 添加更新指令
         .send_with_spinner_and_config(transaction_config)          // 发送交易并等待确认
         .await;
 
-    println!("Update base fee. Signature: {:#?}", signature);
+    info!(?signature, "Update base fee");
 
     // 检查交易是否成功执行
     signature?;
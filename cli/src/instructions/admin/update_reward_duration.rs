@@ -1,5 +1,14 @@
 use crate::*;
-use commons::dlmm::accounts::{BinArray, LbPair};
+use commons::dlmm::accounts::LbPair;
+use solana_sdk::sysvar::clock::Clock;
+
+/// 未指定时允许的奖励速率最大变化倍数
+/// Default maximum reward rate change factor allowed without confirmation
+const DEFAULT_MAX_RATE_CHANGE_FACTOR: f64 = 2.0;
+
+/// 每秒奖励低于1个最小单位时视为过小，舍入会吞掉大部分奖励
+/// A reward rate below one smallest token unit per second is tiny, the rounding swallows most of the rewards
+const MIN_REWARD_RATE_PER_SECOND: u64 = 1;
 
 /// 更新奖励持续时间的参数结构体
 /// 该功能允许管理员调整奖励系统的分发周期
@@ -12,9 +21,19 @@ pub struct UpdateRewardDurationParams {
     /// 奖励索引
     /// 指定要更新的奖励系统索引
     pub reward_index: u64,
-    /// 新的奖励持续时间（以秒为单位）
+    /// 新的奖励持续时间，秒数或带单位的时长（如 `30d`、`12h`、`1w`）
     /// 决定了奖励将在多长时间内分发给流动性提供者
+    /// New reward duration, in seconds or with units (e.g. `30d`, `12h`, `1w`)
+    #[clap(value_parser = parse_reward_duration)]
     pub reward_duration: u64,
+    /// 新旧奖励速率之间允许的最大变化倍数，超过时需要`--yes`确认
+    /// Maximum factor between the current and new reward rate before `--yes` is required
+    #[clap(long, default_value_t = DEFAULT_MAX_RATE_CHANGE_FACTOR)]
+    pub max_rate_change_factor: f64,
+    /// 确认奖励速率的变化、奖励金库不足或奖励速率过小并继续执行
+    /// Confirm the reward rate change, an insufficient reward vault or a tiny reward rate and proceed
+    #[clap(long)]
+    pub yes: bool,
 }
 
 /// 执行更新奖励持续时间操作
@@ -30,6 +49,7 @@ pub struct UpdateRewardDurationParams {
 /// * `params` - 更新参数，包括池对、奖励索引和新持续时间
 /// * `program` - Solana程序客户端，用于执行链上操作
 /// * `transaction_config` - 交易配置，包含确认级别等设置
+/// * `compute_unit_price` - 计算单元价格设置
 /// 
 /// # 返回值
 /// * `Result<()>` - 成功时返回空值，失败时返回错误
@@ -43,14 +63,23 @@ pub async fn execute_update_reward_duration<C: Deref<Target = impl Signer> + Clo
     params: UpdateRewardDurationParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<()> {
     // 解构更新奖励持续时间参数
     let UpdateRewardDurationParams {
         lb_pair,
         reward_index,
         reward_duration,
+        max_rate_change_factor,
+        yes,
     } = params;
 
+    ensure!(reward_duration > 0, "Reward duration must be greater than 0");
+    ensure!(
+        max_rate_change_factor >= 1.0,
+        "Max rate change factor must be at least 1.0"
+    );
+
     let rpc_client = program.rpc();
     // 获取并反序列化流动性池对状态数据
     // 需要这些数据来获取活跃箱子ID和其他相关信息
@@ -60,10 +89,107 @@ pub async fn execute_update_reward_duration<C: Deref<Target = impl Signer> + Clo
         })
         .await?;
 
-    // 计算当前活跃箱子数组的索引和地址
+    let reward_info = get_initialized_reward_info(&lb_pair_state, reward_index)?;
+
+    let clock_account = rpc_client
+        .get_account(&solana_sdk::sysvar::clock::ID)
+        .await?;
+    let clock: Clock = bincode::deserialize(clock_account.data.as_ref())?;
+    let current_time = clock.unix_timestamp as u64;
+
+    // 计算尚未分发的奖励余额
+    // 奖励仍在分发中时，剩余余额将按新的持续时间重新分配；否则按相同的资助金额预估下一期的速率
+    let remaining_seconds = reward_info.reward_duration_end.saturating_sub(current_time);
+    let undistributed_amount = reward_info
+        .reward_rate
+        .checked_mul(remaining_seconds.into())
+        .context("overflow")?
+        >> SCALE_OFFSET;
+
+    let new_reward_rate = if undistributed_amount > 0 {
+        (undistributed_amount << SCALE_OFFSET) / u128::from(reward_duration)
+    } else {
+        reward_info
+            .reward_rate
+            .checked_mul(reward_info.reward_duration.into())
+            .context("overflow")?
+            / u128::from(reward_duration)
+    };
+
+    let current_rate_per_second =
+        reward_rate_per_second(reward_info.reward_rate).context("overflow")?;
+    let new_rate_per_second = reward_rate_per_second(new_reward_rate).context("overflow")?;
+
+    println!("Reward mint: {}", reward_info.mint);
+    println!(
+        "Reward duration: {} -> {} seconds",
+        reward_info.reward_duration, reward_duration
+    );
+    println!("Reward duration end: {}", reward_info.reward_duration_end);
+    println!(
+        "Undistributed amount: {} ({} seconds remaining)",
+        undistributed_amount, remaining_seconds
+    );
+    println!(
+        "Reward rate: {} -> {} per second",
+        current_rate_per_second, new_rate_per_second
+    );
+
+    // 奖励金库余额需覆盖新持续时间内按新速率发放的奖励。余额还包含尚未领取的奖励，因此只是上限
+    // The reward vault balance must cover the rewards emitted at the new rate over the new duration. The balance
+    // also holds the unclaimed rewards, so it is only an upper bound
+    let vault_balance = get_token_balance_or_zero(&rpc_client, reward_info.vault).await?;
+    let required_amount = new_reward_rate
+        .checked_mul(reward_duration.into())
+        .context("overflow")?
+        >> SCALE_OFFSET;
+    println!(
+        "Reward vault balance: {} (required for the new duration: {})",
+        vault_balance, required_amount
+    );
+
+    if required_amount > u128::from(vault_balance) {
+        let exhausted_after_seconds = (u128::from(vault_balance) << SCALE_OFFSET) / new_reward_rate;
+        ensure!(
+            yes,
+            "Reward vault balance {} would be exhausted after about {} of the {} seconds. Use --yes to proceed",
+            vault_balance,
+            exhausted_after_seconds,
+            reward_duration
+        );
+    }
+
+    // 尚未资助的奖励没有速率可被稀释
+    // A reward which was never funded has no rate to dilute
+    ensure!(
+        yes || reward_info.reward_rate == 0
+            || new_rate_per_second >= MIN_REWARD_RATE_PER_SECOND.into(),
+        "Reward rate {} per second is below {} token unit per second. Use --yes to proceed",
+        new_rate_per_second,
+        MIN_REWARD_RATE_PER_SECOND
+    );
+
+    if reward_info.reward_rate > 0 && new_reward_rate > 0 {
+        let (larger, smaller) = if new_reward_rate > reward_info.reward_rate {
+            (new_reward_rate, reward_info.reward_rate)
+        } else {
+            (reward_info.reward_rate, new_reward_rate)
+        };
+        let change_factor = larger as f64 / smaller as f64;
+
+        ensure!(
+            yes || change_factor <= max_rate_change_factor,
+            "Reward rate changes by {:.2}x which exceeds the max rate change factor {}. Use --yes to proceed",
+            change_factor,
+            max_rate_change_factor
+        );
+    }
+
+    // 获取当前活跃箱子数组的地址，未初始化时先初始化
     // 更新奖励持续时间需要访问活跃箱子数组来更新奖励信息
-    let active_bin_array_idx = BinArray::bin_id_to_bin_array_index(lb_pair_state.active_id)?;
-    let (bin_array, _bump) = derive_bin_array_pda(lb_pair, active_bin_array_idx as i64);
+    let (bin_array, init_bin_array_ix) =
+        get_active_bin_array_with_init_ix(&rpc_client, lb_pair, &lb_pair_state, program.payer())
+            .await?;
 
     // 生成事件权限账户PDA，用于记录奖励更新事件
     let (event_authority, _bump) = derive_event_authority_pda();
@@ -93,13 +219,16 @@ pub async fn execute_update_reward_duration<C: Deref<Target = impl Signer> + Clo
     };
 
     // 构建并发送交易请求
-    let request_builder = program.request();
+    let mut request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
+    if let Some(init_bin_array_ix) = init_bin_array_ix {
+        request_builder = request_builder.instruction(init_bin_array_ix);
+    }
     let signature = request_builder
         .instruction(ix)                                            // 添加更新持续时间指令
         .send_with_spinner_and_config(transaction_config)          // 发送交易并等待确认
         .await;
 
-    println!("Update reward duration. Signature: {:#?}", signature);
+    info!(?signature, "Update reward duration");
 
     // 检查交易是否成功执行
     signature?;
@@ -29,6 +29,7 @@ pub struct UpdateRewardFunderParams {
 /// * `params` - 更新参数，包括池对、奖励索引和新资助者地址
 /// * `program` - Solana程序客户端，用于执行链上操作
 /// * `transaction_config` - 交易配置，包含确认级别等设置
+/// * `compute_unit_price` - 计算单元价格设置
 /// 
 /// # 返回值
 /// * `Result<()>` - 成功时返回空值，失败时返回错误
@@ -43,6 +44,7 @@ pub async fn execute_update_reward_funder<C: Deref<Target = impl Signer> + Clone
     params: UpdateRewardFunderParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<()> {
     // 解构更新奖励资助者参数
     let UpdateRewardFunderParams {
@@ -51,6 +53,8 @@ pub async fn execute_update_reward_funder<C: Deref<Target = impl Signer> + Clone
         funder,
     } = params;
 
+    validate_reward_index(reward_index)?;
+
     // 生成事件权限账户PDA，用于记录资助者更新事件
     let (event_authority, _bump) = derive_event_authority_pda();
 
@@ -78,13 +82,13 @@ pub async fn execute_update_reward_funder<C: Deref<Target = impl Signer> + Clone
     };
 
     // 构建并发送交易请求
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
     let signature = request_builder
         .instruction(ix)                                            // 添加更新资助者指令
         .send_with_spinner_and_config(transaction_config)          // 发送交易并等待确认
         .await;
 
-    println!("Update reward funder. Signature: {:#?}", signature);
+    info!(?signature, "Update reward funder");
 
     // 检查交易是否成功执行
     signature?;
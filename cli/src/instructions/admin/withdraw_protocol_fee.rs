@@ -1,5 +1,7 @@
 use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token::spl_token;
 use commons::dlmm::accounts::LbPair;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
 
 use crate::*;
 
@@ -11,6 +13,27 @@ pub struct WithdrawProtocolFeeParams {
     /// 流动性池对的地址
     /// 从该池对中提取积累的协议手续费
     pub lb_pair: Pubkey,
+    /// 以SPL备忘录指令附加到交易的备注，例如用于财务对账
+    /// Memo attached to the transaction as an SPL memo instruction, e.g. for treasury reconciliation
+    #[clap(long)]
+    pub memo: Option<String>,
+    /// 代币X的最大提取数量（最小单位），用于部分提取。默认提取全部
+    /// Maximum amount of token X to withdraw in smallest unit, for partial withdrawals. Defaults to all
+    #[clap(long, default_value_t = u64::MAX)]
+    pub max_amount_x: u64,
+    /// 代币Y的最大提取数量（最小单位），用于部分提取。默认提取全部
+    /// Maximum amount of token Y to withdraw in smallest unit, for partial withdrawals. Defaults to all
+    #[clap(long, default_value_t = u64::MAX)]
+    pub max_amount_y: u64,
+    /// 可提取的协议手续费为0时仍然发送交易
+    /// Send the transaction even when there is no protocol fee to withdraw
+    #[clap(long)]
+    pub force: bool,
+    /// 将提取的WSOL协议手续费解包为SOL。接收WSOL的关联账户不存在时在同一交易中创建并在提取后关闭，已存在的账户不受影响
+    /// Unwrap the withdrawn WSOL protocol fee to SOL. A missing WSOL receiver account is created in the same
+    /// transaction and closed after the withdrawal. A pre-existing account is left as is
+    #[clap(long)]
+    pub unwrap_sol: bool,
 }
 
 /// 执行提取协议手续费操作
@@ -21,11 +44,14 @@ pub struct WithdrawProtocolFeeParams {
 /// - 激励生态系统参与者
 /// - 财务储备和风险管理
 /// - 支持新功能开发
+///
+/// 发送交易前读取交易对的协议手续费，可提取的数量（不超过最大提取数量）均为0时跳过，除非指定 `--force`
 /// 
 /// # 参数
 /// * `params` - 包含池对地址的参数
 /// * `program` - Solana程序客户端，用于执行链上操作
 /// * `transaction_config` - 交易配置，包含确认级别等设置
+/// * `compute_unit_price` - 计算单元价格设置
 /// 
 /// # 返回值
 /// * `Result<()>` - 成功时返回空值，失败时返回错误
@@ -39,9 +65,17 @@ pub async fn execute_withdraw_protocol_fee<C: Deref<Target = impl Signer> + Clon
     params: WithdrawProtocolFeeParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<()> {
     // 解构参数，获取池对地址
-    let WithdrawProtocolFeeParams { lb_pair } = params;
+    let WithdrawProtocolFeeParams {
+        lb_pair,
+        memo,
+        max_amount_x,
+        max_amount_y,
+        force,
+        unwrap_sol,
+    } = params;
 
     let rpc_client = program.rpc();
 
@@ -53,6 +87,27 @@ pub async fn execute_withdraw_protocol_fee<C: Deref<Target = impl Signer> + Clon
         })
         .await?;
 
+    // 检查可提取的协议手续费，均为0时跳过，避免浪费交易
+    // Check the withdrawable protocol fees, skipping when both are zero to avoid a wasted transaction
+    let ProtocolFee { amount_x, amount_y } = lb_pair_state.protocol_fee;
+    let withdraw_amount_x = amount_x.min(max_amount_x);
+    let withdraw_amount_y = amount_y.min(max_amount_y);
+    info!(
+        amount_x,
+        amount_y, withdraw_amount_x, withdraw_amount_y, "Protocol fees"
+    );
+
+    if withdraw_amount_x == 0 && withdraw_amount_y == 0 {
+        if !force {
+            println!(
+                "No protocol fee to withdraw from pair {} (amount x {}, amount y {}), skipped. Use --force to send anyway",
+                lb_pair, amount_x, amount_y
+            );
+            return Ok(());
+        }
+        warn!("No protocol fee to withdraw, sending anyway");
+    }
+
     // 获取代币X和代币Y的程序ID，支持SPL Token和Token-2022标准
     let [token_x_program, token_y_program] = lb_pair_state.get_token_programs()?;
 
@@ -113,8 +168,8 @@ pub async fn execute_withdraw_protocol_fee<C: Deref<Target = impl Signer> + Clon
 
     // 构建提取协议手续费指令的数据
     let data = dlmm::client::args::WithdrawProtocolFee {
-        max_amount_x: u64::MAX,                                     // 代币X的最大提取数量（无限制）
-        max_amount_y: u64::MAX,                                     // 代币Y的最大提取数量（无限制）
+        max_amount_x,                                               // 代币X的最大提取数量
+        max_amount_y,                                               // 代币Y的最大提取数量
         remaining_accounts_info,                                    // 额外账户信息
     }
     .data();
@@ -131,17 +186,50 @@ pub async fn execute_withdraw_protocol_fee<C: Deref<Target = impl Signer> + Clon
 
     // 设置计算预算限制，由于涉及多个账户和复杂的Token-2022操作
     // 需要较高的计算单位来确保交易成功
-    let compute_budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(200_000);
+    let compute_unit_limit = 200_000;
+
+    // 只解包本命令创建的WSOL账户：在提取之前创建，提取之后关闭
+    // Only a WSOL account created by this command is unwrapped: created before the withdrawal and closed after it
+    let mut unwrap_sol_at_end = false;
+    if unwrap_sol {
+        for token_mint in [lb_pair_state.token_x_mint, lb_pair_state.token_y_mint] {
+            unwrap_sol_at_end |=
+                should_unwrap_sol(&rpc_client, token_mint, program.payer(), program.payer())
+                    .await?;
+        }
+    }
 
     // 构建并发送交易请求
-    let request_builder = program.request();
+    // 先设置计算预算
+    let mut request_builder =
+        request_with_compute_unit_limit(program, compute_unit_price, compute_unit_limit)?;
+
+    if unwrap_sol_at_end {
+        request_builder = request_builder.instruction(create_associated_token_account_idempotent(
+            &program.payer(),
+            &program.payer(),
+            &spl_token::native_mint::ID,
+            &spl_token::ID,
+        ));
+    }
+
+    request_builder = request_builder.instruction(withdraw_ix);   // 再添加提取指令
+
+    if unwrap_sol_at_end {
+        request_builder = request_builder.instruction(unwrap_sol_instruction(program.payer())?);
+    }
+
+    // 附加备忘录指令（如果提供）
+    if let Some(memo) = memo.as_deref() {
+        request_builder =
+            request_builder.instruction(spl_memo::build_memo(memo.as_bytes(), &[]));
+    }
+
     let signature = request_builder
-        .instruction(compute_budget_ix)                             // 先设置计算预算
-        .instruction(withdraw_ix)                                   // 再添加提取指令
         .send_with_spinner_and_config(transaction_config)          // 发送交易并等待确认
         .await;
 
-    println!("WithdrawProtocolFee. Signature: {:#?}", signature);
+    info!(?signature, "WithdrawProtocolFee");
 
     // 检查交易是否成功执行
     signature?;
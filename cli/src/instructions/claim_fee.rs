@@ -8,6 +8,22 @@ pub struct ClaimFeeParams {
     /// 仓位地址
     /// Position address
     pub position: Pubkey,
+    /// 领取范围的起始bin ID，默认为仓位的最低bin ID
+    /// Start bin id of the claim range. Defaults to the position lower bin id
+    #[clap(long, allow_hyphen_values = true)]
+    pub from_bin_id: Option<i32>,
+    /// 领取范围的结束bin ID，默认为仓位的最高bin ID
+    /// End bin id of the claim range. Defaults to the position upper bin id
+    #[clap(long, allow_hyphen_values = true)]
+    pub to_bin_id: Option<i32>,
+    /// 以SPL备忘录指令附加到每笔交易的备注，例如用于对账
+    /// Memo attached to every transaction as an SPL memo instruction, e.g. for accounting
+    #[clap(long)]
+    pub memo: Option<String>,
+    /// 将领取到新建WSOL账户的手续费解包为SOL，已存在的WSOL账户不受影响
+    /// Unwrap fees claimed into a newly created WSOL account to SOL. Pre-existing WSOL accounts are left as is
+    #[clap(long)]
+    pub unwrap_sol: bool,
 }
 
 /// 执行领取手续费指令
@@ -17,7 +33,7 @@ pub struct ClaimFeeParams {
 /// * `params` - 领取手续费的参数 / Parameters for fee claiming
 /// * `program` - Solana程序引用 / Solana program reference
 /// * `transaction_config` - 交易配置 / Transaction configuration
-/// * `compute_unit_price` - 计算单元价格指令（可选）/ Compute unit price instruction (optional)
+/// * `compute_unit_price` - 计算单元价格设置 / Compute unit price setting
 /// 
 /// # 功能说明 / Functionality
 /// 从指定的流动性仓位中领取累积的交易手续费到用户的代币账户
@@ -26,9 +42,15 @@ pub async fn execute_claim_fee<C: Deref<Target = impl Signer> + Clone>(
     params: ClaimFeeParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
-    compute_unit_price: Option<Instruction>,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<()> {
-    let ClaimFeeParams { position } = params;
+    let ClaimFeeParams {
+        position,
+        from_bin_id,
+        to_bin_id,
+        memo,
+        unwrap_sol,
+    } = params;
 
     let rpc_client = program.rpc();
     
@@ -36,10 +58,28 @@ pub async fn execute_claim_fee<C: Deref<Target = impl Signer> + Clone>(
     // Get position state data
     let position_state: PositionV2 = rpc_client
         .get_account_and_deserialize(&position, |account| {
-            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
+            deserialize_position_v2(&account.data)
         })
         .await?;
 
+    // 确定领取的bin范围，并校验其位于仓位范围内
+    // Determine the bin range to claim and validate it lies within the position range
+    let from_bin_id = from_bin_id.unwrap_or(position_state.lower_bin_id);
+    let to_bin_id = to_bin_id.unwrap_or(position_state.upper_bin_id);
+
+    ensure!(
+        from_bin_id >= position_state.lower_bin_id && to_bin_id <= position_state.upper_bin_id,
+        "Claim range [{}, {}] is outside of position range [{}, {}]",
+        from_bin_id,
+        to_bin_id,
+        position_state.lower_bin_id,
+        position_state.upper_bin_id
+    );
+    ensure!(
+        from_bin_id <= to_bin_id,
+        "from_bin_id must be less than or equal to to_bin_id"
+    );
+
     // 获取流动性交易对状态数据
     // Get liquidity pair state data
     let lb_pair_state: LbPair = rpc_client
@@ -48,6 +88,22 @@ pub async fn execute_claim_fee<C: Deref<Target = impl Signer> + Clone>(
         })
         .await?;
 
+    // 在创建代币账户之前判断是否解包WSOL，只解包本命令创建的账户
+    // Decide whether to unwrap WSOL before the token accounts are created, only accounts created by this command
+    // are unwrapped
+    let fee_receiver = if position_state.fee_owner.eq(&Pubkey::default()) {
+        program.payer()
+    } else {
+        position_state.fee_owner
+    };
+    let mut unwrap_sol_at_end = false;
+    if unwrap_sol {
+        for token_mint in [lb_pair_state.token_x_mint, lb_pair_state.token_y_mint] {
+            unwrap_sol_at_end |=
+                should_unwrap_sol(&rpc_client, token_mint, fee_receiver, program.payer()).await?;
+        }
+    }
+
     // 确定手续费接收者并创建或获取相应的代币账户
     // Determine fee receiver and create or get corresponding token accounts
     let (user_token_x, user_token_y) = if position_state.fee_owner.eq(&Pubkey::default()) {
@@ -145,9 +201,9 @@ pub async fn execute_claim_fee<C: Deref<Target = impl Signer> + Clone>(
 
     // 分块处理仓位的bin范围以领取手续费
     // Process position bin range in chunks to claim fees
-    for (min_bin_id, max_bin_id) in
-        position_bin_range_chunks(position_state.lower_bin_id, position_state.upper_bin_id)
-    {
+    let bin_range_chunks = position_bin_range_chunks(from_bin_id, to_bin_id);
+    let chunk_count = bin_range_chunks.len();
+    for (chunk_index, (min_bin_id, max_bin_id)) in bin_range_chunks.into_iter().enumerate() {
         // 构建领取手续费指令数据
         // Build claim fee instruction data
         let data = dlmm::client::args::ClaimFee2 {
@@ -181,22 +237,30 @@ pub async fn execute_claim_fee<C: Deref<Target = impl Signer> + Clone>(
 
         // 构建交易请求
         // Build transaction request
-        let mut request_builder = program.request();
+        let mut request_builder =
+            request_with_compute_unit_price(program, compute_unit_price.clone())?
+                .instruction(claim_fee_ix);
+
+        // 最后一笔交易关闭WSOL账户，将手续费解包为SOL
+        // The last transaction closes the WSOL account, unwrapping the fees to SOL
+        if unwrap_sol_at_end && chunk_index + 1 == chunk_count {
+            request_builder = request_builder.instruction(unwrap_sol_instruction(program.payer())?);
+        }
 
-        // 如果提供了计算单元价格指令，则添加
-        // Add compute unit price instruction if provided
-        if let Some(compute_unit_price_ix) = compute_unit_price.clone() {
-            request_builder = request_builder.instruction(compute_unit_price_ix);
+        // 如果提供了备注，则附加备忘录指令
+        // Attach the memo instruction if provided
+        if let Some(memo) = memo.as_deref() {
+            request_builder =
+                request_builder.instruction(spl_memo::build_memo(memo.as_bytes(), &[]));
         }
 
         // 发送交易
         // Send transaction
         let signature = request_builder
-            .instruction(claim_fee_ix)
             .send_with_spinner_and_config(transaction_config)
             .await;
 
-        println!("Claim fee. Signature: {:#?}", signature);
+        info!(?signature, "Claim fee");
 
         signature?;
     }
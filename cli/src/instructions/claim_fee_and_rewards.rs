@@ -0,0 +1,310 @@
+use crate::*;
+use instructions::*;
+
+/// 领取手续费及所有奖励的参数结构体
+/// Parameters for claiming fees and all rewards
+#[derive(Debug, Parser)]
+pub struct ClaimFeeAndRewardsParams {
+    /// 仓位地址
+    /// Position address
+    pub position: Pubkey,
+}
+
+/// 执行领取手续费及所有奖励指令
+/// Executes the claim fee and rewards instruction
+///
+/// # 功能说明 / Functionality
+/// 领取仓位的手续费及流动性对已初始化奖励索引的奖励，跳过可领取数量为0的手续费及奖励，并打包为尽可能少的交易依次发送
+/// Claims the position fee and the rewards of the initialized reward indexes of the pair, skipping the fee and
+/// rewards with nothing to claim, packed into as few transactions as possible
+pub async fn execute_claim_fee_and_rewards<C: Deref<Target = impl Signer> + Clone>(
+    params: ClaimFeeAndRewardsParams,
+    program: &Program<C>,
+    transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
+) -> Result<()> {
+    let ClaimFeeAndRewardsParams { position } = params;
+
+    let rpc_client = program.rpc();
+
+    let position_state: PositionV2 = rpc_client
+        .get_account_and_deserialize(&position, |account| {
+            deserialize_position_v2(&account.data)
+        })
+        .await?;
+
+    let lb_pair_state: LbPair = rpc_client
+        .get_account_and_deserialize(&position_state.lb_pair, |account| {
+            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
+        })
+        .await?;
+
+    // 由仓位覆盖的bin数组计算可领取的手续费及奖励，奖励包括活跃bin自上次更新以来的发放
+    // Compute the claimable fees and rewards from the bin arrays covered by the position. Rewards include the emission
+    // to the active bin since the last update
+    let bin_array_keys = position_state.get_bin_array_keys_coverage()?;
+    let bin_arrays = rpc_client
+        .get_multiple_accounts_chunked(&bin_array_keys)
+        .await?
+        .into_iter()
+        .zip(bin_array_keys.iter())
+        .map(|(account, key)| {
+            let account = account.with_context(|| format!("Bin array {} not found", key))?;
+            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
+        })
+        .collect::<Result<Vec<BinArray>>>()?;
+
+    let current_time = current_timestamp(&rpc_client).await?;
+    let claimable =
+        position_state.get_claimable_fees_and_rewards(&lb_pair_state, &bin_arrays, current_time)?;
+
+    if claimable.is_zero() {
+        info!(%position, "Nothing to claim");
+        return Ok(());
+    }
+
+    let instructions = build_claim_fee_and_rewards_instructions(
+        program,
+        transaction_config,
+        compute_unit_price.clone(),
+        position,
+        &position_state,
+        &lb_pair_state,
+        &claimable,
+    )
+    .await?
+    .instructions;
+
+    let transactions = pack_instructions(
+        instructions,
+        &program.payer(),
+        MAX_ACCOUNTS_PER_TRANSACTION,
+        compute_unit_price,
+        CLAIM_COMPUTE_UNITS,
+    )?;
+
+    for ixs in transactions {
+        let signature = ixs
+            .into_iter()
+            .fold(program.request(), |builder, ix| builder.instruction(ix))
+            .send_with_spinner_and_config(transaction_config)
+            .await;
+
+        info!(%position, ?signature, "Claim fee and rewards");
+
+        signature?;
+    }
+
+    Ok(())
+}
+
+/// 领取仓位手续费及奖励的指令，及接收领取代币的账户
+/// Instructions claiming the position fee and rewards, and the token accounts receiving the claimed tokens
+pub struct ClaimFeeAndRewardsInstructions {
+    pub instructions: Vec<Instruction>,
+    /// 接收领取代币的代币账户及其铸造地址
+    /// Token accounts receiving the claimed tokens, with their mint
+    pub receivers: Vec<(Pubkey, Pubkey)>,
+}
+
+/// 构建领取仓位手续费及奖励的指令，跳过可领取数量为0的手续费及奖励
+/// Build the instructions claiming the position fee and rewards, skipping the fee and rewards with nothing to claim
+pub async fn build_claim_fee_and_rewards_instructions<C: Deref<Target = impl Signer> + Clone>(
+    program: &Program<C>,
+    transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
+    position: Pubkey,
+    position_state: &PositionV2,
+    lb_pair_state: &LbPair,
+    claimable: &PositionClaimable,
+) -> Result<ClaimFeeAndRewardsInstructions> {
+    let rpc_client = program.rpc();
+    let lb_pair = position_state.lb_pair;
+
+    let (event_authority, _bump) = derive_event_authority_pda();
+    let bin_range_chunks =
+        position_bin_range_chunks(position_state.lower_bin_id, position_state.upper_bin_id);
+
+    let mut instructions = vec![];
+    let mut receivers = vec![];
+
+    if claimable.fee_x > 0 || claimable.fee_y > 0 {
+        // 手续费接收者，未指定手续费所有者时使用支付者
+        // Fee receiver. The payer is used when no fee owner is specified
+        let fee_receiver = if position_state.fee_owner.eq(&Pubkey::default()) {
+            program.payer()
+        } else {
+            position_state.fee_owner
+        };
+
+        let user_token_x = get_or_create_ata(
+            program,
+            transaction_config,
+            lb_pair_state.token_x_mint,
+            fee_receiver,
+            compute_unit_price.clone(),
+        )
+        .await?;
+
+        let user_token_y = get_or_create_ata(
+            program,
+            transaction_config,
+            lb_pair_state.token_y_mint,
+            fee_receiver,
+            compute_unit_price.clone(),
+        )
+        .await?;
+
+        receivers.push((user_token_x, lb_pair_state.token_x_mint));
+        receivers.push((user_token_y, lb_pair_state.token_y_mint));
+
+        let [token_program_x, token_program_y] = lb_pair_state.get_token_programs()?;
+
+        let main_accounts = dlmm::client::accounts::ClaimFee2 {
+            lb_pair,
+            sender: program.payer(),
+            position,
+            reserve_x: lb_pair_state.reserve_x,
+            reserve_y: lb_pair_state.reserve_y,
+            token_program_x,
+            token_program_y,
+            token_x_mint: lb_pair_state.token_x_mint,
+            token_y_mint: lb_pair_state.token_y_mint,
+            user_token_x,
+            user_token_y,
+            event_authority,
+            program: dlmm::ID,
+            memo_program: spl_memo::id(),
+        }
+        .to_account_metas(None);
+
+        let mut remaining_accounts_info = RemainingAccountsInfo { slices: vec![] };
+        let mut token_2022_remaining_accounts = vec![];
+
+        if let Some((slices, transfer_hook_remaining_accounts)) =
+            get_potential_token_2022_related_ix_data_and_accounts(
+                lb_pair_state,
+                program.rpc(),
+                ActionType::Liquidity,
+            )
+            .await?
+        {
+            remaining_accounts_info.slices = slices;
+            token_2022_remaining_accounts.extend(transfer_hook_remaining_accounts);
+        };
+
+        for &(min_bin_id, max_bin_id) in bin_range_chunks.iter() {
+            let data = dlmm::client::args::ClaimFee2 {
+                min_bin_id,
+                max_bin_id,
+                remaining_accounts_info: remaining_accounts_info.clone(),
+            }
+            .data();
+
+            let bin_arrays_account_meta = position_state
+                .get_bin_array_accounts_meta_coverage_by_chunk(min_bin_id, max_bin_id)?;
+
+            let accounts = [
+                main_accounts.to_vec(),
+                token_2022_remaining_accounts.clone(),
+                bin_arrays_account_meta,
+            ]
+            .concat();
+
+            instructions.push(Instruction {
+                program_id: dlmm::ID,
+                accounts,
+                data,
+            });
+        }
+    }
+
+    // 领取已初始化且可领取数量不为0的奖励
+    // Claim the rewards of the initialized reward indexes with a nonzero pending amount
+    for (reward_index, reward_info) in lb_pair_state.reward_infos.iter().enumerate() {
+        if reward_info.mint.eq(&Pubkey::default()) {
+            continue;
+        }
+
+        if claimable.rewards[reward_index] == 0 {
+            continue;
+        }
+
+        let reward_mint = reward_info.mint;
+        let (reward_vault, _bump) = derive_reward_vault_pda(lb_pair, reward_index as u64);
+        let reward_mint_program = rpc_client.get_account(&reward_mint).await?.owner;
+
+        // 奖励归仓位所有者，支付者可能是仓位的操作员
+        // Rewards belong to the position owner, the payer may be an operator of the position
+        let user_token_account = get_or_create_ata(
+            program,
+            transaction_config,
+            reward_mint,
+            position_state.owner,
+            compute_unit_price.clone(),
+        )
+        .await?;
+
+        receivers.push((user_token_account, reward_mint));
+
+        let main_accounts = dlmm::client::accounts::ClaimReward2 {
+            lb_pair,
+            reward_vault,
+            reward_mint,
+            memo_program: spl_memo::ID,
+            token_program: reward_mint_program,
+            position,
+            user_token_account,
+            sender: program.payer(),
+            event_authority,
+            program: dlmm::ID,
+        }
+        .to_account_metas(None);
+
+        let mut remaining_accounts_info = RemainingAccountsInfo { slices: vec![] };
+        let mut token_2022_remaining_accounts = vec![];
+
+        if let Some((slices, transfer_hook_remaining_accounts)) =
+            get_potential_token_2022_related_ix_data_and_accounts(
+                lb_pair_state,
+                program.rpc(),
+                ActionType::Reward(reward_index),
+            )
+            .await?
+        {
+            remaining_accounts_info.slices = slices;
+            token_2022_remaining_accounts.extend(transfer_hook_remaining_accounts);
+        };
+
+        for &(min_bin_id, max_bin_id) in bin_range_chunks.iter() {
+            let data = dlmm::client::args::ClaimReward2 {
+                reward_index: reward_index as u64,
+                min_bin_id,
+                max_bin_id,
+                remaining_accounts_info: remaining_accounts_info.clone(),
+            }
+            .data();
+
+            let bin_arrays_account_meta = position_state
+                .get_bin_array_accounts_meta_coverage_by_chunk(min_bin_id, max_bin_id)?;
+
+            let accounts = [
+                main_accounts.to_vec(),
+                token_2022_remaining_accounts.clone(),
+                bin_arrays_account_meta,
+            ]
+            .concat();
+
+            instructions.push(Instruction {
+                program_id: dlmm::ID,
+                accounts,
+                data,
+            });
+        }
+    }
+
+    Ok(ClaimFeeAndRewardsInstructions {
+        instructions,
+        receivers,
+    })
+}
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use crate::*;
+use instructions::*;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+
+/// 批量领取手续费的参数结构体
+/// Parameters for claiming fees of multiple positions
+#[derive(Debug, Parser)]
+pub struct ClaimFeeBatchParams {
+    /// 流动性交易对地址
+    /// Liquidity pair address
+    pub lb_pair: Pubkey,
+    /// 需要领取手续费的仓位，以逗号分隔。未提供时领取支付者在该交易对的所有仓位
+    /// Positions to claim fee from, separated by comma. Defaults to all positions of the payer in the pair
+    #[clap(long, value_delimiter = ',')]
+    pub positions: Vec<Pubkey>,
+}
+
+/// 执行批量领取手续费指令
+/// Executes the batch claim fee instruction
+///
+/// # 功能说明 / Functionality
+/// 为多个仓位构建领取手续费指令，并打包为尽可能少的交易依次发送
+/// Builds the claim fee instructions of multiple positions and packs them into as few transactions as possible
+pub async fn execute_claim_fee_batch<C: Deref<Target = impl Signer> + Clone>(
+    params: ClaimFeeBatchParams,
+    program: &Program<C>,
+    transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
+) -> Result<()> {
+    let ClaimFeeBatchParams { lb_pair, positions } = params;
+
+    let rpc_client = program.rpc();
+
+    let lb_pair_state: LbPair = rpc_client
+        .get_account_and_deserialize(&lb_pair, |account| {
+            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
+        })
+        .await?;
+
+    let positions = if positions.is_empty() {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(position_filter_by_wallet_and_pair(program.payer(), lb_pair)),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        rpc_client
+            .get_program_accounts_with_config(&dlmm::ID, config)
+            .await?
+            .into_iter()
+            .map(|(position, account)| {
+                let position_state = deserialize_position_v2(&account.data)
+                    .with_context(|| format!("Failed to read position {}", position))?;
+                Ok((position, position_state))
+            })
+            .collect::<Result<Vec<_>>>()?
+    } else {
+        let accounts = rpc_client.get_multiple_accounts_chunked(&positions).await?;
+
+        positions
+            .into_iter()
+            .zip(accounts)
+            .map(|(position, account)| {
+                let account =
+                    account.with_context(|| format!("Position {} not found", position))?;
+                let position_state = deserialize_position_v2(&account.data)
+                    .with_context(|| format!("Failed to read position {}", position))?;
+
+                ensure!(
+                    position_state.lb_pair == lb_pair,
+                    "Position {} does not belong to the liquidity pair",
+                    position
+                );
+
+                Ok((position, position_state))
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    if positions.is_empty() {
+        info!(%lb_pair, "No position found");
+        return Ok(());
+    }
+
+    let [token_program_x, token_program_y] = lb_pair_state.get_token_programs()?;
+    let (event_authority, _bump) = derive_event_authority_pda();
+
+    let mut remaining_accounts_info = RemainingAccountsInfo { slices: vec![] };
+    let mut token_2022_remaining_accounts = vec![];
+
+    if let Some((slices, transfer_hook_remaining_accounts)) =
+        get_potential_token_2022_related_ix_data_and_accounts(
+            &lb_pair_state,
+            program.rpc(),
+            ActionType::Liquidity,
+        )
+        .await?
+    {
+        remaining_accounts_info.slices = slices;
+        token_2022_remaining_accounts.extend(transfer_hook_remaining_accounts);
+    };
+
+    // 手续费接收者的代币账户，未指定手续费所有者时使用支付者
+    // Token accounts of the fee receivers. The payer is used when no fee owner is specified
+    let mut fee_receiver_token_accounts: HashMap<Pubkey, (Pubkey, Pubkey)> = HashMap::new();
+    let mut instructions = vec![];
+
+    for (position, position_state) in positions {
+        let fee_receiver = if position_state.fee_owner.eq(&Pubkey::default()) {
+            program.payer()
+        } else {
+            position_state.fee_owner
+        };
+
+        let (user_token_x, user_token_y) = match fee_receiver_token_accounts.get(&fee_receiver) {
+            Some(token_accounts) => *token_accounts,
+            None => {
+                let user_token_x = get_or_create_ata(
+                    program,
+                    transaction_config,
+                    lb_pair_state.token_x_mint,
+                    fee_receiver,
+                    compute_unit_price.clone(),
+                )
+                .await?;
+
+                let user_token_y = get_or_create_ata(
+                    program,
+                    transaction_config,
+                    lb_pair_state.token_y_mint,
+                    fee_receiver,
+                    compute_unit_price.clone(),
+                )
+                .await?;
+
+                fee_receiver_token_accounts.insert(fee_receiver, (user_token_x, user_token_y));
+                (user_token_x, user_token_y)
+            }
+        };
+
+        let main_accounts = dlmm::client::accounts::ClaimFee2 {
+            lb_pair,
+            sender: program.payer(),
+            position,
+            reserve_x: lb_pair_state.reserve_x,
+            reserve_y: lb_pair_state.reserve_y,
+            token_program_x,
+            token_program_y,
+            token_x_mint: lb_pair_state.token_x_mint,
+            token_y_mint: lb_pair_state.token_y_mint,
+            user_token_x,
+            user_token_y,
+            event_authority,
+            program: dlmm::ID,
+            memo_program: spl_memo::id(),
+        }
+        .to_account_metas(None);
+
+        for (min_bin_id, max_bin_id) in
+            position_bin_range_chunks(position_state.lower_bin_id, position_state.upper_bin_id)
+        {
+            let data = dlmm::client::args::ClaimFee2 {
+                min_bin_id,
+                max_bin_id,
+                remaining_accounts_info: remaining_accounts_info.clone(),
+            }
+            .data();
+
+            let bin_arrays_account_meta = position_state
+                .get_bin_array_accounts_meta_coverage_by_chunk(min_bin_id, max_bin_id)?;
+
+            let accounts = [
+                main_accounts.to_vec(),
+                token_2022_remaining_accounts.clone(),
+                bin_arrays_account_meta,
+            ]
+            .concat();
+
+            instructions.push(Instruction {
+                program_id: dlmm::ID,
+                accounts,
+                data,
+            });
+        }
+    }
+
+    let transactions = pack_instructions(
+        instructions,
+        &program.payer(),
+        MAX_ACCOUNTS_PER_TRANSACTION,
+        compute_unit_price,
+        CLAIM_COMPUTE_UNITS,
+    )?;
+
+    info!(transactions = transactions.len(), "Claim fee batch");
+
+    for ixs in transactions {
+        let signature = ixs
+            .into_iter()
+            .fold(program.request(), |builder, ix| builder.instruction(ix))
+            .send_with_spinner_and_config(transaction_config)
+            .await;
+
+        info!(?signature, "Claim fee");
+
+        signature?;
+    }
+
+    Ok(())
+}
@@ -8,12 +8,46 @@ pub struct ClaimRewardParams {
     /// 流动性交易对地址
     /// Liquidity pair address
     pub lb_pair: Pubkey,
-    /// 奖励索引（0或1）
-    /// Reward index (0 or 1)
-    pub reward_index: u64,
+    /// 奖励索引（0或1），`all` 领取所有已初始化的奖励
+    /// Reward index (0 or 1), or `all` to claim every initialized reward
+    pub reward_index: RewardIndexSelection,
     /// 仓位地址
     /// Position address
     pub position: Pubkey,
+    /// 以SPL备忘录指令附加到每笔交易的备注，例如用于对账
+    /// Memo attached to every transaction as an SPL memo instruction, e.g. for accounting
+    #[clap(long)]
+    pub memo: Option<String>,
+    /// 将领取到新建WSOL账户的奖励解包为SOL，已存在的WSOL账户不受影响
+    /// Unwrap rewards claimed into a newly created WSOL account to SOL. Pre-existing WSOL accounts are left as is
+    #[clap(long)]
+    pub unwrap_sol: bool,
+}
+
+/// 要领取的奖励索引
+/// Reward indexes to claim
+#[derive(Debug, Clone, Copy)]
+pub enum RewardIndexSelection {
+    /// 所有已初始化的奖励索引 / Every initialized reward index
+    All,
+    /// 指定的奖励索引 / The given reward index
+    Index(u64),
+}
+
+impl std::str::FromStr for RewardIndexSelection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.eq_ignore_ascii_case("all") {
+            return Ok(Self::All);
+        }
+
+        let reward_index = s
+            .parse::<u64>()
+            .with_context(|| format!("Invalid reward index {}, expected a number or all", s))?;
+
+        Ok(Self::Index(reward_index))
+    }
 }
 
 /// 执行领取奖励指令
@@ -23,7 +57,7 @@ pub struct ClaimRewardParams {
 /// * `params` - 领取奖励的参数 / Parameters for reward claiming
 /// * `program` - Solana程序引用 / Solana program reference
 /// * `transaction_config` - 交易配置 / Transaction configuration
-/// * `compute_unit_price` - 计算单元价格指令（可选）/ Compute unit price instruction (optional)
+/// * `compute_unit_price` - 计算单元价格设置 / Compute unit price setting
 /// 
 /// # 功能说明 / Functionality
 /// 从指定的流动性仓位中领取累积的奖励代币到用户的代币账户
@@ -32,19 +66,17 @@ pub async fn execute_claim_reward<C: Deref<Target = impl Signer> + Clone>(
     params: ClaimRewardParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
-    compute_unit_price: Option<Instruction>,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<()> {
     let ClaimRewardParams {
         lb_pair,
         reward_index,
         position,
+        memo,
+        unwrap_sol,
     } = params;
 
     let rpc_client = program.rpc();
-    
-    // 派生奖励金库PDA
-    // Derive reward vault PDA
-    let (reward_vault, _bump) = derive_reward_vault_pda(lb_pair, reward_index);
 
     // 获取流动性交易对状态数据
     // Get liquidity pair state data
@@ -57,27 +89,132 @@ pub async fn execute_claim_reward<C: Deref<Target = impl Signer> + Clone>(
     // 获取仓位状态数据
     // Get position state data
     let position_state: PositionV2 = rpc_client
-        .get_account_and_deserialize(&position, |account| {
-            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
-        })
+        .get_account_and_deserialize(&position, |account| deserialize_position_v2(&account.data))
+        .await?;
+
+    ensure!(
+        position_state.lb_pair == lb_pair,
+        "Position {} belongs to liquidity pair {}, not {}",
+        position,
+        position_state.lb_pair,
+        lb_pair
+    );
+
+    // 确定要领取的奖励索引，`all` 时从交易对的奖励信息中找出所有已初始化的索引
+    // Determine the reward indexes to claim. For `all`, every initialized index is discovered from the reward
+    // infos of the pair
+    let reward_indexes = match reward_index {
+        RewardIndexSelection::Index(reward_index) => vec![reward_index],
+        RewardIndexSelection::All => {
+            let reward_indexes = lb_pair_state
+                .reward_infos
+                .iter()
+                .enumerate()
+                .filter(|(_, reward_info)| reward_info.mint != Pubkey::default())
+                .map(|(reward_index, _)| reward_index as u64)
+                .collect::<Vec<_>>();
+
+            ensure!(
+                !reward_indexes.is_empty(),
+                "Liquidity pair {} has no initialized reward",
+                lb_pair
+            );
+
+            reward_indexes
+        }
+    };
+
+    for reward_index in reward_indexes {
+        // 在构建指令（创建奖励代币账户）之前判断是否解包WSOL，只解包本命令创建的账户
+        // Decide whether to unwrap WSOL before building the instructions, which creates the reward token account.
+        // Only accounts created by this command are unwrapped
+        let unwrap_sol_at_end = unwrap_sol
+            && should_unwrap_sol(
+                &rpc_client,
+                get_initialized_reward_info(&lb_pair_state, reward_index)?.mint,
+                position_state.owner,
+                program.payer(),
+            )
+            .await?;
+
+        let claim_reward_ixs = build_claim_reward_instructions(
+            program,
+            transaction_config,
+            compute_unit_price.clone(),
+            &lb_pair_state,
+            position,
+            &position_state,
+            reward_index,
+        )
         .await?;
 
+        // 每个bin范围分块发送一笔交易，提供备注时附加备忘录指令
+        // One transaction per bin range chunk, with the memo instruction attached when provided
+        let claim_count = claim_reward_ixs.len();
+        for (claim_index, claim_reward_ix) in claim_reward_ixs.into_iter().enumerate() {
+            let mut request_builder =
+                request_with_compute_unit_price(program, compute_unit_price.clone())?
+                    .instruction(claim_reward_ix);
+
+            // 最后一笔交易关闭WSOL账户，将奖励解包为SOL
+            // The last transaction closes the WSOL account, unwrapping the rewards to SOL
+            if unwrap_sol_at_end && claim_index + 1 == claim_count {
+                request_builder =
+                    request_builder.instruction(unwrap_sol_instruction(program.payer())?);
+            }
+
+            if let Some(memo) = memo.as_deref() {
+                request_builder =
+                    request_builder.instruction(spl_memo::build_memo(memo.as_bytes(), &[]));
+            }
+
+            let signature = request_builder
+                .send_with_spinner_and_config(transaction_config)
+                .await;
+
+            info!(reward_index, ?signature, "Claim reward");
+
+            signature?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 构建领取仓位指定奖励索引奖励的指令，每个bin范围分块一个指令
+/// Build the instructions claiming the reward of the given reward index of the position, one per bin range chunk
+async fn build_claim_reward_instructions<C: Deref<Target = impl Signer> + Clone>(
+    program: &Program<C>,
+    transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
+    lb_pair_state: &LbPair,
+    position: Pubkey,
+    position_state: &PositionV2,
+    reward_index: u64,
+) -> Result<Vec<Instruction>> {
+    let rpc_client = program.rpc();
+    let lb_pair = position_state.lb_pair;
+
+    // 派生奖励金库PDA
+    // Derive reward vault PDA
+    let (reward_vault, _bump) = derive_reward_vault_pda(lb_pair, reward_index);
+
     // 获取指定索引的奖励信息
     // Get reward information for specified index
-    let reward_info = lb_pair_state.reward_infos[reward_index as usize];
+    let reward_info = get_initialized_reward_info(lb_pair_state, reward_index)?;
     let reward_mint = reward_info.mint;
 
     // 获取奖励代币的程序所有者
     // Get reward token's program owner
     let reward_mint_program = rpc_client.get_account(&reward_mint).await?.owner;
 
-    // 创建或获取用户的奖励代币账户
-    // Create or get user's reward token account
+    // 创建或获取仓位所有者的奖励代币账户，支付者可能是仓位的操作员
+    // Create or get the reward token account of the position owner, the payer may be an operator of the position
     let user_token_account = get_or_create_ata(
         program,
         transaction_config,
         reward_mint,
-        program.payer(),
+        position_state.owner,
         compute_unit_price.clone(),
     )
     .await?;
@@ -111,7 +248,7 @@ pub async fn execute_claim_reward<C: Deref<Target = impl Signer> + Clone>(
     // Get potential Token 2022 related instruction data and accounts (for reward action)
     if let Some((slices, transfer_hook_remaining_accounts)) =
         get_potential_token_2022_related_ix_data_and_accounts(
-            &lb_pair_state,
+            lb_pair_state,
             program.rpc(),
             ActionType::Reward(reward_index as usize),
         )
@@ -123,6 +260,8 @@ pub async fn execute_claim_reward<C: Deref<Target = impl Signer> + Clone>(
 
     // 分块处理仓位的bin范围以领取奖励
     // Process position bin range in chunks to claim rewards
+    let mut claim_reward_ixs = vec![];
+
     for (min_bin_id, max_bin_id) in
         position_bin_range_chunks(position_state.lower_bin_id, position_state.upper_bin_id)
     {
@@ -158,18 +297,8 @@ pub async fn execute_claim_reward<C: Deref<Target = impl Signer> + Clone>(
             data,
         };
 
-        // 构建并发送交易
-        // Build and send transaction
-        let request_builder = program.request();
-        let signature = request_builder
-            .instruction(claim_reward_ix)
-            .send_with_spinner_and_config(transaction_config)
-            .await;
-
-        println!("Claim reward. Signature: {:#?}", signature);
-
-        signature?;
+        claim_reward_ixs.push(claim_reward_ix);
     }
 
-    Ok(())
+    Ok(claim_reward_ixs)
 }
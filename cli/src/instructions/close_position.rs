@@ -7,6 +7,10 @@ pub struct ClosePositionParams {
     /// 仓位地址
     /// Position address
     pub position: Pubkey,
+    /// 接收回收租金的账户，默认为仓位所有者
+    /// Account receiving the reclaimed rent. Defaults to the position owner
+    #[clap(long)]
+    pub rent_receiver: Option<Pubkey>,
 }
 
 /// 执行关闭仓位指令
@@ -16,16 +20,21 @@ pub struct ClosePositionParams {
 /// * `params` - 关闭仓位的参数 / Parameters for closing position
 /// * `program` - Solana程序引用 / Solana program reference
 /// * `transaction_config` - 交易配置 / Transaction configuration
+/// * `compute_unit_price` - 计算单元价格设置 / Compute unit price setting
 /// 
 /// # 功能说明 / Functionality
-/// 关闭一个空的流动性仓位，回收租金到指定账户
-/// Closes an empty liquidity position and recovers rent to the specified account
+/// 关闭一个空的流动性仓位，回收租金到指定账户（默认为仓位所有者）
+/// Closes an empty liquidity position and recovers rent to the specified account (the position owner by default)
 pub async fn execute_close_position<C: Deref<Target = impl Signer> + Clone>(
     params: ClosePositionParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<()> {
-    let ClosePositionParams { position } = params;
+    let ClosePositionParams {
+        position,
+        rent_receiver,
+    } = params;
 
     let rpc_client = program.rpc();
     
@@ -33,13 +42,19 @@ pub async fn execute_close_position<C: Deref<Target = impl Signer> + Clone>(
     // Get position state data
     let position_state: PositionV2 = rpc_client
         .get_account_and_deserialize(&position, |account| {
-            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
+            deserialize_position_v2(&account.data)
         })
         .await?;
 
-    // 获取仓位覆盖的所有bin数组账户元数据
-    // Get all bin array account metadata covered by the position
-    let bin_arrays_account_meta = position_state.get_bin_array_accounts_meta_coverage()?;
+    // 链上只能关闭空仓位，提前检查以给出处理建议
+    // Only empty positions can be closed on chain, checked upfront to give guidance
+    ensure!(
+        position_state.is_empty(),
+        "Position {} still has liquidity or unclaimed fees / rewards. Remove its liquidity with remove-liquidity and claim with claim-fee-and-rewards before closing",
+        position
+    );
+
+    let rent_receiver = rent_receiver.unwrap_or(position_state.owner);
 
     // 派生事件权限PDA
     // Derive event authority PDA
@@ -49,7 +64,7 @@ pub async fn execute_close_position<C: Deref<Target = impl Signer> + Clone>(
     // Build main account information
     let main_accounts = dlmm::client::accounts::ClosePosition2 {
         sender: position_state.owner,        // 发送者（仓位所有者）/ Sender (position owner)
-        rent_receiver: position_state.owner, // 租金接收者（仓位所有者）/ Rent receiver (position owner)
+        rent_receiver,                      // 租金接收者（默认为仓位所有者）/ Rent receiver (position owner by default)
         position,                           // 要关闭的仓位账户 / Position account to close
         event_authority,                    // 事件权限 / Event authority
         program: dlmm::ID,                  // DLMM程序ID / DLMM program ID
@@ -62,30 +77,26 @@ pub async fn execute_close_position<C: Deref<Target = impl Signer> + Clone>(
     
     // 设置计算预算限制
     // Set compute budget limit
-    let compute_budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(1_400_000);
-
-    // 组合所有必需的账户
-    // Combine all required accounts
-    let accounts = [main_accounts.to_vec(), bin_arrays_account_meta].concat();
+    let compute_unit_limit = 1_400_000;
 
-    // 创建关闭仓位指令
-    // Create close position instruction
+    // ClosePosition2 不需要bin数组账户，因此任意宽度的仓位都不会超出交易账户数量限制
+    // ClosePosition2 takes no bin array accounts, so positions of any width stay within the transaction account limit
     let close_position_ix = Instruction {
         program_id: dlmm::ID,
-        accounts,
+        accounts: main_accounts,
         data,
     };
 
     // 构建并发送交易
     // Build and send transaction
-    let request_builder = program.request();
+    let request_builder =
+        request_with_compute_unit_limit(program, compute_unit_price, compute_unit_limit)?;
     let signature = request_builder
-        .instruction(compute_budget_ix)     // 添加计算预算指令 / Add compute budget instruction
         .instruction(close_position_ix)     // 添加关闭仓位指令 / Add close position instruction
         .send_with_spinner_and_config(transaction_config)
         .await;
 
-    println!("Close position. Signature: {:#?}", signature);
+    info!(?signature, "Close position");
 
     signature?;
 
@@ -0,0 +1,155 @@
+use rust_decimal::Decimal;
+
+use crate::*;
+
+/// 由价格计算bin ID的参数结构体
+/// Parameters for computing the bin id of a price
+#[derive(Debug, Clone, Parser)]
+pub struct ComputeBinIdParams {
+    /// bin步长（基点）
+    /// Bin step in bps
+    #[clap(long)]
+    pub bin_step: u16,
+    /// 以报价代币计价的基础代币UI价格
+    /// UI price of the base token in the quote token
+    #[clap(long)]
+    pub price: f64,
+    /// 基础代币（X代币）的小数位数
+    /// Decimals of the base token (token X)
+    #[clap(long)]
+    pub base_decimals: u8,
+    /// 报价代币（Y代币）的小数位数
+    /// Decimals of the quote token (token Y)
+    #[clap(long)]
+    pub quote_decimals: u8,
+    /// 价格位于两个bin之间时的舍入方式，默认向下舍入到较低的bin。`none` 要求价格恰好对应某个bin
+    /// Rounding when the price lies between two bins. Rounds down to the lower bin by default. `none` requires the
+    /// price to match a bin exactly
+    #[clap(long, value_enum, default_value_t = SelectiveRounding::Down)]
+    pub rounding: SelectiveRounding,
+}
+
+/// 执行由价格计算bin ID，离线计算，不连接链
+/// Executes computing the bin id of a price. Computed offline, the chain is not accessed
+///
+/// # 功能说明 / Functionality
+/// 将UI价格按代币小数位数转换为每lamport价格，再按bin步长计算bin ID，并打印该bin的实际UI价格。
+/// 舍入方式为 `none` 且价格位于两个bin之间时返回错误，并给出相邻的两个bin
+/// Converts the UI price to the price per lamport with the token decimals, computes the bin id with the bin step
+/// and prints the actual UI price of the bin. With the `none` rounding, a price between two bins returns an error
+/// naming the neighbouring bins
+pub fn execute_compute_bin_id(params: ComputeBinIdParams) -> Result<()> {
+    let ComputeBinIdParams {
+        bin_step,
+        base_decimals,
+        quote_decimals,
+        ..
+    } = params;
+
+    let bin_id = compute_bin_id(&params)?;
+
+    warn_if_bin_id_near_limit(bin_id, bin_step);
+
+    println!("Bin id {}", bin_id);
+    println!(
+        "Price {}",
+        ui_price_of_bin(bin_step, bin_id, base_decimals, quote_decimals)?
+    );
+
+    Ok(())
+}
+
+/// 由价格计算bin ID
+/// Compute the bin id of the price
+fn compute_bin_id(params: &ComputeBinIdParams) -> Result<i32> {
+    let ComputeBinIdParams {
+        bin_step,
+        price,
+        base_decimals,
+        quote_decimals,
+        ref rounding,
+    } = *params;
+
+    ensure!(bin_step > 0, "bin_step must be positive");
+    ensure!(price > 0.0, "price must be positive");
+
+    let price_per_lamport = price_per_token_to_per_lamport(price, base_decimals, quote_decimals)
+        .context("price_per_token_to_per_lamport overflow")?;
+
+    let bin_id = match rounding {
+        SelectiveRounding::Up => get_id_from_price(bin_step, &price_per_lamport, Rounding::Up)
+            .context("get_id_from_price overflow")?,
+        SelectiveRounding::Down => get_id_from_price(bin_step, &price_per_lamport, Rounding::Down)
+            .context("get_id_from_price overflow")?,
+        SelectiveRounding::None => match get_precise_id_from_price(bin_step, &price_per_lamport) {
+            Some(bin_id) => bin_id,
+            None => {
+                let lower_bin_id = get_id_from_price(bin_step, &price_per_lamport, Rounding::Down)
+                    .context("get_id_from_price overflow")?;
+                bail!(
+                    "Price {} lies between bin {} ({}) and bin {} ({}), use --rounding up or down",
+                    price,
+                    lower_bin_id,
+                    ui_price_of_bin(bin_step, lower_bin_id, base_decimals, quote_decimals)?,
+                    lower_bin_id + 1,
+                    ui_price_of_bin(bin_step, lower_bin_id + 1, base_decimals, quote_decimals)?,
+                );
+            }
+        },
+    };
+
+    Ok(bin_id)
+}
+
+fn ui_price_of_bin(
+    bin_step: u16,
+    bin_id: i32,
+    base_decimals: u8,
+    quote_decimals: u8,
+) -> Result<Decimal> {
+    get_ui_price_from_id(bin_step, bin_id, base_decimals, quote_decimals)
+        .with_context(|| format!("UI price of bin {} overflow", bin_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(price: f64, rounding: Option<&str>) -> ComputeBinIdParams {
+        let price = price.to_string();
+        let mut args = vec![
+            "compute-bin-id",
+            "--bin-step",
+            "100",
+            "--price",
+            &price,
+            "--base-decimals",
+            "6",
+            "--quote-decimals",
+            "6",
+        ];
+        if let Some(rounding) = rounding {
+            args.extend(["--rounding", rounding]);
+        }
+
+        ComputeBinIdParams::try_parse_from(args).unwrap()
+    }
+
+    #[test]
+    fn test_compute_bin_id() {
+        // 价格恰好对应某个bin时与舍入方式无关 / A price matching a bin is independent of the rounding
+        for rounding in [None, Some("up"), Some("down"), Some("none")] {
+            assert_eq!(compute_bin_id(&params(1.0, rounding)).unwrap(), 0);
+        }
+
+        // 1.005位于bin 0（1.0）与bin 1（1.01）之间，默认向下舍入
+        // 1.005 lies between bin 0 (1.0) and bin 1 (1.01), rounded down by default
+        assert_eq!(compute_bin_id(&params(1.005, None)).unwrap(), 0);
+        assert_eq!(compute_bin_id(&params(1.005, Some("down"))).unwrap(), 0);
+        assert_eq!(compute_bin_id(&params(1.005, Some("up"))).unwrap(), 1);
+        assert!(compute_bin_id(&params(1.005, Some("none"))).is_err());
+
+        assert_eq!(compute_bin_id(&params(0.995, None)).unwrap(), -1);
+        assert!(compute_bin_id(&params(0.0, None)).is_err());
+    }
+}
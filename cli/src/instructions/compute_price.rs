@@ -0,0 +1,46 @@
+use crate::*;
+
+/// 由bin ID计算价格的参数结构体
+/// Parameters for computing the price of a bin id
+#[derive(Debug, Clone, Parser)]
+pub struct ComputePriceParams {
+    /// bin步长（基点）
+    /// Bin step in bps
+    #[clap(long)]
+    pub bin_step: u16,
+    /// bin ID
+    /// Bin id
+    #[clap(long, allow_hyphen_values = true)]
+    pub bin_id: i32,
+    /// 基础代币（X代币）的小数位数
+    /// Decimals of the base token (token X)
+    #[clap(long)]
+    pub base_decimals: u8,
+    /// 报价代币（Y代币）的小数位数
+    /// Decimals of the quote token (token Y)
+    #[clap(long)]
+    pub quote_decimals: u8,
+}
+
+/// 执行由bin ID计算以报价代币计价的基础代币UI价格，离线计算，不连接链
+/// Executes computing the UI price of the base token in the quote token of a bin id. Computed offline, the chain is
+/// not accessed
+pub fn execute_compute_price(params: ComputePriceParams) -> Result<()> {
+    let ComputePriceParams {
+        bin_step,
+        bin_id,
+        base_decimals,
+        quote_decimals,
+    } = params;
+
+    ensure!(bin_step > 0, "bin_step must be positive");
+
+    let price = get_ui_price_from_id(bin_step, bin_id, base_decimals, quote_decimals)
+        .with_context(|| format!("UI price of bin {} overflow", bin_id))?;
+
+    warn_if_bin_id_near_limit(bin_id, bin_step);
+
+    println!("Price {}", price);
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use crate::*;
+use solana_sdk::address_lookup_table::{
+    instruction::{create_lookup_table, extend_lookup_table},
+    state::AddressLookupTable,
+};
+
+/// 每条扩展指令最多写入的地址数量，保证交易不超过大小限制
+/// Maximum addresses written per extend instruction to keep the transaction within the size limit
+const MAX_ADDRESSES_PER_EXTEND: usize = 20;
+
+/// 创建或扩展地址查找表的参数
+/// Parameters for creating or extending an address lookup table
+#[derive(Debug, Parser)]
+pub struct CreateLookupTableParams {
+    /// Address of the liquidity pair.
+    /// 流动性对地址
+    pub lb_pair: Pubkey,
+    /// Lower bin id of the range to cover.
+    /// 需要覆盖的最低bin ID
+    #[clap(long, allow_hyphen_values = true)]
+    pub lower_bin_id: i32,
+    /// Upper bin id of the range to cover.
+    /// 需要覆盖的最高bin ID
+    #[clap(long, allow_hyphen_values = true)]
+    pub upper_bin_id: i32,
+    /// Existing lookup table to extend. A new lookup table is created when not provided.
+    /// 需要扩展的已有地址查找表，未提供时创建新的查找表
+    #[clap(long)]
+    pub lookup_table: Option<Pubkey>,
+}
+
+/// 创建或扩展包含流动性对常用账户及bin数组PDA的地址查找表
+/// Create or extend an address lookup table holding the pair accounts and bin array PDAs
+pub async fn execute_create_lookup_table<C: Deref<Target = impl Signer> + Clone>(
+    params: CreateLookupTableParams,
+    program: &Program<C>,
+    transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
+) -> Result<Pubkey> {
+    let CreateLookupTableParams {
+        lb_pair,
+        lower_bin_id,
+        upper_bin_id,
+        lookup_table,
+    } = params;
+
+    ensure!(
+        lower_bin_id <= upper_bin_id,
+        "lower_bin_id must be less than or equal to upper_bin_id"
+    );
+
+    let rpc_client = program.rpc();
+
+    let lb_pair_state: LbPair = rpc_client
+        .get_account_and_deserialize(&lb_pair, |account| {
+            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
+        })
+        .await?;
+
+    let [token_x_program, token_y_program] = lb_pair_state.get_token_programs()?;
+
+    let (bin_array_bitmap_extension, _bump) = derive_bin_array_bitmap_extension(lb_pair);
+    let (event_authority, _bump) = derive_event_authority_pda();
+
+    let mut addresses = vec![
+        lb_pair,
+        lb_pair_state.reserve_x,
+        lb_pair_state.reserve_y,
+        lb_pair_state.token_x_mint,
+        lb_pair_state.token_y_mint,
+        lb_pair_state.oracle,
+        token_x_program,
+        token_y_program,
+        event_authority,
+        dlmm::ID,
+    ];
+
+    if let Some(bin_array_bitmap_extension) =
+        resolve_optional_account(&rpc_client, bin_array_bitmap_extension).await?
+    {
+        addresses.push(bin_array_bitmap_extension);
+    }
+
+    let lower_bin_array_idx = BinArray::bin_id_to_bin_array_index(lower_bin_id)?;
+    let upper_bin_array_idx = BinArray::bin_id_to_bin_array_index(upper_bin_id)?;
+
+    for idx in lower_bin_array_idx..=upper_bin_array_idx {
+        let (bin_array, _bump) = derive_bin_array_pda(lb_pair, idx.into());
+        addresses.push(bin_array);
+    }
+
+    let (lookup_table, create_ix) = match lookup_table {
+        Some(lookup_table) => {
+            // 跳过查找表中已存在的地址
+            // Skip addresses already present in the lookup table
+            let lookup_table_account = rpc_client.get_account(&lookup_table).await?;
+            let lookup_table_state = AddressLookupTable::deserialize(&lookup_table_account.data)
+                .map_err(|e| {
+                    anyhow!("Failed to deserialize lookup table {}: {}", lookup_table, e)
+                })?;
+
+            addresses.retain(|address| !lookup_table_state.addresses.contains(address));
+            (lookup_table, None)
+        }
+        None => {
+            let recent_slot = rpc_client.get_slot().await?;
+            let (create_ix, lookup_table) =
+                create_lookup_table(program.payer(), program.payer(), recent_slot);
+            (lookup_table, Some(create_ix))
+        }
+    };
+
+    if addresses.is_empty() {
+        info!(%lookup_table, "Lookup table already contains all addresses");
+        return Ok(lookup_table);
+    }
+
+    for (i, chunk) in addresses.chunks(MAX_ADDRESSES_PER_EXTEND).enumerate() {
+        let mut builder = request_with_compute_unit_price(program, compute_unit_price.clone())?;
+
+        if i == 0 {
+            if let Some(create_ix) = create_ix.clone() {
+                builder = builder.instruction(create_ix);
+            }
+        }
+
+        builder = builder.instruction(extend_lookup_table(
+            lookup_table,
+            program.payer(),
+            Some(program.payer()),
+            chunk.to_vec(),
+        ));
+
+        let signature = builder
+            .send_with_spinner_and_config(transaction_config)
+            .await;
+
+        info!(?signature, "Extend lookup table");
+
+        signature?;
+    }
+
+    info!(
+        %lookup_table,
+        lower_bin_id,
+        upper_bin_id,
+        "Lookup table extended. Newly added addresses are usable after the next slot"
+    );
+
+    Ok(lookup_table)
+}
@@ -1,4 +1,6 @@
 use crate::*;
+use anchor_lang::AccountDeserialize;
+use anchor_spl::token_interface::Mint;
 use instructions::*;
 
 /// 资助奖励系统的参数结构体
@@ -13,8 +15,13 @@ pub struct FundRewardParams {
     /// 指定要资助的奖励系统索引
     pub reward_index: u64,
     /// 资助金额
-    /// 添加到奖励池中的代币数量
-    pub funding_amount: u64,
+    /// 添加到奖励池中的代币数量（最小单位）
+    #[clap(required_unless_present = "funding_amount_ui")]
+    pub funding_amount: Option<u64>,
+    /// 以UI单位表示的资助金额，根据奖励代币的小数位数转换
+    /// Funding amount in UI units, converted with the reward mint decimals
+    #[clap(long, conflicts_with = "funding_amount")]
+    pub funding_amount_ui: Option<f64>,
 }
 
 /// 执行资助奖励系统操作
@@ -30,7 +37,7 @@ pub struct FundRewardParams {
 /// * `params` - 资助参数，包括池对、奖励索引和资助金额
 /// * `program` - Solana程序客户端，用于执行链上操作
 /// * `transaction_config` - 交易配置，包含确认级别等设置
-/// * `compute_unit_price` - 可选的计算单位价格设置指令
+/// * `compute_unit_price` - 计算单位价格设置
 /// 
 /// # 返回值
 /// * `Result<()>` - 成功时返回空值，失败时返回错误
@@ -44,13 +51,14 @@ pub async fn execute_fund_reward<C: Deref<Target = impl Signer> + Clone>(
     params: FundRewardParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
-    compute_unit_price: Option<Instruction>,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<()> {
     // 解构资助奖励参数
     let FundRewardParams {
         lb_pair,
         reward_index,
         funding_amount,
+        funding_amount_ui,
     } = params;
 
     let rpc_client = program.rpc();
@@ -66,11 +74,32 @@ pub async fn execute_fund_reward<C: Deref<Target = impl Signer> + Clone>(
         .await?;
 
     // 获取指定索引的奖励信息和奖励代币地址
-    let reward_info = lb_pair_state.reward_infos[reward_index as usize];
+    let reward_info = get_initialized_reward_info(&lb_pair_state, reward_index)?;
     let reward_mint = reward_info.mint;
 
     // 获取奖励代币的程序ID（SPL Token或Token-2022）
-    let reward_mint_program = rpc_client.get_account(&reward_mint).await?.owner;
+    let reward_mint_account = rpc_client.get_account(&reward_mint).await?;
+    let reward_mint_program = reward_mint_account.owner;
+
+    // 确定资助金额，UI单位时按奖励代币小数位数转换
+    let funding_amount = match (funding_amount, funding_amount_ui) {
+        (Some(funding_amount), _) => funding_amount,
+        (None, Some(funding_amount_ui)) => {
+            let reward_mint_state = Mint::try_deserialize(&mut reward_mint_account.data.as_ref())?;
+            let funding_amount = ui_amount_to_amount(funding_amount_ui, reward_mint_state.decimals)
+                .context("Invalid funding amount")?;
+
+            info!(
+                funding_amount_ui,
+                funding_amount,
+                decimals = reward_mint_state.decimals,
+                "Converted UI funding amount"
+            );
+
+            funding_amount
+        }
+        (None, None) => bail!("Either funding_amount or --funding-amount-ui must be provided"),
+    };
 
     // 获取或创建资助者的奖励代币关联账户
     // 该账户必须有足够的代币余额来进行资助
@@ -83,10 +112,11 @@ pub async fn execute_fund_reward<C: Deref<Target = impl Signer> + Clone>(
     )
     .await?;
 
-    // 计算当前活跃箱子数组的索引和地址
+    // 获取当前活跃箱子数组的地址，未初始化时先初始化
     // 奖励分发需要更新活跃箱子数组的奖励信息
-    let active_bin_array_idx = BinArray::bin_id_to_bin_array_index(lb_pair_state.active_id)?;
-    let (bin_array, _bump) = derive_bin_array_pda(lb_pair, active_bin_array_idx as i64);
+    let (bin_array, init_bin_array_ix) =
+        get_active_bin_array_with_init_ix(&rpc_client, lb_pair, &lb_pair_state, program.payer())
+            .await?;
 
     // 生成事件权限账户PDA，用于记录资助事件
     let (event_authority, _bump) = derive_event_authority_pda();
@@ -138,13 +168,16 @@ pub async fn execute_fund_reward<C: Deref<Target = impl Signer> + Clone>(
     };
 
     // 构建并发送交易请求
-    let request_builder = program.request();
+    let mut request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
+    if let Some(init_bin_array_ix) = init_bin_array_ix {
+        request_builder = request_builder.instruction(init_bin_array_ix);
+    }
     let signature = request_builder
         .instruction(fund_reward_ix)                                // 添加资助奖励指令
         .send_with_spinner_and_config(transaction_config)          // 发送交易并等待确认
         .await;
 
-    println!("Fund reward. Signature: {:#?}", signature);
+    info!(?signature, "Fund reward");
 
     // 检查交易是否成功执行
     signature?;
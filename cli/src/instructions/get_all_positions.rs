@@ -1,5 +1,6 @@
+use std::collections::HashMap;
+
 use crate::*;
-use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
 
 /// 获取所有头寸的参数结构体
 /// Parameters for getting all positions
@@ -8,55 +9,124 @@ pub struct GetAllPositionsParams {
     /// 流动性对的地址 / Address of the liquidity pair
     #[clap(long)]
     lb_pair: Pubkey,
-    /// 头寸所有者 / Owner of position
+    /// 头寸所有者，未提供时列出交易对所有所有者的头寸并汇总流动性 / Owner of position. When omitted, positions of every owner of the pair are listed and their liquidity aggregated
+    #[clap(long)]
+    owner: Option<Pubkey>,
+    /// 最多显示的头寸数量，未提供时显示全部 / Maximum number of positions to show. Shows every position when omitted
     #[clap(long)]
-    owner: Pubkey,
+    limit: Option<usize>,
+    /// 按头寸地址排序后跳过的头寸数量，用于分页 / Number of positions to skip in the position address ordering, for
+    /// paging
+    #[clap(long, default_value_t = 0)]
+    offset: usize,
+    /// 只输出头寸总数 / Only print the total number of positions
+    #[clap(long, conflicts_with_all = ["limit", "offset"])]
+    count_only: bool,
 }
 
 /// 执行获取所有头寸操作
 /// Execute get all positions operation
 pub async fn execute_get_all_positions<C: Deref<Target = impl Signer> + Clone>(
-    program: &Program<C>,
+    client: &DlmmClient<C>,
     params: GetAllPositionsParams,
 ) -> Result<()> {
     // 解构参数
     // Destructure parameters
-    let GetAllPositionsParams { lb_pair, owner } = params;
-
-    let rpc_client = program.rpc();
-
-    // 设置账户配置，使用Base64编码
-    // Set account configuration with Base64 encoding
-    let account_config = RpcAccountInfoConfig {
-        encoding: Some(UiAccountEncoding::Base64),
-        ..Default::default()
-    };
-    
-    // 设置程序账户查询配置，按钱包和流动性对过滤头寸
-    // Set program account query configuration, filter positions by wallet and pair
-    let config = RpcProgramAccountsConfig {
-        filters: Some(position_filter_by_wallet_and_pair(owner, lb_pair)),
-        account_config,
-        ..Default::default()
-    };
-
-    // 获取所有匹配的头寸账户
-    // Get all matching position accounts
-    let accounts = rpc_client
-        .get_program_accounts_with_config(&dlmm::ID, config)
-        .await?;
-
-    // 遍历并显示所有头寸信息
-    // Iterate and display all position information
-    for (position_key, position_raw_account) in accounts {
-        // 解析头寸状态
-        // Parse position state
-        let position_state: PositionV2 =
-            bytemuck::pod_read_unaligned(&position_raw_account.data[8..]);
+    let GetAllPositionsParams {
+        lb_pair,
+        owner,
+        limit,
+        offset,
+        count_only,
+    } = params;
+
+    // 获取所有匹配的头寸，未提供所有者时获取交易对所有所有者的头寸
+    // Get all matching positions, or the positions of every owner of the pair when no owner is provided
+    let mut positions = client.get_positions(lb_pair, owner).await?;
+    let position_count = positions.len();
+
+    if count_only {
+        println!("Positions {}", position_count);
+        return Ok(());
+    }
+
+    // 按头寸地址排序，使输出及分页结果确定
+    // Sort by position address so the output and the pages are deterministic
+    positions.sort_by_key(|(position_key, _)| *position_key);
+
+    // 按所有者汇总的流动性份额，汇总覆盖所有头寸而不只是当前页
+    // Liquidity shares aggregated by owner. The aggregation covers every position, not only the current page
+    let mut owner_liquidity: HashMap<Pubkey, u128> = HashMap::new();
+    let page_end = limit.map_or(position_count, |limit| offset.saturating_add(limit));
+
+    // 遍历头寸并显示当前页的头寸信息
+    // Iterate the positions and display the information of the positions in the current page
+    for (index, (position_key, position_state)) in positions.into_iter().enumerate() {
+        let in_page = (offset..page_end).contains(&index);
+
+        if owner.is_some() {
+            if in_page {
+                println!(
+                    "Position {} fee owner {}",
+                    position_key, position_state.fee_owner
+                );
+            }
+            continue;
+        }
+
+        let liquidity = position_state
+            .liquidity_shares
+            .iter()
+            .fold(0u128, |total, share| total.saturating_add(*share));
+
+        if in_page {
+            println!(
+                "Position {} owner {} fee owner {} liquidity {}",
+                position_key, position_state.owner, position_state.fee_owner, liquidity
+            );
+        }
+
+        let total = owner_liquidity.entry(position_state.owner).or_default();
+        *total = total.saturating_add(liquidity);
+    }
+
+    if limit.is_some() || offset > 0 {
+        println!(
+            "Showing positions {} to {} of {}",
+            offset.min(position_count),
+            page_end.min(position_count),
+            position_count
+        );
+    }
+
+    if owner.is_none() {
+        let total_liquidity = owner_liquidity
+            .values()
+            .fold(0u128, |total, liquidity| total.saturating_add(*liquidity));
+
         println!(
-            "Position {} fee owner {}",
-            position_key, position_state.fee_owner
+            "Positions {} unique owners {} total liquidity {}",
+            position_count,
+            owner_liquidity.len(),
+            total_liquidity
         );
+
+        // 按流动性从高到低显示每个所有者的占比，用于了解流动性集中度
+        // Show the share of every owner from the largest liquidity, to understand the liquidity concentration
+        let mut owner_liquidity = owner_liquidity.into_iter().collect::<Vec<_>>();
+        owner_liquidity.sort_by(|a, b| b.1.cmp(&a.1));
+
+        for (owner, liquidity) in owner_liquidity {
+            let share_pct = if total_liquidity == 0 {
+                0.0
+            } else {
+                liquidity as f64 / total_liquidity as f64 * 100.0
+            };
+            println!(
+                "Owner {} liquidity {} ({:.2}%)",
+                owner, liquidity, share_pct
+            );
+        }
     }
 
     Ok(())
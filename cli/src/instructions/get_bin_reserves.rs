@@ -0,0 +1,124 @@
+use anchor_lang::AccountDeserialize;
+use anchor_spl::token_interface::Mint;
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::*;
+
+/// 查询bin储备的参数结构体
+/// Parameters for querying bin reserves
+#[derive(Debug, Parser)]
+pub struct GetBinReservesParams {
+    /// 流动性交易对地址
+    /// Liquidity pair address
+    pub lb_pair: Pubkey,
+    /// 查询范围的最低bin ID
+    /// Lower bin id of the range to query
+    #[clap(long, allow_hyphen_values = true)]
+    pub lower_bin_id: i32,
+    /// 查询范围的最高bin ID
+    /// Upper bin id of the range to query
+    #[clap(long, allow_hyphen_values = true)]
+    pub upper_bin_id: i32,
+    /// 包含范围内的空bin（数量为0）
+    /// Include empty bins within the range (as zeros)
+    #[clap(long)]
+    pub include_empty: bool,
+}
+
+/// 以JSON数组输出指定bin范围内每个bin的代币数量、价格及流动性
+/// Print the token amounts, price and liquidity of each bin within the range as a JSON array
+pub async fn execute_get_bin_reserves<C: Deref<Target = impl Signer> + Clone>(
+    params: GetBinReservesParams,
+    program: &Program<C>,
+) -> Result<()> {
+    let GetBinReservesParams {
+        lb_pair,
+        lower_bin_id,
+        upper_bin_id,
+        include_empty,
+    } = params;
+
+    ensure!(
+        lower_bin_id <= upper_bin_id,
+        "lower_bin_id must be less than or equal to upper_bin_id"
+    );
+
+    let rpc_client = program.rpc();
+
+    let lb_pair_state: LbPair = rpc_client
+        .get_account_and_deserialize(&lb_pair, |account| {
+            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
+        })
+        .await?;
+
+    let bin_array_indexes = BinArray::get_bin_array_indexes_coverage(lower_bin_id, upper_bin_id)?;
+    let bin_array_keys = bin_array_indexes
+        .iter()
+        .map(|&index| derive_bin_array_pda(lb_pair, index.into()).0)
+        .collect::<Vec<_>>();
+
+    let accounts = rpc_client
+        .get_multiple_accounts(
+            &[
+                vec![lb_pair_state.token_x_mint, lb_pair_state.token_y_mint],
+                bin_array_keys,
+            ]
+            .concat(),
+        )
+        .await?;
+
+    let token_x_mint_account = accounts[0].as_ref().context("token_x_mint not found")?;
+    let token_y_mint_account = accounts[1].as_ref().context("token_y_mint not found")?;
+
+    let token_x_mint = Mint::try_deserialize(&mut token_x_mint_account.data.as_ref())?;
+    let token_y_mint = Mint::try_deserialize(&mut token_y_mint_account.data.as_ref())?;
+
+    // 未初始化的bin数组视为全部为空
+    // Uninitialized bin arrays are treated as entirely empty
+    let bin_arrays = accounts[2..]
+        .iter()
+        .flatten()
+        .map(|account| bytemuck::pod_read_unaligned::<BinArray>(&account.data[8..]))
+        .collect::<Vec<_>>();
+
+    let mut bins = vec![];
+
+    for bin_id in lower_bin_id..=upper_bin_id {
+        let bin = bin_arrays
+            .iter()
+            .find(|bin_array| bin_array.is_bin_id_within_range(bin_id).unwrap_or(false))
+            .map(|bin_array| bin_array.get_bin(bin_id))
+            .transpose()?;
+
+        let (amount_x, amount_y, liquidity) = bin
+            .map(|bin| (bin.amount_x, bin.amount_y, bin.liquidity_supply))
+            .unwrap_or_default();
+
+        if !include_empty && amount_x == 0 && amount_y == 0 {
+            continue;
+        }
+
+        let price = get_ui_price_from_id(
+            lb_pair_state.bin_step,
+            bin_id,
+            token_x_mint.decimals,
+            token_y_mint.decimals,
+        )
+        .and_then(|price| price.to_f64())
+        .with_context(|| format!("UI price of bin {} overflow", bin_id))?;
+
+        // u128流动性以字符串输出，避免JSON数值精度丢失
+        // u128 liquidity is emitted as a string to avoid JSON number precision loss
+        bins.push(serde_json::json!({
+            "bin_id": bin_id,
+            "amount_x": amount_x,
+            "amount_y": amount_y,
+            "price": price,
+            "liquidity": liquidity.to_string(),
+        }));
+    }
+
+    println!("{}", serde_json::to_string_pretty(&bins)?);
+
+    Ok(())
+}
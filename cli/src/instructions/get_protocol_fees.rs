@@ -0,0 +1,56 @@
+use anchor_lang::AccountDeserialize;
+use anchor_spl::token_interface::Mint;
+
+use crate::*;
+
+/// 查询协议费用的参数结构体
+/// Parameters for getting the protocol fees
+#[derive(Debug, Parser)]
+pub struct GetProtocolFeesParams {
+    /// 流动性交易对地址
+    /// Liquidity pair address
+    pub lb_pair: Pubkey,
+}
+
+/// 执行查询协议费用指令，显示流动性对累积的协议费用
+/// Executes the get protocol fees instruction, showing the protocol fees accrued by the pair
+pub async fn execute_get_protocol_fees<C: Deref<Target = impl Signer> + Clone>(
+    params: GetProtocolFeesParams,
+    program: &Program<C>,
+) -> Result<()> {
+    let GetProtocolFeesParams { lb_pair } = params;
+
+    let rpc_client = program.rpc();
+
+    let lb_pair_state: LbPair = rpc_client
+        .get_account_and_deserialize(&lb_pair, |account| {
+            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
+        })
+        .await?;
+
+    let mut accounts = rpc_client
+        .get_multiple_accounts(&[lb_pair_state.token_x_mint, lb_pair_state.token_y_mint])
+        .await?;
+
+    let token_x_mint_account = accounts[0].take().context("token_x_mint not found")?;
+    let token_y_mint_account = accounts[1].take().context("token_y_mint not found")?;
+
+    let token_x_mint = Mint::try_deserialize(&mut token_x_mint_account.data.as_ref())?;
+    let token_y_mint = Mint::try_deserialize(&mut token_y_mint_account.data.as_ref())?;
+
+    let ProtocolFee { amount_x, amount_y } = lb_pair_state.protocol_fee;
+
+    let ui_amount_x = amount_to_ui_amount(amount_x, token_x_mint.decimals).context("overflow")?;
+    let ui_amount_y = amount_to_ui_amount(amount_y, token_y_mint.decimals).context("overflow")?;
+
+    println!(
+        "Protocol fee X: {} ({} {})",
+        amount_x, ui_amount_x, lb_pair_state.token_x_mint
+    );
+    println!(
+        "Protocol fee Y: {} ({} {})",
+        amount_y, ui_amount_y, lb_pair_state.token_y_mint
+    );
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use anchor_lang::{prelude::Clock, AccountDeserialize};
+use anchor_spl::token_interface::Mint;
+
+use crate::*;
+
+/// 计算时间加权平均价格的参数结构体
+/// Parameters for computing the time weighted average price
+#[derive(Debug, Parser)]
+pub struct GetTwapParams {
+    /// 流动性交易对地址
+    /// Liquidity pair address
+    pub lb_pair: Pubkey,
+    /// 回看窗口（秒）
+    /// Lookback window in seconds
+    pub window_seconds: u32,
+}
+
+/// 执行计算时间加权平均价格，只读取账户不发送交易
+/// Executes computing the time weighted average price. Only reads accounts, no transaction is sent
+///
+/// # 功能说明 / Functionality
+/// 读取交易对预言机的观测样本，计算回看窗口内的时间加权平均活跃bin ID并转换为UI价格。
+/// 窗口起点取不晚于窗口开始的最近样本，因此实际观测窗口可能略长于请求的窗口。预言机历史不足时返回错误
+/// Reads the observation samples of the pair oracle, computes the time weighted average active bin id over the
+/// lookback window and converts it to a UI price. The window starts at the latest sample updated at or before the
+/// requested start, so the observed window can be slightly longer than requested. Returns an error when the oracle
+/// history doesn't cover the window
+pub async fn execute_get_twap<C: Deref<Target = impl Signer> + Clone>(
+    params: GetTwapParams,
+    program: &Program<C>,
+) -> Result<()> {
+    let GetTwapParams {
+        lb_pair,
+        window_seconds,
+    } = params;
+
+    ensure!(window_seconds > 0, "window_seconds must be positive");
+
+    let rpc_client = program.rpc();
+
+    let lb_pair_state: LbPair = rpc_client
+        .get_account_and_deserialize(&lb_pair, |account| {
+            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
+        })
+        .await?;
+
+    let (oracle_key, _bump) = derive_oracle_pda(lb_pair);
+    let mut accounts = rpc_client
+        .get_multiple_accounts(&[
+            oracle_key,
+            solana_sdk::sysvar::clock::ID,
+            lb_pair_state.token_x_mint,
+            lb_pair_state.token_y_mint,
+        ])
+        .await?;
+
+    let oracle_account = accounts[0].take().context("oracle not found")?;
+    let oracle = DynamicOracle::from_account_data(&oracle_account.data)?;
+    let clock_account = accounts[1].take().context("clock not found")?;
+    let clock = bincode::deserialize::<Clock>(&clock_account.data)?;
+    let mint_x_account = accounts[2].take().context("mint x not found")?;
+    let mint_x = Mint::try_deserialize(&mut mint_x_account.data.as_ref())?;
+    let mint_y_account = accounts[3].take().context("mint y not found")?;
+    let mint_y = Mint::try_deserialize(&mut mint_y_account.data.as_ref())?;
+
+    let (average_bin_id, observed_window) = oracle
+        .get_time_weighted_average_bin_id(
+            lb_pair_state.active_id,
+            clock.unix_timestamp,
+            window_seconds.into(),
+        )
+        .with_context(|| {
+            let history = oracle
+                .samples()
+                .first()
+                .map(|oldest| clock.unix_timestamp - oldest.last_updated_at)
+                .unwrap_or_default();
+            format!(
+                "Oracle history covers {} seconds, less than the {} seconds window. Use a shorter window or increase the oracle length with increase-oracle-length (current length {})",
+                history, window_seconds, oracle.metadata.length
+            )
+        })?;
+
+    let twap = get_ui_price_from_fractional_id(
+        lb_pair_state.bin_step,
+        average_bin_id,
+        mint_x.decimals,
+        mint_y.decimals,
+    )
+    .context("TWAP price overflow")?;
+
+    println!("Pair {} TWAP", lb_pair);
+    println!(
+        "Window {} seconds (observed {} seconds)",
+        window_seconds, observed_window
+    );
+    println!("Average active bin id {}", average_bin_id);
+    println!("TWAP {}", twap);
+
+    Ok(())
+}
@@ -0,0 +1,260 @@
+use std::collections::{BTreeMap, HashMap};
+use std::result::Result::Ok;
+
+use crate::*;
+use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::StateWithExtensions, state::Account as TokenAccountState,
+};
+use futures_util::stream::{self, StreamExt};
+use indicatif::{ProgressBar, ProgressStyle};
+use instructions::*;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+
+/// 领取所有仓位手续费及奖励的参数结构体
+/// Parameters for harvesting the fees and rewards of all positions
+#[derive(Debug, Parser)]
+pub struct HarvestAllParams {
+    /// 同时领取的最大仓位数量
+    /// Maximum number of positions harvested concurrently
+    #[clap(long, default_value_t = 4)]
+    pub concurrency: usize,
+}
+
+/// 执行领取所有仓位手续费及奖励指令
+/// Executes the harvest all instruction
+///
+/// # 功能说明 / Functionality
+/// 枚举支付者在所有交易对的仓位，计算每个仓位可领取的手续费及奖励并跳过没有可领取数量的仓位，
+/// 然后并发领取并以进度条显示进度。某笔交易失败时继续发送其余交易，最后按代币汇总接收账户实际收到的数量
+///
+/// Enumerates the positions of the payer across all pairs, computes the claimable fees and rewards of every
+/// position and skips the ones with nothing to claim. The rest are harvested concurrently with a progress bar. The
+/// remaining transactions are still sent when one fails, and the amounts actually received by the receiving token
+/// accounts are summarized per token
+pub async fn execute_harvest_all<C: Deref<Target = impl Signer> + Clone>(
+    params: HarvestAllParams,
+    program: &Program<C>,
+    transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
+) -> Result<()> {
+    let HarvestAllParams { concurrency } = params;
+
+    ensure!(concurrency > 0, "concurrency must be greater than 0");
+
+    let rpc_client = program.rpc();
+    let owner = program.payer();
+
+    let config = RpcProgramAccountsConfig {
+        filters: Some(position_filter_by_wallet(owner)),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let positions = get_program_accounts_with_retry(&rpc_client, config)
+        .await?
+        .into_iter()
+        .map(|(position, account)| {
+            let position_state = deserialize_position_v2(&account.data)
+                .with_context(|| format!("Failed to read position {}", position))?;
+            Ok((position, position_state))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    info!(%owner, positions = positions.len(), "Found positions");
+
+    if positions.is_empty() {
+        return Ok(());
+    }
+
+    let mut lb_pair_keys = positions
+        .iter()
+        .map(|(_, position_state)| position_state.lb_pair)
+        .collect::<Vec<_>>();
+    lb_pair_keys.sort();
+    lb_pair_keys.dedup();
+
+    let mut bin_array_keys = positions
+        .iter()
+        .map(|(_, position_state)| position_state.get_bin_array_keys_coverage())
+        .collect::<Result<Vec<_>>>()?
+        .concat();
+    bin_array_keys.sort();
+    bin_array_keys.dedup();
+
+    let mut lb_pairs: HashMap<Pubkey, LbPair> = HashMap::new();
+    let lb_pair_accounts = rpc_client
+        .get_multiple_accounts_chunked(&lb_pair_keys)
+        .await?;
+    for (key, account) in lb_pair_keys.into_iter().zip(lb_pair_accounts) {
+        let account = account.with_context(|| format!("Liquidity pair {} not found", key))?;
+        lb_pairs.insert(key, bytemuck::pod_read_unaligned(&account.data[8..]));
+    }
+
+    let mut bin_arrays: HashMap<Pubkey, BinArray> = HashMap::new();
+    let bin_array_accounts = rpc_client
+        .get_multiple_accounts_chunked(&bin_array_keys)
+        .await?;
+    for (key, account) in bin_array_keys.into_iter().zip(bin_array_accounts) {
+        let account = account.with_context(|| format!("Bin array {} not found", key))?;
+        bin_arrays.insert(key, bytemuck::pod_read_unaligned(&account.data[8..]));
+    }
+
+    // 计算每个仓位可领取的手续费及奖励，跳过没有可领取数量的仓位
+    // Compute the claimable fees and rewards of every position, skipping the ones with nothing to claim
+    let current_time = current_timestamp(&rpc_client).await?;
+    let mut harvests = vec![];
+    // 接收领取代币的代币账户及其铸造地址 / Token accounts receiving the claimed tokens, with their mint
+    let mut receivers: BTreeMap<Pubkey, Pubkey> = BTreeMap::new();
+
+    for (position, position_state) in positions {
+        let position_bin_arrays = position_state
+            .get_bin_array_keys_coverage()?
+            .iter()
+            .map(|key| bin_arrays[key])
+            .collect::<Vec<_>>();
+
+        let lb_pair_state = &lb_pairs[&position_state.lb_pair];
+        let claimable = position_state.get_claimable_fees_and_rewards(
+            lb_pair_state,
+            &position_bin_arrays,
+            current_time,
+        )?;
+
+        if claimable.is_zero() {
+            continue;
+        }
+
+        let claim = build_claim_fee_and_rewards_instructions(
+            program,
+            transaction_config,
+            compute_unit_price.clone(),
+            position,
+            &position_state,
+            lb_pair_state,
+            &claimable,
+        )
+        .await?;
+
+        receivers.extend(claim.receivers);
+
+        let transactions = pack_instructions(
+            claim.instructions,
+            &owner,
+            MAX_ACCOUNTS_PER_TRANSACTION,
+            compute_unit_price.clone(),
+            CLAIM_COMPUTE_UNITS,
+        )?;
+
+        harvests.push((position, transactions));
+    }
+
+    let total = harvests.len();
+    info!(total, "Harvesting positions");
+
+    if total == 0 {
+        return Ok(());
+    }
+
+    // 领取前后读取接收账户的余额，汇总实际收到的数量（已扣除转账手续费），部分交易失败时也准确
+    // Read the receiving account balances before and after harvesting, to summarize the amounts actually received
+    // (after transfer fees), which stay accurate when only some transactions land
+    let receiver_keys = receivers.keys().copied().collect::<Vec<_>>();
+    let balances_before = get_token_balances(&rpc_client, &receiver_keys).await?;
+
+    let progress = ProgressBar::new(total as u64).with_style(ProgressStyle::with_template(
+        "{bar:40} {pos}/{len} positions",
+    )?);
+
+    let mut harvested_positions = 0;
+    let mut partial_positions = 0;
+    let mut failed_transactions = 0;
+
+    let mut results = stream::iter(harvests)
+        .map(|(position, transactions)| async move {
+            // 每笔交易独立领取，某笔交易失败时继续发送其余交易
+            // Every transaction claims on its own, the rest are still sent when one fails
+            let mut results = vec![];
+            for ixs in transactions.iter() {
+                results.push(send_with_blockhash_refresh(program, ixs, transaction_config).await);
+            }
+            (position, results)
+        })
+        .buffer_unordered(concurrency);
+
+    while let Some((position, results)) = results.next().await {
+        let landed = results.iter().filter(|result| result.is_ok()).count();
+
+        for error in results.iter().filter_map(|result| result.as_ref().err()) {
+            failed_transactions += 1;
+            progress.suspend(|| error!(%position, %error, "Harvest transaction failed"));
+        }
+
+        if landed == results.len() {
+            harvested_positions += 1;
+        } else if landed > 0 {
+            partial_positions += 1;
+            progress.suspend(|| {
+                warn!(
+                    %position,
+                    "Partially harvested, {} of {} transactions landed",
+                    landed,
+                    results.len()
+                )
+            });
+        }
+
+        progress.inc(1);
+    }
+
+    progress.finish_and_clear();
+
+    let balances_after = get_token_balances(&rpc_client, &receiver_keys).await?;
+
+    let mut received: BTreeMap<Pubkey, u64> = BTreeMap::new();
+    for ((_, mint), (before, after)) in receivers
+        .iter()
+        .zip(balances_before.into_iter().zip(balances_after))
+    {
+        let amount = received.entry(*mint).or_default();
+        *amount = amount.saturating_add(after.saturating_sub(before));
+    }
+
+    println!(
+        "Harvested {} of {} positions, {} partially",
+        harvested_positions, total, partial_positions
+    );
+    for (mint, amount) in received.iter() {
+        println!("Token {} received {}", mint, amount);
+    }
+
+    ensure!(
+        failed_transactions == 0,
+        "{} harvest transactions failed",
+        failed_transactions
+    );
+
+    Ok(())
+}
+
+/// 读取代币账户的余额，账户不存在时为0，返回的余额与地址一一对应
+/// Read the balances of the token accounts, 0 for a missing account, returning the balances in the order of the
+/// addresses
+async fn get_token_balances(rpc_client: &RpcClient, token_accounts: &[Pubkey]) -> Result<Vec<u64>> {
+    rpc_client
+        .get_multiple_accounts_chunked(token_accounts)
+        .await?
+        .into_iter()
+        .map(|account| match account {
+            Some(account) => Ok(
+                StateWithExtensions::<TokenAccountState>::unpack(&account.data)?
+                    .base
+                    .amount,
+            ),
+            None => Ok(0),
+        })
+        .collect()
+}
@@ -17,3 +17,8 @@ pub use seed_liquidity_from_operator::*;
 /// Seed liquidity in a single bin by operator
 pub mod seed_liquidity_single_bin_by_operator;
 pub use seed_liquidity_single_bin_by_operator::*;
+
+/// 预览操作员播种流动性的bin分布
+/// Preview the bin distribution of seed liquidity by operator
+pub mod preview_seed;
+pub use preview_seed::*;
@@ -0,0 +1,123 @@
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::*;
+
+/// 预览播种流动性分布的参数结构体
+/// Parameters for previewing the seed liquidity distribution
+#[derive(Debug, Parser)]
+pub struct PreviewSeedParameters {
+    /// 流动性对的地址 / Address of the liquidity pair
+    #[clap(long)]
+    pub lb_pair: Pubkey,
+    /// X代币的数量 / Amount of X token
+    #[clap(long)]
+    pub amount: u64,
+    /// 最小价格 / Minimum price
+    #[clap(long)]
+    pub min_price: f64,
+    /// 最大价格 / Maximum price
+    #[clap(long)]
+    pub max_price: f64,
+    /// 曲率参数 / Curvature parameter
+    #[clap(long)]
+    pub curvature: f64,
+    /// 报价代币为X代币（反向交易对），价格为以X代币计价的Y代币价格 / Quote token is token X (reversed pair), prices are token Y priced in token X
+    #[clap(long)]
+    pub quote_is_x: bool,
+}
+
+/// 预览操作员播种流动性的bin分布，不发送任何交易
+/// Preview the bin distribution of seed liquidity by operator without sending any transaction
+pub async fn execute_preview_seed<C: Deref<Target = impl Signer> + Clone>(
+    params: PreviewSeedParameters,
+    program: &Program<C>,
+) -> Result<()> {
+    let PreviewSeedParameters {
+        lb_pair,
+        amount,
+        min_price,
+        max_price,
+        curvature,
+        quote_is_x,
+    } = params;
+
+    let rpc_client = program.rpc();
+
+    let lb_pair_state: LbPair = rpc_client
+        .get_account_and_deserialize(&lb_pair, |account| {
+            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
+        })
+        .await?;
+
+    let mut mint_cache = MintCache::new();
+    mint_cache
+        .fetch(&rpc_client, &[lb_pair_state.token_x_mint, lb_pair_state.token_y_mint])
+        .await?;
+    let token_mint_base = mint_cache.get_cached(&lb_pair_state.token_x_mint)?;
+    let token_mint_quote = mint_cache.get_cached(&lb_pair_state.token_y_mint)?;
+
+    let fund_amount = to_wei_amount(amount, token_mint_base.decimals)?;
+
+    let (pair_min_price, pair_max_price) =
+        flip_price_range_if_quote_is_x(min_price, max_price, quote_is_x);
+
+    let SeedDistribution {
+        min_bin_id,
+        max_bin_id,
+        actual_min_price,
+        actual_max_price,
+        position_number,
+        bins_amount,
+        compressed_bin_amount,
+        compression_loss,
+        decompress_multiplier,
+    } = compute_seed_distribution(
+        lb_pair_state.bin_step,
+        pair_min_price,
+        pair_max_price,
+        token_mint_base.decimals,
+        token_mint_quote.decimals,
+        fund_amount,
+        curvature,
+    )?;
+
+    let (ui_actual_min_price, ui_actual_max_price) =
+        flip_price_range_if_quote_is_x(actual_min_price, actual_max_price, quote_is_x);
+
+    println!("Bin ID | Price | Amount | Deposited amount");
+    for (bin_id, bin_amount) in bins_amount.iter() {
+        let price = get_ui_price_from_id(
+            lb_pair_state.bin_step,
+            *bin_id,
+            token_mint_base.decimals,
+            token_mint_quote.decimals,
+        )
+        .and_then(|price| price.to_f64())
+        .with_context(|| format!("UI price of bin {} overflow", bin_id))?;
+        let price = flip_price_if_quote_is_x(price, quote_is_x);
+
+        let deposited_amount = compressed_bin_amount
+            .get(bin_id)
+            .map(|amount| u64::from(*amount) * decompress_multiplier)
+            .unwrap_or_default();
+
+        println!(
+            "{} | {} | {} | {}",
+            bin_id, price, bin_amount, deposited_amount
+        );
+    }
+
+    println!("Total amount: {}", fund_amount);
+    println!(
+        "Compression loss: {} (deposited to bin {})",
+        compression_loss,
+        max_bin_id - 1
+    );
+    println!("Position: {}", position_number);
+    println!(
+        "Min price: {} Max price: {} Actual min price: {} Actual max price: {} Min bin id: {} Max bin id: {}",
+        min_price, max_price, ui_actual_min_price, ui_actual_max_price, min_bin_id, max_bin_id
+    );
+
+    Ok(())
+}
@@ -1,8 +1,14 @@
 use crate::*;
-use anchor_lang::AccountDeserialize;
-use anchor_spl::token_interface::Mint;
 use instructions::*;
 
+/// 移除一个头寸全部流动性预估的计算单元
+/// Estimated compute units to remove all the liquidity of a position
+const REMOVE_LIQUIDITY_COMPUTE_UNITS: u32 = 400_000;
+
+/// 关闭头寸预估的计算单元
+/// Estimated compute units to close a position
+const CLOSE_POSITION_COMPUTE_UNITS: u32 = 50_000;
+
 /// 按价格范围移除流动性的参数结构体
 /// Parameters for removing liquidity by price range
 #[derive(Debug, Parser)]
@@ -15,15 +21,34 @@ pub struct RemoveLiquidityByPriceRangeParameters {
     pub min_price: f64,
     /// 最大价格 / Maximum price
     pub max_price: f64,
+    /// 接收关闭头寸回收租金的账户，默认为交易付款人 / Account receiving the reclaimed rent of the closed positions.
+    /// Defaults to the payer
+    #[clap(long, conflicts_with = "no_close")]
+    pub rent_receiver: Option<Pubkey>,
+    /// 只移除流动性，不关闭头寸，以便之后重新添加流动性 / Only remove the liquidity without closing the positions, to
+    /// add liquidity again later
+    #[clap(long)]
+    pub no_close: bool,
+    /// 不申领手续费。关闭头寸前必须申领手续费，因此需要同时指定 `--no-close` / Don't claim the fees. The fees must be
+    /// claimed before closing a position, so `--no-close` is required as well
+    #[clap(long, requires = "no_close")]
+    pub no_claim_fees: bool,
 }
 
 /// 执行按价格范围移除流动性
 /// Execute removing liquidity by price range
+///
+/// # 功能说明 / Functionality
+/// 每个头寸的移除流动性、申领费用及关闭头寸指令总在同一笔交易中，任一交易失败时停止并报告已处理及失败的头寸。
+/// `--no-close` 及 `--no-claim-fees` 可跳过关闭头寸及申领费用，默认执行全部步骤
+/// The remove liquidity, claim fee and close position instructions of a position are always in the same
+/// transaction. Stops at the first failed transaction and reports the processed and the failed positions.
+/// `--no-close` and `--no-claim-fees` skip closing the positions and claiming the fees, every step runs by default
 pub async fn execute_remove_liquidity_by_price_range<C: Deref<Target = impl Signer> + Clone>(
     params: RemoveLiquidityByPriceRangeParameters,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
-    compute_unit_price: Option<Instruction>,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<()> {
     // 解构参数
     // Destructure parameters
@@ -32,6 +57,9 @@ pub async fn execute_remove_liquidity_by_price_range<C: Deref<Target = impl Sign
         base_position_key,
         min_price,
         max_price,
+        rent_receiver,
+        no_close,
+        no_claim_fees,
     } = params;
 
     let rpc_client = program.rpc();
@@ -51,39 +79,22 @@ pub async fn execute_remove_liquidity_by_price_range<C: Deref<Target = impl Sign
 
     // 获取代币铸币账户信息
     // Get token mint account information
-    let mut accounts = rpc_client
-        .get_multiple_accounts(&[lb_pair_state.token_x_mint, lb_pair_state.token_y_mint])
+    let mut mint_cache = MintCache::new();
+    mint_cache
+        .fetch(&rpc_client, &[lb_pair_state.token_x_mint, lb_pair_state.token_y_mint])
         .await?;
+    let token_mint_base = mint_cache.get_cached(&lb_pair_state.token_x_mint)?;
+    let token_mint_quote = mint_cache.get_cached(&lb_pair_state.token_y_mint)?;
 
-    let token_mint_base_account = accounts[0].take().context("token_mint_base not found")?;
-    let token_mint_quote_account = accounts[1].take().context("token_mint_quote not found")?;
-
-    let token_mint_base = Mint::try_deserialize(&mut token_mint_base_account.data.as_ref())?;
-    let token_mint_quote = Mint::try_deserialize(&mut token_mint_quote_account.data.as_ref())?;
-
-    // 将最小价格转换为每lamport价格并计算对应的bin ID
-    // Convert min price to per-lamport price and calculate corresponding bin ID
-    let min_price_per_lamport = price_per_token_to_per_lamport(
+    // 将价格范围转换为对应的bin ID范围
+    // Convert the price range to the corresponding bin ID range
+    let (min_active_id, max_active_id) = convert_min_max_ui_price_to_min_max_bin_id(
+        bin_step,
         min_price,
-        token_mint_base.decimals,
-        token_mint_quote.decimals,
-    )
-    .context("price_per_token_to_per_lamport overflow")?;
-
-    let min_active_id = get_id_from_price(bin_step, &min_price_per_lamport, Rounding::Up)
-        .context("get_id_from_price overflow")?;
-
-    // 将最大价格转换为每lamport价格并计算对应的bin ID
-    // Convert max price to per-lamport price and calculate corresponding bin ID
-    let max_price_per_lamport = price_per_token_to_per_lamport(
         max_price,
         token_mint_base.decimals,
         token_mint_quote.decimals,
-    )
-    .context("price_per_token_to_per_lamport overflow")?;
-
-    let max_active_id = get_id_from_price(bin_step, &max_price_per_lamport, Rounding::Up)
-        .context("get_id_from_price overflow")?;
+    )?;
 
     // 验证价格范围有效
     // Verify price range is valid
@@ -114,12 +125,10 @@ pub async fn execute_remove_liquidity_by_price_range<C: Deref<Target = impl Sign
     let (event_authority, _bump) = derive_event_authority_pda();
 
     let (bin_array_bitmap_extension, _bump) = derive_bin_array_bitmap_extension(lb_pair);
-    let bin_array_bitmap_extension = rpc_client
-        .get_account(&bin_array_bitmap_extension)
-        .await
-        .map(|_| bin_array_bitmap_extension)
-        .ok()
-        .or(Some(dlmm::ID));
+    let bin_array_bitmap_extension =
+        resolve_optional_account(&rpc_client, bin_array_bitmap_extension)
+            .await?
+            .or(Some(dlmm::ID));
 
     let width = DEFAULT_BIN_PER_POSITION as i32;
 
@@ -138,6 +147,8 @@ pub async fn execute_remove_liquidity_by_price_range<C: Deref<Target = impl Sign
         transfer_hook_remaining_accounts.extend(remaining_accounts);
     };
 
+    let mut position_groups = vec![];
+
     // 遍历价格范围内的所有bin ID
     // Iterate through all bin IDs in the price range
     for i in min_active_id..=max_active_id {
@@ -152,6 +163,7 @@ pub async fn execute_remove_liquidity_by_price_range<C: Deref<Target = impl Sign
             // 解析头寸状态
             // Parse position state
             let position_state: PositionV2 = bytemuck::pod_read_unaligned(&account.data[8..]);
+            let mut instructions = vec![];
 
             let bin_arrays_account_meta = position_state.get_bin_array_accounts_meta_coverage()?;
 
@@ -161,11 +173,6 @@ pub async fn execute_remove_liquidity_by_price_range<C: Deref<Target = impl Sign
             ]
             .concat();
 
-            // 设置计算单元限制
-            // Set compute unit limit
-            let mut instructions =
-                vec![ComputeBudgetInstruction::set_compute_unit_limit(1_400_000)];
-
             // 创建移除流动性指令
             // Create remove liquidity instruction
             let main_accounts = dlmm::client::accounts::RemoveLiquidityByRange2 {
@@ -207,69 +214,207 @@ pub async fn execute_remove_liquidity_by_price_range<C: Deref<Target = impl Sign
 
             // 创建申领费用指令
             // Create claim fee instruction
-            let main_accounts = dlmm::client::accounts::ClaimFee2 {
-                lb_pair,
-                position,
-                sender: program.payer(),
-                reserve_x: lb_pair_state.reserve_x,
-                reserve_y: lb_pair_state.reserve_y,
-                token_x_mint: lb_pair_state.token_x_mint,
-                token_y_mint: lb_pair_state.token_y_mint,
-                token_program_x: token_x_program,
-                token_program_y: token_y_program,
-                memo_program: spl_memo::ID,
-                event_authority,
-                program: dlmm::ID,
-                user_token_x,
-                user_token_y,
+            if !no_claim_fees {
+                let main_accounts = dlmm::client::accounts::ClaimFee2 {
+                    lb_pair,
+                    position,
+                    sender: program.payer(),
+                    reserve_x: lb_pair_state.reserve_x,
+                    reserve_y: lb_pair_state.reserve_y,
+                    token_x_mint: lb_pair_state.token_x_mint,
+                    token_y_mint: lb_pair_state.token_y_mint,
+                    token_program_x: token_x_program,
+                    token_program_y: token_y_program,
+                    memo_program: spl_memo::ID,
+                    event_authority,
+                    program: dlmm::ID,
+                    user_token_x,
+                    user_token_y,
+                }
+                .to_account_metas(None);
+
+                let data = dlmm::client::args::ClaimFee2 {
+                    min_bin_id: position_state.lower_bin_id,
+                    max_bin_id: position_state.upper_bin_id,
+                    remaining_accounts_info: remaining_accounts_info.clone(),
+                }
+                .data();
+
+                let accounts = [main_accounts.to_vec(), remaining_accounts.clone()].concat();
+
+                let claim_fee_ix = Instruction {
+                    program_id: dlmm::ID,
+                    accounts,
+                    data,
+                };
+
+                instructions.push(claim_fee_ix);
             }
-            .to_account_metas(None);
 
-            let data = dlmm::client::args::ClaimFee2 {
-                min_bin_id: position_state.lower_bin_id,
-                max_bin_id: position_state.upper_bin_id,
-                remaining_accounts_info: remaining_accounts_info.clone(),
+            // 创建关闭头寸指令
+            // Create close position instruction
+            if !no_close {
+                let accounts = dlmm::client::accounts::ClosePosition2 {
+                    position,
+                    sender: program.payer(),
+                    rent_receiver: rent_receiver.unwrap_or(program.payer()),
+                    event_authority,
+                    program: dlmm::ID,
+                }
+                .to_account_metas(None);
+
+                let data = dlmm::client::args::ClosePosition2 {}.data();
+
+                let close_position_ix = Instruction {
+                    program_id: dlmm::ID,
+                    accounts,
+                    data,
+                };
+
+                instructions.push(close_position_ix);
             }
-            .data();
 
-            let accounts = [main_accounts.to_vec(), remaining_accounts.clone()].concat();
+            // 打印头寸处理信息
+            // Print position processing information
+            info!(
+                %position,
+                lower_bin_id = position_state.lower_bin_id,
+                upper_bin_id = position_state.upper_bin_id,
+                claim_fee = !no_claim_fees,
+                close = !no_close,
+                "Remove liquidity of position"
+            );
 
-            let claim_fee_ix = Instruction {
-                program_id: dlmm::ID,
-                accounts,
-                data,
-            };
+            position_groups.push((position, instructions));
+        }
+    }
 
-            instructions.push(claim_fee_ix);
+    if position_groups.is_empty() {
+        info!("No position found in the price range");
+        return Ok(());
+    }
 
-            // 创建关闭头寸指令
-            // Create close position instruction
-            let accounts = dlmm::client::accounts::ClosePosition2 {
-                position,
-                sender: program.payer(),
-                rent_receiver: program.payer(),
-                event_authority,
-                program: dlmm::ID,
-            }
-            .to_account_metas(None);
+    // 将所有头寸的指令组打包为尽可能少的交易并按顺序发送，同一头寸的指令不会被拆分
+    // Pack the instruction groups of all positions into as few transactions as possible and send them in order.
+    // The instructions of a position are never split
+    let compute_units_per_position = REMOVE_LIQUIDITY_COMPUTE_UNITS
+        + if no_claim_fees { 0 } else { CLAIM_COMPUTE_UNITS }
+        + if no_close { 0 } else { CLOSE_POSITION_COMPUTE_UNITS };
+
+    let positions = position_groups
+        .iter()
+        .map(|(position, _)| *position)
+        .collect::<Vec<_>>();
+
+    let transactions = pack_instruction_groups(
+        position_groups
+            .into_iter()
+            .map(|(_, instructions)| instructions)
+            .collect(),
+        &program.payer(),
+        usize::MAX,
+        compute_unit_price,
+        compute_units_per_position,
+    )?;
+
+    let mut processed_positions = vec![];
+
+    for ixs in transactions {
+        let transaction_positions = positions_in_transaction(&positions, &ixs);
+
+        let signature = ixs
+            .into_iter()
+            .fold(program.request(), |builder, ix| builder.instruction(ix))
+            .send_with_spinner_and_config(transaction_config)
+            .await;
+
+        info!(
+            ?signature,
+            positions = ?transaction_positions,
+            claim_fee = !no_claim_fees,
+            close = !no_close,
+            "Remove liquidity by price range"
+        );
+
+        if let Err(error) = signature {
+            println!("Processed positions {:?}", processed_positions);
+            return Err(error).with_context(|| {
+                format!(
+                    "Failed to process positions {:?}, {} of {} positions processed",
+                    transaction_positions,
+                    processed_positions.len(),
+                    positions.len()
+                )
+            });
+        }
 
-            let data = dlmm::client::args::ClosePosition2 {}.data();
+        processed_positions.extend(transaction_positions);
+    }
 
-            let close_position_ix = Instruction {
-                program_id: dlmm::ID,
-                accounts,
-                data,
-            };
+    println!(
+        "Processed {} positions: {:?}",
+        processed_positions.len(),
+        processed_positions
+    );
 
-            instructions.push(close_position_ix);
+    Ok(())
+}
 
-            // 打印关闭头寸信息
-            // Print position closing information
-            println!(
-                "Close position {}. Min bin id {}, Max bin id {}",
-                position, position_state.lower_bin_id, position_state.upper_bin_id
-            );
+/// 交易中包含的头寸，按 `positions` 的顺序
+/// Positions included in the transaction, in the order of `positions`
+fn positions_in_transaction(positions: &[Pubkey], instructions: &[Instruction]) -> Vec<Pubkey> {
+    positions
+        .iter()
+        .filter(|position| {
+            instructions.iter().any(|ix| {
+                ix.accounts
+                    .iter()
+                    .any(|account| account.pubkey == **position)
+            })
+        })
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position_group(position: Pubkey) -> Vec<Instruction> {
+        (0..3)
+            .map(|_| Instruction {
+                program_id: dlmm::ID,
+                accounts: vec![
+                    AccountMeta::new(position, false),
+                    AccountMeta::new(Pubkey::new_unique(), false),
+                ],
+                data: vec![0u8; 64],
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_position_groups_are_never_split() {
+        let payer = Pubkey::new_unique();
+        let positions = (0..40).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+
+        let transactions = pack_instruction_groups(
+            positions.iter().copied().map(position_group).collect(),
+            &payer,
+            usize::MAX,
+            ComputeUnitPrice::default(),
+            100_000,
+        )
+        .unwrap();
+        assert!(transactions.len() > 1);
+
+        let mut packed_positions = vec![];
+        for ixs in transactions.iter() {
+            let transaction_positions = positions_in_transaction(&positions, ixs);
+            assert_eq!(ixs.len(), 1 + 3 * transaction_positions.len());
+            packed_positions.extend(transaction_positions);
         }
+
+        assert_eq!(packed_positions, positions);
     }
-    Ok(())
 }
@@ -1,13 +1,16 @@
 use std::{collections::HashMap, ops::Index, u64};
+use std::result::Result::Ok;
 
 use crate::*;
+use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+use anchor_client::solana_sdk::{account::Account, signature::Signature};
 use anchor_lang::{prelude::Clock, AccountDeserialize};
 use anchor_spl::{
     associated_token::get_associated_token_address_with_program_id,
-    token_interface::{spl_token_2022::instruction::transfer_checked, Mint, TokenAccount},
+    token::spl_token,
+    token_interface::{spl_token_2022::instruction::transfer_checked, TokenAccount},
 };
-
-use futures_util::future::try_join_all;
+use rust_decimal::prelude::ToPrimitive;
 use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
 
 /// 将代币数量转换为最小单位（Wei）
@@ -56,53 +59,46 @@ pub fn convert_min_max_ui_price_to_min_max_bin_id(
     Ok((min_active_id, max_active_id))
 }
 
-/// 根据bin步长获取基础值
-/// Get base value from bin step
-fn get_base(bin_step: u16) -> f64 {
-    // 基础值 = 1 + bin_step/10000, 用于价格计算
-    // Base value = 1 + bin_step/10000, used for price calculations
-    1.0 + bin_step as f64 / 10_000.0
-}
-
-/// 从bin ID获取用户界面价格
-/// Get UI price from bin ID
-pub fn get_ui_price_from_id(
+/// 从bin ID获取f64格式的UI价格，使用与链上一致的精确价格。播种的价格范围及每个bin的分布均使用该价格，
+/// 使最小及最大价格处的累积分布恰好为0及资金数量
+/// Get the f64 UI price from bin ID using the precise on-chain consistent price. Both the seed price range and
+/// the distribution of every bin use it, so the cumulative distribution is exactly 0 and the fund amount at the
+/// min and max price
+fn get_seed_ui_price(
     bin_step: u16,
     bin_id: i32,
-    base_token_decimal: i32,
-    quote_token_decimal: i32,
-) -> f64 {
-    let base = get_base(bin_step);
-    // 价格 = 基础值^bin_id * 10^(基础代币小数位-报价代币小数位)
-    // Price = base^bin_id * 10^(base_token_decimal - quote_token_decimal)
-    base.powi(bin_id) * 10.0f64.powi(base_token_decimal - quote_token_decimal)
+    base_token_decimal: u8,
+    quote_token_decimal: u8,
+) -> Result<f64> {
+    get_ui_price_from_id(bin_step, bin_id, base_token_decimal, quote_token_decimal)
+        .and_then(|price| price.to_f64())
+        .with_context(|| format!("UI price of bin {} overflow", bin_id))
 }
 
-/// 获取覆盖指定范围所需的头寸数量
-/// Get number of positions required to cover the specified range
+/// 获取覆盖指定范围 [min_bin_id, max_bin_id) 所需的头寸数量，范围为空或反向时返回错误
+/// Get number of positions required to cover the range [min_bin_id, max_bin_id). Returns an error when the range
+/// is empty or reversed
 pub fn get_number_of_position_required_to_cover_range(
     min_bin_id: i32,
     max_bin_id: i32,
 ) -> Result<i32> {
-    // 计算bin ID的差值
-    // Calculate the difference between bin IDs
+    // 计算bin ID的差值，即范围内的bin数量
+    // Calculate the difference between bin IDs, the number of bins in the range
     let bin_delta = max_bin_id
         .checked_sub(min_bin_id)
         .context("bin_delta overflow")?;
-        
-    // 计算需要的头寸数量（每个头寸包含DEFAULT_BIN_PER_POSITION个bin）
-    // Calculate required positions (each position contains DEFAULT_BIN_PER_POSITION bins)
-    let mut position_required = bin_delta
-        .checked_div(DEFAULT_BIN_PER_POSITION as i32)
-        .context("position_required overflow")?;
-        
-    // 如果有余数，需要额外的头寸
-    // If there's a remainder, need an additional position
-    let rem = bin_delta % DEFAULT_BIN_PER_POSITION as i32;
+    ensure!(
+        bin_delta > 0,
+        "Invalid bin range [{}, {}), max_bin_id must be greater than min_bin_id",
+        min_bin_id,
+        max_bin_id
+    );
 
-    if rem > 0 {
-        position_required += 1;
-    }
+    // 计算需要的头寸数量（每个头寸包含DEFAULT_BIN_PER_POSITION个bin），有余数时需要额外的头寸
+    // Calculate required positions (each position contains DEFAULT_BIN_PER_POSITION bins), with an additional
+    // position for the remainder
+    let width = DEFAULT_BIN_PER_POSITION as i32;
+    let position_required = bin_delta / width + i32::from(bin_delta % width > 0);
 
     Ok(position_required)
 }
@@ -122,6 +118,8 @@ fn compress_bin_amount(
     bins_amount: HashMap<i32, u64>,
     multiplier: u64,
 ) -> Result<CompressionResult> {
+    ensure!(multiplier > 0, "Compression multiplier must be positive");
+
     let mut compressed_bin_amount = HashMap::new();
     let mut compression_loss = 0u64;
 
@@ -156,6 +154,243 @@ fn compress_bin_amount(
     })
 }
 
+/// 初始化一个头寸及其bin数组预估的计算单元
+/// Estimated compute units to initialize a position and its bin arrays
+const SEED_SETUP_COMPUTE_UNITS: u32 = 200_000;
+
+/// 向一个头寸存入播种流动性预估的计算单元，单笔交易最多可打包两个头寸
+/// Estimated compute units to deposit the seed liquidity of a position. At most two positions fit in a transaction
+const SEED_LIQUIDITY_COMPUTE_UNITS: u32 = 700_000;
+
+/// 播种流动性的分布结果
+/// Seed liquidity distribution result
+pub struct SeedDistribution {
+    /// 最小bin ID / Min bin id
+    pub min_bin_id: i32,
+    /// 最大bin ID（不包含）/ Max bin id (exclusive)
+    pub max_bin_id: i32,
+    /// 最小bin ID对应的实际价格 / Actual price of the min bin id
+    pub actual_min_price: f64,
+    /// 最大bin ID对应的实际价格 / Actual price of the max bin id
+    pub actual_max_price: f64,
+    /// 需要的头寸数量 / Number of positions required
+    pub position_number: i32,
+    /// 每个bin的存款数量 / Deposit amount of each bin
+    pub bins_amount: Vec<(i32, u64)>,
+    /// 压缩后的bin数量映射 / Compressed bin amount mapping
+    pub compressed_bin_amount: HashMap<i32, u32>,
+    /// 压缩损失 / Compression loss
+    pub compression_loss: u64,
+    /// 解压乘数 / Decompress multiplier
+    pub decompress_multiplier: u64,
+}
+
+impl SeedDistribution {
+    /// 第`index`个头寸覆盖的bin范围（包含两端）
+    /// Bin range covered by the position at `index`, both ends inclusive
+    pub fn position_bin_range(&self, index: i32) -> (i32, i32) {
+        let lower_bin_id = self.min_bin_id + (DEFAULT_BIN_PER_POSITION as i32 * index);
+        let upper_bin_id = lower_bin_id + DEFAULT_BIN_PER_POSITION as i32 - 1;
+        (lower_bin_id, std::cmp::min(upper_bin_id, self.max_bin_id - 1))
+    }
+
+    /// 第`index`个头寸存入的X代币数量，最后一个头寸包含压缩损失的补足
+    /// Token X amount deposited by the position at `index`, the last position includes the compression loss top up
+    pub fn position_deposit_amount(&self, index: i32) -> Result<u64> {
+        let (lower_bin_id, upper_bin_id) = self.position_bin_range(index);
+
+        let mut amount = 0u64;
+        for bin_id in lower_bin_id..=upper_bin_id {
+            let compressed = self
+                .compressed_bin_amount
+                .get(&bin_id)
+                .context("Missing bin amount to deposit")?;
+            amount = u64::from(*compressed)
+                .checked_mul(self.decompress_multiplier)
+                .and_then(|decompressed| amount.checked_add(decompressed))
+                .context("overflow")?;
+        }
+
+        if index + 1 == self.position_number {
+            amount = amount
+                .checked_add(self.compression_loss)
+                .context("overflow")?;
+        }
+
+        Ok(amount)
+    }
+}
+
+/// 已完成的播种进度，用于中断后重新执行时只计算剩余部分
+/// Seeding progress already made, so a resumed run only accounts for the remaining part
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SeedProgress {
+    /// 已初始化的头寸数量 / Number of positions already initialized
+    pub initialized_positions: u64,
+    /// 已存入流动性的头寸存入的X代币数量 / Token X amount deposited by the positions already holding liquidity
+    pub deposited_amount: u64,
+}
+
+impl SeedProgress {
+    /// 根据各头寸账户（按头寸顺序）计算进度，账户不存在表示头寸尚未初始化
+    /// Compute the progress from the position accounts in position order, a missing account means the position is
+    /// not initialized yet
+    pub fn from_position_accounts(
+        distribution: &SeedDistribution,
+        position_accounts: &[Option<Account>],
+    ) -> Result<Self> {
+        let mut progress = SeedProgress::default();
+
+        for (index, account) in position_accounts.iter().enumerate() {
+            let Some(account) = account else {
+                continue;
+            };
+            progress.initialized_positions += 1;
+
+            let state: PositionV2 = bytemuck::pod_read_unaligned(&account.data[8..]);
+            if state.liquidity_shares.iter().any(|share| *share > 0) {
+                progress.deposited_amount = progress
+                    .deposited_amount
+                    .checked_add(distribution.position_deposit_amount(index as i32)?)
+                    .context("overflow")?;
+            }
+        }
+
+        Ok(progress)
+    }
+}
+
+/// 读取各头寸账户并计算已完成的播种进度
+/// Read the position accounts and compute the seeding progress already made
+async fn fetch_seed_progress(
+    rpc_client: &RpcClient,
+    lb_pair: Pubkey,
+    position_base: Pubkey,
+    distribution: &SeedDistribution,
+) -> Result<SeedProgress> {
+    let width = DEFAULT_BIN_PER_POSITION as i32;
+    let positions: Vec<_> = (0..distribution.position_number)
+        .map(|index| {
+            let (lower_bin_id, _) = distribution.position_bin_range(index);
+            derive_position_pda(lb_pair, position_base, lower_bin_id, width).0
+        })
+        .collect();
+
+    let position_accounts = rpc_client.get_multiple_accounts_chunked(&positions).await?;
+    SeedProgress::from_position_accounts(distribution, &position_accounts)
+}
+
+/// 计算播种流动性在各个bin中的分布，不涉及链上操作
+/// Compute the seed liquidity distribution across bins without touching the chain
+pub fn compute_seed_distribution(
+    bin_step: u16,
+    min_price: f64,
+    max_price: f64,
+    base_token_decimal: u8,
+    quote_token_decimal: u8,
+    fund_amount: u64,
+    curvature: f64,
+) -> Result<SeedDistribution> {
+    // 计算k值（曲率的倒数）用于流动性分布
+    // Calculate k value (reciprocal of curvature) for liquidity distribution
+    let k = 1.0 / curvature;
+
+    let (min_bin_id, max_bin_id) = convert_min_max_ui_price_to_min_max_bin_id(
+        bin_step,
+        min_price,
+        max_price,
+        base_token_decimal,
+        quote_token_decimal,
+    )?;
+
+    ensure!(min_bin_id < max_bin_id, "Invalid price range");
+
+    let actual_min_price = get_seed_ui_price(
+        bin_step,
+        min_bin_id,
+        base_token_decimal,
+        quote_token_decimal,
+    )?;
+    let actual_max_price = get_seed_ui_price(
+        bin_step,
+        max_bin_id,
+        base_token_decimal,
+        quote_token_decimal,
+    )?;
+
+    let position_number = get_number_of_position_required_to_cover_range(min_bin_id, max_bin_id)?;
+
+    let bins_amount = generate_amount_for_bins(
+        bin_step,
+        min_bin_id,
+        max_bin_id,
+        actual_min_price,
+        actual_max_price,
+        base_token_decimal,
+        quote_token_decimal,
+        fund_amount,
+        k,
+    )?;
+
+    let bins_amount_map: HashMap<i32, u64> = bins_amount.iter().copied().collect();
+
+    let decompress_multiplier = 10u64.pow(base_token_decimal.into());
+
+    let CompressionResult {
+        compressed_bin_amount,
+        compression_loss,
+    } = compress_bin_amount(bins_amount_map, decompress_multiplier)?;
+
+    Ok(SeedDistribution {
+        min_bin_id,
+        max_bin_id,
+        actual_min_price,
+        actual_max_price,
+        position_number,
+        bins_amount,
+        compressed_bin_amount,
+        compression_loss,
+        decompress_multiplier,
+    })
+}
+
+/// 解析锁定释放点：直接使用原始释放点，或将RFC3339时间转换为unix时间戳
+/// 时间仅适用于按时间戳激活的交易对，且转换后的释放点不能早于当前时间
+///
+/// Resolve the lock release point: the raw release point is used as is, while an RFC3339 datetime is converted
+/// to a unix timestamp. A datetime is only valid for timestamp activated pairs and must not be in the past
+pub fn resolve_lock_release_point(
+    lock_release_point: Option<u64>,
+    lock_release_at: Option<&str>,
+    lb_pair_state: &LbPair,
+    clock: &Clock,
+) -> Result<u64> {
+    let Some(lock_release_at) = lock_release_at else {
+        return lock_release_point
+            .context("Either lock_release_point or lock_release_at is required");
+    };
+
+    ensure!(
+        matches!(lb_pair_state.activation_type()?, ActivationType::Timestamp),
+        "lock_release_at requires a timestamp activated pair, the pair is slot activated. Use lock_release_point with a slot instead"
+    );
+
+    let release_at = chrono::DateTime::parse_from_rfc3339(lock_release_at)
+        .with_context(|| format!("Invalid RFC3339 datetime {}", lock_release_at))?;
+
+    let lock_release_point = u64::try_from(release_at.timestamp())
+        .context("lock_release_at must be after the unix epoch")?;
+
+    ensure!(
+        lock_release_point > clock.unix_timestamp as u64,
+        "lock_release_at {} is in the past, current cluster time is {}",
+        lock_release_at,
+        clock.unix_timestamp
+    );
+
+    Ok(lock_release_point)
+}
+
 /// 操作员播种流动性的参数结构体
 /// Seed liquidity by operator parameters structure
 #[derive(Debug, Parser, Clone)]
@@ -164,8 +399,12 @@ pub struct SeedLiquidityByOperatorParameters {
     #[clap(long)]
     pub lb_pair: Pubkey,
     /// 基础头寸路径 / Base position path
+    #[clap(long, required_unless_present = "base_seed", conflicts_with = "base_seed")]
+    pub base_position_path: Option<String>,
+    /// 用于确定性派生基础头寸密钥对的种子，派生方式为 sha256("dlmm-position-base" || seed) 作为ed25519私钥种子
+    /// Seed to deterministically derive the base position keypair. The ed25519 secret seed is sha256("dlmm-position-base" || seed)
     #[clap(long)]
-    pub base_position_path: String,
+    pub base_seed: Option<String>,
     /// X代币的数量 / Amount of X token
     #[clap(long)]
     pub amount: u64,
@@ -175,9 +414,9 @@ pub struct SeedLiquidityByOperatorParameters {
     /// 最大价格 / Maximum price
     #[clap(long)]
     pub max_price: f64,
-    /// 基础公钥 / Base public key
+    /// 基础公钥，提供时校验与基础头寸密钥对是否匹配 / Base public key, validated against the base position keypair when provided
     #[clap(long)]
-    pub base_pubkey: Pubkey,
+    pub base_pubkey: Option<Pubkey>,
     /// 曲率参数 / Curvature parameter
     #[clap(long)]
     pub curvature: f64,
@@ -187,12 +426,264 @@ pub struct SeedLiquidityByOperatorParameters {
     /// 费用所有者 / Fee owner
     #[clap(long)]
     pub fee_owner: Pubkey,
-    /// 锁定释放点 / Lock release point
+    /// 锁定释放点（时隙或unix时间戳，取决于交易对的激活类型） / Lock release point (slot or unix timestamp, depending on the pair activation type)
+    #[clap(
+        long,
+        required_unless_present = "lock_release_at",
+        conflicts_with = "lock_release_at"
+    )]
+    pub lock_release_point: Option<u64>,
+    /// 以RFC3339时间指定的锁定释放点，例如 "2025-01-01T00:00:00Z"，仅适用于按时间戳激活的交易对
+    /// Lock release point as an RFC3339 datetime, e.g. "2025-01-01T00:00:00Z". Only for timestamp activated pairs
     #[clap(long)]
-    pub lock_release_point: u64,
+    pub lock_release_at: Option<String>,
     /// 最大重试次数 / Maximum retries
     #[clap(long)]
     pub max_retries: u16,
+    /// 报价代币为X代币（反向交易对），价格为以X代币计价的Y代币价格 / Quote token is token X (reversed pair), prices are token Y priced in token X
+    #[clap(long)]
+    pub quote_is_x: bool,
+    /// 仅执行预检查并报告所有问题，不发送任何交易 / Only run the pre-flight check and report every problem, without sending any transaction
+    #[clap(long)]
+    pub dry_validate: bool,
+    /// 每笔交易最多打包的头寸数量，头寸及bin数组初始化和流动性存入均适用，放不下时自动拆分 / Maximum number of positions packed per transaction, for both the position and bin array setup and the liquidity deposit. Split automatically when they do not fit
+    #[clap(long, default_value_t = 1)]
+    pub max_position_per_tx: usize,
+    /// 跳过向头寸所有者转账1个代币的证明转账及其代币账户创建，头寸所有者未持有X代币时仅给出警告 / Skip the 1 token prove transfer to the position owner and the creation of its token account. Only a warning is logged when the position owner holds no token X
+    #[clap(long)]
+    pub no_token_prove: bool,
+}
+
+/// 播种前的预检查，一次性报告所有问题而不是在第一个错误处失败：基础头寸密钥对、交易对及代币是否存在、
+/// 价格范围、锁定释放点、播种者的X代币余额（播种数量 + 证明转账 + 压缩损失）以及支付头寸租金的SOL余额
+///
+/// Pre-flight check of the seeding, reporting every problem at once instead of failing at the first one: the
+/// base position keypair, the existence of the pair and mints, the price range, the lock release point, the
+/// token X balance of the seeder (seed amount + token prove + compression loss) and the SOL balance for the
+/// position rent
+pub async fn validate_seed_liquidity_by_operator<C: Deref<Target = impl Signer> + Clone>(
+    params: &SeedLiquidityByOperatorParameters,
+    program: &Program<C>,
+) -> Result<()> {
+    let mut problems = vec![];
+
+    let position_base = match resolve_position_base_keypair(
+        params.base_position_path.clone(),
+        params.base_seed.clone(),
+        params.base_pubkey,
+    ) {
+        Ok(position_base_kp) => Some(position_base_kp.pubkey()),
+        Err(error) => {
+            problems.push(format!("Base position keypair: {:#}", error));
+            None
+        }
+    };
+
+    if params.amount == 0 {
+        problems.push("Amount must be greater than 0".to_string());
+    }
+
+    if params.max_position_per_tx == 0 {
+        problems.push("max_position_per_tx must be greater than 0".to_string());
+    }
+
+    if params.min_price <= 0.0 || params.min_price >= params.max_price {
+        problems.push(format!(
+            "Invalid price range: min_price {} must be positive and below max_price {}",
+            params.min_price, params.max_price
+        ));
+    }
+
+    let rpc_client = program.rpc();
+    let seeder = program.payer();
+
+    let mut accounts = rpc_client
+        .get_multiple_accounts(&[params.lb_pair, solana_sdk::sysvar::clock::ID])
+        .await?;
+
+    let Some(lb_pair_account) = accounts[0].take() else {
+        problems.push(format!("Liquidity pair {} not found", params.lb_pair));
+        return report_seed_problems(problems);
+    };
+    let lb_pair_state: LbPair = bytemuck::pod_read_unaligned(&lb_pair_account.data[8..]);
+
+    let clock_account = accounts[1].take().context("clock not found")?;
+    let clock = bincode::deserialize::<Clock>(&clock_account.data)?;
+
+    if let Err(error) = resolve_lock_release_point(
+        params.lock_release_point,
+        params.lock_release_at.as_deref(),
+        &lb_pair_state,
+        &clock,
+    ) {
+        problems.push(format!("Lock release point: {:#}", error));
+    }
+
+    let mut mint_cache = MintCache::new();
+    if let Err(error) = mint_cache
+        .fetch(
+            &rpc_client,
+            &[lb_pair_state.token_x_mint, lb_pair_state.token_y_mint],
+        )
+        .await
+    {
+        problems.push(format!("Mints: {:#}", error));
+        return report_seed_problems(problems);
+    }
+    let token_mint_base = mint_cache.get_cached(&lb_pair_state.token_x_mint)?;
+    let token_mint_quote = mint_cache.get_cached(&lb_pair_state.token_y_mint)?;
+
+    let fund_amount = match to_wei_amount(params.amount, token_mint_base.decimals) {
+        Ok(fund_amount) => fund_amount,
+        Err(error) => {
+            problems.push(format!("Amount: {:#}", error));
+            return report_seed_problems(problems);
+        }
+    };
+
+    let (pair_min_price, pair_max_price) =
+        flip_price_range_if_quote_is_x(params.min_price, params.max_price, params.quote_is_x);
+
+    let distribution = match compute_seed_distribution(
+        lb_pair_state.bin_step,
+        pair_min_price,
+        pair_max_price,
+        token_mint_base.decimals,
+        token_mint_quote.decimals,
+        fund_amount,
+        params.curvature,
+    ) {
+        Ok(distribution) => distribution,
+        Err(error) => {
+            problems.push(format!("Seed distribution: {:#}", error));
+            return report_seed_problems(problems);
+        }
+    };
+
+    let seeder_token_x = get_associated_token_address_with_program_id(
+        &seeder,
+        &lb_pair_state.token_x_mint,
+        &token_mint_base.program_owner,
+    );
+    let owner_token_x = get_associated_token_address_with_program_id(
+        &params.position_owner,
+        &lb_pair_state.token_x_mint,
+        &token_mint_base.program_owner,
+    );
+
+    let accounts = rpc_client
+        .get_multiple_accounts(&[seeder_token_x, owner_token_x, seeder])
+        .await?;
+
+    let token_amount = |account: &Option<Account>| -> Result<u64> {
+        match account {
+            Some(account) => {
+                Ok(TokenAccount::try_deserialize(&mut account.data.as_slice())?.amount)
+            }
+            None => Ok(0),
+        }
+    };
+
+    let seeder_token_x_balance = token_amount(&accounts[0])?;
+    let owner_token_x_balance = token_amount(&accounts[1])?;
+    let seeder_lamports = accounts[2]
+        .as_ref()
+        .map(|account| account.lamports)
+        .unwrap_or(0);
+
+    // 跳过证明转账时，头寸所有者未持有X代币只给出警告，不作为阻止播种的问题
+    // When the token prove is skipped, an owner holding no token X only gets a warning and does not block seeding
+    if params.no_token_prove && owner_token_x_balance == 0 {
+        warn!(
+            position_owner = %params.position_owner,
+            "Token prove skipped but the position owner holds no token X"
+        );
+    }
+
+    let prove_amount = if owner_token_x_balance == 0 && !params.no_token_prove {
+        token_mint_base.transfer_fee_included_amount(1, clock.epoch)?
+    } else {
+        0
+    };
+
+    // 中断后重新执行时，已初始化的头寸无需再付租金，已存入的数量无需再准备
+    // When resuming, the positions already initialized need no rent and the amount already deposited is not
+    // required again
+    let progress = match position_base {
+        Some(position_base) => {
+            fetch_seed_progress(&rpc_client, params.lb_pair, position_base, &distribution).await?
+        }
+        None => SeedProgress::default(),
+    };
+
+    if progress.initialized_positions > 0 {
+        info!(
+            initialized_positions = progress.initialized_positions,
+            deposited_amount = progress.deposited_amount,
+            "Resume seeding, only the remaining part is checked"
+        );
+    }
+
+    let required_amount = fund_amount
+        .checked_add(prove_amount)
+        .and_then(|amount| amount.checked_add(distribution.compression_loss))
+        .context("overflow")?
+        .saturating_sub(progress.deposited_amount);
+
+    let remaining_positions =
+        (distribution.position_number as u64).saturating_sub(progress.initialized_positions);
+
+    // 只估算头寸账户的租金，bin数组及代币账户的租金未计入
+    // Only the rent of the position accounts is estimated, bin arrays and token accounts are not included
+    let position_rent = rpc_client
+        .get_minimum_balance_for_rent_exemption(8 + std::mem::size_of::<PositionV2>())
+        .await?
+        .checked_mul(remaining_positions)
+        .context("overflow")?;
+
+    // 基础代币为原生SOL时，不足的部分会从播种者的SOL余额包装
+    // When the base token is native SOL, the shortfall is wrapped from the SOL balance of the seeder
+    let available_amount = if lb_pair_state.token_x_mint == spl_token::native_mint::ID {
+        seeder_token_x_balance.saturating_add(seeder_lamports.saturating_sub(position_rent))
+    } else {
+        seeder_token_x_balance
+    };
+
+    if available_amount < required_amount {
+        problems.push(format!(
+            "Insufficient token X: required {} (seed {} + prove {} + compression loss {} - deposited {}), available {}",
+            required_amount,
+            fund_amount,
+            prove_amount,
+            distribution.compression_loss,
+            progress.deposited_amount,
+            available_amount
+        ));
+    }
+
+    if seeder_lamports < position_rent {
+        problems.push(format!(
+            "Insufficient SOL: about {} lamports required for the rent of {} positions, available {}",
+            position_rent, remaining_positions, seeder_lamports
+        ));
+    }
+
+    report_seed_problems(problems)
+}
+
+/// 输出预检查报告，存在问题时返回错误
+/// Print the pre-flight report, returning an error when there is any problem
+fn report_seed_problems(problems: Vec<String>) -> Result<()> {
+    if problems.is_empty() {
+        info!("Seed pre-flight check passed");
+        return Ok(());
+    }
+
+    for problem in problems.iter() {
+        error!("{}", problem);
+    }
+
+    bail!("Seed pre-flight check found {} problem(s)", problems.len())
 }
 
 /// 执行操作员播种流动性
@@ -201,13 +692,22 @@ pub async fn execute_seed_liquidity_by_operator<C: Deref<Target = impl Signer> +
     params: SeedLiquidityByOperatorParameters,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
-    compute_unit_price: Option<Instruction>,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<()> {
+    // 发送任何交易前先一次性报告所有问题
+    // Report every problem at once before sending any transaction
+    validate_seed_liquidity_by_operator(&params, program).await?;
+
+    if params.dry_validate {
+        return Ok(());
+    }
+
     // 解构参数
     // Destructure parameters
     let SeedLiquidityByOperatorParameters {
         lb_pair,
         base_position_path,
+        base_seed,
         amount,
         min_price,
         max_price,
@@ -216,27 +716,20 @@ pub async fn execute_seed_liquidity_by_operator<C: Deref<Target = impl Signer> +
         position_owner,
         fee_owner,
         lock_release_point,
+        lock_release_at,
+        quote_is_x,
+        max_position_per_tx,
+        no_token_prove,
         ..
     } = params;
 
-    // 读取头寸基础密钥对文件
-    // Read position base keypair file
-    let position_base_kp = read_keypair_file(base_position_path.clone())
-        .expect("position base keypair file not found");
-
-    // 验证基础公钥是否匹配
-    // Verify base public key matches
-    assert!(
-        position_base_kp.pubkey() == base_pubkey,
-        "base_pubkey mismatch"
-    );
+    // 读取或派生头寸基础密钥对，并验证基础公钥是否匹配
+    // Read or derive position base keypair and verify base public key matches
+    let position_base_kp =
+        resolve_position_base_keypair(base_position_path, base_seed, base_pubkey)?;
 
     let rpc_client = program.rpc();
 
-    // 计算k值（曲率的倒数）用于流动性分布
-    // Calculate k value (reciprocal of curvature) for liquidity distribution
-    let k = 1.0 / curvature;
-
     // 获取流动性对状态
     // Get liquidity pair state
     let lb_pair_state: LbPair = rpc_client
@@ -249,7 +742,7 @@ pub async fn execute_seed_liquidity_by_operator<C: Deref<Target = impl Signer> +
     // Get bin step
     let bin_step = lb_pair_state.bin_step;
 
-    let (mut bitmap_extension, _bump) = derive_bin_array_bitmap_extension(lb_pair);
+    let (bitmap_extension, _bump) = derive_bin_array_bitmap_extension(lb_pair);
 
     let mut accounts = rpc_client
         .get_multiple_accounts(&[
@@ -260,68 +753,76 @@ pub async fn execute_seed_liquidity_by_operator<C: Deref<Target = impl Signer> +
         ])
         .await?;
 
-    let token_mint_base_account = accounts[0].take().context("token_mint_base not found")?;
-    let token_mint_quote_account = accounts[1].take().context("token_mint_quote not found")?;
+    // 缓存代币铸造账户，后续的转账钩子账户及转账手续费计算无需重新获取
+    // Cache the mint accounts so the transfer hook accounts and transfer fees do not refetch them
+    let mut mint_cache = MintCache::new();
+    let token_mint_base = mint_cache
+        .insert(
+            lb_pair_state.token_x_mint,
+            accounts[0].take().context("token_mint_base not found")?,
+        )?
+        .clone();
+    let token_mint_quote = mint_cache
+        .insert(
+            lb_pair_state.token_y_mint,
+            accounts[1].take().context("token_mint_quote not found")?,
+        )?
+        .clone();
     let clock_account = accounts[2].take().context("clock not found")?;
     let bitmap_extension_account = accounts[3].take();
 
-    let token_mint_base = Mint::try_deserialize(&mut token_mint_base_account.data.as_ref())?;
-    let token_mint_quote = Mint::try_deserialize(&mut token_mint_quote_account.data.as_ref())?;
     let clock = bincode::deserialize::<Clock>(&clock_account.data)?;
 
+    let lock_release_point = resolve_lock_release_point(
+        lock_release_point,
+        lock_release_at.as_deref(),
+        &lb_pair_state,
+        &clock,
+    )?;
+
     let fund_amount = to_wei_amount(amount, token_mint_base.decimals)?;
 
-    let (min_bin_id, max_bin_id) = convert_min_max_ui_price_to_min_max_bin_id(
+    // 反向交易对的价格为倒数，最小和最大价格需要互换
+    // Prices of a reversed pair are reciprocal, so min and max price swap places
+    let (pair_min_price, pair_max_price) =
+        flip_price_range_if_quote_is_x(min_price, max_price, quote_is_x);
+
+    let distribution = compute_seed_distribution(
         bin_step,
-        min_price,
-        max_price,
+        pair_min_price,
+        pair_max_price,
         token_mint_base.decimals,
         token_mint_quote.decimals,
+        fund_amount,
+        curvature,
     )?;
 
-    let actual_min_price = get_ui_price_from_id(
-        bin_step,
+    let SeedDistribution {
         min_bin_id,
-        token_mint_base.decimals.into(),
-        token_mint_quote.decimals.into(),
-    );
-    let actual_max_price = get_ui_price_from_id(
-        bin_step,
         max_bin_id,
-        token_mint_base.decimals.into(),
-        token_mint_quote.decimals.into(),
-    );
-
-    let position_number = get_number_of_position_required_to_cover_range(min_bin_id, max_bin_id)?;
-
-    println!("Start seed. Min price: {} Max price: {} Actual min price: {} Actual max price: {} Min bin id: {} Max bin id: {} Position: {}", min_price, max_price, actual_min_price, actual_max_price, min_bin_id, max_bin_id, position_number);
+        actual_min_price,
+        actual_max_price,
+        position_number,
+        ref compressed_bin_amount,
+        compression_loss,
+        decompress_multiplier,
+        ..
+    } = distribution;
 
-    assert!(min_bin_id < max_bin_id, "Invalid price range");
+    let (ui_actual_min_price, ui_actual_max_price) =
+        flip_price_range_if_quote_is_x(actual_min_price, actual_max_price, quote_is_x);
 
-    let bins_amount = generate_amount_for_bins(
-        bin_step,
+    info!(
+        min_price,
+        max_price,
+        actual_min_price = ui_actual_min_price,
+        actual_max_price = ui_actual_max_price,
         min_bin_id,
         max_bin_id,
-        actual_min_price,
-        actual_max_price,
-        token_mint_base.decimals,
-        token_mint_quote.decimals,
-        fund_amount,
-        k,
+        position_number,
+        "Start seed"
     );
 
-    let bins_amount_map: HashMap<i32, u64> = bins_amount
-        .iter()
-        .map(|(bin_id, amount_x)| (*bin_id, *amount_x))
-        .collect();
-
-    let decompress_multiplier = 10u64.pow(token_mint_base.decimals.into());
-
-    let CompressionResult {
-        compressed_bin_amount,
-        compression_loss,
-    } = compress_bin_amount(bins_amount_map, decompress_multiplier)?;
-
     let width = DEFAULT_BIN_PER_POSITION as i32;
 
     let mut token_account_and_bitmap_ext_and_token_prove_setup_ixs = vec![];
@@ -331,8 +832,8 @@ pub async fn execute_seed_liquidity_by_operator<C: Deref<Target = impl Signer> +
     let (event_authority, _bump) = derive_event_authority_pda();
     let seeder = program.payer();
 
-    let token_mint_base_owner = token_mint_base_account.owner;
-    let token_mint_quote_owner = token_mint_quote_account.owner;
+    let token_mint_base_owner = token_mint_base.program_owner;
+    let token_mint_quote_owner = token_mint_quote.program_owner;
 
     let seeder_token_x = get_associated_token_address_with_program_id(
         &seeder,
@@ -352,13 +853,13 @@ pub async fn execute_seed_liquidity_by_operator<C: Deref<Target = impl Signer> +
         &token_mint_base_owner,
     );
 
-    let transfer_hook_x_account =
-        get_extra_account_metas_for_transfer_hook(lb_pair_state.token_x_mint, program.rpc())
-            .await?;
+    let transfer_hook_x_account = mint_cache
+        .get_extra_account_metas_for_transfer_hook(&rpc_client, lb_pair_state.token_x_mint)
+        .await?;
 
-    let transfer_hook_y_account =
-        get_extra_account_metas_for_transfer_hook(lb_pair_state.token_y_mint, program.rpc())
-            .await?;
+    let transfer_hook_y_account = mint_cache
+        .get_extra_account_metas_for_transfer_hook(&rpc_client, lb_pair_state.token_y_mint)
+        .await?;
 
     let accounts = rpc_client
         .get_multiple_accounts(&[seeder_token_x, seeder_token_y, owner_token_x])
@@ -398,6 +899,62 @@ pub async fn execute_seed_liquidity_by_operator<C: Deref<Target = impl Signer> +
         require_token_prove = owner_token_x_state.amount == 0;
     }
 
+    // 跳过证明转账时不创建所有者的代币账户也不转账
+    // When the token prove is skipped, neither the owner token account nor the transfer is created
+    require_token_prove &= !no_token_prove;
+
+    let prove_amount = if require_token_prove {
+        token_mint_base.transfer_fee_included_amount(1, clock.epoch)?
+    } else {
+        0
+    };
+
+    // 基础代币为原生SOL时，将不足的部分包装为WSOL，覆盖播种金额、证明转账及压缩损失补足
+    // When the base token is native SOL, wrap the shortfall into WSOL to cover the seed amount,
+    // the token prove transfer and the compression loss top up
+    let mut unwrap_sol = false;
+
+    if lb_pair_state.token_x_mint == spl_token::native_mint::ID {
+        // 中断后重新执行时已存入的数量无需再包装
+        // When resuming, the amount already deposited is not wrapped again
+        let progress =
+            fetch_seed_progress(&rpc_client, lb_pair, position_base_kp.pubkey(), &distribution)
+                .await?;
+
+        let required_amount = fund_amount
+            .checked_add(prove_amount)
+            .and_then(|amount| amount.checked_add(compression_loss))
+            .context("overflow")?
+            .saturating_sub(progress.deposited_amount);
+
+        let seeder_token_x_balance = match seeder_token_x_account {
+            Some(account) => TokenAccount::try_deserialize(&mut account.data.as_slice())?.amount,
+            None => 0,
+        };
+
+        let wrap_amount = required_amount.saturating_sub(seeder_token_x_balance);
+
+        if wrap_amount > 0 {
+            info!(
+                wrap_amount,
+                seeder_token_x_balance,
+                required_amount,
+                "Wrap SOL for seeding"
+            );
+
+            token_account_and_bitmap_ext_and_token_prove_setup_ixs
+                .extend(wrap_sol_instructions(seeder, wrap_amount)?);
+
+            // 只解包本次创建的WSOL账户，已存在的账户可能有执行前包装的余额，保持不变
+            // Only unwrap the WSOL account created by this run. A pre-existing account may hold SOL wrapped before
+            // the run and is left as is
+            unwrap_sol = seeder_token_x_account.is_none();
+            if !unwrap_sol {
+                info!(%seeder_token_x, "WSOL account already exists, it is not unwrapped");
+            }
+        }
+    }
+
     if require_token_prove {
         token_account_and_bitmap_ext_and_token_prove_setup_ixs.push(
             create_associated_token_account_idempotent(
@@ -408,10 +965,6 @@ pub async fn execute_seed_liquidity_by_operator<C: Deref<Target = impl Signer> +
             ),
         );
 
-        let prove_amount =
-            calculate_transfer_fee_included_amount(&token_mint_base_account, 1, clock.epoch)?
-                .amount;
-
         let mut transfer_ix = transfer_checked(
             &token_mint_base_owner,
             &seeder_token_x,
@@ -430,12 +983,14 @@ pub async fn execute_seed_liquidity_by_operator<C: Deref<Target = impl Signer> +
         token_account_and_bitmap_ext_and_token_prove_setup_ixs.push(transfer_ix);
     }
 
-    let (min_bitmap_id, max_bitmap_id) = LbPair::bitmap_range();
     let lower_bin_array_index = BinArray::bin_id_to_bin_array_index(min_bin_id)?;
     let upper_bin_array_index = BinArray::bin_id_to_bin_array_index(max_bin_id - 1)?;
 
+    // 整个范围有任意bin数组超出内部bitmap范围时需要bitmap扩展账户，每个仓位是否传入扩展账户则由其覆盖的bin数组决定
+    // The bitmap extension is required when any bin array of the whole range overflows the internal bitmap range,
+    // whether each position passes it is decided by the bin arrays the position covers
     let overflow_internal_bitmap_range =
-        upper_bin_array_index > max_bitmap_id || lower_bin_array_index < min_bitmap_id;
+        is_overflow_internal_bitmap_range([lower_bin_array_index, upper_bin_array_index]);
 
     if overflow_internal_bitmap_range && bitmap_extension_account.is_none() {
         let accounts = dlmm::client::accounts::InitializeBinArrayBitmapExtension {
@@ -456,14 +1011,10 @@ pub async fn execute_seed_liquidity_by_operator<C: Deref<Target = impl Signer> +
         };
 
         token_account_and_bitmap_ext_and_token_prove_setup_ixs.push(init_bitmap_ext_ix);
-    } else {
-        bitmap_extension = dlmm::ID;
     }
 
     for i in 0..position_number {
-        let lower_bin_id = min_bin_id + (DEFAULT_BIN_PER_POSITION as i32 * i);
-        let upper_bin_id = lower_bin_id + DEFAULT_BIN_PER_POSITION as i32 - 1;
-        let upper_bin_id = std::cmp::min(upper_bin_id, max_bin_id - 1);
+        let (lower_bin_id, upper_bin_id) = distribution.position_bin_range(i);
 
         let mut instructions = vec![];
 
@@ -475,6 +1026,10 @@ pub async fn execute_seed_liquidity_by_operator<C: Deref<Target = impl Signer> +
 
         let bin_array_indexes =
             BinArray::get_bin_array_indexes_coverage(lower_bin_id, upper_bin_id)?;
+        let position_bitmap_extension = get_bitmap_extension_for_bin_arrays(
+            bin_array_indexes.iter().copied(),
+            bitmap_extension,
+        );
 
         let keys: Vec<_> = [position]
             .into_iter()
@@ -485,7 +1040,7 @@ pub async fn execute_seed_liquidity_by_operator<C: Deref<Target = impl Signer> +
             )
             .collect();
 
-        let accounts = rpc_client.get_multiple_accounts(&keys).await?;
+        let accounts = rpc_client.get_multiple_accounts_chunked(&keys).await?;
 
         let position_account = accounts.index(0).to_owned();
         if position_account.is_none() {
@@ -550,10 +1105,6 @@ pub async fn execute_seed_liquidity_by_operator<C: Deref<Target = impl Signer> +
         }
 
         if !instructions.is_empty() {
-            if let Some(cu_price_ix) = compute_unit_price.clone() {
-                instructions.push(cu_price_ix);
-            }
-
             position_and_bin_array_setup_ixs.push(instructions.clone());
         }
 
@@ -596,7 +1147,7 @@ pub async fn execute_seed_liquidity_by_operator<C: Deref<Target = impl Signer> +
             let accounts = dlmm::client::accounts::AddLiquidityOneSidePrecise2 {
                 position,
                 lb_pair,
-                bin_array_bitmap_extension: Some(bitmap_extension),
+                bin_array_bitmap_extension: Some(position_bitmap_extension),
                 user_token: seeder_token_x,
                 reserve: lb_pair_state.reserve_x,
                 token_mint: lb_pair_state.token_x_mint,
@@ -617,29 +1168,26 @@ pub async fn execute_seed_liquidity_by_operator<C: Deref<Target = impl Signer> +
                 data: ix_data,
             };
 
-            if instructions.is_empty() {
-                if let Some(cu_price_ix) = compute_unit_price.clone() {
-                    instructions.push(cu_price_ix);
-                }
-                instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(800_000));
-            }
-
             instructions.push(add_liquidity_ix);
 
             // Last position
             if i + 1 == position_number && compression_loss > 0 {
-                let loss_includes_transfer_fee = calculate_transfer_fee_included_amount(
-                    &token_mint_base_account,
-                    compression_loss,
-                    clock.epoch,
-                )?
-                .amount;
+                // 构建所有头寸期间可能已跨越epoch边界，重新读取epoch计算转账手续费
+                // Building every position may have crossed an epoch boundary, read the epoch again for the
+                // transfer fee
+                let epoch = current_epoch(&rpc_client).await?;
+                let loss_includes_transfer_fee =
+                    token_mint_base.transfer_fee_included_amount(compression_loss, epoch)?;
 
                 let bin_array_account_metas = BinArray::get_bin_array_account_metas_coverage(
                     upper_bin_id,
                     upper_bin_id,
                     lb_pair,
                 )?;
+                let loss_bitmap_extension = get_bitmap_extension_for_bin_arrays(
+                    [BinArray::bin_id_to_bin_array_index(upper_bin_id)?],
+                    bitmap_extension,
+                );
 
                 let ix_data = dlmm::client::args::AddLiquidity2 {
                     liquidity_parameter: LiquidityParameter {
@@ -669,7 +1217,7 @@ pub async fn execute_seed_liquidity_by_operator<C: Deref<Target = impl Signer> +
                 let accounts = dlmm::client::accounts::AddLiquidity2 {
                     position,
                     lb_pair,
-                    bin_array_bitmap_extension: Some(bitmap_extension),
+                    bin_array_bitmap_extension: Some(loss_bitmap_extension),
                     user_token_x: seeder_token_x,
                     user_token_y: seeder_token_y,
                     reserve_x: lb_pair_state.reserve_x,
@@ -695,24 +1243,39 @@ pub async fn execute_seed_liquidity_by_operator<C: Deref<Target = impl Signer> +
                     data: ix_data,
                 };
 
-                if instructions.is_empty() {
-                    if let Some(cu_price_ix) = compute_unit_price.clone() {
-                        instructions.push(cu_price_ix);
-                    }
-
-                    instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(800_000));
-                }
-
                 instructions.push(add_liquidity_ix);
             }
 
-            if !instructions.is_empty() {
-                liquidity_setup_ixs.push(instructions);
-            }
+            liquidity_setup_ixs.push(instructions);
         }
     }
 
-    println!("Init token account, bitmap extension and transfer token prove if necessary");
+    // 按 `max_position_per_tx` 将每个头寸的指令打包为交易，默认每笔交易一个头寸
+    // Pack the instructions of the positions into transactions by `max_position_per_tx`, one position per
+    // transaction by default
+    let position_and_bin_array_setup_ixs = pack_instruction_groups(
+        position_and_bin_array_setup_ixs,
+        &seeder,
+        max_position_per_tx,
+        compute_unit_price.clone(),
+        SEED_SETUP_COMPUTE_UNITS,
+    )?;
+
+    let liquidity_setup_ixs = pack_instruction_groups(
+        liquidity_setup_ixs,
+        &seeder,
+        max_position_per_tx,
+        compute_unit_price.clone(),
+        SEED_LIQUIDITY_COMPUTE_UNITS,
+    )?;
+
+    info!(
+        setup_transactions = position_and_bin_array_setup_ixs.len(),
+        liquidity_transactions = liquidity_setup_ixs.len(),
+        "Packed seed transactions"
+    );
+
+    info!("Init token account, bitmap extension and transfer token prove if necessary");
     if !token_account_and_bitmap_ext_and_token_prove_setup_ixs.is_empty() {
         let mut builder = program.request();
 
@@ -724,49 +1287,72 @@ pub async fn execute_seed_liquidity_by_operator<C: Deref<Target = impl Signer> +
             .send_with_spinner_and_config(transaction_config)
             .await;
 
-        println!("{:#?}", signature);
+        info!(?signature, "Init token account, bitmap extension and transfer token prove");
         signature?;
     }
-    println!("Init token account, bitmap extension and transfer token prove if necessary - DONE");
+    info!("Init token account, bitmap extension and transfer token prove if necessary - DONE");
 
-    println!("Setup position and bin arrays if necessary");
+    info!("Setup position and bin arrays if necessary");
     if !position_and_bin_array_setup_ixs.is_empty() {
-        let mut futures = vec![];
+        let results = send_bundles_with_blockhash_refresh(
+            program,
+            &position_and_bin_array_setup_ixs,
+            transaction_config,
+        )
+        .await;
+
+        ensure_all_bundles_succeeded(results, "Setup position and bin arrays")?;
+    }
+    info!("Setup position and bin arrays if necessary - DONE");
 
-        for ixs in position_and_bin_array_setup_ixs {
-            let mut builder = program.request();
+    info!("Seed liquidity");
+    if !liquidity_setup_ixs.is_empty() {
+        let results =
+            send_bundles_with_blockhash_refresh(program, &liquidity_setup_ixs, transaction_config)
+                .await;
 
-            for ix in ixs {
-                builder = builder.instruction(ix);
-            }
+        ensure_all_bundles_succeeded(results, "Seed liquidity")?;
+    }
+    info!("Seed liquidity - DONE");
 
-            futures.push(builder.send_with_spinner_and_config(transaction_config));
-        }
+    if unwrap_sol {
+        let signature = request_with_compute_unit_price(program, compute_unit_price.clone())?
+            .instruction(unwrap_sol_instruction(seeder)?)
+            .send_with_spinner_and_config(transaction_config)
+            .await;
 
-        let result = try_join_all(futures).await;
-        println!("{:#?}", result);
-        result?;
+        info!(?signature, "Unwrap leftover SOL");
+        signature?;
     }
-    println!("Setup position and bin arrays if necessary - DONE");
 
-    println!("Seed liquidity");
-    if !liquidity_setup_ixs.is_empty() {
-        let mut futures = vec![];
-        for ixs in liquidity_setup_ixs {
-            let mut builder = program.request();
+    Ok(())
+}
 
-            for ix in ixs {
-                builder = builder.instruction(ix);
+/// 记录每个交易包的发送结果，存在失败时返回错误。重新执行命令时已完成的仓位及bin数组会被跳过
+/// Log the send result of every bundle and error when any failed. Re-running the command skips the
+/// positions and bin arrays already completed
+fn ensure_all_bundles_succeeded(results: Vec<Result<Signature>>, stage: &str) -> Result<()> {
+    let total = results.len();
+    let mut failed_bundles = vec![];
+
+    for (i, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(signature) => info!(bundle = i, %signature, "{}", stage),
+            Err(error) => {
+                error!(bundle = i, %error, "{}", stage);
+                failed_bundles.push(i);
             }
-
-            futures.push(builder.send_with_spinner_and_config(transaction_config));
         }
-
-        let result = try_join_all(futures).await;
-        println!("{:#?}", result);
-        result?;
     }
-    println!("Seed liquidity - DONE");
+
+    ensure!(
+        failed_bundles.is_empty(),
+        "{}: {} of {} bundles failed {:?}. Re-run the command to resume, completed bundles are skipped",
+        stage,
+        failed_bundles.len(),
+        total,
+        failed_bundles
+    );
 
     Ok(())
 }
@@ -782,7 +1368,7 @@ fn get_bin_deposit_amount(
     min_price: f64,
     max_price: f64,
     k: f64,
-) -> u64 {
+) -> Result<u64> {
     // 计算下一个bin的累积函数值
     // Calculate cumulative function value for next bin
     let c1 = get_c(
@@ -794,7 +1380,7 @@ fn get_bin_deposit_amount(
         min_price,
         max_price,
         k,
-    );
+    )?;
 
     // 计算当前bin的累积函数值
     // Calculate cumulative function value for current bin
@@ -807,14 +1393,14 @@ fn get_bin_deposit_amount(
         min_price,
         max_price,
         k,
-    );
+    )?;
 
     assert!(c1 > c0);
 
     // 该bin的存款数量 = c1 - c0
     // Deposit amount for this bin = c1 - c0
     let amount_into_bin = c1 - c0;
-    amount_into_bin
+    Ok(amount_into_bin)
 }
 
 /// 累积分布函数
@@ -830,15 +1416,11 @@ fn get_c(
     min_price: f64,
     max_price: f64,
     k: f64,
-) -> u64 {
-    // 计算每lamport价格
-    // Calculate price per lamport
-    let price_per_lamport = (1.0 + bin_step as f64 / 10000.0).powi(bin_id);
-
+) -> Result<u64> {
     // 计算当前用户界面价格
     // Calculate current UI price
     let current_price =
-        price_per_lamport * 10.0f64.powi(base_token_decimal as i32 - quote_token_decimal as i32);
+        get_seed_ui_price(bin_step, bin_id, base_token_decimal, quote_token_decimal)?;
 
     // 价格范围和当前价格相对于最小价格的偏移
     // Price range and current price offset from min price
@@ -848,7 +1430,7 @@ fn get_c(
     // 计算累积分布函数值
     // Calculate cumulative distribution function value
     let c = amount as f64 * ((current_price_delta_from_min / price_range).powf(k));
-    c as u64
+    Ok(c as u64)
 }
 
 /// 为每个bin生成流动性数量
@@ -863,7 +1445,7 @@ pub fn generate_amount_for_bins(
     quote_token_decimal: u8,
     amount: u64,
     k: f64,
-) -> Vec<(i32, u64)> {
+) -> Result<Vec<(i32, u64)>> {
     let mut total_amount = 0;
     let mut bin_amounts = vec![];
 
@@ -881,7 +1463,7 @@ pub fn generate_amount_for_bins(
             min_price,
             max_price,
             k,
-        );
+        )?;
 
         bin_amounts.push((bin_id, bin_amount));
         total_amount += bin_amount;
@@ -894,5 +1476,318 @@ pub fn generate_amount_for_bins(
         "Amount distributed to bins not equals to funding amount"
     );
 
-    bin_amounts
+    Ok(bin_amounts)
+}
+
+/// 任意bin数组超出交易对内部bitmap范围时返回 `true`，此时需要bitmap扩展账户
+/// Returns `true` when any of the bin arrays overflows the internal bitmap range of the pair, which requires the
+/// bitmap extension
+pub fn is_overflow_internal_bitmap_range(bin_array_indexes: impl IntoIterator<Item = i32>) -> bool {
+    let (min_bitmap_id, max_bitmap_id) = LbPair::bitmap_range();
+    bin_array_indexes
+        .into_iter()
+        .any(|index| index > max_bitmap_id || index < min_bitmap_id)
+}
+
+/// 添加流动性时使用的bitmap扩展账户：任意bin数组超出交易对内部bitmap范围时为 `bitmap_extension`，否则为程序ID
+/// Bitmap extension account to pass when adding liquidity. `bitmap_extension` when any of the bin arrays overflows
+/// the internal bitmap range of the pair, the program id otherwise
+pub fn get_bitmap_extension_for_bin_arrays(
+    bin_array_indexes: impl IntoIterator<Item = i32>,
+    bitmap_extension: Pubkey,
+) -> Pubkey {
+    if is_overflow_internal_bitmap_range(bin_array_indexes) {
+        bitmap_extension
+    } else {
+        dlmm::ID
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn test_get_number_of_position_required_to_cover_range() {
+        let width = DEFAULT_BIN_PER_POSITION as i32;
+
+        assert!(get_number_of_position_required_to_cover_range(100, 100).is_err());
+        assert!(get_number_of_position_required_to_cover_range(100, 99).is_err());
+        assert!(get_number_of_position_required_to_cover_range(i32::MIN, i32::MAX).is_err());
+
+        for (bin_delta, expected) in [(1, 1), (width, 1), (width + 1, 2), (2 * width, 2)] {
+            assert_eq!(
+                get_number_of_position_required_to_cover_range(-10, -10 + bin_delta).unwrap(),
+                expected,
+                "bin_delta {}",
+                bin_delta
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_lock_release_point() {
+        let mut lb_pair: LbPair = bytemuck::Zeroable::zeroed();
+        let clock = Clock {
+            slot: 1_000,
+            unix_timestamp: 1_735_689_600, // 2025-01-01T00:00:00Z
+            ..Default::default()
+        };
+
+        // 原始释放点直接使用 / The raw release point is used as is
+        assert_eq!(
+            resolve_lock_release_point(Some(42), None, &lb_pair, &clock).unwrap(),
+            42
+        );
+        assert!(resolve_lock_release_point(None, None, &lb_pair, &clock).is_err());
+
+        // 按时隙激活的交易对不接受时间 / A slot activated pair rejects a datetime
+        lb_pair.activation_type = 0;
+        assert!(
+            resolve_lock_release_point(None, Some("2025-06-01T00:00:00Z"), &lb_pair, &clock)
+                .is_err()
+        );
+
+        lb_pair.activation_type = 1;
+        assert_eq!(
+            resolve_lock_release_point(None, Some("2025-06-01T00:00:00Z"), &lb_pair, &clock)
+                .unwrap(),
+            1_748_736_000
+        );
+        assert_eq!(
+            resolve_lock_release_point(None, Some("2025-06-01T08:00:00+08:00"), &lb_pair, &clock)
+                .unwrap(),
+            1_748_736_000
+        );
+
+        // 过去的时间、当前时间及无效的时间均返回错误 / Past, current and invalid datetimes are rejected
+        assert!(
+            resolve_lock_release_point(None, Some("2024-12-31T23:59:59Z"), &lb_pair, &clock)
+                .is_err()
+        );
+        assert!(
+            resolve_lock_release_point(None, Some("2025-01-01T00:00:00Z"), &lb_pair, &clock)
+                .is_err()
+        );
+        assert!(resolve_lock_release_point(None, Some("2025-06-01"), &lb_pair, &clock).is_err());
+    }
+
+    /// 压缩后的数量乘以乘数再加上压缩损失必须等于原始总量，最后一个头寸补足压缩损失依赖该不变量
+    /// The compressed amounts times the multiplier plus the compression loss must equal the original total. The
+    /// compression loss top-up of the last position relies on this invariant
+    fn assert_compression_round_trip(bins_amount: HashMap<i32, u64>, multiplier: u64) {
+        let original_total = bins_amount
+            .values()
+            .map(|&amount| u128::from(amount))
+            .sum::<u128>();
+
+        let CompressionResult {
+            compressed_bin_amount,
+            compression_loss,
+        } = compress_bin_amount(bins_amount.clone(), multiplier).unwrap();
+
+        assert_eq!(compressed_bin_amount.len(), bins_amount.len());
+
+        let decompressed_total = compressed_bin_amount
+            .values()
+            .map(|&compressed| u128::from(compressed) * u128::from(multiplier))
+            .sum::<u128>();
+        assert_eq!(
+            decompressed_total + u128::from(compression_loss),
+            original_total
+        );
+
+        for (bin_id, compressed) in compressed_bin_amount {
+            let amount = bins_amount[&bin_id];
+            let decompressed = u64::from(compressed) * multiplier;
+            assert!(decompressed <= amount);
+            assert!(amount - decompressed < multiplier);
+        }
+    }
+
+    #[test]
+    fn test_compress_bin_amount_round_trip_random() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..1_000 {
+            let multiplier = 10u64.pow(rng.gen_range(0..=12));
+            let bin_count = rng.gen_range(1..=200);
+            // 保证压缩后的数量不超过 u32::MAX
+            // Keep the compressed amounts within u32::MAX
+            let max_amount = u64::from(u32::MAX).saturating_mul(multiplier);
+
+            let bins_amount = (0..bin_count)
+                .map(|bin_id| (bin_id, rng.gen_range(0..=max_amount)))
+                .collect::<HashMap<_, _>>();
+
+            assert_compression_round_trip(bins_amount, multiplier);
+        }
+    }
+
+    #[test]
+    fn test_compress_bin_amount_not_clean_division() {
+        let bins_amount = HashMap::from([(0, 1_999), (1, 999), (2, 1_000), (3, 0)]);
+        let CompressionResult {
+            compressed_bin_amount,
+            compression_loss,
+        } = compress_bin_amount(bins_amount.clone(), 1_000).unwrap();
+
+        assert_eq!(compressed_bin_amount[&0], 1);
+        assert_eq!(compressed_bin_amount[&1], 0);
+        assert_eq!(compressed_bin_amount[&2], 1);
+        assert_eq!(compressed_bin_amount[&3], 0);
+        assert_eq!(compression_loss, 999 + 999);
+
+        assert_compression_round_trip(bins_amount, 1_000);
+    }
+
+    #[test]
+    fn test_compress_bin_amount_zero_multiplier() {
+        let bins_amount = HashMap::from([(0, 1_000)]);
+        assert!(compress_bin_amount(bins_amount, 0).is_err());
+    }
+
+    #[test]
+    fn test_compress_bin_amount_exceeding_u32() {
+        let max_compressible = u64::from(u32::MAX) * 10;
+        assert_compression_round_trip(HashMap::from([(0, max_compressible + 9)]), 10);
+        assert!(compress_bin_amount(HashMap::from([(0, max_compressible + 10)]), 10).is_err());
+        assert!(compress_bin_amount(HashMap::from([(0, u64::MAX)]), 1).is_err());
+    }
+
+    #[test]
+    fn test_compute_seed_distribution_round_trip_random() {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for _ in 0..100 {
+            let bin_step = [1u16, 5, 10, 25, 80, 100][rng.gen_range(0..6)];
+            let base_token_decimal = rng.gen_range(0..=9u8);
+            let quote_token_decimal = rng.gen_range(0..=9u8);
+            let min_price = rng.gen_range(0.01..100.0);
+            let max_price = min_price * rng.gen_range(1.1..3.0);
+            let fund_amount =
+                rng.gen_range(1_000_000..1_000_000_000u64) * 10u64.pow(base_token_decimal.into());
+            let curvature = rng.gen_range(0.5..2.0);
+
+            let SeedDistribution {
+                bins_amount,
+                compressed_bin_amount,
+                compression_loss,
+                decompress_multiplier,
+                ..
+            } = compute_seed_distribution(
+                bin_step,
+                min_price,
+                max_price,
+                base_token_decimal,
+                quote_token_decimal,
+                fund_amount,
+                curvature,
+            )
+            .unwrap();
+
+            let decompressed_total = compressed_bin_amount
+                .values()
+                .map(|&compressed| u128::from(compressed) * u128::from(decompress_multiplier))
+                .sum::<u128>();
+            assert_eq!(
+                decompressed_total + u128::from(compression_loss),
+                u128::from(fund_amount)
+            );
+
+            assert_compression_round_trip(bins_amount.into_iter().collect(), decompress_multiplier);
+        }
+    }
+
+    #[test]
+    fn test_seed_progress_from_position_accounts() {
+        let distribution =
+            compute_seed_distribution(10, 1.0, 2.0, 6, 6, 1_000_000_000_000, 1.0).unwrap();
+        assert!(distribution.position_number > 2);
+
+        // 所有头寸的存入数量之和等于播种总量 / The deposits of every position add up to the seed amount
+        let total_deposit = (0..distribution.position_number)
+            .map(|index| distribution.position_deposit_amount(index).unwrap())
+            .sum::<u64>();
+        assert_eq!(total_deposit, 1_000_000_000_000);
+
+        let position_account = |deposited: bool| {
+            let mut position: PositionV2 = bytemuck::Zeroable::zeroed();
+            if deposited {
+                position.liquidity_shares[0] = 1;
+            }
+
+            let mut data = PositionV2::DISCRIMINATOR.to_vec();
+            data.extend_from_slice(bytemuck::bytes_of(&position));
+
+            Some(Account {
+                data,
+                ..Default::default()
+            })
+        };
+
+        // 第一个头寸已存入，第二个头寸已初始化但未存入，其余头寸未初始化
+        // The first position is deposited, the second is initialized without liquidity and the rest are missing
+        let mut position_accounts = vec![position_account(true), position_account(false)];
+        position_accounts.resize(distribution.position_number as usize, None);
+
+        let progress =
+            SeedProgress::from_position_accounts(&distribution, &position_accounts).unwrap();
+        assert_eq!(
+            progress,
+            SeedProgress {
+                initialized_positions: 2,
+                deposited_amount: distribution.position_deposit_amount(0).unwrap(),
+            }
+        );
+
+        // 全新执行没有任何进度 / A fresh run has no progress
+        let progress = SeedProgress::from_position_accounts(
+            &distribution,
+            &vec![None; distribution.position_number as usize],
+        )
+        .unwrap();
+        assert_eq!(progress, SeedProgress::default());
+    }
+
+    #[test]
+    fn test_get_bitmap_extension_for_bin_arrays_across_boundary() {
+        let bitmap_extension = Pubkey::new_unique();
+        let (_, max_bitmap_id) = LbPair::bitmap_range();
+        let bin_per_array = MAX_BIN_PER_ARRAY as i32;
+
+        // 第一个扩展bin数组的最低bin ID，范围横跨内部bitmap与扩展的边界，仓位宽度与bin数组相同
+        // Lowest bin id of the first extension bin array. The range straddles the internal bitmap / extension
+        // boundary, positions are as wide as a bin array
+        let boundary_bin_id = (max_bitmap_id + 1) * bin_per_array;
+        let min_bin_id = boundary_bin_id - bin_per_array - 10;
+        let max_bin_id = min_bin_id + 3 * bin_per_array;
+
+        let lower_bin_array_index = BinArray::bin_id_to_bin_array_index(min_bin_id).unwrap();
+        let upper_bin_array_index = BinArray::bin_id_to_bin_array_index(max_bin_id - 1).unwrap();
+        assert!(is_overflow_internal_bitmap_range([
+            lower_bin_array_index,
+            upper_bin_array_index
+        ]));
+
+        let position_bitmap_extensions = (min_bin_id..max_bin_id)
+            .step_by(MAX_BIN_PER_ARRAY)
+            .map(|lower_bin_id| {
+                let upper_bin_id = lower_bin_id + bin_per_array - 1;
+                let indexes =
+                    BinArray::get_bin_array_indexes_coverage(lower_bin_id, upper_bin_id).unwrap();
+                get_bitmap_extension_for_bin_arrays(indexes, bitmap_extension)
+            })
+            .collect::<Vec<_>>();
+
+        // 第一个仓位位于内部范围，第二个仓位横跨边界，第三个仓位位于扩展范围
+        // The first position is in the internal range, the second one straddles the boundary and the third one is
+        // in the extension range
+        assert_eq!(
+            position_bitmap_extensions,
+            vec![dlmm::ID, bitmap_extension, bitmap_extension]
+        );
+    }
 }
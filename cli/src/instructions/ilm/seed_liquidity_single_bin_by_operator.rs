@@ -4,7 +4,7 @@ use anchor_lang::{prelude::Clock, AccountDeserialize};
 use anchor_spl::{
     associated_token::get_associated_token_address_with_program_id,
     token::spl_token::instruction::transfer_checked,
-    token_interface::{Mint, TokenAccount},
+    token_interface::TokenAccount,
 };
 use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
 
@@ -18,11 +18,15 @@ pub struct SeedLiquiditySingleBinByOperatorParameters {
     #[clap(long)]
     pub lb_pair: Pubkey,
     /// 基础头寸路径 / Base position path
+    #[clap(long, required_unless_present = "base_seed", conflicts_with = "base_seed")]
+    pub base_position_path: Option<String>,
+    /// 用于确定性派生基础头寸密钥对的种子，派生方式为 sha256("dlmm-position-base" || seed) 作为ed25519私钥种子
+    /// Seed to deterministically derive the base position keypair. The ed25519 secret seed is sha256("dlmm-position-base" || seed)
     #[clap(long)]
-    pub base_position_path: String,
-    /// 基础头寸公钥 / Base position public key
+    pub base_seed: Option<String>,
+    /// 基础头寸公钥，提供时校验与基础头寸密钥对是否匹配 / Base position public key, validated against the base position keypair when provided
     #[clap(long)]
-    pub base_pubkey: Pubkey,
+    pub base_pubkey: Option<Pubkey>,
     /// X代币的数量 / Amount of X token
     #[clap(long)]
     pub amount: u64,
@@ -32,15 +36,29 @@ pub struct SeedLiquiditySingleBinByOperatorParameters {
     /// 头寸所有者 / Position owner
     #[clap(long)]
     pub position_owner: Pubkey,
-    /// 锁定释放点 / Lock release point
+    /// 锁定释放点（时隙或unix时间戳，取决于交易对的激活类型） / Lock release point (slot or unix timestamp, depending on the pair activation type)
+    #[clap(
+        long,
+        required_unless_present = "lock_release_at",
+        conflicts_with = "lock_release_at"
+    )]
+    pub lock_release_point: Option<u64>,
+    /// 以RFC3339时间指定的锁定释放点，例如 "2025-01-01T00:00:00Z"，仅适用于按时间戳激活的交易对
+    /// Lock release point as an RFC3339 datetime, e.g. "2025-01-01T00:00:00Z". Only for timestamp activated pairs
     #[clap(long)]
-    pub lock_release_point: u64,
+    pub lock_release_at: Option<String>,
     /// 费用所有者 / Fee owner
     #[clap(long)]
     pub fee_owner: Pubkey,
     /// 选择性舍入 / Selective rounding
     #[clap(long)]
     pub selective_rounding: SelectiveRounding,
+    /// 报价代币为X代币（反向交易对），价格为以X代币计价的Y代币价格 / Quote token is token X (reversed pair), prices are token Y priced in token X
+    #[clap(long)]
+    pub quote_is_x: bool,
+    /// 跳过向头寸所有者转账1个代币的证明转账及其代币账户创建，头寸所有者未持有X代币时仅给出警告 / Skip the 1 token prove transfer to the position owner and the creation of its token account. Only a warning is logged when the position owner holds no token X
+    #[clap(long)]
+    pub no_token_prove: bool,
 }
 
 /// 执行操作员在单个bin中播种流动性
@@ -51,35 +69,34 @@ pub async fn execute_seed_liquidity_single_bin_by_operator<
     params: SeedLiquiditySingleBinByOperatorParameters,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
-    compute_unit_price: Option<Instruction>,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<()> {
     // 解构参数
     // Destructure parameters
     let SeedLiquiditySingleBinByOperatorParameters {
         lb_pair,
         base_position_path,
+        base_seed,
         base_pubkey,
         amount,
         price,
         position_owner,
         lock_release_point,
+        lock_release_at,
         fee_owner,
         selective_rounding,
+        quote_is_x,
+        no_token_prove,
     } = params;
 
-    // 读取头寸基础密钥对文件
-    // Read position base keypair file
-    let position_base_kp = Arc::new(
-        read_keypair_file(base_position_path).expect("position base keypair file not found"),
-    );
-
-    // 验证头寸基础密钥是否正确
-    // Verify position base key is correct
-    assert_eq!(
-        position_base_kp.pubkey(),
+    // 读取或派生头寸基础密钥对，并验证头寸基础密钥是否正确
+    // Read or derive position base keypair and verify position base key is correct
+    let position_base_kp = Arc::new(resolve_position_base_keypair(
+        base_position_path,
+        base_seed,
         base_pubkey,
-        "Invalid position base key"
-    );
+    )?);
+    let base_pubkey = position_base_kp.pubkey();
 
     let rpc_client = program.rpc();
     let operator = program.payer();
@@ -124,7 +141,7 @@ pub async fn execute_seed_liquidity_single_bin_by_operator<
         &token_x_owner,
     );
 
-    let (mut bin_array_bitmap_extension, _bump) = derive_bin_array_bitmap_extension(lb_pair);
+    let (bin_array_bitmap_extension, _bump) = derive_bin_array_bitmap_extension(lb_pair);
 
     let mut accounts = rpc_client
         .get_multiple_accounts(&[
@@ -144,22 +161,38 @@ pub async fn execute_seed_liquidity_single_bin_by_operator<
 
     let clock = bincode::deserialize::<Clock>(&clock_account.data)?;
 
-    let token_mint_base = Mint::try_deserialize(&mut token_mint_base_account.data.as_ref())?;
-    let token_mint_quote = Mint::try_deserialize(&mut token_mint_quote_account.data.as_ref())?;
+    let lock_release_point = resolve_lock_release_point(
+        lock_release_point,
+        lock_release_at.as_deref(),
+        &lb_pair_state,
+        &clock,
+    )?;
+
+    let mut mint_cache = MintCache::new();
+    mint_cache.insert(lb_pair_state.token_x_mint, token_mint_base_account)?;
+    mint_cache.insert(lb_pair_state.token_y_mint, token_mint_quote_account)?;
+    let token_mint_base = mint_cache.get_cached(&lb_pair_state.token_x_mint)?;
+    let token_mint_quote = mint_cache.get_cached(&lb_pair_state.token_y_mint)?;
 
     let native_amount = to_wei_amount(amount, token_mint_base.decimals)?;
-    let native_amount = calculate_transfer_fee_included_amount(
-        &token_mint_base_account,
-        native_amount,
-        clock.epoch,
-    )?
-    .amount;
+    let native_amount = token_mint_base.transfer_fee_included_amount(native_amount, clock.epoch)?;
 
     // 将价格转换为每lamport价格
     // Convert price to per-lamport price
-    let price =
-        price_per_token_to_per_lamport(price, token_mint_base.decimals, token_mint_quote.decimals)
-            .context("price_per_token_per_lamport overflow")?;
+    let price = price_per_token_to_per_lamport(
+        flip_price_if_quote_is_x(price, quote_is_x),
+        token_mint_base.decimals,
+        token_mint_quote.decimals,
+    )
+    .context("price_per_token_per_lamport overflow")?;
+
+    // 价格翻转后，舍入方向也需要翻转
+    // Rounding direction flips together with the price
+    let selective_rounding = match (selective_rounding, quote_is_x) {
+        (SelectiveRounding::Up, true) => SelectiveRounding::Down,
+        (SelectiveRounding::Down, true) => SelectiveRounding::Up,
+        (selective_rounding, _) => selective_rounding,
+    };
 
     // 根据选择性舍入方式计算bin ID
     // Calculate bin ID based on selective rounding
@@ -186,16 +219,12 @@ pub async fn execute_seed_liquidity_single_bin_by_operator<
 
     let bin_array_index = BinArray::bin_id_to_bin_array_index(bin_id)?;
 
-    let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(1_400_000)];
-
-    if let Some(priority_fee_ix) = compute_unit_price {
-        instructions.push(priority_fee_ix);
-    }
+    let mut instructions = compute_unit_price.instructions_with_limit(Some(1_400_000))?;
 
-    let (min_bitmap_id, max_bitmap_id) = LbPair::bitmap_range();
     // We only deposit to lower bin array
-    let overflow_internal_bitmap_range =
-        bin_array_index > max_bitmap_id || bin_array_index < min_bitmap_id;
+    let overflow_internal_bitmap_range = is_overflow_internal_bitmap_range([bin_array_index]);
+    let position_bitmap_extension =
+        get_bitmap_extension_for_bin_arrays([bin_array_index], bin_array_bitmap_extension);
 
     if overflow_internal_bitmap_range && bin_array_bitmap_extension_account.is_none() {
         let accounts = dlmm::client::accounts::InitializeBinArrayBitmapExtension {
@@ -216,8 +245,6 @@ pub async fn execute_seed_liquidity_single_bin_by_operator<
         };
 
         instructions.push(initialize_bitmap_extension_ix);
-    } else {
-        bin_array_bitmap_extension = dlmm::ID;
     }
 
     let account = dlmm::client::accounts::InitializeBinArray {
@@ -248,6 +275,21 @@ pub async fn execute_seed_liquidity_single_bin_by_operator<
         true
     };
 
+    // 跳过证明转账时，头寸所有者未持有X代币只给出警告，由链上程序决定是否接受
+    // When the token prove is skipped, an owner holding no token X only gets a warning and the program decides
+    // whether to accept it
+    let require_token_prove = if no_token_prove {
+        if require_token_prove {
+            warn!(
+                %position_owner,
+                "Token prove skipped but the position owner holds no token X"
+            );
+        }
+        false
+    } else {
+        require_token_prove
+    };
+
     if require_token_prove {
         instructions.push(create_associated_token_account_idempotent(
             &operator,
@@ -256,9 +298,7 @@ pub async fn execute_seed_liquidity_single_bin_by_operator<
             &token_x_owner,
         ));
 
-        let prove_amount =
-            calculate_transfer_fee_included_amount(&token_mint_base_account, 1, clock.epoch)?
-                .amount;
+        let prove_amount = token_mint_base.transfer_fee_included_amount(1, clock.epoch)?;
 
         instructions.push(transfer_checked(
             &token_x_owner,
@@ -323,7 +363,7 @@ pub async fn execute_seed_liquidity_single_bin_by_operator<
     let main_accounts = dlmm::client::accounts::AddLiquidity2 {
         position,
         lb_pair,
-        bin_array_bitmap_extension: Some(bin_array_bitmap_extension),
+        bin_array_bitmap_extension: Some(position_bitmap_extension),
         user_token_x: operator_token_x,
         user_token_y: operator_token_y,
         reserve_x: lb_pair_state.reserve_x,
@@ -331,8 +371,8 @@ pub async fn execute_seed_liquidity_single_bin_by_operator<
         token_x_mint: lb_pair_state.token_x_mint,
         token_y_mint: lb_pair_state.token_y_mint,
         sender: program.payer(),
-        token_x_program: token_mint_base_account.owner,
-        token_y_program: token_mint_quote_account.owner,
+        token_x_program: token_mint_base.program_owner,
+        token_y_program: token_mint_quote.program_owner,
         event_authority,
         program: dlmm::ID,
     }
@@ -372,7 +412,7 @@ pub async fn execute_seed_liquidity_single_bin_by_operator<
         .send_with_spinner_and_config(transaction_config)
         .await;
 
-    println!("{:#?}", signature);
+    info!(?signature, "Seed liquidity single bin");
 
     signature?;
 
@@ -17,6 +17,7 @@ pub async fn execute_increase_oracle_length<C: Deref<Target = impl Signer> + Clo
     params: IncreaseOracleLengthParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<()> {
     // 解构参数
     // Destructure parameters
@@ -55,13 +56,13 @@ pub async fn execute_increase_oracle_length<C: Deref<Target = impl Signer> + Clo
 
     // 构建并发送交易
     // Build and send transaction
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
     let signature = request_builder
         .instruction(increase_length_ix)
         .send_with_spinner_and_config(transaction_config)
         .await;
 
-    println!("Increase oracle {oracle} length. Signature: {signature:#?}");
+    info!(%oracle, ?signature, "Increase oracle length");
 
     signature?;
 
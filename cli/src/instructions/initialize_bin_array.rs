@@ -20,6 +20,7 @@ pub struct InitBinArrayParams {
 /// * `params` - 初始化bin数组的参数 / Parameters for bin array initialization
 /// * `program` - Solana程序引用 / Solana program reference
 /// * `transaction_config` - 交易配置 / Transaction configuration
+/// * `compute_unit_price` - 计算单元价格设置 / Compute unit price setting
 /// 
 /// # 返回值 / Returns
 /// 返回新创建的bin数组公钥 / Returns the public key of the newly created bin array
@@ -31,6 +32,7 @@ pub async fn execute_initialize_bin_array<C: Deref<Target = impl Signer> + Clone
     params: InitBinArrayParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<Pubkey> {
     let InitBinArrayParams {
         lb_pair,
@@ -68,13 +70,13 @@ pub async fn execute_initialize_bin_array<C: Deref<Target = impl Signer> + Clone
 
     // 构建并发送交易
     // Build and send transaction
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
     let signature = request_builder
         .instruction(init_bin_array_ix)
         .send_with_spinner_and_config(transaction_config)
         .await;
 
-    println!("Initialize Bin Array {bin_array}. Signature: {signature:#?}");
+    info!(%bin_array, ?signature, "Initialize Bin Array");
 
     signature?;
 
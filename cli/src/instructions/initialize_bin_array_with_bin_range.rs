@@ -17,6 +17,7 @@ pub async fn execute_initialize_bin_array_with_bin_range<C: Deref<Target = impl
     params: InitBinArrayWithBinRangeParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<Vec<Pubkey>> {
     let InitBinArrayWithBinRangeParams {
         lb_pair,
@@ -34,8 +35,13 @@ pub async fn execute_initialize_bin_array_with_bin_range<C: Deref<Target = impl
             bin_array_index: idx.into(),
             lb_pair,
         };
-        let bin_array_pubkey =
-            execute_initialize_bin_array(params, program, transaction_config).await?;
+        let bin_array_pubkey = execute_initialize_bin_array(
+            params,
+            program,
+            transaction_config,
+            compute_unit_price.clone(),
+        )
+        .await?;
         bin_arrays_pubkey.push(bin_array_pubkey);
     }
 
@@ -18,6 +18,7 @@ pub async fn execute_initialize_bin_array_with_price_range<
     params: InitBinArrayWithPriceRangeParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<Vec<Pubkey>> {
     let InitBinArrayWithPriceRangeParams {
         lb_pair,
@@ -52,5 +53,11 @@ pub async fn execute_initialize_bin_array_with_price_range<
         upper_bin_id,
     };
 
-    execute_initialize_bin_array_with_bin_range(params, program, transaction_config).await
+    execute_initialize_bin_array_with_bin_range(
+        params,
+        program,
+        transaction_config,
+        compute_unit_price,
+    )
+    .await
 }
@@ -1,6 +1,5 @@
 use crate::*;
-use anchor_lang::AccountDeserialize;
-use anchor_spl::token_interface::Mint;
+use anchor_lang::prelude::Clock;
 use instructions::*;
 
 #[derive(Debug, Parser)]
@@ -35,6 +34,10 @@ pub struct InitCustomizablePermissionlessLbPairParam {
     /// Pool activation point. None = Now
     #[clap(long)]
     pub activation_point: Option<u64>,
+    /// Maximum deviation in bps of the actual initial price, at the computed active bin, from the initial price.
+    /// 活跃bin对应的实际初始价格相对 `initial_price` 的最大偏差（基点）
+    #[clap(long)]
+    pub max_price_deviation_bps: Option<u16>,
 }
 
 pub async fn execute_initialize_customizable_permissionless_lb_pair<
@@ -43,7 +46,7 @@ pub async fn execute_initialize_customizable_permissionless_lb_pair<
     params: InitCustomizablePermissionlessLbPairParam,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
-    compute_unit_price: Option<Instruction>,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<Pubkey> {
     let InitCustomizablePermissionlessLbPairParam {
         bin_step,
@@ -56,18 +59,26 @@ pub async fn execute_initialize_customizable_permissionless_lb_pair<
         has_alpha_vault,
         selective_rounding,
         creator_pool_on_off_control,
+        max_price_deviation_bps,
     } = params;
 
+    validate_mint_pair(token_mint_x, token_mint_y)?;
+
     let rpc_client = program.rpc();
     let mut accounts = rpc_client
-        .get_multiple_accounts(&[token_mint_x, token_mint_y])
+        .get_multiple_accounts(&[token_mint_x, token_mint_y, solana_sdk::sysvar::clock::ID])
         .await?;
 
     let token_mint_base_account = accounts[0].take().context("token_mint_base not found")?;
     let token_mint_quote_account = accounts[1].take().context("token_mint_quote not found")?;
+    let clock_account = accounts[2].take().context("clock not found")?;
+    let clock = bincode::deserialize::<Clock>(&clock_account.data)?;
 
-    let token_mint_base = Mint::try_deserialize(&mut token_mint_base_account.data.as_ref())?;
-    let token_mint_quote = Mint::try_deserialize(&mut token_mint_quote_account.data.as_ref())?;
+    let mut mint_cache = MintCache::new();
+    mint_cache.insert(token_mint_x, token_mint_base_account)?;
+    mint_cache.insert(token_mint_y, token_mint_quote_account)?;
+    let token_mint_base = mint_cache.get_cached(&token_mint_x)?;
+    let token_mint_quote = mint_cache.get_cached(&token_mint_y)?;
 
     let price_per_lamport = price_per_token_to_per_lamport(
         initial_price,
@@ -85,12 +96,47 @@ pub async fn execute_initialize_customizable_permissionless_lb_pair<
             .context("get_id_from_price overflow"),
     }?;
 
+    check_initial_price(
+        initial_price,
+        computed_active_id,
+        bin_step,
+        token_mint_base.decimals,
+        token_mint_quote.decimals,
+        max_price_deviation_bps,
+    )?;
+
+    let (base_factor, base_fee_power_factor) =
+        compute_base_factor_from_fee_bps(bin_step, base_fee_bps)?;
+
+    ensure!(
+        base_fee_power_factor == 0,
+        "Base fee {} bps requires a base fee power factor, use initialize-customizable-permissionless-lb-pair2",
+        base_fee_bps
+    );
+
+    let customizable_params = CustomizableParams {
+        active_id: computed_active_id,
+        bin_step,
+        base_factor,
+        activation_type,
+        activation_point,
+        has_alpha_vault,
+        base_fee_power_factor,
+        creator_pool_on_off_control,
+        padding: [0u8; 62],
+    };
+
     let (lb_pair, _bump) = derive_customizable_permissionless_lb_pair(token_mint_x, token_mint_y);
 
     if program.rpc().get_account_data(&lb_pair).await.is_ok() {
         return Ok(lb_pair);
     }
 
+    // 交易对创建后无法修改这些参数，发送前校验并打印配置摘要
+    // These parameters can't be changed once the pair is created, validate them and print a summary before sending
+    validate_customizable_params(&customizable_params, &clock)?;
+    print_customizable_pair_summary(lb_pair, &customizable_params)?;
+
     let (reserve_x, _bump) = derive_reserve_pda(token_mint_x, lb_pair);
     let (reserve_y, _bump) = derive_reserve_pda(token_mint_y, lb_pair);
     let (oracle, _bump) = derive_oracle_pda(lb_pair);
@@ -125,7 +171,7 @@ pub async fn execute_initialize_customizable_permissionless_lb_pair<
         oracle,
         funder: program.payer(),
         system_program: solana_sdk::system_program::ID,
-        token_program: token_mint_base_account.owner,
+        token_program: token_mint_base.program_owner,
         event_authority,
         user_token_x,
         user_token_y,
@@ -133,23 +179,8 @@ pub async fn execute_initialize_customizable_permissionless_lb_pair<
     }
     .to_account_metas(None);
 
-    let (base_factor, base_fee_power_factor) =
-        compute_base_factor_from_fee_bps(bin_step, base_fee_bps)?;
-
-    assert!(base_fee_power_factor == 0);
-
     let data = dlmm::client::args::InitializeCustomizablePermissionlessLbPair {
-        params: CustomizableParams {
-            active_id: computed_active_id,
-            bin_step,
-            base_factor,
-            activation_type,
-            activation_point,
-            has_alpha_vault,
-            base_fee_power_factor,
-            creator_pool_on_off_control,
-            padding: [0u8; 62],
-        },
+        params: customizable_params,
     }
     .data();
 
@@ -159,13 +190,13 @@ pub async fn execute_initialize_customizable_permissionless_lb_pair<
         data,
     };
 
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
     let signature = request_builder
         .instruction(init_pair_ix)
         .send_with_spinner_and_config(transaction_config)
         .await;
 
-    println!("Initialize Customizable LB pair {lb_pair}. Signature: {signature:#?}");
+    info!(%lb_pair, ?signature, "Initialize Customizable LB pair");
 
     signature?;
 
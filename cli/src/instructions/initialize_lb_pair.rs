@@ -1,6 +1,4 @@
 use crate::*;
-use anchor_lang::AccountDeserialize;
-use anchor_spl::token_interface::Mint;
 
 #[derive(Debug, Parser)]
 pub struct InitLbPairParams {
@@ -12,31 +10,34 @@ pub struct InitLbPairParams {
     pub token_mint_y: Pubkey,
     /// The initial price of the liquidity pair. Eg: 24123.12312412 USDC per 1 BTC.
     pub initial_price: f64,
+    /// Maximum deviation in bps of the actual initial price, at the computed active bin, from the initial price.
+    /// 活跃bin对应的实际初始价格相对 `initial_price` 的最大偏差（基点）
+    #[clap(long)]
+    pub max_price_deviation_bps: Option<u16>,
 }
 
 pub async fn execute_initialize_lb_pair<C: Deref<Target = impl Signer> + Clone>(
     params: InitLbPairParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<Pubkey> {
     let InitLbPairParams {
         preset_parameter,
         token_mint_x,
         token_mint_y,
         initial_price,
+        max_price_deviation_bps,
     } = params;
 
-    let rpc_client = program.rpc();
-
-    let mut accounts = rpc_client
-        .get_multiple_accounts(&[token_mint_x, token_mint_y])
-        .await?;
+    validate_mint_pair(token_mint_x, token_mint_y)?;
 
-    let token_mint_base_account = accounts[0].take().context("token_mint_base not found")?;
-    let token_mint_quote_account = accounts[1].take().context("token_mint_quote not found")?;
+    let rpc_client = program.rpc();
 
-    let token_mint_base = Mint::try_deserialize(&mut token_mint_base_account.data.as_ref())?;
-    let token_mint_quote = Mint::try_deserialize(&mut token_mint_quote_account.data.as_ref())?;
+    let mut mint_cache = MintCache::new();
+    mint_cache.fetch(&rpc_client, &[token_mint_x, token_mint_y]).await?;
+    let token_mint_base = mint_cache.get_cached(&token_mint_x)?;
+    let token_mint_quote = mint_cache.get_cached(&token_mint_y)?;
 
     let price_per_lamport = price_per_token_to_per_lamport(
         initial_price,
@@ -58,6 +59,15 @@ pub async fn execute_initialize_lb_pair<C: Deref<Target = impl Signer> + Clone>(
     let computed_active_id = get_id_from_price(bin_step, &price_per_lamport, Rounding::Up)
         .context("get_id_from_price overflow")?;
 
+    check_initial_price(
+        initial_price,
+        computed_active_id,
+        bin_step,
+        token_mint_base.decimals,
+        token_mint_quote.decimals,
+        max_price_deviation_bps,
+    )?;
+
     let (lb_pair, _bump) = derive_lb_pair_pda2(
         token_mint_x,
         token_mint_y,
@@ -84,7 +94,7 @@ pub async fn execute_initialize_lb_pair<C: Deref<Target = impl Signer> + Clone>(
         token_mint_y,
         oracle,
         funder: program.payer(),
-        token_program: token_mint_base_account.owner,
+        token_program: token_mint_base.program_owner,
         preset_parameter,
         system_program: solana_sdk::system_program::ID,
         event_authority,
@@ -105,14 +115,14 @@ pub async fn execute_initialize_lb_pair<C: Deref<Target = impl Signer> + Clone>(
         accounts,
     };
 
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
 
     let signature = request_builder
         .instruction(init_pair_ix)
         .send_with_spinner_and_config(transaction_config)
         .await;
 
-    println!("Initialize LB pair {lb_pair}. Signature: {signature:#?}");
+    info!(%lb_pair, ?signature, "Initialize LB pair");
 
     signature?;
 
@@ -1,6 +1,4 @@
 use crate::*;
-use anchor_lang::AccountDeserialize;
-use anchor_spl::token_interface::Mint;
 
 /// 初始化流动性对参数（版本2）
 #[derive(Debug, Parser)]
@@ -17,6 +15,14 @@ pub struct InitLbPair2Params {
     /// The initial price of the liquidity pair. Eg: 24123.12312412 USDC per 1 BTC.
     /// 流动性对的初始价格。例如：每1个BTC价值24123.12312412 USDC
     pub initial_price: f64,
+    /// Maximum deviation in bps of the actual initial price, at the computed active bin, from the initial price.
+    /// 活跃bin对应的实际初始价格相对 `initial_price` 的最大偏差（基点）
+    #[clap(long)]
+    pub max_price_deviation_bps: Option<u16>,
+    /// Expected bin step of the preset parameter. Guards against picking the wrong preset pubkey.
+    /// 预设参数的预期bin步长，用于防止选错预设参数公钥
+    #[clap(long)]
+    pub expected_bin_step: Option<u16>,
 }
 
 /// 执行初始化流动性对（版本2）
@@ -25,6 +31,7 @@ pub struct InitLbPair2Params {
 /// * `params` - 初始化参数
 /// * `program` - Anchor程序客户端
 /// * `transaction_config` - 交易配置
+/// * `compute_unit_price` - 计算单元价格设置
 /// 
 /// # 返回
 /// * 创建的流动性对地址
@@ -38,27 +45,48 @@ pub async fn execute_initialize_lb_pair2<C: Deref<Target = impl Signer> + Clone>
     params: InitLbPair2Params,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<Pubkey> {
     let InitLbPair2Params {
         preset_parameter,
         token_mint_x,
         token_mint_y,
         initial_price,
+        max_price_deviation_bps,
+        expected_bin_step,
     } = params;
 
+    validate_mint_pair(token_mint_x, token_mint_y)?;
+
     let rpc_client = program.rpc();
 
-    // 批量获取代币铸造账户信息
+    // 流动性对、代币徽章地址均可由参数推导，因此所有账户通过一次批量请求获取
+    // The lb pair and token badge addresses are derived from the params, so every account is read in one batch
+    let (lb_pair, _bump) =
+        derive_lb_pair_with_preset_parameter_key(preset_parameter, token_mint_x, token_mint_y);
+    let (token_badge_x, _bump) = derive_token_badge_pda(token_mint_x);
+    let (token_badge_y, _bump) = derive_token_badge_pda(token_mint_y);
+
     let mut accounts = rpc_client
-        .get_multiple_accounts(&[token_mint_x, token_mint_y])
+        .get_multiple_accounts(&[
+            token_mint_x,
+            token_mint_y,
+            preset_parameter,
+            lb_pair,
+            token_badge_x,
+            token_badge_y,
+        ])
         .await?;
 
     let token_mint_base_account = accounts[0].take().context("token_mint_base not found")?;
     let token_mint_quote_account = accounts[1].take().context("token_mint_quote not found")?;
 
     // 反序列化代币铸造信息，获取小数位数
-    let token_mint_base = Mint::try_deserialize(&mut token_mint_base_account.data.as_ref())?;
-    let token_mint_quote = Mint::try_deserialize(&mut token_mint_quote_account.data.as_ref())?;
+    let mut mint_cache = MintCache::new();
+    mint_cache.insert(token_mint_x, token_mint_base_account)?;
+    mint_cache.insert(token_mint_y, token_mint_quote_account)?;
+    let token_mint_base = mint_cache.get_cached(&token_mint_x)?;
+    let token_mint_quote = mint_cache.get_cached(&token_mint_y)?;
 
     // 将UI价格转换为每lamport价格
     let price_per_lamport = price_per_token_to_per_lamport(
@@ -69,25 +97,42 @@ pub async fn execute_initialize_lb_pair2<C: Deref<Target = impl Signer> + Clone>
     .context("price_per_token_to_per_lamport overflow")?;
 
     // 获取预设参数状态
-    let preset_parameter_state: PresetParameter2 = rpc_client
-        .get_account_and_deserialize(&preset_parameter, |account| {
-            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
-        })
-        .await?;
+    let preset_parameter_account = accounts[2].take().context("preset_parameter not found")?;
+    let preset_parameter_state: PresetParameter2 = bytemuck::pod_read_unaligned(
+        preset_parameter_account
+            .data
+            .get(8..8 + std::mem::size_of::<PresetParameter2>())
+            .context("Invalid preset parameter account data")?,
+    );
 
     // 获取bin步长，用于计算活跃bin ID
     let bin_step = preset_parameter_state.bin_step;
 
+    if let Some(expected_bin_step) = expected_bin_step {
+        ensure!(
+            bin_step == expected_bin_step,
+            "Preset parameter {} has bin step {}, expected {}",
+            preset_parameter,
+            bin_step,
+            expected_bin_step
+        );
+    }
+
     // 根据初始价格计算活跃bin ID（向上舍入）
     let computed_active_id = get_id_from_price(bin_step, &price_per_lamport, Rounding::Up)
         .context("get_id_from_price overflow")?;
 
-    // 派生流动性对PDA地址
-    let (lb_pair, _bump) =
-        derive_lb_pair_with_preset_parameter_key(preset_parameter, token_mint_x, token_mint_y);
+    check_initial_price(
+        initial_price,
+        computed_active_id,
+        bin_step,
+        token_mint_base.decimals,
+        token_mint_quote.decimals,
+        max_price_deviation_bps,
+    )?;
 
     // 如果流动性对已存在，直接返回地址
-    if program.rpc().get_account_data(&lb_pair).await.is_ok() {
+    if accounts[3].is_some() {
         return Ok(lb_pair);
     }
 
@@ -96,19 +141,13 @@ pub async fn execute_initialize_lb_pair2<C: Deref<Target = impl Signer> + Clone>
     let (oracle, _bump) = derive_oracle_pda(lb_pair);
 
     let (event_authority, _bump) = derive_event_authority_pda();
-    let (token_badge_x, _bump) = derive_token_badge_pda(token_mint_x);
-    let (token_badge_y, _bump) = derive_token_badge_pda(token_mint_y);
-
-    let accounts = rpc_client
-        .get_multiple_accounts(&[token_badge_x, token_badge_y])
-        .await?;
 
-    let token_badge_x = accounts[0]
+    let token_badge_x = accounts[4]
         .as_ref()
         .map(|_| token_badge_x)
         .or(Some(dlmm::ID));
 
-    let token_badge_y = accounts[1]
+    let token_badge_y = accounts[5]
         .as_ref()
         .map(|_| token_badge_y)
         .or(Some(dlmm::ID));
@@ -124,8 +163,8 @@ pub async fn execute_initialize_lb_pair2<C: Deref<Target = impl Signer> + Clone>
         funder: program.payer(),
         token_badge_x,
         token_badge_y,
-        token_program_x: token_mint_base_account.owner,
-        token_program_y: token_mint_quote_account.owner,
+        token_program_x: token_mint_base.program_owner,
+        token_program_y: token_mint_quote.program_owner,
         preset_parameter,
         system_program: solana_sdk::system_program::ID,
         event_authority,
@@ -147,14 +186,14 @@ pub async fn execute_initialize_lb_pair2<C: Deref<Target = impl Signer> + Clone>
         accounts,
     };
 
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
 
     let signature = request_builder
         .instruction(init_pair_ix)
         .send_with_spinner_and_config(transaction_config)
         .await;
 
-    println!("Initialize LB pair2 {lb_pair}. Signature: {signature:#?}");
+    info!(%lb_pair, ?signature, "Initialize LB pair2");
 
     signature?;
 
@@ -25,6 +25,7 @@ pub struct InitPositionParams {
 /// * `params` - 初始化仓位的参数 / Parameters for position initialization
 /// * `program` - Solana程序引用 / Solana program reference
 /// * `transaction_config` - 交易配置 / Transaction configuration
+/// * `compute_unit_price` - 计算单元价格设置 / Compute unit price setting
 /// 
 /// # 返回值 / Returns
 /// 返回新创建的仓位公钥 / Returns the public key of the newly created position
@@ -36,6 +37,7 @@ pub async fn execute_initialize_position<C: Deref<Target = impl Signer> + Clone>
     params: InitPositionParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<Pubkey> {
     let InitPositionParams {
         lb_pair,
@@ -43,6 +45,10 @@ pub async fn execute_initialize_position<C: Deref<Target = impl Signer> + Clone>
         width,
     } = params;
 
+    // 校验仓位宽度及bin范围
+    // Validate the position width and bin range
+    validate_position_width(lower_bin_id, width)?;
+
     // 创建新的仓位密钥对
     // Create a new position keypair
     let position_keypair = Arc::new(Keypair::new());
@@ -83,16 +89,17 @@ pub async fn execute_initialize_position<C: Deref<Target = impl Signer> + Clone>
 
     // 构建并发送交易
     // Build and send transaction
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
     let signature = request_builder
         .instruction(init_position_ix)
         .signer(position_keypair.clone())  // 仓位密钥对需要签名 / Position keypair needs to sign
         .send_with_spinner_and_config(transaction_config)
         .await;
 
-    println!(
-        "Initialize position {}. Signature: {signature:#?}",
-        position_keypair.pubkey()
+    info!(
+        position = %position_keypair.pubkey(),
+        ?signature,
+        "Initialize position"
     );
 
     signature?;
@@ -0,0 +1,381 @@
+use std::sync::Arc;
+
+use crate::*;
+use instructions::*;
+
+/// 初始化仓位并添加流动性的参数结构体
+/// Parameters for initializing a position and adding liquidity
+#[derive(Debug, Parser)]
+pub struct InitPositionAndAddLiquidityParams {
+    /// 流动性交易对的地址
+    /// Address of the liquidity pair.
+    pub lb_pair: Pubkey,
+    /// bin范围的下界ID，复用仓位时取自该仓位
+    /// Lower bound of the bin range. Taken from the position when reusing one.
+    #[clap(
+        long,
+        allow_negative_numbers = true,
+        required_unless_present = "reuse_position",
+        conflicts_with = "reuse_position"
+    )]
+    pub lower_bin_id: Option<i32>,
+    /// 仓位的宽度，从1到70，复用仓位时取自该仓位
+    /// Width of the position. Start with 1 until 70. Taken from the position when reusing one.
+    #[clap(
+        long,
+        required_unless_present = "reuse_position",
+        conflicts_with = "reuse_position"
+    )]
+    pub width: Option<i32>,
+    /// 要存入的X代币数量
+    /// Amount of token X to be deposited.
+    pub amount_x: u64,
+    /// 要存入的Y代币数量
+    /// Amount of token Y to be deposited.
+    pub amount_y: u64,
+    /// Liquidity distribution to the bins. "<BIN_ID,DIST_X,DIST_Y, BIN_ID,DIST_X,DIST_Y, ...>" where
+    /// BIN_ID = Bin to deposit to. Must be within the position range.
+    /// DIST_X = Percentage of amount_x to be deposited to the bins. Must not > 1.0
+    /// DIST_Y = Percentage of amount_y to be deposited to the bins. Must not > 1.0
+    /// For example: --bin-liquidity-distribution "5554,0.0,0.25 5555,0.75,0.75 5556,0.25,0.0"
+    ///
+    /// 流动性在各个bin中的分配。格式："<BIN_ID,DIST_X,DIST_Y, ...>"
+    /// BIN_ID = 存入的bin，必须位于仓位范围内
+    /// DIST_X = X代币分配到该bin的百分比（不得大于1.0）
+    /// DIST_Y = Y代币分配到该bin的百分比（不得大于1.0）
+    #[clap(long, value_parser = parse_bin_liquidity_distribution, value_delimiter = ' ', allow_hyphen_values = true)]
+    pub bin_liquidity_distribution: Vec<(i32, f64, f64)>,
+    /// JSON or CSV file with the liquidity distribution, one `BIN_ID,DIST_X,DIST_Y` row per entry of
+    /// `--bin-liquidity-distribution`. JSON: `[[BIN_ID, DIST_X, DIST_Y], ...]`. CSV: one row per line, an optional
+    /// header line and `#` comments are skipped. The file extension selects the format.
+    /// 包含流动性分配的JSON或CSV文件，每行对应 `--bin-liquidity-distribution` 的一项。由文件扩展名决定格式
+    #[clap(long, conflicts_with = "bin_liquidity_distribution")]
+    pub distribution_file: Option<String>,
+    /// 存入已有仓位而不创建新仓位，bin范围取自该仓位，分配的bin必须位于该仓位的 `[lower_bin_id, upper_bin_id]` 内。
+    /// 避免在一个仓位足够时创建许多小仓位
+    /// Deposit into this existing position instead of creating a new one. The bin range is taken from the position
+    /// and the distribution bins must fall within its `[lower_bin_id, upper_bin_id]`. Avoids creating many small
+    /// positions when one would do
+    #[clap(long)]
+    pub reuse_position: Option<Pubkey>,
+}
+
+/// 执行初始化仓位并添加流动性
+/// Executes initialize position and add liquidity
+///
+/// # 返回值 / Returns
+/// 返回存入流动性的仓位公钥 / Returns the public key of the position the liquidity was deposited to
+///
+/// # 功能说明 / Functionality
+/// 初始化缺失的bin数组，创建新仓位（或使用 `--reuse-position` 指定的已有仓位）并存入流动性。
+/// 能放入单笔交易时一次发送，否则拆分为两笔交易依次发送
+/// Initializes missing bin arrays, creates a new position (or uses the existing position given by
+/// `--reuse-position`) and deposits liquidity. Sent as a single transaction when it fits, otherwise as two
+/// chained transactions
+pub async fn execute_initialize_position_and_add_liquidity<
+    C: Deref<Target = impl Signer> + Clone,
+>(
+    params: InitPositionAndAddLiquidityParams,
+    program: &Program<C>,
+    transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
+) -> Result<Pubkey> {
+    let InitPositionAndAddLiquidityParams {
+        lb_pair,
+        lower_bin_id,
+        width,
+        amount_x,
+        amount_y,
+        bin_liquidity_distribution,
+        distribution_file,
+        reuse_position,
+    } = params;
+
+    let rpc_client = program.rpc();
+
+    let lb_pair_state: LbPair = rpc_client
+        .get_account_and_deserialize(&lb_pair, |account| {
+            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
+        })
+        .await?;
+
+    let [token_x_program, token_y_program] = lb_pair_state.get_token_programs()?;
+
+    // 复用已有仓位时，bin范围取自该仓位
+    // When reusing an existing position, the bin range is taken from the position
+    let (lower_bin_id, width) = match reuse_position {
+        Some(reuse_position) => {
+            let position_state = rpc_client
+                .get_account_and_deserialize(&reuse_position, |account| {
+                    deserialize_position_v2(&account.data)
+                })
+                .await?;
+
+            ensure!(
+                position_state.lb_pair == lb_pair,
+                "Position {} belongs to pair {}, not {}",
+                reuse_position,
+                position_state.lb_pair,
+                lb_pair
+            );
+            ensure!(
+                position_state.owner == program.payer(),
+                "Position {} is owned by {}, not the payer {}",
+                reuse_position,
+                position_state.owner,
+                program.payer()
+            );
+
+            (
+                position_state.lower_bin_id,
+                position_state.upper_bin_id - position_state.lower_bin_id + 1,
+            )
+        }
+        None => (
+            lower_bin_id.context("--lower-bin-id is required without --reuse-position")?,
+            width.context("--width is required without --reuse-position")?,
+        ),
+    };
+
+    validate_position_width(lower_bin_id, width)?;
+
+    let upper_bin_id = lower_bin_id + width - 1;
+
+    let mut bin_liquidity_distribution = match distribution_file {
+        Some(path) => load_bin_liquidity_distribution_file(&path)?,
+        None => bin_liquidity_distribution,
+    };
+
+    // 按bin ID排序，确保从低到高
+    bin_liquidity_distribution.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let min_bin_id = bin_liquidity_distribution
+        .first()
+        .map(|(bin_id, _, _)| *bin_id)
+        .context("No bin liquidity distribution provided")?;
+
+    let max_bin_id = bin_liquidity_distribution
+        .last()
+        .map(|(bin_id, _, _)| *bin_id)
+        .context("No bin liquidity distribution provided")?;
+
+    ensure!(
+        min_bin_id >= lower_bin_id && max_bin_id <= upper_bin_id,
+        "Bin liquidity distribution [{}, {}] is outside of position range [{}, {}]",
+        min_bin_id,
+        max_bin_id,
+        lower_bin_id,
+        upper_bin_id
+    );
+
+    let bin_liquidity_distribution = bin_liquidity_distribution
+        .into_iter()
+        .map(|(bin_id, dist_x, dist_y)| BinLiquidityDistribution {
+            bin_id,
+            distribution_x: (dist_x * BASIS_POINT_MAX as f64) as u16,
+            distribution_y: (dist_y * BASIS_POINT_MAX as f64) as u16,
+        })
+        .collect::<Vec<_>>();
+
+    let user_token_x = get_or_create_ata(
+        program,
+        transaction_config,
+        lb_pair_state.token_x_mint,
+        program.payer(),
+        compute_unit_price.clone(),
+    )
+    .await?;
+
+    let user_token_y = get_or_create_ata(
+        program,
+        transaction_config,
+        lb_pair_state.token_y_mint,
+        program.payer(),
+        compute_unit_price.clone(),
+    )
+    .await?;
+
+    let (bin_array_bitmap_extension, _bump) = derive_bin_array_bitmap_extension(lb_pair);
+    let (event_authority, _bump) = derive_event_authority_pda();
+
+    let bin_array_indexes = BinArray::get_bin_array_indexes_coverage(lower_bin_id, upper_bin_id)?;
+    let bin_array_keys = bin_array_indexes
+        .iter()
+        .map(|&index| derive_bin_array_pda(lb_pair, index.into()).0)
+        .collect::<Vec<_>>();
+
+    let accounts = rpc_client
+        .get_multiple_accounts_chunked(
+            &[vec![bin_array_bitmap_extension], bin_array_keys.clone()].concat(),
+        )
+        .await?;
+
+    let bin_array_bitmap_extension = accounts[0]
+        .as_ref()
+        .map(|_| bin_array_bitmap_extension)
+        .or(Some(dlmm::ID));
+
+    // 初始化缺失的bin数组
+    // Initialize missing bin arrays
+    let mut setup_ixs = vec![];
+
+    for ((account, bin_array), index) in accounts[1..]
+        .iter()
+        .zip(bin_array_keys.iter())
+        .zip(bin_array_indexes)
+    {
+        if account.is_none() {
+            let accounts = dlmm::client::accounts::InitializeBinArray {
+                bin_array: *bin_array,
+                lb_pair,
+                funder: program.payer(),
+                system_program: solana_sdk::system_program::ID,
+            }
+            .to_account_metas(None);
+
+            let data = dlmm::client::args::InitializeBinArray {
+                index: index.into(),
+            }
+            .data();
+
+            setup_ixs.push(Instruction {
+                program_id: dlmm::ID,
+                accounts,
+                data,
+            });
+        }
+    }
+
+    // 复用已有仓位，或创建新的仓位
+    // Reuse the existing position, or create the new position
+    let (position, position_keypair) = match reuse_position {
+        Some(position) => (position, None),
+        None => {
+            let position_keypair = Arc::new(Keypair::new());
+            let position = position_keypair.pubkey();
+
+            let accounts = dlmm::client::accounts::InitializePosition {
+                lb_pair,
+                payer: program.payer(),
+                position,
+                owner: program.payer(),
+                rent: solana_sdk::sysvar::rent::ID,
+                system_program: solana_sdk::system_program::ID,
+                event_authority,
+                program: dlmm::ID,
+            }
+            .to_account_metas(None);
+
+            let data = dlmm::client::args::InitializePosition {
+                lower_bin_id,
+                width,
+            }
+            .data();
+
+            setup_ixs.push(Instruction {
+                program_id: dlmm::ID,
+                accounts,
+                data,
+            });
+
+            (position, Some(position_keypair))
+        }
+    };
+
+    // 构建添加流动性指令
+    // Build add liquidity instruction
+    let main_accounts = dlmm::client::accounts::AddLiquidity2 {
+        lb_pair,
+        bin_array_bitmap_extension,
+        position,
+        reserve_x: lb_pair_state.reserve_x,
+        reserve_y: lb_pair_state.reserve_y,
+        token_x_mint: lb_pair_state.token_x_mint,
+        token_y_mint: lb_pair_state.token_y_mint,
+        sender: program.payer(),
+        user_token_x,
+        user_token_y,
+        token_x_program,
+        token_y_program,
+        event_authority,
+        program: dlmm::ID,
+    }
+    .to_account_metas(None);
+
+    let mut remaining_accounts_info = RemainingAccountsInfo { slices: vec![] };
+    let mut remaining_accounts = vec![];
+
+    if let Some((slices, transfer_hook_remaining_accounts)) =
+        get_potential_token_2022_related_ix_data_and_accounts(
+            &lb_pair_state,
+            program.rpc(),
+            ActionType::Liquidity,
+        )
+        .await?
+    {
+        remaining_accounts_info.slices = slices;
+        remaining_accounts.extend(transfer_hook_remaining_accounts);
+    };
+
+    remaining_accounts.extend(BinArray::get_bin_array_account_metas_coverage(
+        min_bin_id, max_bin_id, lb_pair,
+    )?);
+
+    let data = dlmm::client::args::AddLiquidity2 {
+        liquidity_parameter: LiquidityParameter {
+            amount_x,
+            amount_y,
+            bin_liquidity_dist: bin_liquidity_distribution,
+        },
+        remaining_accounts_info,
+    }
+    .data();
+
+    let add_liquidity_ix = Instruction {
+        program_id: dlmm::ID,
+        accounts: [main_accounts.to_vec(), remaining_accounts].concat(),
+        data,
+    };
+
+    let compute_budget_ixs = compute_unit_price.instructions_with_limit(Some(1_400_000))?;
+
+    let mut all_ixs = compute_budget_ixs.clone();
+    all_ixs.extend(setup_ixs.iter().cloned());
+    all_ixs.push(add_liquidity_ix.clone());
+
+    let transactions = if fits_in_single_transaction(&all_ixs, &program.payer()) {
+        vec![all_ixs]
+    } else {
+        // 拆分为两笔交易：初始化bin数组及仓位，然后添加流动性
+        // Split into two transactions: initialize bin arrays and position, then add liquidity
+        let mut setup = compute_unit_price.instructions()?;
+        setup.extend(setup_ixs);
+
+        let mut deposit = compute_budget_ixs;
+        deposit.push(add_liquidity_ix);
+
+        vec![setup, deposit]
+    };
+
+    for (i, ixs) in transactions.into_iter().enumerate() {
+        let mut builder = ixs
+            .into_iter()
+            .fold(program.request(), |builder, ix| builder.instruction(ix));
+
+        // 仓位密钥对仅在初始化仓位的交易中签名
+        // Position keypair only signs the transaction initializing the position
+        if let Some(position_keypair) = position_keypair.as_ref().filter(|_| i == 0) {
+            builder = builder.signer(position_keypair.clone());
+        }
+
+        let signature = builder
+            .send_with_spinner_and_config(transaction_config)
+            .await;
+
+        info!(%position, ?signature, "Initialize position and add liquidity");
+
+        signature?;
+    }
+
+    Ok(position)
+}
@@ -18,6 +18,7 @@ pub async fn execute_initialize_position_with_price_range<
     params: InitPositionWithPriceRangeParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<Pubkey> {
     let InitPositionWithPriceRangeParams {
         lb_pair,
@@ -39,11 +40,14 @@ pub async fn execute_initialize_position_with_price_range<
     )
     .context("get_id_from_price overflow")?;
 
+    validate_position_width(lower_bin_id, width)
+        .with_context(|| format!("Invalid position for lower price {}", lower_price))?;
+
     let params = InitPositionParams {
         lb_pair,
         lower_bin_id,
         width,
     };
 
-    execute_initialize_position(params, program, transaction_config).await
+    execute_initialize_position(params, program, transaction_config, compute_unit_price).await
 }
@@ -6,11 +6,97 @@ use solana_client::{
 
 use crate::*;
 
+/// 列出所有bin步长的参数
+/// Parameters for listing all bin steps
+#[derive(Debug, Parser)]
+pub struct ListAllBinStepParams {
+    /// 显示每个预设参数的完整费用范围（基础费、最大费、过滤/衰减周期、协议分成）
+    /// Show the full fee envelope of each preset (base fee, max fee, filter/decay period, protocol share)
+    #[clap(long)]
+    pub verbose: bool,
+}
+
+/// 预设参数中与费用相关的字段
+/// Fee related fields of a preset parameter
+struct PresetFeeParameters {
+    bin_step: u16,
+    base_factor: u16,
+    base_fee_power_factor: u8,
+    filter_period: u16,
+    decay_period: u16,
+    reduction_factor: u16,
+    variable_fee_control: u32,
+    max_volatility_accumulator: u32,
+    protocol_share: u16,
+}
+
+impl From<PresetParameter> for PresetFeeParameters {
+    fn from(state: PresetParameter) -> Self {
+        Self {
+            bin_step: state.bin_step,
+            base_factor: state.base_factor,
+            base_fee_power_factor: 0,
+            filter_period: state.filter_period,
+            decay_period: state.decay_period,
+            reduction_factor: state.reduction_factor,
+            variable_fee_control: state.variable_fee_control,
+            max_volatility_accumulator: state.max_volatility_accumulator,
+            protocol_share: state.protocol_share,
+        }
+    }
+}
+
+impl From<PresetParameter2> for PresetFeeParameters {
+    fn from(state: PresetParameter2) -> Self {
+        Self {
+            bin_step: state.bin_step,
+            base_factor: state.base_factor,
+            base_fee_power_factor: state.base_fee_power_factor,
+            filter_period: state.filter_period,
+            decay_period: state.decay_period,
+            reduction_factor: state.reduction_factor,
+            variable_fee_control: state.variable_fee_control,
+            max_volatility_accumulator: state.max_volatility_accumulator,
+            protocol_share: state.protocol_share,
+        }
+    }
+}
+
+impl PresetFeeParameters {
+    /// 构造一个仅包含费用参数的流动性对，以复用LbPair上的费用计算
+    /// Build an lb pair holding only the fee parameters to reuse the fee math on LbPair
+    fn to_lb_pair(&self, volatility_accumulator: u32) -> LbPair {
+        let mut lb_pair: LbPair = bytemuck::Zeroable::zeroed();
+        lb_pair.bin_step = self.bin_step;
+        lb_pair.parameters.base_factor = self.base_factor;
+        lb_pair.parameters.base_fee_power_factor = self.base_fee_power_factor;
+        lb_pair.parameters.variable_fee_control = self.variable_fee_control;
+        lb_pair.parameters.max_volatility_accumulator = self.max_volatility_accumulator;
+        lb_pair.v_parameters.volatility_accumulator = volatility_accumulator;
+        lb_pair
+    }
+
+    /// 基础费率
+    /// Base fee rate
+    fn base_fee_rate(&self) -> Result<u128> {
+        self.to_lb_pair(0).get_base_fee()
+    }
+
+    /// 波动率累加器达到上限时的总费率（基础费 + 最大可变费，受MAX_FEE_RATE限制）
+    /// Total fee rate when the volatility accumulator is maxed out (base + max variable fee, capped by MAX_FEE_RATE)
+    fn max_fee_rate(&self) -> Result<u128> {
+        self.to_lb_pair(self.max_volatility_accumulator).get_total_fee()
+    }
+}
+
 /// 执行列出所有bin步长操作
 /// Execute list all bin step operation
 pub async fn execute_list_all_bin_step<C: Deref<Target = impl Signer> + Clone>(
+    params: ListAllBinStepParams,
     program: &Program<C>,
 ) -> Result<()> {
+    let ListAllBinStepParams { verbose } = params;
+
     let rpc_client = program.rpc();
 
     // 设置账户配置，不获取数据内容
@@ -26,85 +112,93 @@ pub async fn execute_list_all_bin_step<C: Deref<Target = impl Signer> + Clone>(
 
     // 获取所有PresetParameter（v1）账户的密钥
     // Get all PresetParameter (v1) account keys
-    let preset_parameter_keys = rpc_client
-        .get_program_accounts_with_config(
-            &dlmm::ID,
-            RpcProgramAccountsConfig {
-                filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
-                    0,
-                    &PresetParameter::DISCRIMINATOR,
-                ))]),
-                account_config: account_config.clone(),
-                ..Default::default()
-            },
-        )
-        .await?
-        .into_iter()
-        .map(|(key, _)| key)
-        .collect::<Vec<_>>();
+    let preset_parameter_keys = get_program_accounts_with_retry(
+        &rpc_client,
+        RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                0,
+                PresetParameter::DISCRIMINATOR,
+            ))]),
+            account_config: account_config.clone(),
+            ..Default::default()
+        },
+    )
+    .await?
+    .into_iter()
+    .map(|(key, _)| key)
+    .collect::<Vec<_>>();
 
     // 获取所有PresetParameter2（v2）账户的密钥
     // Get all PresetParameter2 (v2) account keys
-    let preset_parameter_v2_keys = rpc_client
-        .get_program_accounts_with_config(
-            &dlmm::ID,
-            RpcProgramAccountsConfig {
-                filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
-                    0,
-                    &PresetParameter2::DISCRIMINATOR,
-                ))]),
-                account_config,
-                ..Default::default()
-            },
-        )
-        .await?
-        .into_iter()
-        .map(|(key, _)| key)
-        .collect::<Vec<_>>();
+    let preset_parameter_v2_keys = get_program_accounts_with_retry(
+        &rpc_client,
+        RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                0,
+                PresetParameter2::DISCRIMINATOR,
+            ))]),
+            account_config,
+            ..Default::default()
+        },
+    )
+    .await?
+    .into_iter()
+    .map(|(key, _)| key)
+    .collect::<Vec<_>>();
 
     // 合并所有版本的预设参数密钥
     // Combine all versioned preset parameter keys
     let all_versioned_keys = [preset_parameter_keys, preset_parameter_v2_keys].concat();
 
-    // 批量处理账户，每次100个以避免RPC限制
-    // Process accounts in batches of 100 to avoid RPC limits
-    for keys in all_versioned_keys.chunks(100) {
-        let accounts = rpc_client.get_multiple_accounts(keys).await?;
-        for (key, account) in keys.iter().zip(accounts) {
-            if let Some(account) = account {
-                // 读取账户判别器
-                // Read account discriminator
-                let mut disc = [0u8; 8];
-                disc.copy_from_slice(&account.data[..8]);
-
-                // 根据账户类型解析参数
-                // Parse parameters based on account type
-                let (bin_step, base_factor, base_fee_power_factor) = if disc
-                    == PresetParameter::DISCRIMINATOR
-                {
-                    let state = PresetParameter::try_deserialize(&mut account.data.as_ref())?;
-                    (state.bin_step, state.base_factor, 0)
-                } else if disc == PresetParameter2::DISCRIMINATOR {
-                    let state: PresetParameter2 = bytemuck::pod_read_unaligned(&account.data[8..]);
-                    (
-                        state.bin_step,
-                        state.base_factor,
-                        state.base_fee_power_factor,
-                    )
-                } else {
-                    continue;
-                };
-
-                // 计算基础费用百分比
-                // Calculate base fee percentage
-                let base_fee = (u128::from(bin_step)
-                    * u128::from(base_factor).pow(base_fee_power_factor.into())
-                    * 1000) as f64
-                    / FEE_PRECISION as f64;
+    // 分批获取账户以避免RPC限制
+    // Fetch the accounts in batches to avoid RPC limits
+    let accounts = rpc_client
+        .get_multiple_accounts_chunked(&all_versioned_keys)
+        .await?;
+
+    for (key, account) in all_versioned_keys.iter().zip(accounts) {
+        if let Some(account) = account {
+            // 读取账户判别器
+            // Read account discriminator
+            let mut disc = [0u8; 8];
+            disc.copy_from_slice(&account.data[..8]);
+
+            // 根据账户类型解析参数
+            // Parse parameters based on account type
+            let preset: PresetFeeParameters = if disc == PresetParameter::DISCRIMINATOR {
+                PresetParameter::try_deserialize(&mut account.data.as_ref())?.into()
+            } else if disc == PresetParameter2::DISCRIMINATOR {
+                bytemuck::pod_read_unaligned::<PresetParameter2>(&account.data[8..]).into()
+            } else {
+                continue;
+            };
 
+            // 计算基础费用百分比
+            // Calculate base fee percentage
+            let base_fee = fee_rate_to_fee_pct(preset.base_fee_rate()?)
+                .context("base fee convert to percentage overflow")?;
+
+            println!(
+                "Preset Pubkey: {}. Bin step {}. Base fee: {}%",
+                key, preset.bin_step, base_fee
+            );
+
+            if verbose {
+                let max_fee = fee_rate_to_fee_pct(preset.max_fee_rate()?)
+                    .context("max fee convert to percentage overflow")?;
+
+                println!("    Max fee: {}%", max_fee);
+                println!(
+                    "    Filter period: {}. Decay period: {}. Reduction factor: {}",
+                    preset.filter_period, preset.decay_period, preset.reduction_factor
+                );
+                println!(
+                    "    Variable fee control: {}. Max volatility accumulator: {}",
+                    preset.variable_fee_control, preset.max_volatility_accumulator
+                );
                 println!(
-                    "Preset Pubkey: {}. Bin step {}. Base fee: {}%",
-                    key, bin_step, base_fee
+                    "    Protocol share: {}%",
+                    preset.protocol_share as f64 * 100.0 / BASIS_POINT_MAX as f64
                 );
             }
         }
@@ -0,0 +1,92 @@
+use anchor_lang::{prelude::Clock, AccountDeserialize};
+use anchor_spl::token_interface::Mint;
+
+use crate::*;
+
+/// 列出交易对奖励配置的参数结构体
+/// Parameters for listing the reward configuration of a pair
+#[derive(Debug, Parser)]
+pub struct ListRewardsParams {
+    /// 流动性交易对地址
+    /// Liquidity pair address
+    pub lb_pair: Pubkey,
+}
+
+/// 执行列出交易对奖励配置指令
+/// Executes the list rewards instruction
+///
+/// # 功能说明 / Functionality
+/// 按奖励索引显示奖励代币、资助者、持续时间、速率、最后更新时间及尚未分发的奖励数量
+/// Shows the reward mint, funder, duration, rate, last update time and undistributed amount of every reward index
+pub async fn execute_list_rewards<C: Deref<Target = impl Signer> + Clone>(
+    params: ListRewardsParams,
+    program: &Program<C>,
+) -> Result<()> {
+    let ListRewardsParams { lb_pair } = params;
+
+    let rpc_client = program.rpc();
+
+    let lb_pair_state: LbPair = rpc_client
+        .get_account_and_deserialize(&lb_pair, |account| {
+            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
+        })
+        .await?;
+
+    let reward_mints = lb_pair_state
+        .reward_infos
+        .iter()
+        .map(|reward_info| reward_info.mint)
+        .collect::<Vec<_>>();
+
+    let mut accounts = rpc_client
+        .get_multiple_accounts_chunked(
+            &[vec![solana_sdk::sysvar::clock::ID], reward_mints].concat(),
+        )
+        .await?;
+
+    let clock_account = accounts[0].take().context("clock not found")?;
+    let clock = bincode::deserialize::<Clock>(&clock_account.data)?;
+    let current_time = clock.unix_timestamp as u64;
+
+    for (reward_index, reward_info) in lb_pair_state.reward_infos.iter().enumerate() {
+        println!("Reward index {}", reward_index);
+
+        if reward_info.mint.eq(&Pubkey::default()) {
+            println!("  Not initialized");
+            continue;
+        }
+
+        let reward_mint_account = accounts[reward_index + 1]
+            .take()
+            .context("reward_mint not found")?;
+        let reward_mint = Mint::try_deserialize(&mut reward_mint_account.data.as_ref())?;
+
+        // 奖励仍在分发中时，尚未分发的数量为速率乘以剩余时间
+        // While the reward is still distributing, the undistributed amount is the rate times the remaining time
+        let remaining_seconds = reward_info.reward_duration_end.saturating_sub(current_time);
+        let undistributed_amount: u64 = safe_mul_shr_cast(
+            reward_info.reward_rate,
+            remaining_seconds.into(),
+            SCALE_OFFSET,
+            Rounding::Down,
+        )?;
+        let ui_undistributed_amount =
+            amount_to_ui_amount(undistributed_amount, reward_mint.decimals).context("overflow")?;
+        let rate_per_second =
+            reward_rate_per_second(reward_info.reward_rate).context("overflow")?;
+
+        println!("  Mint: {}", reward_info.mint);
+        println!("  Vault: {}", reward_info.vault);
+        println!("  Funder: {}", reward_info.funder);
+        println!("  Reward duration: {} seconds", reward_info.reward_duration);
+        println!("  Reward duration end: {}", reward_info.reward_duration_end);
+        println!("  Reward rate: {} per second", rate_per_second);
+        println!("  Last update time: {}", reward_info.last_update_time);
+        println!(
+            "  Undistributed amount: {} ({}) with {} seconds remaining",
+            undistributed_amount, ui_undistributed_amount, remaining_seconds
+        );
+    }
+
+    Ok(())
+}
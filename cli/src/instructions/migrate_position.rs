@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use anchor_lang::Discriminator;
+
+use crate::*;
+
+/// 将旧版本仓位迁移为 `PositionV2` 的参数结构体
+/// Parameters for migrating a legacy position to `PositionV2`
+#[derive(Debug, Parser)]
+pub struct MigratePositionParams {
+    /// 旧版本（v1）仓位地址
+    /// Legacy (v1) position address
+    pub position: Pubkey,
+    /// 接收旧仓位回收租金的账户，默认为仓位所有者
+    /// Account receiving the reclaimed rent of the legacy position. Defaults to the position owner
+    #[clap(long)]
+    pub rent_receiver: Option<Pubkey>,
+}
+
+/// 执行迁移仓位指令
+/// Executes the migrate position instruction
+///
+/// # 功能说明 / Functionality
+/// 读取旧版本 `Position` 仓位，调用链上的迁移指令将流动性份额及手续费、奖励记录转移到新的 `PositionV2` 仓位，
+/// 旧仓位关闭后租金退还给仓位所有者。支付者必须是仓位所有者
+///
+/// Reads the legacy `Position` and invokes the on-chain migration instruction, which moves the liquidity shares
+/// and the fee and reward checkpoints into a new `PositionV2`. The legacy position is closed and its rent is
+/// returned to the owner. The payer must be the position owner
+pub async fn execute_migrate_position<C: Deref<Target = impl Signer> + Clone>(
+    params: MigratePositionParams,
+    program: &Program<C>,
+    transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
+) -> Result<Pubkey> {
+    let MigratePositionParams {
+        position,
+        rent_receiver,
+    } = params;
+
+    let rpc_client = program.rpc();
+
+    let position_account = rpc_client.get_account(&position).await?;
+
+    let disc = position_account
+        .data
+        .get(..8)
+        .context("Not a valid position account")?;
+
+    if disc == PositionV2::DISCRIMINATOR {
+        bail!("Position {} is already a PositionV2", position);
+    }
+
+    ensure!(
+        disc == Position::DISCRIMINATOR,
+        "Not a valid position account"
+    );
+
+    let position_state: Position = bytemuck::pod_read_unaligned(&position_account.data[8..]);
+
+    ensure!(
+        position_state.owner == program.payer(),
+        "Payer {} is not the position owner {}",
+        program.payer(),
+        position_state.owner
+    );
+
+    // 旧版本仓位固定覆盖两个相邻的bin数组
+    // A legacy position always covers two consecutive bin arrays
+    let lower_bin_array_index = BinArray::bin_id_to_bin_array_index(position_state.lower_bin_id)?;
+    let upper_bin_array_index = lower_bin_array_index.checked_add(1).context("overflow")?;
+
+    let (bin_array_lower, _bump) =
+        derive_bin_array_pda(position_state.lb_pair, lower_bin_array_index.into());
+    let (bin_array_upper, _bump) =
+        derive_bin_array_pda(position_state.lb_pair, upper_bin_array_index.into());
+
+    let position_v2_keypair = Arc::new(Keypair::new());
+    let (event_authority, _bump) = derive_event_authority_pda();
+
+    let accounts = dlmm::client::accounts::MigratePosition {
+        position_v2: position_v2_keypair.pubkey(),
+        position_v1: position,
+        lb_pair: position_state.lb_pair,
+        bin_array_lower,
+        bin_array_upper,
+        owner: position_state.owner,
+        system_program: solana_sdk::system_program::ID,
+        rent_receiver: rent_receiver.unwrap_or(position_state.owner),
+        event_authority,
+        program: dlmm::ID,
+    }
+    .to_account_metas(None);
+
+    let data = dlmm::client::args::MigratePosition {}.data();
+
+    let migrate_position_ix = Instruction {
+        program_id: dlmm::ID,
+        accounts,
+        data,
+    };
+
+    let signature = request_with_compute_unit_price(program, compute_unit_price)?
+        .instruction(migrate_position_ix)
+        .signer(position_v2_keypair.clone())
+        .send_with_spinner_and_config(transaction_config)
+        .await;
+
+    info!(
+        position_v1 = %position,
+        position_v2 = %position_v2_keypair.pubkey(),
+        ?signature,
+        "Migrate position"
+    );
+
+    signature?;
+
+    Ok(position_v2_keypair.pubkey())
+}
@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+
+use crate::*;
+use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+use anchor_client::solana_sdk::account::Account;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::state::Mint as MintState;
+
+/// 缓存的代币铸造账户信息
+/// Cached mint account information
+#[derive(Debug, Clone)]
+pub struct CachedMint {
+    /// 代币小数位数 / Token decimals
+    pub decimals: u8,
+    /// 铸造账户所属的代币程序 / Token program owning the mint account
+    pub program_owner: Pubkey,
+    /// Token-2022转账手续费配置 / Token-2022 transfer fee config
+    pub transfer_fee_config: Option<TransferFeeConfig>,
+    /// 原始铸造账户，用于计算转账手续费及转账钩子账户 / Raw mint account, used for transfer fee and transfer hook accounts
+    pub account: Account,
+}
+
+impl CachedMint {
+    /// 计算包含转账手续费的数量，没有转账手续费配置的代币直接返回原数量
+    /// Calculate the transfer fee included amount. Mints without a transfer fee config return the amount as is
+    pub fn transfer_fee_included_amount(&self, amount: u64, epoch: u64) -> Result<u64> {
+        if self.transfer_fee_config.is_none() {
+            return Ok(amount);
+        }
+
+        Ok(calculate_transfer_fee_included_amount(&self.account, amount, epoch)?.amount)
+    }
+}
+
+/// 按地址缓存代币铸造账户，避免单个命令中重复获取及反序列化
+/// Caches mint accounts by address so a single command does not refetch and deserialize them
+#[derive(Debug, Default)]
+pub struct MintCache {
+    mints: HashMap<Pubkey, CachedMint>,
+}
+
+impl MintCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 解析并缓存已获取的铸造账户
+    /// Parse and cache an already fetched mint account
+    pub fn insert(&mut self, mint: Pubkey, account: Account) -> Result<&CachedMint> {
+        let mint_state = StateWithExtensions::<MintState>::unpack(&account.data)
+            .with_context(|| format!("Failed to deserialize mint {}", mint))?;
+
+        let cached_mint = CachedMint {
+            decimals: mint_state.base.decimals,
+            program_owner: account.owner,
+            transfer_fee_config: mint_state
+                .get_extension::<TransferFeeConfig>()
+                .ok()
+                .copied(),
+            account,
+        };
+
+        self.mints.insert(mint, cached_mint);
+        self.get_cached(&mint)
+    }
+
+    /// 批量获取尚未缓存的铸造账户
+    /// Fetch the mint accounts not cached yet in a single request
+    pub async fn fetch(&mut self, rpc_client: &RpcClient, mints: &[Pubkey]) -> Result<()> {
+        let mut missing_mints = mints
+            .iter()
+            .copied()
+            .filter(|mint| !self.mints.contains_key(mint))
+            .collect::<Vec<_>>();
+        missing_mints.sort();
+        missing_mints.dedup();
+
+        if missing_mints.is_empty() {
+            return Ok(());
+        }
+
+        let accounts = rpc_client.get_multiple_accounts(&missing_mints).await?;
+
+        for (mint, account) in missing_mints.into_iter().zip(accounts) {
+            let account = account.with_context(|| format!("Mint {} not found", mint))?;
+            self.insert(mint, account)?;
+        }
+
+        Ok(())
+    }
+
+    /// 获取铸造账户信息，未缓存时从链上获取
+    /// Get the mint account information, fetching it when not cached
+    pub async fn get(&mut self, rpc_client: &RpcClient, mint: Pubkey) -> Result<&CachedMint> {
+        self.fetch(rpc_client, &[mint]).await?;
+        self.mints
+            .get(&mint)
+            .with_context(|| format!("Mint {} not found", mint))
+    }
+
+    /// 获取已缓存的铸造账户信息
+    /// Get the cached mint account information
+    pub fn get_cached(&self, mint: &Pubkey) -> Result<&CachedMint> {
+        self.mints
+            .get(mint)
+            .with_context(|| format!("Mint {} not cached", mint))
+    }
+
+    /// 获取转账钩子所需的额外账户，使用缓存的铸造账户避免重复获取
+    /// Get the extra accounts required by the transfer hook, using the cached mint account instead of refetching
+    pub async fn get_extra_account_metas_for_transfer_hook(
+        &mut self,
+        rpc_client: &RpcClient,
+        mint: Pubkey,
+    ) -> Result<Vec<AccountMeta>> {
+        let cached_mint = self.get(rpc_client, mint).await?;
+
+        get_extra_account_metas_for_transfer_hook_with_mint_account(
+            mint,
+            &cached_mint.account,
+            RpcClient::new(rpc_client.url()),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_spl::token_2022::spl_token_2022::extension::{
+        transfer_fee::TransferFee, BaseStateWithExtensionsMut, ExtensionType,
+        StateWithExtensionsMut,
+    };
+    use anchor_spl::token_2022::spl_token_2022::solana_program::program_pack::Pack;
+
+    fn mint_account(decimals: u8) -> Account {
+        let mut data = vec![0u8; MintState::LEN];
+        MintState {
+            decimals,
+            is_initialized: true,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+
+        Account {
+            lamports: 1,
+            data,
+            owner: anchor_spl::token::ID,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    fn mint_account_with_transfer_fee(decimals: u8, transfer_fee_basis_points: u16) -> Account {
+        let account_len = ExtensionType::try_calculate_account_len::<MintState>(&[
+            ExtensionType::TransferFeeConfig,
+        ])
+        .unwrap();
+        let mut data = vec![0u8; account_len];
+
+        let mut state =
+            StateWithExtensionsMut::<MintState>::unpack_uninitialized(&mut data).unwrap();
+        let transfer_fee = TransferFee {
+            epoch: 0.into(),
+            maximum_fee: u64::MAX.into(),
+            transfer_fee_basis_points: transfer_fee_basis_points.into(),
+        };
+        let transfer_fee_config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+        transfer_fee_config.older_transfer_fee = transfer_fee;
+        transfer_fee_config.newer_transfer_fee = transfer_fee;
+        state.base = MintState {
+            decimals,
+            is_initialized: true,
+            ..Default::default()
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+
+        Account {
+            lamports: 1,
+            data,
+            owner: anchor_spl::token_2022::ID,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_insert_caches_mint() {
+        let mint_x = Pubkey::new_unique();
+        let mint_y = Pubkey::new_unique();
+
+        let mut mint_cache = MintCache::new();
+        mint_cache.insert(mint_x, mint_account(9)).unwrap();
+        mint_cache
+            .insert(mint_y, mint_account_with_transfer_fee(6, 100))
+            .unwrap();
+
+        let cached_x = mint_cache.get_cached(&mint_x).unwrap();
+        assert_eq!(cached_x.decimals, 9);
+        assert_eq!(cached_x.program_owner, anchor_spl::token::ID);
+        assert!(cached_x.transfer_fee_config.is_none());
+        assert_eq!(
+            cached_x.transfer_fee_included_amount(1_000, 0).unwrap(),
+            1_000
+        );
+
+        // 1%转账手续费，净转出10,000时需要支付102的手续费（向上取整）
+        // 1% transfer fee, a net transfer of 10,000 costs a fee of 102 (rounded up)
+        let cached_y = mint_cache.get_cached(&mint_y).unwrap();
+        assert_eq!(cached_y.decimals, 6);
+        assert_eq!(cached_y.program_owner, anchor_spl::token_2022::ID);
+        assert!(cached_y.transfer_fee_config.is_some());
+        assert_eq!(
+            cached_y.transfer_fee_included_amount(10_000, 0).unwrap(),
+            10_102
+        );
+
+        assert!(mint_cache.get_cached(&Pubkey::new_unique()).is_err());
+    }
+
+    #[test]
+    fn test_insert_rejects_invalid_mint() {
+        let mut account = mint_account(9);
+        account.data.truncate(10);
+
+        assert!(MintCache::new()
+            .insert(Pubkey::new_unique(), account)
+            .is_err());
+    }
+}
@@ -19,10 +19,22 @@ pub use remove_liquidity::*;
 pub mod claim_fee;
 pub use claim_fee::*;
 
+/// 批量申领费用指令 / Batch claim fee instruction
+pub mod claim_fee_batch;
+pub use claim_fee_batch::*;
+
 /// 申领奖励指令 / Claim reward instruction
 pub mod claim_reward;
 pub use claim_reward::*;
 
+/// 申领费用及所有奖励指令 / Claim fee and rewards instruction
+pub mod claim_fee_and_rewards;
+pub use claim_fee_and_rewards::*;
+
+/// 领取所有头寸的费用及奖励指令 / Harvest all positions instruction
+pub mod harvest_all;
+pub use harvest_all::*;
+
 /// 注资奖励指令 / Fund reward instruction
 pub mod fund_reward;
 pub use fund_reward::*;
@@ -37,6 +49,10 @@ pub use close_position::*;
 pub mod get_all_positions;
 pub use get_all_positions::*;
 
+/// 迁移旧版本头寸指令 / Migrate legacy position instruction
+pub mod migrate_position;
+pub use migrate_position::*;
+
 /// 初始化头寸指令 / Initialize position instruction
 pub mod initialize_position;
 pub use initialize_position::*;
@@ -45,10 +61,18 @@ pub use initialize_position::*;
 pub mod initialize_position_with_price_range;
 pub use initialize_position_with_price_range::*;
 
+/// 初始化头寸并添加流动性指令 / Initialize position and add liquidity instruction
+pub mod initialize_position_and_add_liquidity;
+pub use initialize_position_and_add_liquidity::*;
+
 /// 显示头寸指令 / Show position instruction
 pub mod show_position;
 pub use show_position::*;
 
+/// 估算头寸在假设价格下的代币组成指令 / Value position at price instruction
+pub mod value_position_at_price;
+pub use value_position_at_price::*;
+
 // === 预言机管理 / Oracle Management ===
 
 /// 增加预言机长度指令 / Increase oracle length instruction
@@ -91,6 +115,40 @@ pub use initialize_lb_pair::*;
 pub mod show_pair;
 pub use show_pair::*;
 
+/// 查询bin储备指令 / Get bin reserves instruction
+pub mod get_bin_reserves;
+pub use get_bin_reserves::*;
+
+/// 导出交易对bin流动性快照指令 / Snapshot pair bin liquidity instruction
+pub mod snapshot_pair;
+pub use snapshot_pair::*;
+
+/// 显示bin数组位图扩展指令 / Show bin array bitmap extension instruction
+pub mod show_bitmap_extension;
+pub use show_bitmap_extension::*;
+
+/// 交易对健康检查指令 / Pair health check instruction
+pub mod pair_health;
+pub use pair_health::*;
+
+pub mod show_oracle;
+pub use show_oracle::*;
+
+pub mod get_twap;
+pub use get_twap::*;
+
+/// 由价格计算bin ID指令（离线） / Compute bin id instruction (offline)
+pub mod compute_bin_id;
+pub use compute_bin_id::*;
+
+/// 由bin ID计算价格指令（离线） / Compute price instruction (offline)
+pub mod compute_price;
+pub use compute_price::*;
+
+/// 显示程序ID、IDL及CLI版本指令 / Show program id, IDL and CLI version instruction
+pub mod version;
+pub use version::*;
+
 /// 同步价格指令 / Sync price instruction
 pub mod sync_price;
 pub use sync_price::*;
@@ -109,6 +167,30 @@ pub use swap_exact_out::*;
 pub mod swap_with_price_impact;
 pub use swap_with_price_impact::*;
 
+/// 批量精确输入报价指令 / Quote exact in batch instruction
+pub mod quote_exact_in_batch;
+pub use quote_exact_in_batch::*;
+
+/// 双向深度报价指令 / Show depth instruction
+pub mod show_depth;
+pub use show_depth::*;
+
+// === 地址查找表 / Address Lookup Table ===
+
+/// 创建或扩展地址查找表指令 / Create or extend address lookup table instruction
+pub mod create_lookup_table;
+pub use create_lookup_table::*;
+
+// === 多签名 / Multi-signature ===
+
+/// 为部分签名的交易补充签名指令 / Sign a partially signed transaction instruction
+pub mod sign_transaction;
+pub use sign_transaction::*;
+
+/// 发送已完成签名的交易指令 / Send a fully signed transaction instruction
+pub mod send_transaction;
+pub use send_transaction::*;
+
 // === 查询和显示 / Query and Display ===
 
 /// 列出所有bin步长指令 / List all bin step instruction
@@ -119,6 +201,14 @@ pub use list_all_binstep::*;
 pub mod show_preset_parameters;
 pub use show_preset_parameters::*;
 
+/// 查询协议费用指令 / Get protocol fees instruction
+pub mod get_protocol_fees;
+pub use get_protocol_fees::*;
+
+/// 列出奖励配置指令 / List rewards instruction
+pub mod list_rewards;
+pub use list_rewards::*;
+
 // === 状态管理 / Status Management ===
 
 /// 设置流动性对状态（无权限）/ Set pair status (permissionless)
@@ -141,3 +231,7 @@ pub use ilm::*;
 /// 通用工具函数 / Common utility functions
 mod utils;
 pub use utils::*;
+
+/// 代币铸造账户缓存 / Mint account cache
+mod mint_cache;
+pub use mint_cache::*;
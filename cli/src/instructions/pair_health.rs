@@ -0,0 +1,221 @@
+use std::result::Result::Ok;
+
+use crate::*;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+
+/// 交易对健康检查的参数结构体
+/// Parameters for the pair health check
+#[derive(Debug, Parser)]
+pub struct PairHealthParams {
+    /// 流动性交易对地址
+    /// Liquidity pair address
+    pub lb_pair: Pubkey,
+    /// 预言机需要的最少观测数量
+    /// Minimum number of observations the oracle must have
+    #[clap(long, default_value_t = DEFAULT_OBSERVATION_LENGTH)]
+    pub min_oracle_length: u64,
+    /// 检查活跃bin两侧流动性时，向上及向下包含的bin数量
+    /// Number of bins above and below the active bin included when checking the liquidity around it
+    #[clap(long, default_value_t = 10)]
+    pub liquidity_bin_range: i32,
+}
+
+/// 单项健康检查结果
+/// Result of a single health check
+#[derive(Debug)]
+pub struct HealthCheck {
+    /// 检查名称 / Name of the check
+    pub name: &'static str,
+    /// 是否通过 / Whether the check passed
+    pub passed: bool,
+    /// 检查详情 / Details of the check
+    pub detail: String,
+}
+
+impl HealthCheck {
+    fn new(name: &'static str, passed: bool, detail: String) -> Self {
+        Self {
+            name,
+            passed,
+            detail,
+        }
+    }
+}
+
+/// 执行交易对健康检查，只读取账户不发送交易
+/// Executes the pair health check. Only reads accounts, no transaction is sent
+///
+/// # 功能说明 / Functionality
+/// 依次检查交易对状态、预言机长度、bin数组位图扩展、储备金与bin数量总和的一致性以及活跃bin附近的流动性，打印健康报告。
+/// 任一检查未通过时返回错误，便于集成方在路由前校验交易对
+/// Checks the pair status, the oracle length, the bin array bitmap extension, the consistency of the reserves with
+/// the bin amounts and the liquidity around the active bin, then prints a health report. Returns an error when any
+/// check fails, so integrators can validate a pair before routing through it
+pub async fn execute_pair_health<C: Deref<Target = impl Signer> + Clone>(
+    params: PairHealthParams,
+    program: &Program<C>,
+) -> Result<()> {
+    let PairHealthParams {
+        lb_pair,
+        min_oracle_length,
+        liquidity_bin_range,
+    } = params;
+
+    ensure!(
+        liquidity_bin_range >= 0,
+        "liquidity_bin_range must not be negative"
+    );
+
+    let rpc_client = program.rpc();
+
+    let lb_pair_state: LbPair = rpc_client
+        .get_account_and_deserialize(&lb_pair, |account| {
+            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
+        })
+        .await?;
+
+    // 获取交易对的所有bin数组
+    // Get every bin array of the pair
+    let config = RpcProgramAccountsConfig {
+        filters: Some(bin_array_filter_by_pair(lb_pair)),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let bin_arrays: Vec<BinArray> = get_program_accounts_with_retry(&rpc_client, config)
+        .await?
+        .into_iter()
+        .map(|(_, account)| bytemuck::pod_read_unaligned(&account.data[8..]))
+        .collect();
+
+    let (bitmap_extension_key, _bump) = derive_bin_array_bitmap_extension(lb_pair);
+    let bitmap_extension = get_optional_account(&rpc_client, bitmap_extension_key).await?;
+    let oracle_account = get_optional_account(&rpc_client, lb_pair_state.oracle).await?;
+
+    let reserve_x = get_token_balance_or_zero(&rpc_client, lb_pair_state.reserve_x).await?;
+    let reserve_y = get_token_balance_or_zero(&rpc_client, lb_pair_state.reserve_y).await?;
+
+    let mut checks = vec![];
+
+    // 交易对状态
+    // Pair status
+    checks.push(match lb_pair_state.status() {
+        Ok(status) => HealthCheck::new(
+            "status",
+            status == PairStatus::Enabled,
+            format!("{:?}", PairStatusArg::from(status)),
+        ),
+        Err(_) => HealthCheck::new(
+            "status",
+            false,
+            format!("undefined status {}", lb_pair_state.status),
+        ),
+    });
+
+    // 预言机长度
+    // Oracle length
+    checks.push(match oracle_account {
+        Some(account) => {
+            let oracle: Oracle = bytemuck::pod_read_unaligned(
+                account
+                    .data
+                    .get(8..8 + std::mem::size_of::<Oracle>())
+                    .context("Invalid oracle account data")?,
+            );
+            HealthCheck::new(
+                "oracle",
+                oracle.length >= min_oracle_length,
+                format!(
+                    "length {} (min {}), active size {}",
+                    oracle.length, min_oracle_length, oracle.active_size
+                ),
+            )
+        }
+        None => HealthCheck::new(
+            "oracle",
+            false,
+            format!("oracle {} not found", lb_pair_state.oracle),
+        ),
+    });
+
+    // bin数组位图扩展：活跃bin数组或任一bin数组超出默认位图范围时必须存在
+    // Bin array bitmap extension: required when the active bin array or any bin array is beyond the default
+    // bitmap range
+    let active_bin_array_index = BinArray::bin_id_to_bin_array_index(lb_pair_state.active_id)?;
+    let extension_required = std::iter::once(active_bin_array_index)
+        .chain(bin_arrays.iter().map(|bin_array| bin_array.index as i32))
+        .any(|index| lb_pair_state.is_overflow_default_bin_array_bitmap(index));
+    checks.push(HealthCheck::new(
+        "bitmap_extension",
+        bitmap_extension.is_some() || !extension_required,
+        format!(
+            "required {}, present {}",
+            extension_required,
+            bitmap_extension.is_some()
+        ),
+    ));
+
+    // 储备金需覆盖bin数量总和及协议费用，差额为未领取的手续费及奖励等
+    // The reserves must cover the bin amounts and the protocol fee. The surplus is unclaimed fees, rewards, etc.
+    let (bins_x, bins_y) = bin_arrays
+        .iter()
+        .flat_map(|bin_array| bin_array.bins.iter())
+        .fold((0u128, 0u128), |(x, y), bin| {
+            (x + u128::from(bin.amount_x), y + u128::from(bin.amount_y))
+        });
+    let required_x = bins_x + u128::from(lb_pair_state.protocol_fee.amount_x);
+    let required_y = bins_y + u128::from(lb_pair_state.protocol_fee.amount_y);
+    checks.push(HealthCheck::new(
+        "reserves",
+        u128::from(reserve_x) >= required_x && u128::from(reserve_y) >= required_y,
+        format!(
+            "reserve X {} / bins + protocol fee {}, reserve Y {} / bins + protocol fee {}",
+            reserve_x, required_x, reserve_y, required_y
+        ),
+    ));
+
+    // 活跃bin附近的双边流动性：活跃bin及以上的X代币，活跃bin及以下的Y代币
+    // Two-sided liquidity around the active bin: token X at and above the active bin, token Y at and below it
+    let lower_bin_id = lb_pair_state.active_id - liquidity_bin_range;
+    let upper_bin_id = lb_pair_state.active_id + liquidity_bin_range;
+    let mut liquidity_x = 0u128;
+    let mut liquidity_y = 0u128;
+    for bin_array in bin_arrays.iter() {
+        let (bin_array_lower_bin_id, _) =
+            BinArray::get_bin_array_lower_upper_bin_id(bin_array.index as i32)?;
+        for (offset, bin) in bin_array.bins.iter().enumerate() {
+            let bin_id = bin_array_lower_bin_id + offset as i32;
+            if bin_id < lower_bin_id || bin_id > upper_bin_id {
+                continue;
+            }
+            if bin_id >= lb_pair_state.active_id {
+                liquidity_x += u128::from(bin.amount_x);
+            }
+            if bin_id <= lb_pair_state.active_id {
+                liquidity_y += u128::from(bin.amount_y);
+            }
+        }
+    }
+    checks.push(HealthCheck::new(
+        "active_liquidity",
+        liquidity_x > 0 && liquidity_y > 0,
+        format!(
+            "X {} Y {} within bins {}..={} (active {})",
+            liquidity_x, liquidity_y, lower_bin_id, upper_bin_id, lb_pair_state.active_id
+        ),
+    ));
+
+    println!("Pair {} health", lb_pair);
+    for check in checks.iter() {
+        let result = if check.passed { "OK" } else { "FAIL" };
+        println!("[{}] {}: {}", result, check.name, check.detail);
+    }
+
+    let failed = checks.iter().filter(|check| !check.passed).count();
+    ensure!(failed == 0, "Pair {} failed {} health checks", lb_pair, failed);
+
+    Ok(())
+}
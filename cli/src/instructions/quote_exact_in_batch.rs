@@ -0,0 +1,176 @@
+use anchor_lang::AccountDeserialize;
+use anchor_spl::token_interface::Mint;
+use solana_sdk::commitment_config::CommitmentLevel;
+
+use crate::*;
+
+/// 批量计算精确输入报价的参数结构体
+/// Parameters for quoting multiple exact input amounts
+#[derive(Debug, Parser)]
+pub struct QuoteExactInBatchParams {
+    /// 流动性交易对的地址
+    /// Address of the liquidity pair.
+    pub lb_pair: Pubkey,
+    /// 以逗号分隔的输入数量列表
+    /// Comma separated list of input amounts.
+    #[clap(long, value_delimiter = ',', required = true)]
+    pub amounts: Vec<u64>,
+    /// 购买方向：true = 买入Y代币，false = 买入X代币
+    /// Buy direction. true = buy token Y, false = buy token X.
+    #[clap(long)]
+    pub swap_for_y: bool,
+    /// Mint of the token to sell. Derives the swap direction instead of `--swap-for-y`.
+    /// 卖出代币的铸造地址，用于推导交易方向，替代 `--swap-for-y`
+    #[clap(long, conflicts_with = "swap_for_y")]
+    pub input_mint: Option<Pubkey>,
+    /// Mint of the token to buy. Derives the swap direction instead of `--swap-for-y`.
+    /// 买入代币的铸造地址，用于推导交易方向，替代 `--swap-for-y`
+    #[clap(long, conflicts_with = "swap_for_y")]
+    pub output_mint: Option<Pubkey>,
+    /// Number of bin arrays fetched from the active bin in the swap direction. Increase it when the largest
+    /// amount crosses more bin arrays.
+    /// 从活跃bin沿交易方向获取的bin数组数量，最大数量跨越更多bin数组时需要增加
+    #[clap(long, default_value_t = 3)]
+    pub bin_array_count: u8,
+    /// Commitment used to read the accounts for quoting. Defaults to the client commitment.
+    /// 读取报价所需账户时使用的确认级别，默认使用客户端的确认级别
+    #[clap(long)]
+    pub commitment_for_quote: Option<CommitmentLevel>,
+}
+
+/// 单个输入数量的报价结果
+/// Quote result of a single input amount
+#[derive(Debug, Clone)]
+pub struct BatchQuote {
+    /// 输入数量 / Input amount
+    pub amount_in: u64,
+    /// 输出数量 / Output amount
+    pub amount_out: u64,
+    /// 手续费 / Fee
+    pub fee: u64,
+    /// 成交均价（以Y代币计价的X代币价格），输出数量为0时为 `None` / Average execution price (token X priced in
+    /// token Y), `None` when the output amount is 0
+    pub avg_price: Option<f64>,
+}
+
+/// 使用同一份报价账户计算多个输入数量的精确输入报价，只获取一次账户
+/// Quote multiple exact input amounts against the same quote accounts, so the accounts are fetched only once
+pub fn quote_exact_in_batch(
+    lb_pair: Pubkey,
+    quote_accounts: &SwapQuoteAccounts,
+    bitmap_extension: Option<&BinArrayBitmapExtension>,
+    swap_for_y: bool,
+    amounts: &[u64],
+) -> Result<Vec<BatchQuote>> {
+    let SwapQuoteAccounts {
+        lb_pair_state,
+        clock,
+        mint_x_account,
+        mint_y_account,
+        bin_arrays,
+        ..
+    } = quote_accounts;
+
+    let token_x_decimals =
+        Mint::try_deserialize(&mut mint_x_account.data.as_ref())?.decimals as i32;
+    let token_y_decimals =
+        Mint::try_deserialize(&mut mint_y_account.data.as_ref())?.decimals as i32;
+
+    let to_ui_x = |amount: u64| amount as f64 / 10f64.powi(token_x_decimals);
+    let to_ui_y = |amount: u64| amount as f64 / 10f64.powi(token_y_decimals);
+
+    amounts
+        .iter()
+        .map(|&amount_in| {
+            let quote = quote_exact_in(
+                lb_pair,
+                lb_pair_state,
+                amount_in,
+                swap_for_y,
+                bin_arrays.clone(),
+                bitmap_extension,
+                clock,
+                mint_x_account,
+                mint_y_account,
+            )
+            .with_context(|| format!("Failed to quote amount_in {}", amount_in))?;
+
+            let (amount_x, amount_y) = if swap_for_y {
+                (amount_in, quote.amount_out)
+            } else {
+                (quote.amount_out, amount_in)
+            };
+
+            let avg_price = (quote.amount_out > 0).then(|| to_ui_y(amount_y) / to_ui_x(amount_x));
+
+            Ok(BatchQuote {
+                amount_in,
+                amount_out: quote.amount_out,
+                fee: quote.fee,
+                avg_price,
+            })
+        })
+        .collect()
+}
+
+/// 执行批量精确输入报价
+/// Executes the exact input batch quote
+///
+/// # 功能说明 / Functionality
+/// 获取一次报价所需的账户，然后对每个输入数量计算输出数量、手续费及成交均价，用于绘制订单簿式的深度
+/// Fetches the quote accounts once, then computes the output amount, fee and average execution price of every
+/// input amount. Used to render order book style depth
+pub async fn execute_quote_exact_in_batch<C: Deref<Target = impl Signer> + Clone>(
+    params: QuoteExactInBatchParams,
+    client: &DlmmClient<C>,
+) -> Result<()> {
+    let QuoteExactInBatchParams {
+        lb_pair,
+        amounts,
+        swap_for_y,
+        input_mint,
+        output_mint,
+        bin_array_count,
+        commitment_for_quote,
+    } = params;
+
+    let lb_pair_state = client.get_lb_pair(lb_pair).await?;
+
+    let swap_for_y = resolve_swap_for_y(&lb_pair_state, swap_for_y, input_mint, output_mint)?;
+
+    let SwapQuoteState {
+        bitmap_extension,
+        accounts: quote_accounts,
+        ..
+    } = client
+        .get_swap_quote_state(
+            lb_pair,
+            lb_pair_state,
+            swap_for_y,
+            bin_array_count,
+            commitment_for_quote.map(|commitment| CommitmentConfig { commitment }),
+        )
+        .await?;
+
+    let quotes = quote_exact_in_batch(
+        lb_pair,
+        &quote_accounts,
+        bitmap_extension.as_ref(),
+        swap_for_y,
+        &amounts,
+    )?;
+
+    for quote in quotes {
+        let avg_price = quote
+            .avg_price
+            .map(|price| price.to_string())
+            .unwrap_or_else(|| "-".to_string());
+
+        println!(
+            "amount_in {} amount_out {} fee {} avg_price {}",
+            quote.amount_in, quote.amount_out, quote.fee, avg_price
+        );
+    }
+
+    Ok(())
+}
@@ -19,6 +19,17 @@ pub struct RemoveLiquidityParams {
     /// Position to be withdraw.
     /// 要提取的仓位
     pub position: Pubkey,
+    /// Address lookup table used to compile a v0 transaction. Required for wide bin ranges that exceed the legacy transaction account limit.
+    /// 用于编译v0交易的地址查找表，适用于超出传统交易账户数量限制的宽bin范围
+    #[clap(long)]
+    pub use_lut: Option<Pubkey>,
+    /// Print the built instructions as JSON (program id, accounts, base58 data) instead of sending them, to compose
+    /// them into other transactions. Missing user token accounts are created by idempotent instructions included in
+    /// the output. Compute budget instructions are left out.
+    /// 不发送交易，以JSON打印构建的指令（程序ID、账户、base58数据），用于组合到其他交易中。
+    /// 输出中包含幂等创建用户代币账户的指令，不包含计算预算指令
+    #[clap(long, conflicts_with = "use_lut")]
+    pub build_only: bool,
 }
 
 /// 执行移除流动性操作
@@ -27,7 +38,7 @@ pub struct RemoveLiquidityParams {
 /// * `params` - 移除流动性参数
 /// * `program` - Anchor程序客户端
 /// * `transaction_config` - 交易配置
-/// * `compute_unit_price` - 计算单元价格指令（可选）
+/// * `compute_unit_price` - 计算单元价格设置
 /// 
 /// # 功能
 /// 1. 验证要移除的bin范围
@@ -37,12 +48,15 @@ pub async fn execute_remove_liquidity<C: Deref<Target = impl Signer> + Clone>(
     params: RemoveLiquidityParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
-    compute_unit_price: Option<Instruction>,
+    compute_unit_price: ComputeUnitPrice,
+    payer: &dyn Signer,
 ) -> Result<()> {
     let RemoveLiquidityParams {
         lb_pair,
         position,
         mut bin_liquidity_removal,
+        use_lut,
+        build_only,
     } = params;
 
     // 按bin ID排序，确保从低到高
@@ -60,7 +74,7 @@ pub async fn execute_remove_liquidity<C: Deref<Target = impl Signer> + Clone>(
 
     // 反序列化账户状态
     let lb_pair_state: LbPair = bytemuck::pod_read_unaligned(&lb_pair_account.data[8..]);
-    let position_state: PositionV2 = bytemuck::pod_read_unaligned(&position_account.data[8..]);
+    let position_state = deserialize_position_v2(&position_account.data)?;
 
     // 获取最小和最大bin ID，用于确定需要的bin数组范围
     let min_bin_id = bin_liquidity_removal
@@ -77,31 +91,55 @@ pub async fn execute_remove_liquidity<C: Deref<Target = impl Signer> + Clone>(
     let bin_arrays_account_meta =
         position_state.get_bin_array_accounts_meta_coverage_by_chunk(min_bin_id, max_bin_id)?;
 
-    let user_token_x = get_or_create_ata(
-        program,
-        transaction_config,
-        lb_pair_state.token_x_mint,
-        program.payer(),
-        compute_unit_price.clone(),
-    )
-    .await?;
-
-    let user_token_y = get_or_create_ata(
-        program,
-        transaction_config,
-        lb_pair_state.token_y_mint,
-        program.payer(),
-        compute_unit_price.clone(),
-    )
-    .await?;
+    // 只构建指令时不发送创建代币账户的交易，改为输出幂等创建指令
+    // When only building the instructions, the token accounts are created by idempotent instructions in the output
+    // instead of a separate transaction
+    let mut create_ata_ixs = vec![];
+    let (user_token_x, user_token_y) = if build_only {
+        let (user_token_x, create_user_token_x_ix) = get_ata_with_create_ix(
+            &rpc_client,
+            lb_pair_state.token_x_mint,
+            program.payer(),
+            program.payer(),
+        )
+        .await?;
+        let (user_token_y, create_user_token_y_ix) = get_ata_with_create_ix(
+            &rpc_client,
+            lb_pair_state.token_y_mint,
+            program.payer(),
+            program.payer(),
+        )
+        .await?;
+        create_ata_ixs.extend([create_user_token_x_ix, create_user_token_y_ix]);
+
+        (user_token_x, user_token_y)
+    } else {
+        let user_token_x = get_or_create_ata(
+            program,
+            transaction_config,
+            lb_pair_state.token_x_mint,
+            program.payer(),
+            compute_unit_price.clone(),
+        )
+        .await?;
+
+        let user_token_y = get_or_create_ata(
+            program,
+            transaction_config,
+            lb_pair_state.token_y_mint,
+            program.payer(),
+            compute_unit_price.clone(),
+        )
+        .await?;
+
+        (user_token_x, user_token_y)
+    };
 
     let (bin_array_bitmap_extension, _bump) = derive_bin_array_bitmap_extension(lb_pair);
-    let bin_array_bitmap_extension = rpc_client
-        .get_account(&bin_array_bitmap_extension)
-        .await
-        .map(|_| bin_array_bitmap_extension)
-        .ok()
-        .or(Some(dlmm::ID));
+    let bin_array_bitmap_extension =
+        resolve_optional_account(&rpc_client, bin_array_bitmap_extension)
+            .await?
+            .or(Some(dlmm::ID));
 
     let (event_authority, _bump) = derive_event_authority_pda();
 
@@ -165,16 +203,33 @@ pub async fn execute_remove_liquidity<C: Deref<Target = impl Signer> + Clone>(
         accounts,
     };
 
-    let compute_budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(1_400_000);
+    if build_only {
+        create_ata_ixs.push(remove_liquidity_ix);
+        return print_instructions(&create_ata_ixs);
+    }
+
+    let mut instructions = compute_unit_price.instructions_with_limit(Some(1_400_000))?;
+    instructions.push(remove_liquidity_ix);
 
-    let request_builder = program.request();
-    let signature = request_builder
-        .instruction(compute_budget_ix)
-        .instruction(remove_liquidity_ix)
-        .send_with_spinner_and_config(transaction_config)
-        .await;
+    let signature = if let Some(lookup_table) = use_lut {
+        send_versioned_transaction_with_lookup_table(
+            &rpc_client,
+            payer,
+            &instructions,
+            lookup_table,
+            transaction_config,
+        )
+        .await
+    } else {
+        instructions
+            .into_iter()
+            .fold(program.request(), |builder, ix| builder.instruction(ix))
+            .send_with_spinner_and_config(transaction_config)
+            .await
+            .map_err(anyhow::Error::from)
+    };
 
-    println!("Remove Liquidity. Signature: {:#?}", signature);
+    info!(?signature, "Remove Liquidity");
 
     signature?;
 
@@ -0,0 +1,44 @@
+use crate::*;
+
+/// 发送已完成签名的交易的参数结构体
+/// Parameters for submitting a fully signed transaction
+#[derive(Debug, Parser)]
+pub struct SendTransactionParams {
+    /// `sign-transaction` 打印的base58编码交易
+    /// Base58 encoded transaction printed by `sign-transaction`
+    pub transaction: String,
+}
+
+/// 执行发送已由所有签名者签名的交易，仍缺少签名时返回错误
+/// Executes submitting a transaction signed by all its signers. Returns an error when a signature is still missing
+pub async fn execute_send_transaction<C: Deref<Target = impl Signer> + Clone>(
+    params: SendTransactionParams,
+    program: &Program<C>,
+    transaction_config: RpcSendTransactionConfig,
+) -> Result<()> {
+    let SendTransactionParams { transaction } = params;
+
+    let transaction = decode_transaction(&transaction)?;
+
+    let missing_signers = get_missing_signers(&transaction);
+    ensure!(
+        missing_signers.is_empty(),
+        "Transaction is missing the signatures of {:?}",
+        missing_signers
+    );
+
+    let rpc_client = program.rpc();
+    let signature = rpc_client
+        .send_and_confirm_transaction_with_spinner_and_config(
+            &transaction,
+            rpc_client.commitment(),
+            transaction_config,
+        )
+        .await;
+
+    info!(?signature, "Send transaction");
+
+    signature?;
+
+    Ok(())
+}
@@ -4,16 +4,21 @@ use crate::*;
 pub struct SetPairStatusPermissionlessParams {
     #[clap(long)]
     pub lb_pair: Pubkey,
-    #[clap(long)]
-    pub enable: bool,
+    /// Target status of the pair.
+    /// 交易对的目标状态
+    #[clap(long, value_enum)]
+    pub status: PairStatusArg,
 }
 
 pub async fn execute_set_pair_status_permissionless<C: Deref<Target = impl Signer> + Clone>(
     params: SetPairStatusPermissionlessParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<()> {
-    let SetPairStatusPermissionlessParams { lb_pair, enable } = params;
+    let SetPairStatusPermissionlessParams { lb_pair, status } = params;
+
+    print_pair_status_change(&program.rpc(), lb_pair, status).await?;
 
     let accounts = dlmm::client::accounts::SetPairStatusPermissionless {
         creator: program.payer(),
@@ -21,9 +26,10 @@ pub async fn execute_set_pair_status_permissionless<C: Deref<Target = impl Signe
     }
     .to_account_metas(None);
 
-    let status = if enable { 1 } else { 0 };
-
-    let data = dlmm::client::args::SetPairStatusPermissionless { status }.data();
+    let data = dlmm::client::args::SetPairStatusPermissionless {
+        status: status.into(),
+    }
+    .data();
 
     let set_pair_status_permissionless_ix = Instruction {
         accounts,
@@ -31,16 +37,13 @@ pub async fn execute_set_pair_status_permissionless<C: Deref<Target = impl Signe
         program_id: dlmm::ID,
     };
 
-    let request_builder = program.request();
+    let request_builder = request_with_compute_unit_price(program, compute_unit_price)?;
     let signature = request_builder
         .instruction(set_pair_status_permissionless_ix)
         .send_with_spinner_and_config(transaction_config)
         .await;
 
-    println!(
-        "Set pair status permissionless. Signature: {:#?}",
-        signature
-    );
+    info!(?signature, "Set pair status permissionless");
 
     signature?;
 
@@ -0,0 +1,100 @@
+use crate::*;
+
+/// 显示bin数组位图扩展的参数结构体
+/// Parameters for showing the bin array bitmap extension
+#[derive(Debug, Parser)]
+pub struct ShowBitmapExtensionParams {
+    /// 流动性交易对地址
+    /// Liquidity pair address
+    pub lb_pair: Pubkey,
+}
+
+/// 执行显示bin数组位图扩展指令
+/// Executes the show bin array bitmap extension instruction
+///
+/// # 功能说明 / Functionality
+/// 显示位图扩展中标记为已初始化的bin数组索引，按连续区间分组
+/// Shows the bin array indexes flagged as initialized in the bitmap extension, bucketed into contiguous ranges
+pub async fn execute_show_bitmap_extension<C: Deref<Target = impl Signer> + Clone>(
+    params: ShowBitmapExtensionParams,
+    program: &Program<C>,
+) -> Result<()> {
+    let ShowBitmapExtensionParams { lb_pair } = params;
+
+    let rpc_client = program.rpc();
+
+    let (bitmap_extension_key, _bump) = derive_bin_array_bitmap_extension(lb_pair);
+
+    let (internal_min_bitmap_id, internal_max_bitmap_id) = LbPair::bitmap_range();
+    let (extension_min_bitmap_id, extension_max_bitmap_id) =
+        BinArrayBitmapExtension::bitmap_range();
+
+    println!("Bitmap extension: {}", bitmap_extension_key);
+    println!(
+        "Internal bitmap range: [{}, {}]",
+        internal_min_bitmap_id, internal_max_bitmap_id
+    );
+    println!(
+        "Extension bitmap range: [{}, {}]",
+        extension_min_bitmap_id, extension_max_bitmap_id
+    );
+
+    let Some(account) = rpc_client
+        .get_account_with_commitment(&bitmap_extension_key, rpc_client.commitment())
+        .await?
+        .value
+    else {
+        println!("Bitmap extension is not initialized. Only bin arrays within the internal bitmap range can be used");
+        return Ok(());
+    };
+
+    let bitmap_extension: BinArrayBitmapExtension =
+        bytemuck::pod_read_unaligned(&account.data[8..]);
+
+    ensure!(
+        bitmap_extension.lb_pair == lb_pair,
+        "Bitmap extension does not belong to the liquidity pair"
+    );
+
+    // 扩展位图仅覆盖内部位图范围之外的bin数组索引
+    // The extension bitmap only covers bin array indexes outside of the internal bitmap range
+    let mut initialized_indexes = vec![];
+
+    for bin_array_index in (extension_min_bitmap_id..internal_min_bitmap_id)
+        .chain(internal_max_bitmap_id + 1..=extension_max_bitmap_id)
+    {
+        if bitmap_extension.bit(bin_array_index)? {
+            initialized_indexes.push(bin_array_index);
+        }
+    }
+
+    let mut ranges: Vec<(i32, i32)> = vec![];
+
+    for bin_array_index in initialized_indexes.iter().copied() {
+        match ranges.last_mut() {
+            Some((_, upper)) if *upper + 1 == bin_array_index => *upper = bin_array_index,
+            _ => ranges.push((bin_array_index, bin_array_index)),
+        }
+    }
+
+    println!(
+        "Initialized bin arrays in extension: {}",
+        initialized_indexes.len()
+    );
+
+    for (lower_index, upper_index) in ranges {
+        let (lower_bin_id, _) = BinArray::get_bin_array_lower_upper_bin_id(lower_index)?;
+        let (_, upper_bin_id) = BinArray::get_bin_array_lower_upper_bin_id(upper_index)?;
+
+        println!(
+            "Bin array index [{}, {}] ({} bin arrays), bin id [{}, {}]",
+            lower_index,
+            upper_index,
+            upper_index - lower_index + 1,
+            lower_bin_id,
+            upper_bin_id
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,278 @@
+use anchor_lang::AccountDeserialize;
+use anchor_spl::token_interface::Mint;
+use rust_decimal::prelude::ToPrimitive;
+use solana_sdk::commitment_config::CommitmentLevel;
+
+use crate::*;
+
+/// 双向深度报价的参数结构体
+/// Parameters for quoting the depth in both directions
+#[derive(Debug, Parser)]
+pub struct ShowDepthParams {
+    /// 流动性交易对的地址
+    /// Address of the liquidity pair.
+    pub lb_pair: Pubkey,
+    /// 以逗号分隔的X代币数量阶梯，每一档均为从活跃bin开始的累计数量
+    /// Comma separated ladder of token X amounts. Every level is a cumulative size starting from the active bin.
+    #[clap(long, value_delimiter = ',', required = true)]
+    pub amounts: Vec<u64>,
+    /// Number of bin arrays fetched from the active bin in each direction. Increase it when the largest amount
+    /// crosses more bin arrays.
+    /// 从活跃bin沿每个方向获取的bin数组数量，最大数量跨越更多bin数组时需要增加
+    #[clap(long, default_value_t = 3)]
+    pub bin_array_count: u8,
+    /// Commitment used to read the accounts for quoting. Defaults to the client commitment.
+    /// 读取报价所需账户时使用的确认级别，默认使用客户端的确认级别
+    #[clap(long)]
+    pub commitment_for_quote: Option<CommitmentLevel>,
+}
+
+/// 深度表的一档
+/// A level of the depth table
+#[derive(Debug, Clone)]
+pub struct DepthLevel {
+    /// 累计买入或卖出的X代币数量 / Cumulative amount of token X bought or sold
+    pub amount_x: u64,
+    /// 累计支付或收到的Y代币数量 / Cumulative amount of token Y paid or received
+    pub amount_y: u64,
+    /// 成交均价（以Y代币计价的X代币UI价格），Y代币数量为0时为 `None` / Average execution UI price of token X in
+    /// token Y, `None` when the token Y amount is 0
+    pub avg_price: Option<f64>,
+}
+
+/// 双向深度报价，包含报价所用代币的小数位数
+/// Two-sided depth quote, with the decimals of the tokens it was quoted with
+#[derive(Debug, Clone)]
+pub struct DepthQuote {
+    /// X代币小数位数 / Decimals of token X
+    pub token_x_decimals: u8,
+    /// Y代币小数位数 / Decimals of token Y
+    pub token_y_decimals: u8,
+    /// 买入X（卖盘），按数量升序 / Buying X (asks), by ascending amount
+    pub asks: Vec<DepthLevel>,
+    /// 卖出X（买盘），按数量升序 / Selling X (bids), by ascending amount
+    pub bids: Vec<DepthLevel>,
+}
+
+/// 使用同一份报价账户计算X代币数量阶梯的双向深度
+/// Quote the ladder of token X amounts in both directions against the same quote accounts
+///
+/// 卖出X为精确输入报价，买入X为精确输出报价，因此两侧的每一档都对应相同的X代币数量
+/// Selling X is an exact input quote and buying X an exact output quote, so every level of both sides is the same
+/// token X amount
+pub fn quote_depth(
+    lb_pair: Pubkey,
+    quote_accounts: &SwapQuoteAccounts,
+    bitmap_extension: Option<&BinArrayBitmapExtension>,
+    amounts: &[u64],
+) -> Result<DepthQuote> {
+    let SwapQuoteAccounts {
+        lb_pair_state,
+        clock,
+        mint_x_account,
+        mint_y_account,
+        bin_arrays,
+        ..
+    } = quote_accounts;
+
+    let token_x_decimals = Mint::try_deserialize(&mut mint_x_account.data.as_ref())?.decimals;
+    let token_y_decimals = Mint::try_deserialize(&mut mint_y_account.data.as_ref())?.decimals;
+
+    let to_ui_x = |amount: u64| amount as f64 / 10f64.powi(token_x_decimals.into());
+    let to_ui_y = |amount: u64| amount as f64 / 10f64.powi(token_y_decimals.into());
+
+    let bids = quote_exact_in_batch(lb_pair, quote_accounts, bitmap_extension, true, amounts)?
+        .into_iter()
+        .map(|quote| DepthLevel {
+            amount_x: quote.amount_in,
+            amount_y: quote.amount_out,
+            avg_price: quote.avg_price,
+        })
+        .collect();
+
+    let asks = amounts
+        .iter()
+        .map(|&amount_x| {
+            let quote = quote_exact_out(
+                lb_pair,
+                lb_pair_state,
+                amount_x,
+                false,
+                bin_arrays.clone(),
+                bitmap_extension,
+                clock,
+                mint_x_account,
+                mint_y_account,
+            )
+            .with_context(|| format!("Failed to quote buying {} token X", amount_x))?;
+
+            let avg_price =
+                (quote.amount_in > 0).then(|| to_ui_y(quote.amount_in) / to_ui_x(amount_x));
+
+            Ok(DepthLevel {
+                amount_x,
+                amount_y: quote.amount_in,
+                avg_price,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(DepthQuote {
+        token_x_decimals,
+        token_y_decimals,
+        asks,
+        bids,
+    })
+}
+
+/// 执行双向深度报价
+/// Executes the two-sided depth quote
+///
+/// # 功能说明 / Functionality
+/// 一次读取活跃bin两侧的bin数组，对X代币数量阶梯分别报价买入X与卖出X，按订单簿形式输出双边深度表：
+/// 上方为买入X（卖盘），下方为卖出X（买盘），每一档给出累计X/Y数量、成交均价及相对活跃bin价格的偏离。
+/// 报价超出已读取的bin数组时返回错误，可增加 `--bin-array-count`
+/// Reads the bin arrays on both sides of the active bin once, quotes buying and selling the ladder of token X
+/// amounts, and prints an order book style two-sided depth table: buying X (asks) on top, selling X (bids) below.
+/// Every level shows the cumulative token X / Y amounts, the average execution price and its deviation from the
+/// active bin price. Returns an error when a quote runs past the fetched bin arrays, increase `--bin-array-count`
+pub async fn execute_show_depth<C: Deref<Target = impl Signer> + Clone>(
+    params: ShowDepthParams,
+    client: &DlmmClient<C>,
+) -> Result<()> {
+    let ShowDepthParams {
+        lb_pair,
+        mut amounts,
+        bin_array_count,
+        commitment_for_quote,
+    } = params;
+
+    amounts.sort_unstable();
+    amounts.dedup();
+    ensure!(
+        amounts.iter().all(|&amount| amount > 0),
+        "amounts must be positive"
+    );
+
+    let lb_pair_state = client.get_lb_pair(lb_pair).await?;
+
+    let SwapQuoteState {
+        bitmap_extension,
+        accounts: quote_accounts,
+        ..
+    } = client
+        .get_two_sided_quote_state(
+            lb_pair,
+            lb_pair_state,
+            bin_array_count,
+            commitment_for_quote.map(|commitment| CommitmentConfig { commitment }),
+        )
+        .await?;
+
+    let DepthQuote {
+        token_x_decimals,
+        token_y_decimals,
+        asks,
+        bids,
+    } = quote_depth(
+        lb_pair,
+        &quote_accounts,
+        bitmap_extension.as_ref(),
+        &amounts,
+    )
+    .context("Quote ran past the fetched bin arrays, try a larger --bin-array-count")?;
+
+    let active_price = get_ui_price_from_id(
+        lb_pair_state.bin_step,
+        lb_pair_state.active_id,
+        token_x_decimals,
+        token_y_decimals,
+    )
+    .and_then(|price| price.to_f64())
+    .context("Active bin price overflow")?;
+
+    let to_ui_x = |amount: u64| amount as f64 / 10f64.powi(token_x_decimals.into());
+    let to_ui_y = |amount: u64| amount as f64 / 10f64.powi(token_y_decimals.into());
+
+    let print_level = |side: &str, level: &DepthLevel| {
+        let (avg_price, deviation) = level
+            .avg_price
+            .map(|price| {
+                (
+                    price.to_string(),
+                    format!("{:+.4}%", (price / active_price - 1.0) * 100.0),
+                )
+            })
+            .unwrap_or_else(|| ("-".to_string(), "-".to_string()));
+
+        println!(
+            "{:<5} {:>20} {:>20} {:>24} {:>12}",
+            side,
+            to_ui_x(level.amount_x),
+            to_ui_y(level.amount_y),
+            avg_price,
+            deviation
+        );
+    };
+
+    println!(
+        "Depth of pair {} at slot {}",
+        lb_pair, quote_accounts.context_slot
+    );
+    println!(
+        "{:<5} {:>20} {:>20} {:>24} {:>12}",
+        "side", "cumulative_x", "cumulative_y", "avg_price", "deviation"
+    );
+
+    for level in asks.iter().rev() {
+        print_level("ask", level);
+    }
+    println!(
+        "----- active bin {} price {}",
+        lb_pair_state.active_id, active_price
+    );
+    for level in bids.iter() {
+        print_level("bid", level);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+
+    #[test]
+    fn test_quote_depth() {
+        let quote_accounts = fixture_quote_accounts();
+        let amounts = [1_000_000, 10_000_000, 100_000_000];
+
+        let DepthQuote {
+            token_x_decimals,
+            token_y_decimals,
+            asks,
+            bids,
+        } = quote_depth(FIXTURE_LB_PAIR, &quote_accounts, None, &amounts).unwrap();
+
+        assert_eq!(token_x_decimals, 6);
+        assert_eq!(token_y_decimals, 9);
+        assert_eq!(asks.len(), amounts.len());
+        assert_eq!(bids.len(), amounts.len());
+
+        for (level, &amount) in asks.iter().chain(bids.iter()).zip(amounts.iter().cycle()) {
+            assert_eq!(level.amount_x, amount);
+            assert!(level.amount_y > 0);
+        }
+
+        // 数量越大，买入X支付越多、卖出X收到越多，且买入均价不低于卖出均价
+        // Larger amounts pay more for buying X and receive more for selling X, and buying X never averages below
+        // selling it
+        for side in [&asks, &bids] {
+            assert!(side.windows(2).all(|w| w[0].amount_y < w[1].amount_y));
+        }
+        for (ask, bid) in asks.iter().zip(bids.iter()) {
+            assert!(ask.avg_price.unwrap() >= bid.avg_price.unwrap());
+        }
+    }
+}
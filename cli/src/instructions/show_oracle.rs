@@ -0,0 +1,58 @@
+use crate::*;
+
+/// 显示预言机观测数据的参数结构体
+/// Parameters for showing the oracle observations
+#[derive(Debug, Parser)]
+pub struct ShowOracleParams {
+    /// 流动性交易对地址
+    /// Liquidity pair address
+    pub lb_pair: Pubkey,
+    /// 显示的最近观测数量
+    /// Number of recent observations shown
+    #[clap(long, default_value_t = 10)]
+    pub limit: usize,
+}
+
+/// 执行显示预言机观测数据，只读取账户不发送交易
+/// Executes showing the oracle observations. Only reads accounts, no transaction is sent
+///
+/// # 功能说明 / Functionality
+/// 读取交易对的预言机账户，打印观测长度、已初始化的观测数量以及最近的观测样本（从旧到新）。
+/// 已初始化的观测数量等于长度时，旧样本会被覆盖，可通过 `increase-oracle-length` 保留更长的价格历史
+/// Reads the oracle account of the pair and prints the observation length, the number of initialized observations
+/// and the recent samples from the oldest to the latest. Once every observation is initialized the oldest samples
+/// are overwritten, `increase-oracle-length` keeps a longer price history
+pub async fn execute_show_oracle<C: Deref<Target = impl Signer> + Clone>(
+    params: ShowOracleParams,
+    program: &Program<C>,
+) -> Result<()> {
+    let ShowOracleParams { lb_pair, limit } = params;
+
+    let rpc_client = program.rpc();
+
+    let (oracle_key, _bump) = derive_oracle_pda(lb_pair);
+    let oracle = rpc_client
+        .get_account_and_deserialize(&oracle_key, |account| {
+            DynamicOracle::from_account_data(&account.data)
+        })
+        .await?;
+
+    println!("Oracle {} of pair {}", oracle_key, lb_pair);
+    println!("Length {}", oracle.metadata.length);
+    println!("Active size {}", oracle.metadata.active_size);
+    println!("Latest index {}", oracle.metadata.idx);
+    if oracle.metadata.length > 0 && oracle.metadata.active_size >= oracle.metadata.length {
+        println!("All observations are initialized, the oldest samples are being overwritten");
+    }
+
+    let samples = oracle.samples();
+    let recent_samples = &samples[samples.len().saturating_sub(limit)..];
+    for sample in recent_samples {
+        println!(
+            "created_at {} last_updated_at {} cumulative_active_bin_id {}",
+            sample.created_at, sample.last_updated_at, sample.cumulative_active_bin_id
+        );
+    }
+
+    Ok(())
+}
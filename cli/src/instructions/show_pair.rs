@@ -1,19 +1,13 @@
+use std::result::Result::Ok;
+
 use crate::*;
-use anchor_lang::AccountDeserialize;
+use anchor_lang::{prelude::Clock, AccountDeserialize};
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::{
+    TransferFee, TransferFeeConfig,
+};
 use anchor_spl::token_interface::Mint;
 use rust_decimal::prelude::*;
-use rust_decimal::Decimal;
-use solana_client::{
-    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
-    rpc_filter::{Memcmp, RpcFilterType},
-};
-
-/// 将手续费率转换为手续费百分比
-/// Converts fee rate to fee percentage
-fn fee_rate_to_fee_pct(fee_rate: u128) -> Option<Decimal> {
-    let fee_rate = Decimal::from_u128(fee_rate)?.checked_div(Decimal::from(FEE_PRECISION))?;
-    fee_rate.checked_mul(Decimal::ONE_HUNDRED)
-}
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
 
 /// 显示交易对信息的参数结构体
 /// Parameters for showing pair information
@@ -22,6 +16,15 @@ pub struct ShowPairParams {
     /// 流动性交易对地址
     /// Liquidity pair address
     pub lb_pair: Pubkey,
+    /// 报价代币为X代币（反向交易对）。价格以X代币计价的Y代币价格输入/显示
+    /// Quote token is token X (reversed pair). Prices are given / shown as token Y priced in token X
+    #[clap(long)]
+    pub quote_is_x: bool,
+    /// 最多获取的bin数组数量，超过时只显示最接近活跃bin的bin数组。未提供时获取全部bin数组
+    /// Maximum number of bin arrays to fetch. When exceeded, only the bin arrays closest to the active bin are
+    /// shown. Fetches every bin array when omitted
+    #[clap(long)]
+    pub max_accounts: Option<usize>,
 }
 
 /// 执行显示交易对信息指令
@@ -38,7 +41,11 @@ pub async fn execute_show_pair<C: Deref<Target = impl Signer> + Clone>(
     params: ShowPairParams,
     program: &Program<C>,
 ) -> Result<()> {
-    let ShowPairParams { lb_pair } = params;
+    let ShowPairParams {
+        lb_pair,
+        quote_is_x,
+        max_accounts,
+    } = params;
     let rpc_client = program.rpc();
 
     // 获取流动性交易对状态数据
@@ -49,28 +56,60 @@ pub async fn execute_show_pair<C: Deref<Target = impl Signer> + Clone>(
         })
         .await?;
 
-    // 设置过滤器以获取与此交易对相关的所有bin数组
-    // Set up filter to get all bin arrays related to this pair
-    let lb_pair_filter = RpcFilterType::Memcmp(Memcmp::new_base58_encoded(16, &lb_pair.to_bytes()));
+    // 设置过滤器以获取与此交易对相关的所有bin数组，只获取bin数组索引以减少数据量
+    // Set up filter to get all bin arrays related to this pair, fetching only the bin array index to reduce the data
     let account_config = RpcAccountInfoConfig {
         encoding: Some(UiAccountEncoding::Base64),
+        data_slice: Some(UiDataSliceConfig {
+            offset: 8,
+            length: std::mem::size_of::<i64>(),
+        }),
         ..Default::default()
     };
     let config = RpcProgramAccountsConfig {
-        filters: Some(vec![lb_pair_filter]),
+        filters: Some(bin_array_filter_by_pair(lb_pair)),
         account_config,
         ..Default::default()
     };
 
-    // 获取所有相关的bin数组账户
-    // Get all related bin array accounts
-    let mut bin_arrays: Vec<(Pubkey, BinArray)> = rpc_client
-        .get_program_accounts_with_config(&dlmm::ID, config)
-        .await?
+    let mut bin_array_indexes: Vec<(Pubkey, i64)> =
+        get_program_accounts_with_retry(&rpc_client, config)
+            .await?
+            .into_iter()
+            .map(|(key, account)| (key, bytemuck::pod_read_unaligned(&account.data)))
+            .collect();
+
+    // 限制获取的bin数组数量时，保留最接近活跃bin的bin数组
+    // When the number of fetched bin arrays is capped, keep the bin arrays closest to the active bin
+    if let Some(max_accounts) = max_accounts {
+        ensure!(max_accounts > 0, "max_accounts must be greater than 0");
+
+        if bin_array_indexes.len() > max_accounts {
+            let active_bin_array_index =
+                BinArray::bin_id_to_bin_array_index(lb_pair_state.active_id)? as i64;
+            warn!(
+                bin_arrays = bin_array_indexes.len(),
+                max_accounts, "Showing only the bin arrays closest to the active bin"
+            );
+            bin_array_indexes.sort_by_key(|(_, index)| index.abs_diff(active_bin_array_index));
+            bin_array_indexes.truncate(max_accounts);
+        }
+    }
+
+    // 分批获取相关的bin数组账户以避免RPC限制
+    // Get the related bin array accounts in batches to avoid RPC limits
+    let bin_array_keys = bin_array_indexes
         .into_iter()
+        .map(|(key, _)| key)
+        .collect::<Vec<_>>();
+    let accounts = rpc_client
+        .get_multiple_accounts_chunked(&bin_array_keys)
+        .await?;
+    let mut bin_arrays: Vec<(Pubkey, BinArray)> = bin_array_keys
+        .iter()
+        .zip(accounts)
         .filter_map(|(key, account)| {
-            let bin_array = bytemuck::pod_read_unaligned(&account.data[8..]);
-            Some((key, bin_array))
+            Some((*key, bytemuck::pod_read_unaligned(&account?.data[8..])))
         })
         .collect();
 
@@ -109,11 +148,17 @@ pub async fn execute_show_pair<C: Deref<Target = impl Signer> + Clone>(
     // 获取X和Y代币的铸币账户信息
     // Get X and Y token mint account information
     let mut accounts = rpc_client
-        .get_multiple_accounts(&[lb_pair_state.token_x_mint, lb_pair_state.token_y_mint])
+        .get_multiple_accounts(&[
+            lb_pair_state.token_x_mint,
+            lb_pair_state.token_y_mint,
+            solana_sdk::sysvar::clock::ID,
+        ])
         .await?;
 
     let token_x_account = accounts[0].take().context("token_mint_base not found")?;
     let token_y_account = accounts[1].take().context("token_mint_quote not found")?;
+    let clock_account = accounts[2].take().context("clock not found")?;
+    let clock = bincode::deserialize::<Clock>(&clock_account.data)?;
 
     // 反序列化代币铸币数据
     // Deserialize token mint data
@@ -140,21 +185,227 @@ pub async fn execute_show_pair<C: Deref<Target = impl Signer> + Clone>(
     )
     .context("price_per_lamport_to_price_per_token overflow")?;
 
-    // 计算各种手续费率
-    // Calculate various fee rates
-    let base_fee_rate = fee_rate_to_fee_pct(lb_pair_state.get_total_fee()?)
-        .context("get_total_fee convert to percentage overflow")?;
-    let variable_fee_rate = fee_rate_to_fee_pct(lb_pair_state.get_variable_fee()?)
-        .context("get_total_fee convert to percentage overflow")?;
-    let current_fee_rate = fee_rate_to_fee_pct(lb_pair_state.get_total_fee()?)
-        .context("get_total_fee convert to percentage overflow")?;
+    // 计算各种手续费率，某项无法计算时只显示该项不可用，不影响其余信息
+    // Calculate various fee rates. When one cannot be computed, only that figure is shown as unavailable and the
+    // rest of the pair information is still displayed
+    let fee_pct_or_unavailable = |name: &str, fee_rate: Result<u128>| {
+        match fee_rate.and_then(fee_rate_to_fee_pct) {
+            Ok(fee_pct) => format!("{}%", fee_pct),
+            Err(error) => {
+                warn!(%error, "{} fee rate unavailable", name);
+                "unavailable".to_string()
+            }
+        }
+    };
+    let base_fee_rate = fee_pct_or_unavailable("Base", lb_pair_state.get_base_fee());
+    let variable_fee_rate = fee_pct_or_unavailable("Volatile", lb_pair_state.get_variable_fee());
+    let current_fee_rate = fee_pct_or_unavailable("Current", lb_pair_state.get_total_fee());
 
     // 显示价格和手续费信息
     // Display price and fee information
-    println!("Current price {}", token_price);         // 当前价格
-    println!("Base fee rate {}%", base_fee_rate);      // 基础手续费率
-    println!("Volatile fee rate {}%", variable_fee_rate); // 波动手续费率
-    println!("Current fee rate {}%", current_fee_rate); // 当前总手续费率
+    if quote_is_x {
+        let token_price = Decimal::ONE
+            .checked_div(token_price)
+            .context("flip price overflow")?;
+        println!("Current price {} (token Y priced in token X)", token_price);
+    } else {
+        println!("Current price {}", token_price);         // 当前价格
+    }
+    println!("Base fee rate {}", base_fee_rate);      // 基础手续费率
+    println!("Volatile fee rate {}", variable_fee_rate); // 波动手续费率
+    println!("Current fee rate {}", current_fee_rate); // 当前总手续费率
+
+    // 显示Token-2022代币的转账手续费，普通SPL代币显示为none
+    // Show the transfer fee of Token-2022 tokens, plain SPL tokens are shown as none
+    let mut mint_cache = MintCache::new();
+    for (name, mint, account) in [
+        ("X", lb_pair_state.token_x_mint, token_x_account),
+        ("Y", lb_pair_state.token_y_mint, token_y_account),
+    ] {
+        let cached_mint = mint_cache.insert(mint, account)?;
+        println!(
+            "Token {} transfer fee {}",
+            name,
+            describe_transfer_fee(
+                cached_mint.transfer_fee_config.as_ref(),
+                clock.epoch,
+                cached_mint.decimals
+            )
+        );
+    }
+
+    // 根据激活类型比较当前slot或时间戳与激活点
+    // Compare the current slot or timestamp with the activation point according to the activation type
+    let activation_type = lb_pair_state.activation_type()?;
+    let current_point = match activation_type {
+        ActivationType::Slot => clock.slot,
+        ActivationType::Timestamp => clock.unix_timestamp.try_into()?,
+    };
+    let unit = match activation_type {
+        ActivationType::Slot => "slots",
+        ActivationType::Timestamp => "seconds",
+    };
+    match get_activation_status(&lb_pair_state, current_point) {
+        ActivationStatus::Disabled => println!("Activation status: disabled"),
+        ActivationStatus::Activated => println!("Activation status: activated"),
+        ActivationStatus::PreActivation {
+            remaining,
+            pre_activation_swap,
+        } => {
+            println!(
+                "Activation status: pre-activation, activates in {} {} at {} {}",
+                remaining, unit, lb_pair_state.activation_point, unit
+            );
+            if pre_activation_swap {
+                println!(
+                    "Only the pre-activation swap address {} can swap",
+                    lb_pair_state.pre_activation_swap_address
+                );
+            }
+        }
+    }
 
     Ok(())
 }
+
+/// 描述代币在指定epoch生效的转账手续费，以及尚未生效的已排期手续费。没有转账手续费配置时返回 "none"
+/// Describes the transfer fee of the token in effect at the given epoch, and the scheduled fee not in effect yet.
+/// Returns "none" without a transfer fee config
+pub fn describe_transfer_fee(
+    transfer_fee_config: Option<&TransferFeeConfig>,
+    epoch: u64,
+    decimals: u8,
+) -> String {
+    let Some(transfer_fee_config) = transfer_fee_config else {
+        return "none".to_string();
+    };
+
+    let format_fee = |transfer_fee: &TransferFee| {
+        let maximum_fee = Decimal::from_i128_with_scale(
+            u64::from(transfer_fee.maximum_fee).into(),
+            decimals.into(),
+        );
+        format!(
+            "{} bps, max fee {}",
+            u16::from(transfer_fee.transfer_fee_basis_points),
+            maximum_fee.normalize()
+        )
+    };
+
+    let current_fee = transfer_fee_config.get_epoch_fee(epoch);
+    let newer_fee = &transfer_fee_config.newer_transfer_fee;
+    let newer_fee_epoch = u64::from(newer_fee.epoch);
+
+    if newer_fee_epoch > epoch {
+        format!(
+            "{} (scheduled {} from epoch {})",
+            format_fee(current_fee),
+            format_fee(newer_fee),
+            newer_fee_epoch
+        )
+    } else {
+        format_fee(current_fee)
+    }
+}
+
+/// 交易对的激活状态
+/// Activation status of a pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivationStatus {
+    /// 交易对被禁用 / The pair is disabled
+    Disabled,
+    /// 已激活，可以交易 / Activated, swaps are allowed
+    Activated,
+    /// 尚未激活。`remaining` 为距激活的slot数或秒数，`pre_activation_swap` 表示预激活交易地址已可交易
+    /// Not activated yet. `remaining` is the number of slots or seconds until the activation, `pre_activation_swap`
+    /// tells whether the pre-activation swap address can already swap
+    PreActivation {
+        remaining: u64,
+        pre_activation_swap: bool,
+    },
+}
+
+/// 根据当前slot或时间戳（由激活类型决定）计算交易对的激活状态
+/// Computes the activation status of the pair at the current slot or timestamp, as selected by its activation type
+pub fn get_activation_status(lb_pair: &LbPair, current_point: u64) -> ActivationStatus {
+    if !matches!(lb_pair.status(), Ok(PairStatus::Enabled)) {
+        return ActivationStatus::Disabled;
+    }
+
+    if current_point >= lb_pair.activation_point {
+        return ActivationStatus::Activated;
+    }
+
+    let pre_activation_point = lb_pair
+        .activation_point
+        .saturating_sub(lb_pair.pre_activation_duration);
+
+    ActivationStatus::PreActivation {
+        remaining: lb_pair.activation_point - current_point,
+        pre_activation_swap: lb_pair.pre_activation_swap_address != Pubkey::default()
+            && current_point >= pre_activation_point,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_transfer_fee() {
+        let transfer_fee = |epoch: u64, basis_points: u16, maximum_fee: u64| TransferFee {
+            epoch: epoch.into(),
+            maximum_fee: maximum_fee.into(),
+            transfer_fee_basis_points: basis_points.into(),
+        };
+        let transfer_fee_config = TransferFeeConfig {
+            older_transfer_fee: transfer_fee(0, 100, 5_000_000),
+            newer_transfer_fee: transfer_fee(10, 250, 1_500_000),
+            ..Default::default()
+        };
+
+        assert_eq!(describe_transfer_fee(None, 5, 6), "none");
+        assert_eq!(
+            describe_transfer_fee(Some(&transfer_fee_config), 5, 6),
+            "100 bps, max fee 5 (scheduled 250 bps, max fee 1.5 from epoch 10)"
+        );
+        assert_eq!(
+            describe_transfer_fee(Some(&transfer_fee_config), 10, 6),
+            "250 bps, max fee 1.5"
+        );
+    }
+
+    #[test]
+    fn test_get_activation_status() {
+        let mut lb_pair: LbPair = bytemuck::Zeroable::zeroed();
+        lb_pair.activation_point = 1_000;
+        lb_pair.pre_activation_duration = 100;
+
+        assert_eq!(
+            get_activation_status(&lb_pair, 850),
+            ActivationStatus::PreActivation {
+                remaining: 150,
+                pre_activation_swap: false,
+            }
+        );
+        assert_eq!(
+            get_activation_status(&lb_pair, 1_000),
+            ActivationStatus::Activated
+        );
+
+        lb_pair.pre_activation_swap_address = Pubkey::new_unique();
+        assert_eq!(
+            get_activation_status(&lb_pair, 950),
+            ActivationStatus::PreActivation {
+                remaining: 50,
+                pre_activation_swap: true,
+            }
+        );
+
+        lb_pair.status = PairStatusArg::Disabled.into();
+        assert_eq!(
+            get_activation_status(&lb_pair, 1_000),
+            ActivationStatus::Disabled
+        );
+    }
+}
@@ -9,6 +9,11 @@ pub struct ShowPositionParams {
     /// 仓位地址
     /// Position address
     pub position: Pubkey,
+    /// 以JSON输出每个bin的待领取手续费明细（手续费增长差值、已记入及新增的手续费），只支持PositionV2
+    /// Print the pending fee breakdown of every bin as JSON (fee growth deltas, credited and new fees). PositionV2
+    /// only
+    #[clap(long)]
+    pub fee_breakdown: bool,
 }
 
 /// 执行显示仓位信息指令
@@ -25,7 +30,10 @@ pub async fn execute_show_position<C: Deref<Target = impl Signer> + Clone>(
     params: ShowPositionParams,
     program: &Program<C>,
 ) -> Result<()> {
-    let ShowPositionParams { position } = params;
+    let ShowPositionParams {
+        position,
+        fee_breakdown,
+    } = params;
 
     let rpc_client = program.rpc();
     
@@ -43,6 +51,10 @@ pub async fn execute_show_position<C: Deref<Target = impl Signer> + Clone>(
     if disc == Position::DISCRIMINATOR {
         // 旧版本仓位（Position）
         // Old version position (Position)
+        ensure!(
+            !fee_breakdown,
+            "Fee breakdown is only supported for PositionV2"
+        );
         let position_state: Position = bytemuck::pod_read_unaligned(&position_account.data[8..]);
         println!("{:#?}", position_state);
     } else if disc == PositionV2::DISCRIMINATOR {
@@ -50,6 +62,30 @@ pub async fn execute_show_position<C: Deref<Target = impl Signer> + Clone>(
         // New version position (PositionV2)
         let position_state: PositionV2 = bytemuck::pod_read_unaligned(&position_account.data[8..]);
         println!("{:#?}", position_state);
+
+        if fee_breakdown {
+            // u128手续费增长差值以字符串输出，避免JSON数值精度丢失
+            // u128 fee growth deltas are emitted as strings to avoid JSON number precision loss
+            let bins = get_position_fee_breakdown(&rpc_client, &position_state)
+                .await?
+                .into_iter()
+                .map(|bin| {
+                    Ok(serde_json::json!({
+                        "bin_id": bin.bin_id,
+                        "liquidity_share": bin.liquidity_share.to_string(),
+                        "fee_x_per_token_delta": bin.fee_x_per_token_delta.to_string(),
+                        "fee_y_per_token_delta": bin.fee_y_per_token_delta.to_string(),
+                        "fee_x_pending": bin.fee_x_pending,
+                        "fee_y_pending": bin.fee_y_pending,
+                        "new_fee_x": bin.new_fee_x,
+                        "new_fee_y": bin.new_fee_y,
+                        "fee_x": bin.fee_x()?,
+                        "fee_y": bin.fee_y()?,
+                    }))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            println!("{}", serde_json::to_string_pretty(&bins)?);
+        }
     } else {
         // 无效的仓位账户
         // Invalid position account
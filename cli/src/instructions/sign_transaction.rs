@@ -0,0 +1,37 @@
+use crate::*;
+
+/// 为部分签名的交易补充签名的参数结构体
+/// Parameters for adding a signature to a partially signed transaction
+#[derive(Debug, Parser)]
+pub struct SignTransactionParams {
+    /// 管理员命令配合 `--authority` 或之前的 `sign-transaction` 打印的base58编码交易
+    /// Base58 encoded transaction printed by an admin command with `--authority` or by a previous `sign-transaction`
+    pub transaction: String,
+}
+
+/// 执行使用钱包为部分签名的交易补充签名，不连接链
+/// Executes adding the wallet signature to a partially signed transaction. The chain is not accessed
+///
+/// # 功能说明 / Functionality
+/// 钱包须为交易尚未签名的签名者之一，签名时保留交易原有的区块哈希，因此不会使已有签名失效。
+/// 打印补充签名后的交易及仍未签名的签名者，全部签名后使用 `send-transaction` 发送
+/// The wallet must be one of the signers that didn't sign the transaction yet. The blockhash of the transaction is
+/// kept, so the existing signatures stay valid. Prints the transaction with the added signature and the signers still
+/// missing. Submit it with `send-transaction` once every signer signed
+pub fn execute_sign_transaction(params: SignTransactionParams, signer: &dyn Signer) -> Result<()> {
+    let SignTransactionParams { transaction } = params;
+
+    let mut transaction = decode_transaction(&transaction)?;
+
+    let signer_pubkey = signer.pubkey();
+    ensure!(
+        get_missing_signers(&transaction).contains(&signer_pubkey),
+        "{} is not a missing signer of the transaction",
+        signer_pubkey
+    );
+
+    let recent_blockhash = transaction.message.recent_blockhash;
+    transaction.try_partial_sign(&[signer], recent_blockhash)?;
+
+    print_transaction_signing_status(&transaction)
+}
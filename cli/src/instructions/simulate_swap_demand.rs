@@ -22,7 +22,7 @@ pub async fn simulate_swap_demand<C: Deref<Target = impl Signer> + Clone>(
     params: SimulateSwapDemandParameters,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
-    compute_unit_price: Option<Instruction>,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<()> {
     let SimulateSwapDemandParameters {
         lb_pair,
@@ -0,0 +1,153 @@
+use std::path::PathBuf;
+
+use anchor_lang::{prelude::Clock, AccountDeserialize};
+use anchor_spl::token_interface::Mint;
+use rust_decimal::prelude::ToPrimitive;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+
+use crate::*;
+
+/// 导出交易对bin流动性快照的参数结构体
+/// Parameters for exporting the bin liquidity snapshot of a pair
+#[derive(Debug, Parser)]
+pub struct SnapshotPairParams {
+    /// 流动性交易对地址
+    /// Liquidity pair address
+    pub lb_pair: Pubkey,
+    /// 快照JSON文件的输出路径
+    /// Output path of the snapshot JSON file
+    #[clap(long)]
+    pub output: PathBuf,
+}
+
+/// 执行导出交易对的完整bin流动性快照，只读取账户不发送交易
+/// Executes exporting the complete bin liquidity snapshot of a pair. Only reads accounts, no transaction is sent
+///
+/// # 功能说明 / Functionality
+/// 先只获取交易对所有bin数组的地址，再分批读取bin数组账户，将每个有流动性的bin（bin ID、X/Y代币数量、UI价格、流动性）
+/// 按bin ID排序写入JSON文件，并附带读取时的slot、时间戳及交易对信息。用于离线分析及模拟器的定期存档。
+/// 与只查询指定范围的 `get-bin-reserves` 不同，快照覆盖整个流动性分布
+/// Fetches the addresses of every bin array of the pair first, then reads the bin array accounts in chunks and
+/// writes every bin with liquidity (bin id, token X / Y amounts, UI price, liquidity) ordered by bin id to a JSON
+/// file, along with the slot, timestamp and pair information at read time. Meant for periodic archival for offline
+/// analysis and simulators. Unlike `get-bin-reserves`, which queries a given range, the snapshot covers the whole
+/// liquidity distribution
+pub async fn execute_snapshot_pair<C: Deref<Target = impl Signer> + Clone>(
+    params: SnapshotPairParams,
+    program: &Program<C>,
+) -> Result<()> {
+    let SnapshotPairParams { lb_pair, output } = params;
+
+    let rpc_client = program.rpc();
+
+    let lb_pair_state: LbPair = rpc_client
+        .get_account_and_deserialize(&lb_pair, |account| {
+            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
+        })
+        .await?;
+
+    let mut accounts = rpc_client
+        .get_multiple_accounts(&[
+            solana_sdk::sysvar::clock::ID,
+            lb_pair_state.token_x_mint,
+            lb_pair_state.token_y_mint,
+        ])
+        .await?;
+
+    let clock_account = accounts[0].take().context("clock not found")?;
+    let clock = bincode::deserialize::<Clock>(&clock_account.data)?;
+    let mint_x_account = accounts[1].take().context("mint x not found")?;
+    let mint_x = Mint::try_deserialize(&mut mint_x_account.data.as_ref())?;
+    let mint_y_account = accounts[2].take().context("mint y not found")?;
+    let mint_y = Mint::try_deserialize(&mut mint_y_account.data.as_ref())?;
+
+    // 只获取bin数组地址，再分批读取完整的bin数组账户，避免单次getProgramAccounts返回过大的数据
+    // Fetch only the bin array addresses, then read the full bin array accounts in chunks, to avoid a single
+    // oversized getProgramAccounts response
+    let config = RpcProgramAccountsConfig {
+        filters: Some(bin_array_filter_by_pair(lb_pair)),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: Some(UiDataSliceConfig {
+                offset: 0,
+                length: 0,
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let bin_array_keys = get_program_accounts_with_retry(&rpc_client, config)
+        .await?
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect::<Vec<_>>();
+
+    let mut bin_arrays: Vec<BinArray> = rpc_client
+        .get_multiple_accounts_chunked(&bin_array_keys)
+        .await?
+        .into_iter()
+        .flatten()
+        .map(|account| bytemuck::pod_read_unaligned(&account.data[8..]))
+        .collect();
+    bin_arrays.sort_by_key(|bin_array| bin_array.index);
+
+    let mut bins = vec![];
+    for bin_array in bin_arrays.iter() {
+        let (lower_bin_id, _) = BinArray::get_bin_array_lower_upper_bin_id(bin_array.index as i32)?;
+
+        for (bin_id, bin) in (lower_bin_id..).zip(bin_array.bins.iter()) {
+            if bin.amount_x == 0 && bin.amount_y == 0 {
+                continue;
+            }
+
+            let price = get_ui_price_from_id(
+                lb_pair_state.bin_step,
+                bin_id,
+                mint_x.decimals,
+                mint_y.decimals,
+            )
+            .and_then(|price| price.to_f64())
+            .with_context(|| format!("UI price of bin {} overflow", bin_id))?;
+
+            // u128流动性以字符串输出，避免JSON数值精度丢失
+            // u128 liquidity is emitted as a string to avoid JSON number precision loss
+            bins.push(serde_json::json!({
+                "bin_id": bin_id,
+                "amount_x": bin.amount_x,
+                "amount_y": bin.amount_y,
+                "price": price,
+                "liquidity": bin.liquidity_supply.to_string(),
+            }));
+        }
+    }
+
+    let bin_count = bins.len();
+    let snapshot = serde_json::json!({
+        "lb_pair": lb_pair.to_string(),
+        "slot": clock.slot,
+        "timestamp": clock.unix_timestamp,
+        "active_id": lb_pair_state.active_id,
+        "bin_step": lb_pair_state.bin_step,
+        "token_x_mint": lb_pair_state.token_x_mint.to_string(),
+        "token_y_mint": lb_pair_state.token_y_mint.to_string(),
+        "token_x_decimals": mint_x.decimals,
+        "token_y_decimals": mint_y.decimals,
+        "bin_arrays": bin_arrays.len(),
+        "bins": bins,
+    });
+
+    let file = std::fs::File::create(&output)
+        .with_context(|| format!("Failed to create {}", output.display()))?;
+    serde_json::to_writer_pretty(std::io::BufWriter::new(file), &snapshot)?;
+
+    println!(
+        "Wrote {} bins of {} bin arrays of pair {} at slot {} to {}",
+        bin_count,
+        bin_arrays.len(),
+        lb_pair,
+        clock.slot,
+        output.display()
+    );
+
+    Ok(())
+}
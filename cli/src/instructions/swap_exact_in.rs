@@ -1,5 +1,5 @@
 use crate::*;
-use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use solana_sdk::commitment_config::CommitmentLevel;
 
 /// 精确输入数量的交易参数
 #[derive(Debug, Parser)]
@@ -9,19 +9,68 @@ pub struct SwapExactInParams {
     pub lb_pair: Pubkey,
     /// Amount of token to be sell.
     /// 要卖出的代币数量（精确输入）
-    pub amount_in: u64,
+    #[clap(required_unless_present = "amount_in_ui")]
+    pub amount_in: Option<u64>,
+    /// Amount of token to sell in UI units of the input token, converted with the input mint decimals. Token Y is
+    /// the quote token, e.g. `--amount-in-ui 100 --output-mint <X>` buys token X with 100 token Y.
+    /// 以输入代币UI单位表示的卖出数量，按输入代币的小数位数转换。Y代币为计价代币，例如 `--amount-in-ui 100 --output-mint <X>` 用100个Y代币买入X代币
+    #[clap(long, conflicts_with = "amount_in")]
+    pub amount_in_ui: Option<f64>,
     /// Buy direction. true = buy token Y, false = buy token X.
     /// 交易方向：true = 用X代币买Y代币，false = 用Y代币买X代币
     #[clap(long)]
     pub swap_for_y: bool,
+    /// Commitment used to read the accounts for quoting. Defaults to the client commitment.
+    /// 读取报价所需账户时使用的确认级别，默认使用客户端的确认级别
+    #[clap(long)]
+    pub commitment_for_quote: Option<CommitmentLevel>,
+    /// Print the ordered accounts of the swap instruction before sending.
+    /// 发送前打印交换指令的完整有序账户列表
+    #[clap(long)]
+    pub dump_accounts: bool,
+    /// Token program of token X. Skips the mint owner lookup when provided.
+    /// X代币的代币程序，提供时跳过查询代币所有者
+    #[clap(long)]
+    pub token_x_program: Option<Pubkey>,
+    /// Token program of token Y. Skips the mint owner lookup when provided.
+    /// Y代币的代币程序，提供时跳过查询代币所有者
+    #[clap(long)]
+    pub token_y_program: Option<Pubkey>,
+    /// Mint of the token to sell. Derives the swap direction instead of `--swap-for-y`.
+    /// 卖出代币的铸造地址，用于推导交易方向，替代 `--swap-for-y`
+    #[clap(long, conflicts_with = "swap_for_y")]
+    pub input_mint: Option<Pubkey>,
+    /// Mint of the token to buy. Derives the swap direction instead of `--swap-for-y`.
+    /// 买入代币的铸造地址，用于推导交易方向，替代 `--swap-for-y`
+    #[clap(long, conflicts_with = "swap_for_y")]
+    pub output_mint: Option<Pubkey>,
+    /// Re-read the output token balance after the swap confirmed and verify the received amount matches the quote
+    /// within the transfer fee tolerance.
+    /// 交易确认后重新读取输出代币余额，校验实际收到的数量在转账手续费容差内与报价一致
+    #[clap(long)]
+    pub verify: bool,
+    /// Absolute price limit as the UI price of token X in token Y. The swap is not sent when the execution price of
+    /// the quote, fees included, is worse than the limit: below it when selling token X, above it when buying token X.
+    /// 绝对限价，即以Y代币计价的X代币UI价格。报价的成交价格（包含手续费）劣于限价时不发送交易：卖出X代币时低于限价，买入X代币时高于限价
+    #[clap(long)]
+    pub price_limit: Option<f64>,
+    /// Maximum number of slots the quote accounts may lag the current slot before sending. The pair is re-read and
+    /// re-quoted when the quote is older, up to 3 times.
+    /// 发送前报价账户允许落后当前slot的最大slot数，超过时重新读取交易对并重新报价，最多3次
+    #[clap(long)]
+    pub max_quote_age_slots: Option<u64>,
+    /// Print the built instructions as JSON (program id, accounts, base58 data) instead of sending them, to compose
+    /// them into other transactions. Compute budget instructions are left out.
+    /// 不发送交易，以JSON打印构建的指令（程序ID、账户、base58数据），用于组合到其他交易中。不包含计算预算指令
+    #[clap(long, conflicts_with = "verify")]
+    pub build_only: bool,
 }
 
 /// 执行精确输入的交易
 /// 
 /// # 参数
 /// * `params` - 交易参数
-/// * `program` - Anchor程序客户端
-/// * `transaction_config` - 交易配置
+/// * `client` - DLMM客户端
 /// 
 /// # 功能
 /// 1. 获取流动性对状态
@@ -29,173 +78,107 @@ pub struct SwapExactInParams {
 /// 3. 构建并发送交易
 pub async fn execute_swap<C: Deref<Target = impl Signer> + Clone>(
     params: SwapExactInParams,
-    program: &Program<C>,
-    transaction_config: RpcSendTransactionConfig,
+    client: &DlmmClient<C>,
 ) -> Result<()> {
     let SwapExactInParams {
         amount_in,
+        amount_in_ui,
         lb_pair,
         swap_for_y,
+        commitment_for_quote,
+        dump_accounts,
+        token_x_program,
+        token_y_program,
+        input_mint,
+        output_mint,
+        verify,
+        price_limit,
+        max_quote_age_slots,
+        build_only,
     } = params;
 
-    let rpc_client = program.rpc();
+    let rpc_client = client.program().rpc();
 
     // 获取流动性对的状态
-    let lb_pair_state: LbPair = rpc_client
-        .get_account_and_deserialize(&lb_pair, |account| {
-            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
-        })
-        .await?;
+    let lb_pair_state = client.get_lb_pair(lb_pair).await?;
 
-    // 获取代币程序（支持Token和Token2022）
-    let [token_x_program, token_y_program] = lb_pair_state.get_token_programs()?;
-
-    // 根据交易方向确定输入和输出代币账户
-    let (user_token_in, user_token_out) = if swap_for_y {
-        (
-            get_associated_token_address_with_program_id(
-                &program.payer(),
-                &lb_pair_state.token_x_mint,
-                &token_x_program,
-            ),
-            get_associated_token_address_with_program_id(
-                &program.payer(),
-                &lb_pair_state.token_y_mint,
-                &token_y_program,
-            ),
-        )
+    let swap_for_y = resolve_swap_for_y(&lb_pair_state, swap_for_y, input_mint, output_mint)?;
+
+    let token_in_mint = if swap_for_y {
+        lb_pair_state.token_x_mint
     } else {
-        (
-            get_associated_token_address_with_program_id(
-                &program.payer(),
-                &lb_pair_state.token_y_mint,
-                &token_y_program,
-            ),
-            get_associated_token_address_with_program_id(
-                &program.payer(),
-                &lb_pair_state.token_x_mint,
-                &token_x_program,
-            ),
-        )
+        lb_pair_state.token_y_mint
     };
+    let amount_in =
+        resolve_token_amount(&rpc_client, token_in_mint, amount_in, amount_in_ui).await?;
 
-    // 获取bin数组位图扩展（用于优化bin查找）
-    let (bitmap_extension_key, _bump) = derive_bin_array_bitmap_extension(lb_pair);
-
-    let bitmap_extension = rpc_client
-        .get_account_and_deserialize(&bitmap_extension_key, |account| {
-            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
-        })
-        .await
-        .ok();
-
-    // 获取交易所需的bin数组公钥
-    // 参数3表示获取3个bin数组，用于覆盖可能的交易范围
-    let bin_arrays_for_swap = get_bin_array_pubkeys_for_swap(
-        lb_pair,
-        &lb_pair_state,
-        bitmap_extension.as_ref(),
-        swap_for_y,
-        3,
-    )?;
-
-    let SwapQuoteAccounts {
-        lb_pair_state,
-        clock,
-        mint_x_account,
-        mint_y_account,
-        bin_arrays,
-        bin_array_keys,
-    } = fetch_quote_required_accounts(&rpc_client, lb_pair, &lb_pair_state, bin_arrays_for_swap)
-        .await?;
-
-    let quote = quote_exact_in(
-        lb_pair,
-        &lb_pair_state,
-        amount_in,
-        swap_for_y,
-        bin_arrays,
-        bitmap_extension.as_ref(),
-        &clock,
-        &mint_x_account,
-        &mint_y_account,
-    )?;
-
-    let (event_authority, _bump) = derive_event_authority_pda();
-
-    let main_accounts = dlmm::client::accounts::Swap2 {
-        lb_pair,
-        bin_array_bitmap_extension: bitmap_extension
-            .map(|_| bitmap_extension_key)
-            .or(Some(dlmm::ID)),
-        reserve_x: lb_pair_state.reserve_x,
-        reserve_y: lb_pair_state.reserve_y,
-        token_x_mint: lb_pair_state.token_x_mint,
-        token_y_mint: lb_pair_state.token_y_mint,
+    let args = SwapExactInArgs {
         token_x_program,
         token_y_program,
-        user: program.payer(),
-        user_token_in,
-        user_token_out,
-        oracle: lb_pair_state.oracle,
-        host_fee_in: Some(dlmm::ID),
-        event_authority,
-        program: dlmm::ID,
-        memo_program: spl_memo::ID,
-    }
-    .to_account_metas(None);
-
-    let mut remaining_accounts_info = RemainingAccountsInfo { slices: vec![] };
-    let mut remaining_accounts = vec![];
-
-    if let Some((slices, transfer_hook_remaining_accounts)) =
-        get_potential_token_2022_related_ix_data_and_accounts(
-            &lb_pair_state,
-            program.rpc(),
-            ActionType::Liquidity,
-        )
-        .await?
-    {
-        remaining_accounts_info.slices = slices;
-        remaining_accounts.extend(transfer_hook_remaining_accounts);
-    }
-
-    remaining_accounts.extend(
-        bin_array_keys
-            .into_iter()
-            .map(|key| AccountMeta::new(key, false)),
-    );
-
-    // 100 bps slippage
-    let min_amount_out = quote.amount_out * 9900 / BASIS_POINT_MAX as u64;
+        commitment_for_quote: commitment_for_quote.map(|commitment| CommitmentConfig { commitment }),
+        price_limit,
+        ..SwapExactInArgs::new(lb_pair, amount_in, swap_for_y)
+    };
 
-    let data = dlmm::client::args::Swap2 {
-        amount_in,
-        min_amount_out,
+    // 报价过期时重新读取交易对并重新报价
+    // Re-read the pair and re-quote when the quote is stale
+    let SwapExactInBuild {
+        instruction: swap_ix,
         remaining_accounts_info,
+        quote,
+        user_token_out,
+        quote_accounts,
+        ..
+    } = quote_with_requotes(
+        &rpc_client,
+        lb_pair,
+        lb_pair_state,
+        args.commitment_for_quote,
+        max_quote_age_slots,
+        |build: &SwapExactInBuild| build.quote_accounts.context_slot,
+        |lb_pair_state| client.build_swap_exact_in(lb_pair_state, args.clone()),
+    )
+    .await?;
+
+    if dump_accounts {
+        let (main_accounts, remaining_accounts) =
+            swap_ix.accounts.split_at(SWAP_ACCOUNT_LABELS.len());
+        dump_swap_accounts(main_accounts, &remaining_accounts_info, remaining_accounts);
     }
-    .data();
 
-    let accounts = [main_accounts.to_vec(), remaining_accounts].concat();
+    if build_only {
+        return print_instructions(&[swap_ix]);
+    }
 
-    let swap_ix = Instruction {
-        program_id: dlmm::ID,
-        accounts,
-        data,
+    let balance_before = if verify {
+        Some(get_token_balance_or_zero(&rpc_client, user_token_out).await?)
+    } else {
+        None
     };
 
-    let compute_budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(1_400_000);
-
-    let request_builder = program.request();
-    let signature = request_builder
-        .instruction(compute_budget_ix)
-        .instruction(swap_ix)
-        .send_with_spinner_and_config(transaction_config)
+    let signature = client
+        .send([client.compute_budget_instructions()?, vec![swap_ix]].concat())
         .await;
 
-    println!("Swap. Signature: {:#?}", signature);
+    info!(?signature, "Swap");
 
     signature?;
 
+    if let Some(balance_before) = balance_before {
+        let out_mint_account = if swap_for_y {
+            &quote_accounts.mint_y_account
+        } else {
+            &quote_accounts.mint_x_account
+        };
+        verify_swap_amount_out(
+            &rpc_client,
+            user_token_out,
+            balance_before,
+            quote.amount_out,
+            out_mint_account,
+        )
+        .await?;
+    }
+
     Ok(())
 }
@@ -1,5 +1,5 @@
 use crate::*;
-use anchor_spl::associated_token::get_associated_token_address;
+use solana_sdk::commitment_config::CommitmentLevel;
 
 /// 精确输出交易的参数结构体
 /// Parameters for exact output swap
@@ -10,11 +10,61 @@ pub struct SwapExactOutParams {
     pub lb_pair: Pubkey,
     /// 要购买的代币数量
     /// Amount of token to be buy.
-    pub amount_out: u64,
+    #[clap(required_unless_present = "amount_out_ui")]
+    pub amount_out: Option<u64>,
+    /// 以输出代币UI单位表示的购买数量，按输出代币的小数位数转换。Y代币为计价代币，例如 `--amount-out-ui 100 --output-mint <Y>` 卖出X代币换取100个Y代币
+    /// Amount of token to buy in UI units of the output token, converted with the output mint decimals. Token Y is
+    /// the quote token, e.g. `--amount-out-ui 100 --output-mint <Y>` sells token X for 100 token Y.
+    #[clap(long, conflicts_with = "amount_out")]
+    pub amount_out_ui: Option<f64>,
     /// 购买方向：true = 买入Y代币，false = 买入X代币
     /// Buy direction. true = buy token Y, false = buy token X.
     #[clap(long)]
     pub swap_for_y: bool,
+    /// Commitment used to read the accounts for quoting. Defaults to the client commitment.
+    /// 读取报价所需账户时使用的确认级别，默认使用客户端的确认级别
+    #[clap(long)]
+    pub commitment_for_quote: Option<CommitmentLevel>,
+    /// Print the ordered accounts of the swap instruction before sending.
+    /// 发送前打印交换指令的完整有序账户列表
+    #[clap(long)]
+    pub dump_accounts: bool,
+    /// Token program of token X. Skips the mint owner lookup when provided.
+    /// X代币的代币程序，提供时跳过查询代币所有者
+    #[clap(long)]
+    pub token_x_program: Option<Pubkey>,
+    /// Token program of token Y. Skips the mint owner lookup when provided.
+    /// Y代币的代币程序，提供时跳过查询代币所有者
+    #[clap(long)]
+    pub token_y_program: Option<Pubkey>,
+    /// Mint of the token to sell. Derives the swap direction instead of `--swap-for-y`.
+    /// 卖出代币的铸造地址，用于推导交易方向，替代 `--swap-for-y`
+    #[clap(long, conflicts_with = "swap_for_y")]
+    pub input_mint: Option<Pubkey>,
+    /// Mint of the token to buy. Derives the swap direction instead of `--swap-for-y`.
+    /// 买入代币的铸造地址，用于推导交易方向，替代 `--swap-for-y`
+    #[clap(long, conflicts_with = "swap_for_y")]
+    pub output_mint: Option<Pubkey>,
+    /// Re-read the output token balance after the swap confirmed and verify the received amount matches the quote
+    /// within the transfer fee tolerance.
+    /// 交易确认后重新读取输出代币余额，校验实际收到的数量在转账手续费容差内与报价一致
+    #[clap(long)]
+    pub verify: bool,
+    /// Absolute price limit as the UI price of token X in token Y. The swap is not sent when the execution price of
+    /// the quote, fees included, is worse than the limit: below it when selling token X, above it when buying token X.
+    /// 绝对限价，即以Y代币计价的X代币UI价格。报价的成交价格（包含手续费）劣于限价时不发送交易：卖出X代币时低于限价，买入X代币时高于限价
+    #[clap(long)]
+    pub price_limit: Option<f64>,
+    /// Maximum number of slots the quote accounts may lag the current slot before sending. The pair is re-read and
+    /// re-quoted when the quote is older, up to 3 times.
+    /// 发送前报价账户允许落后当前slot的最大slot数，超过时重新读取交易对并重新报价，最多3次
+    #[clap(long)]
+    pub max_quote_age_slots: Option<u64>,
+    /// Print the built instructions as JSON (program id, accounts, base58 data) instead of sending them, to compose
+    /// them into other transactions. Compute budget instructions are left out.
+    /// 不发送交易，以JSON打印构建的指令（程序ID、账户、base58数据），用于组合到其他交易中。不包含计算预算指令
+    #[clap(long, conflicts_with = "verify")]
+    pub build_only: bool,
 }
 
 /// 执行精确输出交易指令
@@ -22,8 +72,7 @@ pub struct SwapExactOutParams {
 /// 
 /// # 参数 / Parameters
 /// * `params` - 精确输出交易的参数 / Parameters for exact output swap
-/// * `program` - Solana程序引用 / Solana program reference
-/// * `transaction_config` - 交易配置 / Transaction configuration
+/// * `client` - DLMM客户端 / DLMM client
 /// 
 /// # 功能说明 / Functionality
 /// 执行精确输出数量的交易，指定要获得的代币数量，系统计算需要支付的代币数量
@@ -31,194 +80,110 @@ pub struct SwapExactOutParams {
 /// and the system calculates the amount of tokens to pay
 pub async fn execute_swap_exact_out<C: Deref<Target = impl Signer> + Clone>(
     params: SwapExactOutParams,
-    program: &Program<C>,
-    transaction_config: RpcSendTransactionConfig,
+    client: &DlmmClient<C>,
 ) -> Result<()> {
     let SwapExactOutParams {
         amount_out,
+        amount_out_ui,
         lb_pair,
         swap_for_y,
+        commitment_for_quote,
+        dump_accounts,
+        token_x_program,
+        token_y_program,
+        input_mint,
+        output_mint,
+        verify,
+        price_limit,
+        max_quote_age_slots,
+        build_only,
     } = params;
 
-    let rpc_client = program.rpc();
-    
+    let rpc_client = client.program().rpc();
+
     // 获取流动性交易对状态数据
     // Get liquidity pair state data
-    let lb_pair_state: LbPair = rpc_client
-        .get_account_and_deserialize(&lb_pair, |account| {
-            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
-        })
-        .await?;
+    let lb_pair_state = client.get_lb_pair(lb_pair).await?;
 
-    // 根据交易方向确定输入和输出代币账户
-    // Determine input and output token accounts based on swap direction
-    let (user_token_in, user_token_out) = if swap_for_y {
-        // 用X代币买Y代币 / Use X token to buy Y token
-        (
-            get_associated_token_address(&program.payer(), &lb_pair_state.token_x_mint),
-            get_associated_token_address(&program.payer(), &lb_pair_state.token_y_mint),
-        )
+    let swap_for_y = resolve_swap_for_y(&lb_pair_state, swap_for_y, input_mint, output_mint)?;
+
+    let token_out_mint = if swap_for_y {
+        lb_pair_state.token_y_mint
     } else {
-        // 用Y代币买X代币 / Use Y token to buy X token
-        (
-            get_associated_token_address(&program.payer(), &lb_pair_state.token_y_mint),
-            get_associated_token_address(&program.payer(), &lb_pair_state.token_x_mint),
-        )
+        lb_pair_state.token_x_mint
+    };
+    let amount_out =
+        resolve_token_amount(&rpc_client, token_out_mint, amount_out, amount_out_ui).await?;
+
+    let args = SwapExactOutArgs {
+        token_x_program,
+        token_y_program,
+        commitment_for_quote: commitment_for_quote
+            .map(|commitment| CommitmentConfig { commitment }),
+        price_limit,
+        ..SwapExactOutArgs::new(lb_pair, amount_out, swap_for_y)
     };
 
-    // 派生bitmap扩展账户密钥
-    // Derive bitmap extension account key
-    let (bitmap_extension_key, _bump) = derive_bin_array_bitmap_extension(lb_pair);
-    
-    // 获取X和Y代币的程序ID
-    // Get X and Y token program IDs
-    let [token_x_program, token_y_program] = lb_pair_state.get_token_programs()?;
-
-    // 尝试获取bitmap扩展账户（可能不存在）
-    // Try to get bitmap extension account (may not exist)
-    let bitmap_extension = rpc_client
-        .get_account_and_deserialize(&bitmap_extension_key, |account| {
-            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
-        })
-        .await
-        .ok();
-
-    // 获取交换所需的bin数组公钥
-    // Get bin array public keys required for swap
-    let bin_arrays_for_swap = get_bin_array_pubkeys_for_swap(
+    // 报价过期时重新读取交易对并重新报价
+    // Re-read the pair and re-quote when the quote is stale
+    let SwapExactOutBuild {
+        instruction: swap_ix,
+        remaining_accounts_info,
+        user_token_out,
+        quote_accounts,
+        ..
+    } = quote_with_requotes(
+        &rpc_client,
         lb_pair,
-        &lb_pair_state,
-        bitmap_extension.as_ref(),
-        swap_for_y,
-        3,  // 最多查找3个bin数组 / Search up to 3 bin arrays
-    )?;
-
-    // 获取报价所需的账户信息
-    // Fetch accounts required for quote calculation
-    let SwapQuoteAccounts {
         lb_pair_state,
-        clock,
-        mint_x_account,
-        mint_y_account,
-        bin_arrays,
-        bin_array_keys,
-    } = fetch_quote_required_accounts(&rpc_client, lb_pair, &lb_pair_state, bin_arrays_for_swap)
-        .await?;
-
-    // 计算精确输出交易的报价
-    // Calculate quote for exact output swap
-    let quote = quote_exact_out(
-        lb_pair,
-        &lb_pair_state,
-        amount_out,            // 期望输出数量 / Desired output amount
-        swap_for_y,
-        bin_arrays,
-        bitmap_extension.as_ref(),
-        &clock,
-        &mint_x_account,
-        &mint_y_account,
-    )?;
-
-    // 派生事件权限PDA
-    // Derive event authority PDA
-    let (event_authority, _bump) = derive_event_authority_pda();
-
-    // 构建主要账户信息
-    // Build main account information
-    let main_accounts = dlmm::client::accounts::SwapExactOut2 {
-        lb_pair,                           // 流动性交易对 / Liquidity pair
-        bin_array_bitmap_extension: bitmap_extension
-            .map(|_| bitmap_extension_key)
-            .or(Some(dlmm::ID)),           // Bitmap扩展账户或程序ID / Bitmap extension account or program ID
-        reserve_x: lb_pair_state.reserve_x, // X代币储备账户 / X token reserve account
-        reserve_y: lb_pair_state.reserve_y, // Y代币储备账户 / Y token reserve account
-        token_x_mint: lb_pair_state.token_x_mint, // X代币铸币账户 / X token mint account
-        token_y_mint: lb_pair_state.token_y_mint, // Y代币铸币账户 / Y token mint account
-        token_x_program,                   // X代币程序 / X token program
-        token_y_program,                   // Y代币程序 / Y token program
-        user: program.payer(),             // 用户账户 / User account
-        user_token_in,                     // 用户输入代币账户 / User input token account
-        user_token_out,                    // 用户输出代币账户 / User output token account
-        oracle: lb_pair_state.oracle,      // 预言机账户 / Oracle account
-        host_fee_in: Some(dlmm::ID),       // 主机费用输入账户 / Host fee input account
-        event_authority,                   // 事件权限 / Event authority
-        program: dlmm::ID,                 // DLMM程序ID / DLMM program ID
-        memo_program: spl_memo::ID,        // 备忘录程序 / Memo program
-    }
-    .to_account_metas(None);
-
-    // 初始化剩余账户信息和Token 2022相关账户
-    // Initialize remaining accounts info and Token 2022 related accounts
-    let mut remaining_accounts_info = RemainingAccountsInfo { slices: vec![] };
-    let mut remaining_accounts = vec![];
-
-    // 获取可能的Token 2022相关指令数据和账户
-    // Get potential Token 2022 related instruction data and accounts
-    if let Some((slices, transfer_hook_remaining_accounts)) =
-        get_potential_token_2022_related_ix_data_and_accounts(
-            &lb_pair_state,
-            program.rpc(),
-            ActionType::Liquidity,
-        )
-        .await?
-    {
-        remaining_accounts_info.slices = slices;
-        remaining_accounts.extend(transfer_hook_remaining_accounts);
+        args.commitment_for_quote,
+        max_quote_age_slots,
+        |build: &SwapExactOutBuild| build.quote_accounts.context_slot,
+        |lb_pair_state| client.build_swap_exact_out(lb_pair_state, args.clone()),
+    )
+    .await?;
+
+    if dump_accounts {
+        let (main_accounts, remaining_accounts) =
+            swap_ix.accounts.split_at(SWAP_ACCOUNT_LABELS.len());
+        dump_swap_accounts(main_accounts, &remaining_accounts_info, remaining_accounts);
     }
 
-    // 添加bin数组账户到剩余账户列表
-    // Add bin array accounts to remaining accounts list
-    remaining_accounts.extend(
-        bin_array_keys
-            .into_iter()
-            .map(|key| AccountMeta::new(key, false)),
-    );
-
-    // 计算总输入金额（包含手续费）
-    // Calculate total input amount (including fees)
-    let in_amount = quote.amount_in + quote.fee;
-    
-    // 应用100个基点（1%）的滑点保护
-    // Apply 100 basis points (1%) slippage protection
-    let max_in_amount = in_amount * 10100 / BASIS_POINT_MAX as u64;
-
-    // 构建交换指令数据
-    // Build swap instruction data
-    let data = dlmm::client::args::SwapExactOut2 {
-        out_amount: amount_out,     // 精确输出数量 / Exact output amount
-        max_in_amount,              // 最大输入数量 / Maximum input amount
-        remaining_accounts_info,
+    if build_only {
+        return print_instructions(&[swap_ix]);
     }
-    .data();
-
-    // 组合所有必需的账户
-    // Combine all required accounts
-    let accounts = [main_accounts.to_vec(), remaining_accounts].concat();
-
-    // 创建交换指令
-    // Create swap instruction
-    let swap_ix = Instruction {
-        program_id: dlmm::ID,
-        accounts,
-        data,
-    };
 
-    // 设置计算预算限制
-    // Set compute budget limit
-    let compute_budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(1_400_000);
+    let balance_before = if verify {
+        Some(get_token_balance_or_zero(&rpc_client, user_token_out).await?)
+    } else {
+        None
+    };
 
     // 构建并发送交易
     // Build and send transaction
-    let request_builder = program.request();
-    let signature = request_builder
-        .instruction(compute_budget_ix)  // 添加计算预算指令 / Add compute budget instruction
-        .instruction(swap_ix)            // 添加交换指令 / Add swap instruction
-        .send_with_spinner_and_config(transaction_config)
+    let signature = client
+        .send([client.compute_budget_instructions()?, vec![swap_ix]].concat())
         .await;
 
-    println!("Swap. Signature: {:#?}", signature);
+    info!(?signature, "Swap");
 
     signature?;
 
+    if let Some(balance_before) = balance_before {
+        let out_mint_account = if swap_for_y {
+            &quote_accounts.mint_y_account
+        } else {
+            &quote_accounts.mint_x_account
+        };
+        verify_swap_amount_out(
+            &rpc_client,
+            user_token_out,
+            balance_before,
+            amount_out,
+            out_mint_account,
+        )
+        .await?;
+    }
+
     Ok(())
 }
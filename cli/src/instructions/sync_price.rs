@@ -1,5 +1,4 @@
 use crate::*;
-use anchor_spl::token_interface::Mint;
 
 /// 同步价格的参数结构体
 /// Parameters for syncing price
@@ -11,6 +10,31 @@ pub struct SyncPriceParams {
     /// 目标价格
     /// Target price
     pub price: f64,
+    /// 允许跳转的最大bin数量，默认按bin步长折算为约10%的价格变动
+    /// Maximum number of bins the active bin may move. Defaults to the number of bins
+    /// equivalent to a ~10% price move for the pair's bin step.
+    #[clap(long)]
+    pub max_bins: Option<u32>,
+    /// 跳过最大bin数量检查
+    /// Skip the max bins guard
+    #[clap(long)]
+    pub force: bool,
+    /// 报价代币为X代币（反向交易对）。价格以X代币计价的Y代币价格输入/显示
+    /// Quote token is token X (reversed pair). Prices are given / shown as token Y priced in token X
+    #[clap(long)]
+    pub quote_is_x: bool,
+}
+
+/// 默认允许的最大价格变动（百分比）
+/// Default maximum price move (in percent) allowed when `--max-bins` is not given
+const DEFAULT_MAX_PRICE_CHANGE_PCT: f64 = 10.0;
+
+/// 根据bin步长计算默认的最大bin跳转数量
+/// Compute the default max bins for a bin step from `DEFAULT_MAX_PRICE_CHANGE_PCT`
+fn default_max_bins(bin_step: u16) -> u32 {
+    let base = 1.0 + bin_step as f64 / BASIS_POINT_MAX as f64;
+    let max_bins = (1.0 + DEFAULT_MAX_PRICE_CHANGE_PCT / 100.0).ln() / base.ln();
+    (max_bins.floor() as u32).max(1)
 }
 
 /// 执行同步价格指令
@@ -20,7 +44,7 @@ pub struct SyncPriceParams {
 /// * `params` - 同步价格的参数 / Parameters for price synchronization
 /// * `program` - Solana程序引用 / Solana program reference
 /// * `transaction_config` - 交易配置 / Transaction configuration
-/// * `compute_unit_price` - 计算单元价格指令（可选）/ Compute unit price instruction (optional)
+/// * `compute_unit_price` - 计算单元价格设置 / Compute unit price setting
 /// 
 /// # 功能说明 / Functionality
 /// 将流动性交易对的活跃价格同步到指定的目标价格
@@ -29,9 +53,15 @@ pub async fn execute_sync_price<C: Deref<Target = impl Signer> + Clone>(
     params: SyncPriceParams,
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
-    compute_unit_price: Option<Instruction>,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<()> {
-    let SyncPriceParams { lb_pair, price } = params;
+    let SyncPriceParams {
+        lb_pair,
+        price,
+        max_bins,
+        force,
+        quote_is_x,
+    } = params;
 
     let rpc_client = program.rpc();
 
@@ -63,20 +93,54 @@ pub async fn execute_sync_price<C: Deref<Target = impl Signer> + Clone>(
 
     // 反序列化代币铸币数据
     // Deserialize token mint data
-    let token_mint_base = Mint::try_deserialize(&mut token_mint_base_account.data.as_ref())?;
-    let token_mint_quote = Mint::try_deserialize(&mut token_mint_quote_account.data.as_ref())?
+    let mut mint_cache = MintCache::new();
+    mint_cache.insert(lb_pair_state.token_x_mint, token_mint_base_account)?;
+    mint_cache.insert(lb_pair_state.token_y_mint, token_mint_quote_account)?;
+    let token_mint_base = mint_cache.get_cached(&lb_pair_state.token_x_mint)?;
+    let token_mint_quote = mint_cache.get_cached(&lb_pair_state.token_y_mint)?;
 
     // 将每代币价格转换为每单位最小代币价格（考虑小数位数）
     // Convert per-token price to per-lamport price (considering decimals)
-    let price_per_lamport =
-        price_per_token_to_per_lamport(price, token_mint_base.decimals, token_mint_quote.decimals)
-            .context("price_per_token_to_per_lamport overflow")?;
+    let price_per_lamport = price_per_token_to_per_lamport(
+        flip_price_if_quote_is_x(price, quote_is_x),
+        token_mint_base.decimals,
+        token_mint_quote.decimals,
+    )
+    .context("price_per_token_to_per_lamport overflow")?;
 
     // 从价格计算对应的活跃bin ID
     // Calculate corresponding active bin ID from price
     let computed_active_id =
         get_id_from_price(lb_pair_state.bin_step, &price_per_lamport, Rounding::Up)
-            .context("get_id_from_price overflow")?
+            .context("get_id_from_price overflow")?;
+
+    warn_if_bin_id_near_limit(computed_active_id, lb_pair_state.bin_step);
+
+    // 检查跳转的bin数量，防止价格被大幅移动
+    // Guard against moving the active bin (and therefore the price) too far
+    let bins_moved = computed_active_id.abs_diff(lb_pair_state.active_id);
+    // 价格变化按用户输入的价格方向计算
+    // The price change is computed in the direction of the price the user entered
+    let price_ratio = (1.0 + lb_pair_state.bin_step as f64 / BASIS_POINT_MAX as f64)
+        .powi(computed_active_id - lb_pair_state.active_id);
+    let price_change_pct = (flip_price_if_quote_is_x(price_ratio, quote_is_x) - 1.0) * 100.0;
+
+    info!(
+        from_active_id = lb_pair_state.active_id,
+        to_active_id = computed_active_id,
+        bins_moved,
+        price_change_pct,
+        "Sync price"
+    );
+
+    let max_bins = max_bins.unwrap_or_else(|| default_max_bins(lb_pair_state.bin_step));
+    if bins_moved > max_bins && !force {
+        return Err(anyhow!(
+            "Sync price moves the active bin by {} bins, exceeding max bins {}. Use --force to proceed",
+            bins_moved,
+            max_bins
+        ));
+    }
 
     // 构建“跳转到指定bin”指令数据
     // Build "go to a bin" instruction data
@@ -136,13 +200,9 @@ pub async fn execute_sync_price<C: Deref<Target = impl Signer> + Clone>(
 
     // 构建指令列表
     // Build instruction list
-    let mut ixs = vec![];
-
-    // 如果提供了计算单元价格指令，先添加它
-    // Add compute unit price instruction first if provided
-    if let Some(compute_unit_price_ix) = compute_unit_price {
-        ixs.push(compute_unit_price_ix);
-    }
+    // 先添加计算单元价格的计算预算指令
+    // Add the compute budget instructions of the compute unit price first
+    let mut ixs = compute_unit_price.instructions()?;
 
     // 添加价格同步指令
     // Add price sync instruction
@@ -158,9 +218,9 @@ pub async fn execute_sync_price<C: Deref<Target = impl Signer> + Clone>(
     let signature = builder
         .send_with_spinner_and_config(transaction_config)
         .await;
-    println!("{:#?}", signature);
+    info!(?signature, "Sync price");
 
     signature?;
 
-    Ok()
+    Ok(())
 }
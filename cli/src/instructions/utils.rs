@@ -2,20 +2,43 @@
 /// Common utility functions module
 
 use std::collections::HashMap;
+use std::result::Result::Ok;
+use std::sync::Arc;
 
 use crate::*;
 use anchor_client::solana_client::rpc_client::RpcClient as BlockingRpcClient;
 use anchor_client::{
     solana_client::nonblocking::rpc_client::RpcClient, solana_sdk::account::Account,
 };
+use anchor_client::solana_sdk::signature::Signature;
+use anchor_client::ClientError;
+use anchor_lang::{AccountDeserialize, Discriminator};
 use anchor_spl::{
     associated_token::get_associated_token_address_with_program_id,
     token::spl_token,
     token_2022::spl_token_2022::extension::{transfer_hook, StateWithExtensions},
+    token_2022::spl_token_2022::state::Mint as MintState,
+    token_interface::TokenAccount,
 };
+use futures_util::future::join_all;
 use num_integer::Integer;
+use rust_decimal::prelude::{Decimal, FromPrimitive};
+use solana_client::client_error::ClientErrorKind;
+use solana_client::rpc_config::RpcProgramAccountsConfig;
+use solana_client::rpc_custom_error::{
+    JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE, JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED,
+    JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+};
+use solana_client::rpc_request::{RpcError, RpcResponseErrorData};
+use solana_sdk::address_lookup_table::{state::AddressLookupTable, AddressLookupTableAccount};
+use solana_sdk::hash::Hash;
+use solana_sdk::message::{v0, Message, VersionedMessage};
+use solana_sdk::nonce::state::{State as NonceState, Versions as NonceVersions};
+use solana_sdk::packet::PACKET_DATA_SIZE;
 use solana_sdk::program_pack::Pack;
+use solana_sdk::transaction::{Transaction, TransactionError, VersionedTransaction};
 use solana_sdk::sysvar::clock::Clock;
+use solana_sdk::system_instruction::advance_nonce_account;
 use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
 use spl_transfer_hook_interface::offchain::add_extra_account_metas_for_execute;
 
@@ -47,6 +70,761 @@ pub fn position_bin_range_chunks(lower_bin_id: i32, upper_bin_id: i32) -> Vec<(i
     chunked_bin_range
 }
 
+/// 校验流动性对的代币X与代币Y不是同一个铸造地址
+/// Validate that token X and token Y of the pair are not the same mint
+pub fn validate_mint_pair(token_mint_x: Pubkey, token_mint_y: Pubkey) -> Result<()> {
+    ensure!(
+        token_mint_x != token_mint_y,
+        "Token X mint and token Y mint must be different, both are {}",
+        token_mint_x
+    );
+    Ok(())
+}
+
+/// 校验奖励索引在交易对支持的奖励范围内
+/// Validate the reward index is within the rewards supported by the pair
+pub fn validate_reward_index(reward_index: u64) -> Result<usize> {
+    let index = usize::try_from(reward_index).ok().filter(|&index| index < NUM_REWARDS);
+
+    index.with_context(|| {
+        format!(
+            "reward_index {} out of range, pair supports 0..{}",
+            reward_index, NUM_REWARDS
+        )
+    })
+}
+
+/// 获取指定索引已初始化的奖励信息
+/// Get the reward info of the given index, which must be initialized
+pub fn get_initialized_reward_info(
+    lb_pair_state: &LbPair,
+    reward_index: u64,
+) -> Result<RewardInfo> {
+    let reward_info = lb_pair_state.reward_infos[validate_reward_index(reward_index)?];
+
+    ensure!(
+        reward_info.mint != Pubkey::default(),
+        "Reward at reward_index {} is not initialized",
+        reward_index
+    );
+
+    Ok(reward_info)
+}
+
+/// 按鉴别器反序列化 `PositionV2` 仓位账户数据，旧版本 `Position` 仓位需要先迁移
+/// Deserialize `PositionV2` account data by its discriminator. Legacy `Position` accounts must be migrated first
+pub fn deserialize_position_v2(data: &[u8]) -> Result<PositionV2> {
+    let disc = data.get(..8).context("Not a valid position account")?;
+
+    if disc == Position::DISCRIMINATOR {
+        bail!("Legacy position, migrate first with `migrate-position`");
+    }
+
+    ensure!(
+        disc == PositionV2::DISCRIMINATOR,
+        "Not a valid position account"
+    );
+
+    Ok(bytemuck::pod_read_unaligned(&data[8..]))
+}
+
+/// 获取可能不存在的账户。账户不存在时返回 `None`，RPC错误则向上传递，而不是当作账户不存在处理
+/// Get an account which may not exist. Returns `None` when the account does not exist, while RPC errors are
+/// propagated instead of being treated as a missing account
+pub async fn get_optional_account(rpc_client: &RpcClient, key: Pubkey) -> Result<Option<Account>> {
+    let account = rpc_client
+        .get_account_with_commitment(&key, rpc_client.commitment())
+        .await?
+        .value;
+
+    Ok(account)
+}
+
+/// 触发RPC限流（HTTP 429）时重新请求的最大次数
+/// Maximum number of retries when the RPC rate limits the request (HTTP 429)
+const MAX_RATE_LIMIT_RETRIES: u32 = 5;
+
+/// 触发RPC限流后首次重试前的等待时间，之后每次重试翻倍
+/// Delay before the first retry after the RPC rate limited the request, doubled on every retry
+const RATE_LIMIT_INITIAL_BACKOFF_MS: u64 = 500;
+
+/// 限流响应的HTTP状态码
+/// HTTP status code of a rate limited response
+const HTTP_TOO_MANY_REQUESTS: u16 = 429;
+
+/// 判断RPC错误是否由限流导致（HTTP 429）
+/// Check whether the RPC error is caused by rate limiting (HTTP 429)
+pub fn is_rate_limited_error(error: &solana_client::client_error::ClientError) -> bool {
+    match &error.kind {
+        ClientErrorKind::Reqwest(error) => error
+            .status()
+            .is_some_and(|status| status.as_u16() == HTTP_TOO_MANY_REQUESTS),
+        _ => false,
+    }
+}
+
+/// 判断错误是否为暂时性的，可以重试：网络或超时错误、RPC限流、节点落后、区块哈希过期等。
+/// 程序拒绝的交易、签名或参数错误等确定性错误重试也不会成功
+/// Check whether the error is transient and worth retrying: network errors and timeouts, rate limiting, a node
+/// behind, an expired blockhash, etc. Deterministic errors such as a transaction rejected by the program, signing
+/// or argument errors cannot succeed on retry
+pub fn is_retriable(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .find_map(|cause| {
+            if let Some(error) = cause.downcast_ref::<solana_client::client_error::ClientError>() {
+                return Some(is_retriable_client_error(error));
+            }
+
+            match cause.downcast_ref::<ClientError>() {
+                Some(ClientError::IOError(_)) => Some(true),
+                _ => None,
+            }
+        })
+        .unwrap_or(false)
+}
+
+/// 判断RPC客户端错误是否为暂时性的，参见 `is_retriable`
+/// Check whether the RPC client error is transient, see `is_retriable`
+pub fn is_retriable_client_error(error: &solana_client::client_error::ClientError) -> bool {
+    if is_rate_limited_error(error) || is_blockhash_expired_message(&error.to_string()) {
+        return true;
+    }
+
+    if let Some(transaction_error) = error.kind.get_transaction_error() {
+        return matches!(
+            transaction_error,
+            TransactionError::BlockhashNotFound
+                | TransactionError::WouldExceedMaxBlockCostLimit
+                | TransactionError::WouldExceedAccountDataBlockLimit
+                | TransactionError::AccountInUse
+        );
+    }
+
+    match &error.kind {
+        ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_) => true,
+        ClientErrorKind::RpcError(RpcError::RpcRequestError(_)) => true,
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, data, .. }) => {
+            matches!(data, RpcResponseErrorData::NodeUnhealthy { .. })
+                || matches!(
+                    *code,
+                    JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY
+                        | JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE
+                        | JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED
+                )
+        }
+        _ => false,
+    }
+}
+
+/// 获取程序账户，遇到暂时性错误（例如RPC限流）时以指数退避重试。公共RPC通常会对 getProgramAccounts 限流
+/// Get the program accounts, retrying with exponential backoff on transient errors such as rate limiting. Public
+/// RPCs commonly rate limit getProgramAccounts
+pub async fn get_program_accounts_with_retry(
+    rpc_client: &RpcClient,
+    config: RpcProgramAccountsConfig,
+) -> Result<Vec<(Pubkey, Account)>> {
+    let mut attempt = 0;
+
+    loop {
+        match rpc_client
+            .get_program_accounts_with_config(&dlmm::ID, config.clone())
+            .await
+        {
+            Ok(accounts) => return Ok(accounts),
+            Err(error) if attempt < MAX_RATE_LIMIT_RETRIES && is_retriable_client_error(&error) => {
+                let backoff_ms = RATE_LIMIT_INITIAL_BACKOFF_MS << attempt;
+                attempt += 1;
+                warn!(%error, attempt, backoff_ms, "Transient RPC error, retrying");
+                tokio::time::sleep(std::time::Duration::from_millis(backoff_ms)).await;
+            }
+            Err(error) => return Err(error.into()),
+        }
+    }
+}
+
+/// 读取仓位覆盖的bin数组，计算仓位每个bin的待领取手续费明细（手续费增长检查点相对bin当前手续费增长的差值）
+/// Read the bin arrays covered by the position and compute the pending fee breakdown of every bin of the position,
+/// from the fee growth checkpoints of the position against the current fee growth of the bins
+pub async fn get_position_fee_breakdown(
+    rpc_client: &RpcClient,
+    position_state: &PositionV2,
+) -> Result<Vec<BinFeeBreakdown>> {
+    let bin_array_keys = position_state.get_bin_array_keys_coverage()?;
+    let bin_arrays: Vec<BinArray> = rpc_client
+        .get_multiple_accounts(&bin_array_keys)
+        .await?
+        .into_iter()
+        .flatten()
+        .map(|account| bytemuck::pod_read_unaligned(&account.data[8..]))
+        .collect();
+
+    position_state.get_fee_breakdown_by_bin(&bin_arrays)
+}
+
+/// 读取代币账户余额，账户不存在时返回0
+/// Read the balance of the token account. Returns 0 when the account does not exist
+pub async fn get_token_balance_or_zero(
+    rpc_client: &RpcClient,
+    token_account: Pubkey,
+) -> Result<u64> {
+    match get_optional_account(rpc_client, token_account).await? {
+        Some(account) => Ok(TokenAccount::try_deserialize(&mut account.data.as_slice())?.amount),
+        None => Ok(0),
+    }
+}
+
+/// 解析代币数量：提供UI单位数量时按代币铸造账户的小数位数转换为最小单位，否则直接使用最小单位数量
+/// Resolve a token amount. An UI amount is converted to the smallest unit with the decimals of the mint, otherwise
+/// the amount in smallest unit is used as is
+pub async fn resolve_token_amount(
+    rpc_client: &RpcClient,
+    mint: Pubkey,
+    amount: Option<u64>,
+    ui_amount: Option<f64>,
+) -> Result<u64> {
+    let ui_amount = match (amount, ui_amount) {
+        (Some(amount), _) => return Ok(amount),
+        (None, Some(ui_amount)) => ui_amount,
+        (None, None) => bail!("Either the amount or the UI amount is required"),
+    };
+
+    let mint_account = rpc_client.get_account(&mint).await?;
+    let decimals = StateWithExtensions::<MintState>::unpack(&mint_account.data)?
+        .base
+        .decimals;
+    let amount = ui_amount_to_amount(ui_amount, decimals).context("Invalid UI amount")?;
+    ensure!(
+        amount > 0,
+        "UI amount {} is below the smallest unit of mint {} ({} decimals)",
+        ui_amount,
+        mint,
+        decimals
+    );
+
+    info!(ui_amount, amount, decimals, %mint, "Converted UI amount");
+
+    Ok(amount)
+}
+
+/// 读取时钟sysvar获取当前epoch。转账手续费按epoch生效，跨越epoch边界的计算需使用新读取的epoch，而不是之前读取的时钟
+/// Read the clock sysvar for the current epoch. Transfer fees take effect per epoch, so computations that may cross
+/// an epoch boundary need a freshly read epoch instead of a clock read earlier
+pub async fn current_epoch(rpc_client: &RpcClient) -> Result<u64> {
+    let clock_account = rpc_client
+        .get_account(&solana_sdk::sysvar::clock::ID)
+        .await
+        .context("Failed to fetch clock account")?;
+    let clock: Clock = bincode::deserialize(clock_account.data.as_ref())?;
+
+    Ok(clock.epoch)
+}
+
+/// 读取时钟sysvar获取当前的unix时间戳，用于计算自上次更新以来的奖励发放
+/// Read the clock sysvar for the current unix timestamp, used to compute the reward emission since the last update
+pub async fn current_timestamp(rpc_client: &RpcClient) -> Result<u64> {
+    let clock_account = rpc_client
+        .get_account(&solana_sdk::sysvar::clock::ID)
+        .await
+        .context("Failed to fetch clock account")?;
+    let clock: Clock = bincode::deserialize(clock_account.data.as_ref())?;
+
+    Ok(clock.unix_timestamp.try_into()?)
+}
+
+/// 交易确认后重新读取输出代币账户余额，校验实际收到的数量与报价数量的差额不超过报价数量的转账手续费，并打印实际收到的数量。
+/// 用于发现转账钩子或手续费扣除超出预期的情况。交易可能在报价之后的epoch执行，因此转账手续费使用新读取的epoch计算
+///
+/// Re-read the output token account balance after the swap confirmed, verify the received amount is short of the
+/// quoted amount by no more than the transfer fee of the quoted amount, and print the received amount. Catches
+/// transfer hooks or fees taking more than expected. The swap may land in a later epoch than the quote, so the
+/// transfer fee is computed with a freshly read epoch
+pub async fn verify_swap_amount_out(
+    rpc_client: &RpcClient,
+    user_token_out: Pubkey,
+    balance_before: u64,
+    quoted_amount_out: u64,
+    out_mint_account: &Account,
+) -> Result<()> {
+    let balance_after = get_token_balance_or_zero(rpc_client, user_token_out).await?;
+    let received = balance_after.checked_sub(balance_before).with_context(|| {
+        format!(
+            "Balance of {} decreased from {} to {} after the swap",
+            user_token_out, balance_before, balance_after
+        )
+    })?;
+
+    let epoch = current_epoch(rpc_client).await?;
+    let tolerance =
+        calculate_transfer_fee_included_amount(out_mint_account, quoted_amount_out, epoch)?
+            .transfer_fee;
+
+    println!(
+        "Received {} quoted amount out {} tolerance {}",
+        received, quoted_amount_out, tolerance
+    );
+
+    ensure!(
+        received.saturating_add(tolerance) >= quoted_amount_out,
+        "Received {} is below the quoted amount out {} by more than the transfer fee tolerance {}",
+        received,
+        quoted_amount_out,
+        tolerance
+    );
+
+    Ok(())
+}
+
+/// 将活跃bin ID换算回UI价格，打印请求价格与实际初始价格。两者偏差超过 `max_price_deviation_bps` 时返回错误，
+/// 用于发现bin步长舍入或配置错误导致的交易对初始价格偏离
+///
+/// Converts the active bin id back to the UI price and prints the requested and the actual initial price. Returns an
+/// error when they deviate by more than `max_price_deviation_bps`. Catches pairs created at an unintended price
+/// because of the bin step rounding or a misconfiguration
+pub fn check_initial_price(
+    initial_price: f64,
+    active_id: i32,
+    bin_step: u16,
+    base_token_decimal: u8,
+    quote_token_decimal: u8,
+    max_price_deviation_bps: Option<u16>,
+) -> Result<()> {
+    let requested_price = Decimal::from_f64(initial_price)
+        .with_context(|| format!("Invalid initial price {}", initial_price))?;
+    let actual_price =
+        get_ui_price_from_id(bin_step, active_id, base_token_decimal, quote_token_decimal)
+            .context("get_ui_price_from_id overflow")?;
+    let deviation_bps = price_deviation_bps(requested_price, actual_price)
+        .context("Initial price must not be zero")?;
+
+    warn_if_bin_id_near_limit(active_id, bin_step);
+
+    println!(
+        "requested price {}, actual initial price {} (bin id {}), deviation {} bps",
+        requested_price,
+        actual_price,
+        active_id,
+        deviation_bps.round_dp(4)
+    );
+
+    if let Some(max_price_deviation_bps) = max_price_deviation_bps {
+        ensure!(
+            deviation_bps <= Decimal::from(max_price_deviation_bps),
+            "Actual initial price {} deviates from the requested price {} by {} bps, more than {} bps",
+            actual_price,
+            requested_price,
+            deviation_bps.round_dp(4),
+            max_price_deviation_bps
+        );
+    }
+
+    Ok(())
+}
+
+/// 可自定义无许可交易对的基础手续费率，与链上 `base_factor * bin_step * 10 * 10^base_fee_power_factor` 相同
+/// Base fee rate of a customizable permissionless pair, same as the on-chain
+/// `base_factor * bin_step * 10 * 10^base_fee_power_factor`
+pub fn get_customizable_base_fee_rate(params: &CustomizableParams) -> Result<u128> {
+    u128::from(params.base_factor)
+        .checked_mul(params.bin_step.into())
+        .and_then(|fee_rate| fee_rate.checked_mul(10))
+        .and_then(|fee_rate| {
+            fee_rate.checked_mul(10u128.checked_pow(params.base_fee_power_factor.into())?)
+        })
+        .context("Base fee rate overflow")
+}
+
+/// 校验可自定义无许可交易对的参数在程序接受的范围内：bin步长、基础手续费率、激活类型及激活点。
+/// 交易对创建后无法修改这些参数，因此在发送前校验
+///
+/// Validates the parameters of a customizable permissionless pair are within the ranges accepted by the program:
+/// bin step, base fee rate, activation type and activation point. They can't be changed once the pair is created,
+/// so they are checked before sending
+pub fn validate_customizable_params(params: &CustomizableParams, clock: &Clock) -> Result<()> {
+    ensure!(
+        (1..=MAX_BIN_STEP).contains(&params.bin_step),
+        "Bin step {} must be between 1 and {}",
+        params.bin_step,
+        MAX_BIN_STEP
+    );
+
+    let base_fee_rate = get_customizable_base_fee_rate(params)?;
+    ensure!(
+        (MIN_BASE_FEE..=MAX_BASE_FEE).contains(&base_fee_rate),
+        "Base fee {}% must be between {}% and {}%",
+        fee_rate_to_fee_pct(base_fee_rate)?,
+        fee_rate_to_fee_pct(MIN_BASE_FEE)?,
+        fee_rate_to_fee_pct(MAX_BASE_FEE)?
+    );
+
+    let activation_type = ActivationType::try_from(params.activation_type)?;
+    let (current_point, max_activation_duration) = match activation_type {
+        ActivationType::Slot => (clock.slot, MAX_ACTIVATION_SLOT_DURATION),
+        ActivationType::Timestamp => (
+            u64::try_from(clock.unix_timestamp)?,
+            MAX_ACTIVATION_TIME_DURATION,
+        ),
+    };
+
+    match params.activation_point {
+        Some(activation_point) => {
+            ensure!(
+                activation_point > current_point,
+                "Activation point {} is not after the current {:?} point {}",
+                activation_point,
+                activation_type,
+                current_point
+            );
+            ensure!(
+                activation_point - current_point <= max_activation_duration,
+                "Activation point {} is more than {} after the current {:?} point {}",
+                activation_point,
+                max_activation_duration,
+                activation_type,
+                current_point
+            );
+        }
+        None => ensure!(
+            !params.has_alpha_vault,
+            "A pair with an alpha vault requires an activation point, so the vault can buy before trading starts"
+        ),
+    }
+
+    Ok(())
+}
+
+/// 发送前打印可自定义无许可交易对的配置摘要
+/// Prints the configuration summary of a customizable permissionless pair before sending
+pub fn print_customizable_pair_summary(lb_pair: Pubkey, params: &CustomizableParams) -> Result<()> {
+    let activation_type = ActivationType::try_from(params.activation_type)?;
+    let activation_point = params
+        .activation_point
+        .map(|activation_point| activation_point.to_string())
+        .unwrap_or_else(|| "now".to_string());
+
+    println!("Pair {}", lb_pair);
+    println!(
+        "Bin step {} active id {}",
+        params.bin_step, params.active_id
+    );
+    println!(
+        "Base fee {}% (base factor {}, power factor {})",
+        fee_rate_to_fee_pct(get_customizable_base_fee_rate(params)?)?,
+        params.base_factor,
+        params.base_fee_power_factor
+    );
+    println!(
+        "Activation {:?} at {}, alpha vault {}, creator pool on/off control {}",
+        activation_type,
+        activation_point,
+        params.has_alpha_vault,
+        params.creator_pool_on_off_control
+    );
+
+    Ok(())
+}
+
+/// 由价格计算的bin ID接近可表示的极限时发出警告，这几乎总是代币小数位数或价格输入错误
+/// Warns when the bin id computed from a price is near the representable limit, which is almost always a token
+/// decimals or price mistake
+pub fn warn_if_bin_id_near_limit(bin_id: i32, bin_step: u16) {
+    if is_bin_id_near_limit(bin_id, bin_step) {
+        warn!(
+            bin_id,
+            bin_step,
+            "Computed bin id is near the representable limit, double-check the token decimals and the price"
+        );
+    }
+}
+
+/// 由报价的输入及输出数量计算成交价格（以Y代币计价的X代币UI价格），劣于限价时返回错误，用于限价式的交换
+/// Computes the execution price (UI price of token X in token Y) from the quoted amounts in and out, and returns an
+/// error when it is worse than the price limit. Used for limit-order-style swaps
+pub fn ensure_swap_price_within_limit(
+    amount_in: u64,
+    amount_out: u64,
+    swap_for_y: bool,
+    mint_x_account: &Account,
+    mint_y_account: &Account,
+    price_limit: f64,
+) -> Result<()> {
+    let decimals_x = StateWithExtensions::<MintState>::unpack(&mint_x_account.data)?
+        .base
+        .decimals;
+    let decimals_y = StateWithExtensions::<MintState>::unpack(&mint_y_account.data)?
+        .base
+        .decimals;
+
+    let execution_price =
+        get_execution_price(amount_in, amount_out, swap_for_y, decimals_x, decimals_y)
+            .context("Failed to compute the execution price")?;
+
+    println!(
+        "Execution price {} price limit {}",
+        execution_price, price_limit
+    );
+
+    ensure_price_within_limit(execution_price, price_limit, swap_for_y)
+}
+
+/// 从JSON或CSV文件读取流动性分配，每行与 `--bin-liquidity-distribution` 的一项相同，均由
+/// [`parse_bin_liquidity_distribution`] 校验。JSON文件为 `[[BIN_ID, DIST_X, DIST_Y], ...]` 数组；
+/// CSV文件每行一项 `BIN_ID,DIST_X,DIST_Y`，忽略空行、`#` 开头的注释行及可选的表头行
+///
+/// Read a liquidity distribution from a JSON or CSV file, each row being one entry of `--bin-liquidity-distribution`
+/// validated by [`parse_bin_liquidity_distribution`]. A JSON file is an array `[[BIN_ID, DIST_X, DIST_Y], ...]`. A
+/// CSV file has one `BIN_ID,DIST_X,DIST_Y` entry per line, blank lines, lines starting with `#` and an optional
+/// header line are skipped
+pub fn load_bin_liquidity_distribution_file(path: &str) -> Result<Vec<(i32, f64, f64)>> {
+    let path = shellexpand::tilde(path).to_string();
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read distribution file {}", path))?;
+
+    let extension = std::path::Path::new(&path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase());
+
+    let invalid_row = |row: usize, error: String| {
+        anyhow!(
+            "Invalid row {} in distribution file {}: {}",
+            row,
+            path,
+            error
+        )
+    };
+
+    match extension.as_deref() {
+        Some("json") => {
+            let rows = serde_json::from_str::<Vec<Vec<serde_json::Value>>>(&content)
+                .with_context(|| format!("Failed to parse distribution file {}", path))?;
+
+            rows.iter()
+                .enumerate()
+                .map(|(index, row)| {
+                    let row = row
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(",");
+                    parse_bin_liquidity_distribution(&row)
+                        .map_err(|error| invalid_row(index + 1, error))
+                })
+                .collect()
+        }
+        Some("csv") => {
+            let mut distribution = vec![];
+            let mut header_allowed = true;
+
+            for (index, line) in content.lines().enumerate() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+
+                let entry = parse_bin_liquidity_distribution(line);
+
+                // 第一行不含数字且无法解析时视为表头
+                // The first row is treated as a header when it has no digit and cannot be parsed
+                let is_header = header_allowed && !line.chars().any(|c| c.is_ascii_digit());
+                header_allowed = false;
+
+                match entry {
+                    Ok(entry) => distribution.push(entry),
+                    Err(_) if is_header => continue,
+                    Err(error) => return Err(invalid_row(index + 1, error)),
+                }
+            }
+
+            Ok(distribution)
+        }
+        _ => bail!(
+            "Distribution file {} must have a .json or .csv extension",
+            path
+        ),
+    }
+}
+
+/// 账户存在时返回其地址，不存在时返回 `None`，用于可选账户（如bin数组位图扩展）以程序ID占位的情况
+/// Returns the account address when it exists and `None` otherwise. Used for optional accounts such as the
+/// bin array bitmap extension, which fall back to the program id as placeholder
+pub async fn resolve_optional_account(
+    rpc_client: &RpcClient,
+    key: Pubkey,
+) -> Result<Option<Pubkey>> {
+    Ok(get_optional_account(rpc_client, key).await?.map(|_| key))
+}
+
+/// 读取并打印交易对的当前状态及目标状态，便于操作者确认状态变更的方向。交易对的状态字节未定义时返回错误
+/// Read and print the current and the target status of the pair, so the operator can confirm the direction of
+/// the change. Returns an error when the status byte of the pair is undefined
+pub async fn print_pair_status_change(
+    rpc_client: &RpcClient,
+    lb_pair: Pubkey,
+    status: PairStatusArg,
+) -> Result<()> {
+    let lb_pair_state: LbPair = rpc_client
+        .get_account_and_deserialize(&lb_pair, |account| {
+            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
+        })
+        .await?;
+
+    let current_status = PairStatusArg::from(lb_pair_state.status().with_context(|| {
+        format!(
+            "Pair {} has an undefined status {}",
+            lb_pair, lb_pair_state.status
+        )
+    })?);
+
+    println!(
+        "Pair {} status {:?} -> {:?}",
+        lb_pair, current_status, status
+    );
+
+    if current_status == status {
+        warn!(%lb_pair, ?status, "Pair already has the target status");
+    }
+
+    Ok(())
+}
+
+/// 获取活跃bin所在的bin数组地址。bin数组尚未初始化时（例如新创建的交易对）一并返回由 `funder` 支付租金的初始化指令，
+/// 需放在使用该bin数组的指令之前
+///
+/// Get the bin array of the active bin. When the bin array is not initialized yet, e.g. on a fresh pair, the
+/// instruction initializing it with `funder` paying the rent is returned as well, to be placed before the
+/// instruction using the bin array
+pub async fn get_active_bin_array_with_init_ix(
+    rpc_client: &RpcClient,
+    lb_pair: Pubkey,
+    lb_pair_state: &LbPair,
+    funder: Pubkey,
+) -> Result<(Pubkey, Option<Instruction>)> {
+    let active_bin_array_idx = BinArray::bin_id_to_bin_array_index(lb_pair_state.active_id)?;
+    let (bin_array, _bump) = derive_bin_array_pda(lb_pair, active_bin_array_idx.into());
+
+    if resolve_optional_account(rpc_client, bin_array)
+        .await?
+        .is_some()
+    {
+        return Ok((bin_array, None));
+    }
+
+    info!(
+        %bin_array,
+        index = active_bin_array_idx,
+        "Active bin array is not initialized, initializing it"
+    );
+
+    let accounts = dlmm::client::accounts::InitializeBinArray {
+        bin_array,
+        lb_pair,
+        funder,
+        system_program: solana_sdk::system_program::ID,
+    }
+    .to_account_metas(None);
+
+    let data = dlmm::client::args::InitializeBinArray {
+        index: active_bin_array_idx.into(),
+    }
+    .data();
+
+    let init_bin_array_ix = Instruction {
+        program_id: dlmm::ID,
+        accounts,
+        data,
+    };
+
+    Ok((bin_array, Some(init_bin_array_ix)))
+}
+
+/// 检查索引范围内的bin数组均已初始化。缺失的bin数组只会让程序返回难以理解的错误，因此提前返回列出缺失索引的错误
+/// Check the bin arrays of the index range are all initialized. A missing bin array only yields an opaque program
+/// error, so an error listing the missing indexes is returned up front
+pub async fn ensure_bin_arrays_initialized(
+    rpc_client: &RpcClient,
+    lb_pair: Pubkey,
+    lower_bin_array_index: i32,
+    upper_bin_array_index: i32,
+) -> Result<()> {
+    let bin_array_indexes = (lower_bin_array_index..=upper_bin_array_index).collect::<Vec<_>>();
+    let bin_array_keys = bin_array_indexes
+        .iter()
+        .map(|&index| derive_bin_array_pda(lb_pair, index.into()).0)
+        .collect::<Vec<_>>();
+
+    let accounts = rpc_client
+        .get_multiple_accounts_chunked(&bin_array_keys)
+        .await?;
+
+    let missing_indexes = bin_array_indexes
+        .iter()
+        .zip(accounts)
+        .filter(|(_, account)| account.is_none())
+        .map(|(index, _)| index.to_string())
+        .collect::<Vec<_>>();
+
+    ensure!(
+        missing_indexes.is_empty(),
+        "bin arrays {} not initialized, run initialize-bin-array-with-bin-range",
+        missing_indexes.join(",")
+    );
+
+    Ok(())
+}
+
+/// 确定交易方向：提供输入或输出代币铸造地址时按交易对的X/Y代币推导，否则使用 `swap_for_y`
+/// Determine the swap direction. When the input or output mint is provided it is derived by matching against
+/// the token X / Y mint of the pair, otherwise `swap_for_y` is used
+pub fn resolve_swap_for_y(
+    lb_pair_state: &LbPair,
+    swap_for_y: bool,
+    input_mint: Option<Pubkey>,
+    output_mint: Option<Pubkey>,
+) -> Result<bool> {
+    let token_x_mint = lb_pair_state.token_x_mint;
+    let token_y_mint = lb_pair_state.token_y_mint;
+
+    let swap_for_y_from_input = input_mint
+        .map(|mint| {
+            ensure!(
+                mint == token_x_mint || mint == token_y_mint,
+                "Input mint {} does not belong to the pair, token X {} token Y {}",
+                mint,
+                token_x_mint,
+                token_y_mint
+            );
+            Ok(mint == token_x_mint)
+        })
+        .transpose()?;
+
+    let swap_for_y_from_output = output_mint
+        .map(|mint| {
+            ensure!(
+                mint == token_x_mint || mint == token_y_mint,
+                "Output mint {} does not belong to the pair, token X {} token Y {}",
+                mint,
+                token_x_mint,
+                token_y_mint
+            );
+            Ok(mint == token_y_mint)
+        })
+        .transpose()?;
+
+    match (swap_for_y_from_input, swap_for_y_from_output) {
+        (Some(from_input), Some(from_output)) => {
+            ensure!(
+                from_input == from_output,
+                "Input mint and output mint must be different tokens of the pair"
+            );
+            Ok(from_input)
+        }
+        (Some(from_input), None) => Ok(from_input),
+        (None, Some(from_output)) => Ok(from_output),
+        (None, None) => Ok(swap_for_y),
+    }
+}
+
 /// 获取代币转账指令（支持SPL Token和Token-2022）
 /// Get token transfer instruction (supports SPL Token and Token-2022)
 #[allow(dead_code)]
@@ -123,47 +901,648 @@ pub async fn get_transfer_instruction(
     }
 }
 
+/// 创建交易请求，先添加计算单元价格的计算预算指令
+/// Create a transaction request, with the compute budget instructions of the compute unit price first
+pub fn request_with_compute_unit_price<C: Deref<Target = impl Signer> + Clone>(
+    program: &Program<C>,
+    compute_unit_price: ComputeUnitPrice,
+) -> Result<RequestBuilder<'_, C, Arc<dyn ThreadSafeSigner>>> {
+    let request_builder = compute_unit_price
+        .instructions()?
+        .into_iter()
+        .fold(program.request(), |builder, ix| builder.instruction(ix));
+
+    Ok(request_builder)
+}
+
+/// 创建设置计算单元上限的交易请求，先添加计算单元上限及计算单元价格的计算预算指令
+/// Create a transaction request setting the compute unit limit, with the compute budget instructions of the
+/// compute unit limit and price first
+pub fn request_with_compute_unit_limit<C: Deref<Target = impl Signer> + Clone>(
+    program: &Program<C>,
+    compute_unit_price: ComputeUnitPrice,
+    compute_unit_limit: u32,
+) -> Result<RequestBuilder<'_, C, Arc<dyn ThreadSafeSigner>>> {
+    let request_builder = compute_unit_price
+        .instructions_with_limit(Some(compute_unit_limit))?
+        .into_iter()
+        .fold(program.request(), |builder, ix| builder.instruction(ix));
+
+    Ok(request_builder)
+}
+
+/// 获取或创建 `owner` 的关联代币账户，账户租金由程序客户端的支付者支付
+/// Get or create the associated token account of `owner`, the account rent is funded by the payer of the program
+/// client
 pub async fn get_or_create_ata<C: Deref<Target = impl Signer> + Clone>(
     program: &Program<C>,
     transaction_config: RpcSendTransactionConfig,
     token_mint: Pubkey,
-    wallet_address: Pubkey,
-    compute_unit_price: Option<Instruction>,
+    owner: Pubkey,
+    compute_unit_price: ComputeUnitPrice,
 ) -> Result<Pubkey> {
+    let payer = program.payer();
     let rpc_client = program.rpc();
     let token_mint_owner = rpc_client.get_account(&token_mint).await?.owner;
 
-    let user_ata = get_associated_token_address_with_program_id(
-        &wallet_address,
-        &token_mint,
-        &token_mint_owner,
-    );
-    let user_ata_exists = rpc_client.get_account(&user_ata).await.is_ok();
+    let user_ata =
+        get_associated_token_address_with_program_id(&owner, &token_mint, &token_mint_owner);
+    let user_ata_exists = get_optional_account(&rpc_client, user_ata).await?.is_some();
 
     if !user_ata_exists {
-        let mut builder = program.request();
+        let builder = request_with_compute_unit_price(program, compute_unit_price)?.instruction(
+            create_associated_token_account_idempotent(
+                &payer,
+                &owner,
+                &token_mint,
+                &token_mint_owner,
+            ),
+        );
 
-        if let Some(compute_unit_price) = compute_unit_price {
-            builder = builder.instruction(compute_unit_price);
+        // 并发创建时关联账户可能在检查之后出现，或交易已落地但确认超时。创建指令是幂等的，发送失败时重新检查账户，
+        // 账户已存在则直接返回地址
+        // The account may appear between the check and the creation when another invocation creates it
+        // concurrently, or the transaction may land while its confirmation times out. The creation is idempotent,
+        // so when sending fails the account is checked again and its address returned if it exists
+        if let Err(error) = builder
+            .send_with_spinner_and_config(transaction_config)
+            .await
+        {
+            if get_optional_account(&rpc_client, user_ata).await?.is_none() {
+                return Err(error.into());
+            }
+            warn!(%error, %user_ata, "Associated token account creation failed but the account exists");
         }
+    }
 
-        builder = builder.instruction(create_associated_token_account_idempotent(
-            &program.payer(),
-            &wallet_address,
-            &token_mint,
-            &token_mint_owner,
-        ));
+    Ok(user_ata)
+}
+
+/// 推导 `owner` 的关联代币账户，并构建幂等创建该账户的指令，用于只构建不发送交易的场景
+/// Derive the associated token account of `owner` and build the instruction creating it idempotently, for
+/// instructions built without sending any transaction
+pub async fn get_ata_with_create_ix(
+    rpc_client: &RpcClient,
+    token_mint: Pubkey,
+    payer: Pubkey,
+    owner: Pubkey,
+) -> Result<(Pubkey, Instruction)> {
+    let token_mint_owner = rpc_client.get_account(&token_mint).await?.owner;
+
+    let ata = get_associated_token_address_with_program_id(&owner, &token_mint, &token_mint_owner);
+    let create_ata_ix =
+        create_associated_token_account_idempotent(&payer, &owner, &token_mint, &token_mint_owner);
+
+    Ok((ata, create_ata_ix))
+}
+
+/// 以JSON打印指令（程序ID、账户及base58编码的数据），用于将指令组合到其他交易中
+/// Print the instructions as JSON (program id, accounts and base58 encoded data) to compose them into other
+/// transactions
+pub fn print_instructions(instructions: &[Instruction]) -> Result<()> {
+    let instructions = instructions
+        .iter()
+        .map(|ix| {
+            let accounts = ix
+                .accounts
+                .iter()
+                .map(|account| {
+                    serde_json::json!({
+                        "pubkey": account.pubkey.to_string(),
+                        "is_signer": account.is_signer,
+                        "is_writable": account.is_writable,
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            serde_json::json!({
+                "program_id": ix.program_id.to_string(),
+                "accounts": accounts,
+                "data": bs58::encode(&ix.data).into_string(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    println!("{}", serde_json::to_string_pretty(&instructions)?);
+
+    Ok(())
+}
+
+/// 构建将SOL包装为WSOL的指令：向所有者的WSOL关联账户转入SOL并同步余额，关联账户需已存在
+/// Build the instructions wrapping SOL into WSOL: transfer SOL to the owner WSOL associated token account
+/// and sync its balance. The associated token account must already exist
+pub fn wrap_sol_instructions(owner: Pubkey, amount: u64) -> Result<Vec<Instruction>> {
+    let wsol_account = get_associated_token_address_with_program_id(
+        &owner,
+        &spl_token::native_mint::ID,
+        &spl_token::ID,
+    );
+
+    Ok(vec![
+        solana_sdk::system_instruction::transfer(&owner, &wsol_account, amount),
+        spl_token::instruction::sync_native(&spl_token::ID, &wsol_account)?,
+    ])
+}
+
+/// 构建关闭所有者的WSOL关联账户的指令，剩余的WSOL解包为SOL返还给所有者
+/// Build the instruction closing the owner WSOL associated token account, unwrapping the leftover back to SOL
+pub fn unwrap_sol_instruction(owner: Pubkey) -> Result<Instruction> {
+    let wsol_account = get_associated_token_address_with_program_id(
+        &owner,
+        &spl_token::native_mint::ID,
+        &spl_token::ID,
+    );
+
+    Ok(spl_token::instruction::close_account(
+        &spl_token::ID,
+        &wsol_account,
+        &owner,
+        &owner,
+        &[],
+    )?)
+}
+
+/// 判断命令结束时是否解包所有者的WSOL关联账户：代币为WSOL、所有者为交易支付者（关闭账户需要所有者签名），
+/// 且账户在命令执行前不存在。已存在的账户可能有原有余额，保持不变
+/// Check whether the owner WSOL associated token account should be unwrapped at the end of the command: the token
+/// is WSOL, the owner is the transaction payer (closing the account needs the owner signature) and the account did
+/// not exist before the command. A pre-existing account may hold a prior balance and is left as is
+pub async fn should_unwrap_sol(
+    rpc_client: &RpcClient,
+    token_mint: Pubkey,
+    owner: Pubkey,
+    payer: Pubkey,
+) -> Result<bool> {
+    if token_mint != spl_token::native_mint::ID {
+        return Ok(false);
+    }
+
+    if owner != payer {
+        warn!(%owner, "WSOL is received by another owner, it cannot be unwrapped");
+        return Ok(false);
+    }
+
+    let wsol_account = get_associated_token_address_with_program_id(
+        &owner,
+        &spl_token::native_mint::ID,
+        &spl_token::ID,
+    );
+    if get_optional_account(rpc_client, wsol_account)
+        .await?
+        .is_some()
+    {
+        info!(%wsol_account, "WSOL account already exists, it is not unwrapped");
+        return Ok(false);
+    }
+
+    Ok(true)
+}
+
+/// 检查指令能否放入单笔传统交易
+/// Check whether the instructions fit in a single legacy transaction
+pub fn fits_in_single_transaction(instructions: &[Instruction], payer: &Pubkey) -> bool {
+    let transaction = Transaction::new_unsigned(Message::new(instructions, Some(payer)));
 
-        builder
+    bincode::serialized_size(&transaction)
+        .map(|size| size as usize <= PACKET_DATA_SIZE)
+        .unwrap_or(false)
+}
+
+/// Swap2 / SwapExactOut2 / SwapWithPriceImpact2 指令主要账户的名称，按账户顺序排列
+/// Names of the main accounts of the Swap2 / SwapExactOut2 / SwapWithPriceImpact2 instructions, in order
+pub const SWAP_ACCOUNT_LABELS: [&str; 16] = [
+    "lb_pair",
+    "bin_array_bitmap_extension",
+    "reserve_x",
+    "reserve_y",
+    "user_token_in",
+    "user_token_out",
+    "token_x_mint",
+    "token_y_mint",
+    "oracle",
+    "host_fee_in",
+    "user",
+    "token_x_program",
+    "token_y_program",
+    "memo_program",
+    "event_authority",
+    "program",
+];
+
+/// 打印交换指令的完整有序账户列表，剩余账户按转账钩子切片及bin数组标注
+/// Print the full ordered account list of a swap instruction. Remaining accounts are labelled by the
+/// transfer hook slices, followed by the bin arrays
+pub fn dump_swap_accounts(
+    main_accounts: &[AccountMeta],
+    remaining_accounts_info: &RemainingAccountsInfo,
+    remaining_accounts: &[AccountMeta],
+) {
+    let mut labels = main_accounts
+        .iter()
+        .enumerate()
+        .map(|(i, _)| SWAP_ACCOUNT_LABELS.get(i).copied().unwrap_or("main").to_string())
+        .collect::<Vec<_>>();
+
+    for slice in remaining_accounts_info.slices.iter() {
+        let label = match slice.accounts_type {
+            AccountsType::TransferHookX => "transfer_hook_x".to_string(),
+            AccountsType::TransferHookY => "transfer_hook_y".to_string(),
+            AccountsType::TransferHookReward => "transfer_hook_reward".to_string(),
+            AccountsType::TransferHookMultiReward(index) => {
+                format!("transfer_hook_multi_reward_{}", index)
+            }
+        };
+        labels.extend(std::iter::repeat(label).take(slice.length.into()));
+    }
+
+    println!("Index | Label | Pubkey | Signer | Writable");
+    for (i, account) in main_accounts
+        .iter()
+        .chain(remaining_accounts.iter())
+        .enumerate()
+    {
+        let label = labels.get(i).map(String::as_str).unwrap_or("bin_array");
+        println!(
+            "{} | {} | {} | {} | {}",
+            i, label, account.pubkey, account.is_signer, account.is_writable
+        );
+    }
+}
+
+/// 由种子字符串派生头寸基础密钥对时使用的域分隔前缀
+/// Domain separation prefix used when deriving the position base keypair from a seed string
+const POSITION_BASE_SEED_PREFIX: &[u8] = b"dlmm-position-base";
+
+/// 由种子字符串确定性地派生头寸基础密钥对：ed25519密钥对的32字节私钥种子为 sha256("dlmm-position-base" || seed)
+/// 相同的种子总是得到相同的基础公钥
+///
+/// Deterministically derive the position base keypair from a seed string. The 32 bytes ed25519 secret seed is
+/// sha256("dlmm-position-base" || seed), so the same seed always yields the same base pubkey
+pub fn derive_position_base_keypair_from_seed(seed: &str) -> Result<Keypair> {
+    let secret_seed = solana_sdk::hash::hashv(&[POSITION_BASE_SEED_PREFIX, seed.as_bytes()]);
+
+    keypair_from_seed(secret_seed.as_ref())
+        .map_err(|e| anyhow!("Failed to derive position base keypair: {}", e))
+}
+
+/// 从密钥对文件或种子字符串获取头寸基础密钥对，并在提供基础公钥时校验是否匹配
+/// Get the position base keypair from a keypair file or a seed string, validating it against the base pubkey
+/// when provided
+pub fn resolve_position_base_keypair(
+    base_position_path: Option<String>,
+    base_seed: Option<String>,
+    base_pubkey: Option<Pubkey>,
+) -> Result<Keypair> {
+    let position_base_kp = match (base_position_path, base_seed) {
+        (Some(base_position_path), None) => read_keypair_file(&base_position_path).map_err(|e| {
+            anyhow!(
+                "Failed to read position base keypair file {}: {}",
+                base_position_path,
+                e
+            )
+        })?,
+        (None, Some(base_seed)) => derive_position_base_keypair_from_seed(&base_seed)?,
+        _ => bail!("Exactly one of --base-position-path and --base-seed must be provided"),
+    };
+
+    if let Some(base_pubkey) = base_pubkey {
+        ensure!(
+            position_base_kp.pubkey() == base_pubkey,
+            "Position base key mismatch. Expected {} but got {}",
+            base_pubkey,
+            position_base_kp.pubkey()
+        );
+    }
+
+    Ok(position_base_kp)
+}
+
+/// 解析流动性对的代币程序，优先使用显式指定的代币程序
+/// Resolve the token programs of the pair, preferring the explicitly provided token programs
+pub fn resolve_token_programs(
+    lb_pair_state: &LbPair,
+    token_x_program: Option<Pubkey>,
+    token_y_program: Option<Pubkey>,
+) -> Result<[Pubkey; 2]> {
+    if let (Some(token_x_program), Some(token_y_program)) = (token_x_program, token_y_program) {
+        return Ok([token_x_program, token_y_program]);
+    }
+
+    let [pair_token_x_program, pair_token_y_program] = lb_pair_state.get_token_programs()?;
+
+    Ok([
+        token_x_program.unwrap_or(pair_token_x_program),
+        token_y_program.unwrap_or(pair_token_y_program),
+    ])
+}
+
+/// 获取流动性操作的转账钩子剩余账户。SPL Token代币不支持转账钩子，跳过其代币账户的RPC查询
+/// Get the transfer hook remaining accounts of a liquidity action. SPL Token mints do not support transfer
+/// hooks, so their mint accounts are not fetched
+pub async fn get_liquidity_transfer_hook_accounts(
+    lb_pair_state: &LbPair,
+    rpc_client: RpcClient,
+    token_programs: [Pubkey; 2],
+) -> Result<Option<(Vec<RemainingAccountsSlice>, Vec<AccountMeta>)>> {
+    let [token_x_program, token_y_program] = token_programs;
+
+    let mut slices = vec![];
+    let mut accounts = vec![];
+
+    for (mint, token_program, accounts_type) in [
+        (
+            lb_pair_state.token_x_mint,
+            token_x_program,
+            AccountsType::TransferHookX,
+        ),
+        (
+            lb_pair_state.token_y_mint,
+            token_y_program,
+            AccountsType::TransferHookY,
+        ),
+    ] {
+        if token_program == spl_token::ID {
+            continue;
+        }
+
+        let extra_account_metas =
+            get_extra_account_metas_for_transfer_hook(mint, RpcClient::new(rpc_client.url()))
+                .await?;
+
+        if !extra_account_metas.is_empty() {
+            slices.push(RemainingAccountsSlice {
+                accounts_type,
+                length: extra_account_metas.len() as u8,
+            });
+
+            accounts.extend(extra_account_metas);
+        }
+    }
+
+    if slices.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some((slices, accounts)))
+    }
+}
+
+/// 区块哈希过期时重新发送交易的最大次数
+/// Maximum number of resends when the transaction blockhash expired
+const MAX_BLOCKHASH_REFRESH_RETRIES: usize = 3;
+
+/// 判断发送错误是否由区块哈希过期导致，此时交易已无法上链，可以安全地使用新的区块哈希重新发送
+/// Check whether the send error is caused by an expired blockhash. The transaction can no longer land,
+/// so it is safe to resend it with a fresh blockhash
+pub fn is_blockhash_expired_error(error: &ClientError) -> bool {
+    is_blockhash_expired_message(&error.to_string())
+}
+
+fn is_blockhash_expired_message(error: &str) -> bool {
+    error.contains("BlockhashNotFound")
+        || error.contains("Blockhash not found")
+        || error.contains("block height exceeded")
+        || error.contains("unable to confirm transaction")
+}
+
+/// 发送交易，区块哈希过期时使用新的区块哈希重新发送。每次发送都会获取最新的区块哈希。
+/// 其他暂时性错误（例如超时）不会重新发送，因为交易可能已经上链
+/// Send the transaction, resending it with a fresh blockhash when the blockhash expired.
+/// Every send fetches the latest blockhash. Other transient errors such as timeouts are not resent, since the
+/// transaction may have landed
+pub async fn send_with_blockhash_refresh<C: Deref<Target = impl Signer> + Clone>(
+    program: &Program<C>,
+    instructions: &[Instruction],
+    transaction_config: RpcSendTransactionConfig,
+) -> Result<Signature> {
+    let mut attempt = 0;
+
+    loop {
+        let result = instructions
+            .iter()
+            .fold(program.request(), |builder, ix| builder.instruction(ix.clone()))
             .send_with_spinner_and_config(transaction_config)
-            .await?;
+            .await;
+
+        match result {
+            Ok(signature) => return Ok(signature),
+            Err(error)
+                if attempt < MAX_BLOCKHASH_REFRESH_RETRIES && is_blockhash_expired_error(&error) =>
+            {
+                attempt += 1;
+                warn!(%error, attempt, "Blockhash expired, resending with a fresh blockhash");
+            }
+            Err(error) => return Err(error.into()),
+        }
     }
+}
 
-    Ok(user_ata)
+/// 并发发送多个交易，每个交易独立获取区块哈希并在过期时重新发送。按顺序返回每个交易的发送结果，便于恢复时跳过已成功的交易
+/// Send the transactions concurrently. Each transaction fetches its own blockhash and is resent when it expired.
+/// The result of every transaction is returned in order so a resume can skip the succeeded ones
+pub async fn send_bundles_with_blockhash_refresh<C: Deref<Target = impl Signer> + Clone>(
+    program: &Program<C>,
+    bundles: &[Vec<Instruction>],
+    transaction_config: RpcSendTransactionConfig,
+) -> Vec<Result<Signature>> {
+    join_all(
+        bundles
+            .iter()
+            .map(|ixs| send_with_blockhash_refresh(program, ixs, transaction_config)),
+    )
+    .await
+}
+
+/// 单笔交易默认允许锁定的最大账户数量
+/// Default maximum number of accounts a single transaction can lock
+pub const MAX_ACCOUNTS_PER_TRANSACTION: usize = 64;
+
+/// 领取手续费或单个奖励的指令预估的计算单元
+/// Estimated compute units of an instruction claiming the fee or a single reward
+pub const CLAIM_COMPUTE_UNITS: u32 = 200_000;
+
+/// 每条指令（组）的预估计算单元下，单笔交易最多能容纳的指令（组）数量
+/// Maximum number of instructions (groups) a transaction can hold with the estimated compute units of each
+fn max_items_within_compute_unit_limit(compute_units_per_item: u32) -> Result<usize> {
+    ensure!(
+        compute_units_per_item > 0 && compute_units_per_item <= MAX_COMPUTE_UNIT_LIMIT,
+        "Estimated compute units {} must be within (0, {}]",
+        compute_units_per_item,
+        MAX_COMPUTE_UNIT_LIMIT
+    );
+
+    Ok((MAX_COMPUTE_UNIT_LIMIT / compute_units_per_item) as usize)
+}
+
+/// 将指令按顺序贪心打包为尽可能少的交易，每笔交易不超过大小限制、最大账户数量及计算单元上限
+/// 每笔交易都以计算预算指令开头，计算单元上限按交易内的指令数量乘以 `compute_units_per_instruction` 设置
+///
+/// Greedily pack the instructions, in order, into as few transactions as possible while keeping each
+/// transaction within the size limit, the maximum number of accounts and the compute unit limit. Every
+/// transaction starts with the compute budget instructions, its compute unit limit is the number of
+/// instructions in it times `compute_units_per_instruction`
+pub fn pack_instructions(
+    instructions: Vec<Instruction>,
+    payer: &Pubkey,
+    max_accounts: usize,
+    compute_unit_price: ComputeUnitPrice,
+    compute_units_per_instruction: u32,
+) -> Result<Vec<Vec<Instruction>>> {
+    let max_instructions_per_transaction =
+        max_items_within_compute_unit_limit(compute_units_per_instruction)?;
+
+    let with_compute_budget = |ixs: &[Instruction]| -> Result<Vec<Instruction>> {
+        let compute_units = compute_units_per_instruction * ixs.len() as u32;
+        Ok([
+            compute_unit_price.instructions_with_limit(Some(compute_units))?,
+            ixs.to_vec(),
+        ]
+        .concat())
+    };
+
+    let fits = |ixs: &[Instruction]| -> Result<bool> {
+        let ixs = with_compute_budget(ixs)?;
+        let account_count = Message::new(&ixs, Some(payer)).account_keys.len();
+        Ok(account_count <= max_accounts && fits_in_single_transaction(&ixs, payer))
+    };
+
+    let mut packed: Vec<Vec<Instruction>> = vec![];
+    let mut current: Vec<Instruction> = vec![];
+
+    for ix in instructions {
+        if !current.is_empty()
+            && (current.len() == max_instructions_per_transaction
+                || !fits(&[current.as_slice(), std::slice::from_ref(&ix)].concat())?)
+        {
+            // 当前交易已满，将该指令移至新的交易
+            // The current transaction is full, move the instruction to a new transaction
+            packed.push(with_compute_budget(&std::mem::take(&mut current))?);
+        }
+
+        current.push(ix);
+
+        ensure!(
+            fits(&current)?,
+            "Instruction for program {} does not fit in a single transaction",
+            current[current.len() - 1].program_id
+        );
+    }
+
+    if !current.is_empty() {
+        packed.push(with_compute_budget(&current)?);
+    }
+
+    Ok(packed)
+}
+
+/// 将指令组按顺序贪心打包为交易，同一组的指令总在同一笔交易中。每笔交易最多包含 `max_groups_per_transaction`
+/// 个指令组，且不超过大小限制、最大账户数量及计算单元上限。每笔交易都以计算预算指令开头，计算单元上限按交易内的
+/// 指令组数量乘以 `compute_units_per_group` 设置
+///
+/// Greedily pack the instruction groups, in order, into transactions while keeping the instructions of a group
+/// in the same transaction. Every transaction holds at most `max_groups_per_transaction` groups and stays within
+/// the size limit, the maximum number of accounts and the compute unit limit. Every transaction starts with the
+/// compute budget instructions, its compute unit limit is the number of groups in it times
+/// `compute_units_per_group`
+pub fn pack_instruction_groups(
+    groups: Vec<Vec<Instruction>>,
+    payer: &Pubkey,
+    max_groups_per_transaction: usize,
+    compute_unit_price: ComputeUnitPrice,
+    compute_units_per_group: u32,
+) -> Result<Vec<Vec<Instruction>>> {
+    ensure!(
+        max_groups_per_transaction > 0,
+        "max_groups_per_transaction must be greater than 0"
+    );
+
+    let max_groups_per_transaction = max_groups_per_transaction
+        .min(max_items_within_compute_unit_limit(compute_units_per_group)?);
+
+    let with_compute_budget = |ixs: &[Instruction], group_count: usize| -> Result<Vec<Instruction>> {
+        let compute_units = compute_units_per_group * group_count as u32;
+        Ok([
+            compute_unit_price.instructions_with_limit(Some(compute_units))?,
+            ixs.to_vec(),
+        ]
+        .concat())
+    };
+
+    let fits = |ixs: &[Instruction], group_count: usize| -> Result<bool> {
+        let ixs = with_compute_budget(ixs, group_count)?;
+        let account_count = Message::new(&ixs, Some(payer)).account_keys.len();
+        Ok(account_count <= MAX_ACCOUNTS_PER_TRANSACTION && fits_in_single_transaction(&ixs, payer))
+    };
+
+    let mut packed: Vec<Vec<Instruction>> = vec![];
+    let mut current: Vec<Instruction> = vec![];
+    let mut group_count = 0;
+
+    for group in groups {
+        if group_count > 0
+            && (group_count == max_groups_per_transaction
+                || !fits(&[current.as_slice(), group.as_slice()].concat(), group_count + 1)?)
+        {
+            packed.push(with_compute_budget(&std::mem::take(&mut current), group_count)?);
+            group_count = 0;
+        }
+
+        // 单独一组放不下时仍单独发送，由发送结果报告错误
+        // A group which does not fit on its own is still sent alone, so the send reports the error
+        current.extend(group);
+        group_count += 1;
+    }
+
+    if group_count > 0 {
+        packed.push(with_compute_budget(&current, group_count)?);
+    }
+
+    Ok(packed)
+}
+
+/// 使用地址查找表编译为v0版本交易并发送
+/// Compile the instructions into a v0 transaction using the address lookup table and send it
+pub async fn send_versioned_transaction_with_lookup_table(
+    rpc_client: &RpcClient,
+    payer: &dyn Signer,
+    instructions: &[Instruction],
+    lookup_table: Pubkey,
+    transaction_config: RpcSendTransactionConfig,
+) -> Result<Signature> {
+    let lookup_table_account = rpc_client.get_account(&lookup_table).await?;
+    let lookup_table_state = AddressLookupTable::deserialize(&lookup_table_account.data)
+        .map_err(|e| anyhow!("Failed to deserialize lookup table {}: {}", lookup_table, e))?;
+
+    let address_lookup_table_account = AddressLookupTableAccount {
+        key: lookup_table,
+        addresses: lookup_table_state.addresses.to_vec(),
+    };
+
+    let recent_blockhash = rpc_client.get_latest_blockhash().await?;
+
+    let message = v0::Message::try_compile(
+        &payer.pubkey(),
+        instructions,
+        &[address_lookup_table_account],
+        recent_blockhash,
+    )?;
+
+    let transaction = VersionedTransaction::try_new(VersionedMessage::V0(message), &[payer])?;
+
+    let signature = rpc_client
+        .send_and_confirm_transaction_with_spinner_and_config(
+            &transaction,
+            rpc_client.commitment(),
+            transaction_config,
+        )
+        .await?;
+
+    Ok(signature)
 }
 
 pub struct SwapQuoteAccounts {
     pub lb_pair_state: LbPair,
+    /// 读取报价账户时RPC节点所在的slot / Slot of the RPC node when the quote accounts were read
+    pub context_slot: u64,
     pub clock: Clock,
     pub mint_x_account: Account,
     pub mint_y_account: Account,
@@ -171,14 +1550,20 @@ pub struct SwapQuoteAccounts {
     pub bin_array_keys: Vec<Pubkey>,
 }
 
+/// 获取报价所需的账户（时钟、代币mint、bin数组），复用调用方已获取的流动性对状态
+/// Fetch the accounts required for quoting (clock, mints, bin arrays) in a single
+/// `get_multiple_accounts` call, reusing the lb pair state already fetched by the caller.
+///
+/// 可指定独立于发送交易的确认级别读取报价账户，未指定时使用客户端的确认级别
+/// The quote accounts can be read at a commitment distinct from the one used to send the
+/// transaction. The client commitment is used when not provided.
 pub async fn fetch_quote_required_accounts(
     rpc_client: &RpcClient,
-    lb_pair: Pubkey,
-    lb_pair_state: &LbPair,
+    lb_pair_state: LbPair,
     bin_arrays_for_swap: Vec<Pubkey>,
+    commitment: Option<CommitmentConfig>,
 ) -> Result<SwapQuoteAccounts> {
     let prerequisite_accounts = [
-        lb_pair,
         solana_sdk::sysvar::clock::ID,
         lb_pair_state.token_x_mint,
         lb_pair_state.token_y_mint,
@@ -186,16 +1571,15 @@ pub async fn fetch_quote_required_accounts(
 
     let accounts_to_fetch = [prerequisite_accounts.to_vec(), bin_arrays_for_swap.clone()].concat();
 
-    let accounts = rpc_client.get_multiple_accounts(&accounts_to_fetch).await?;
+    let commitment = commitment.unwrap_or_else(|| rpc_client.commitment());
 
-    let mut index = 0;
-    let lb_pair_account = accounts
-        .get(index)
-        .and_then(ToOwned::to_owned)
-        .context("Failed to fetch lb pair account")?;
-    let lb_pair_state = LbPair::try_deserialize(&mut lb_pair_account.data.as_ref())?;
+    let response = rpc_client
+        .get_multiple_accounts_with_commitment(&accounts_to_fetch, commitment)
+        .await?;
+    let context_slot = response.context.slot;
+    let accounts = response.value;
 
-    index.inc();
+    let mut index = 0;
     let clock_account = accounts
         .get(index)
         .and_then(ToOwned::to_owned)
@@ -224,10 +1608,14 @@ pub async fn fetch_quote_required_accounts(
         .zip(bin_arrays_for_swap.iter())
         .filter_map(|(account, &key)| {
             let account = account?;
-            Some((
-                key,
-                BinArray::try_deserialize(&mut account.data.as_ref()).ok()?,
-            ))
+            // 零拷贝账户的 `try_deserialize` 要求数据按16字节对齐，按鉴别器校验后以非对齐方式读取
+            // The `try_deserialize` of zero copy accounts requires 16 byte aligned data, check the discriminator
+            // and read it unaligned instead
+            let data = account
+                .data
+                .strip_prefix(BinArray::DISCRIMINATOR)?
+                .get(..std::mem::size_of::<BinArray>())?;
+            Some((key, bytemuck::pod_read_unaligned(data)))
         })
         .collect::<Vec<_>>();
 
@@ -243,6 +1631,7 @@ pub async fn fetch_quote_required_accounts(
 
     Ok(SwapQuoteAccounts {
         lb_pair_state,
+        context_slot,
         clock,
         mint_x_account,
         mint_y_account,
@@ -250,3 +1639,731 @@ pub async fn fetch_quote_required_accounts(
         bin_array_keys,
     })
 }
+
+/// 报价过期时最多重新报价的次数
+/// Maximum number of re-quotes when the quote is stale
+pub const MAX_REQUOTE_ATTEMPTS: u32 = 3;
+
+/// 当前slot距报价账户读取时的slot超过 `max_quote_age_slots` 时返回 `true`，未指定阈值时不请求RPC直接返回 `false`。
+/// 当前slot与报价账户使用相同的确认级别 `commitment` 读取（未提供时使用客户端的确认级别），
+/// 否则不同确认级别之间的slot差会被误判为报价过期。用于在发送交易前发现活跃bin可能已移动的过期报价
+///
+/// Returns `true` when the current slot is more than `max_quote_age_slots` past the slot the quote accounts were
+/// read at. Returns `false` without an RPC request when no threshold is given. The current slot is read at the same
+/// `commitment` as the quote accounts, the client commitment when omitted, otherwise the slot gap between two
+/// commitment levels would be taken as a stale quote. Used to catch stale quotes, whose active bin may have moved,
+/// before sending
+pub async fn is_quote_stale(
+    rpc_client: &RpcClient,
+    quote_context_slot: u64,
+    commitment: Option<CommitmentConfig>,
+    max_quote_age_slots: Option<u64>,
+) -> Result<bool> {
+    let Some(max_quote_age_slots) = max_quote_age_slots else {
+        return Ok(false);
+    };
+
+    let commitment = commitment.unwrap_or_else(|| rpc_client.commitment());
+    let current_slot = rpc_client.get_slot_with_commitment(commitment).await?;
+    let quote_age_slots = current_slot.saturating_sub(quote_context_slot);
+
+    debug!(quote_context_slot, current_slot, quote_age_slots, "Quote age");
+
+    Ok(quote_age_slots > max_quote_age_slots)
+}
+
+/// 以交易对状态调用 `quote` 报价，`context_slot` 返回报价账户读取时的slot。报价过期时重新读取交易对并重新报价，
+/// 最多 `MAX_REQUOTE_ATTEMPTS` 次
+///
+/// Quote with the pair state through `quote`, `context_slot` returning the slot the quote accounts were read at.
+/// While the quote is stale, the pair is re-read and quoted again, up to `MAX_REQUOTE_ATTEMPTS` times
+pub async fn quote_with_requotes<T, F, Fut>(
+    rpc_client: &RpcClient,
+    lb_pair: Pubkey,
+    mut lb_pair_state: LbPair,
+    commitment: Option<CommitmentConfig>,
+    max_quote_age_slots: Option<u64>,
+    context_slot: impl Fn(&T) -> u64,
+    mut quote: F,
+) -> Result<T>
+where
+    F: FnMut(LbPair) -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let mut requotes = 0;
+
+    loop {
+        let quoted = quote(lb_pair_state).await?;
+
+        if !is_quote_stale(
+            rpc_client,
+            context_slot(&quoted),
+            commitment,
+            max_quote_age_slots,
+        )
+        .await?
+        {
+            return Ok(quoted);
+        }
+
+        ensure!(
+            requotes < MAX_REQUOTE_ATTEMPTS,
+            "Quote is still older than {} slots after {} re-quotes",
+            max_quote_age_slots.unwrap_or_default(),
+            requotes
+        );
+        requotes += 1;
+        warn!(requotes, "Quote is stale, re-quoting");
+
+        lb_pair_state = rpc_client
+            .get_account_and_deserialize(&lb_pair, |account| {
+                Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
+            })
+            .await?;
+    }
+}
+
+/// 部分签名的管理员交易的计算单元上限，即单条指令交易的默认上限。交易签名后无法修改，因此显式设置
+/// Compute unit limit of the partially signed admin transactions, the default limit of a single instruction
+/// transaction. The transaction can't be changed once signed, so the limit is set explicitly
+pub const ADMIN_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+
+/// 管理员权限由钱包以外的密钥（如多签控制的密钥）持有时，钱包只作为付款人部分签名
+/// Admin authority held by a key other than the wallet, such as a multisig controlled key. The wallet only partially
+/// signs the transaction as fee payer
+#[derive(Clone, Copy)]
+pub struct ExternalAuthority<'a> {
+    /// 管理员地址 / Admin authority address
+    pub authority: Pubkey,
+    /// 付款人（钱包） / Fee payer, the wallet
+    pub payer: &'a dyn Signer,
+    /// 代替最新区块哈希的持久随机数账户 / Durable nonce account used instead of the latest blockhash
+    pub nonce_account: Option<Pubkey>,
+}
+
+impl ExternalAuthority<'_> {
+    /// 构建包含计算预算指令的交易，由付款人及 `signers` 部分签名后打印，不发送。管理员之后通过 `sign-transaction`
+    /// 补充签名，再通过 `send-transaction` 发送。使用最新区块哈希时须在其过期（约60至90秒）前完成，
+    /// 指定持久随机数账户时交易先推进随机数并使用其区块哈希，不受过期限制
+    /// Build the transaction with the compute budget instructions, partially sign it by the fee payer and `signers`
+    /// and print it instead of sending it. The admin then adds its signature with `sign-transaction` and the
+    /// transaction is submitted with `send-transaction`. With the latest blockhash this must happen before it expires
+    /// (about 60 to 90 seconds). With a durable nonce account the transaction advances the nonce first and uses its
+    /// blockhash, which doesn't expire
+    pub async fn print_partially_signed_transaction(
+        &self,
+        rpc_client: &RpcClient,
+        compute_unit_price: &ComputeUnitPrice,
+        instructions: &[Instruction],
+        signers: &[&dyn Signer],
+    ) -> Result<()> {
+        let compute_budget_instructions =
+            compute_unit_price.instructions_with_limit(Some(ADMIN_COMPUTE_UNIT_LIMIT))?;
+
+        let (advance_nonce_instruction, recent_blockhash) = match self.nonce_account {
+            Some(nonce_account) => {
+                let account = rpc_client.get_account(&nonce_account).await?;
+                let (blockhash, nonce_authority) = get_durable_nonce(&account.data)
+                    .with_context(|| format!("Invalid nonce account {}", nonce_account))?;
+                (
+                    Some(advance_nonce_account(&nonce_account, &nonce_authority)),
+                    blockhash,
+                )
+            }
+            None => (None, rpc_client.get_latest_blockhash().await?),
+        };
+
+        // 推进随机数的指令必须是交易的第一条指令
+        // The instruction advancing the nonce must be the first instruction of the transaction
+        let instructions = advance_nonce_instruction
+            .into_iter()
+            .chain(compute_budget_instructions)
+            .chain(instructions.iter().cloned())
+            .collect::<Vec<_>>();
+
+        let mut transaction = Transaction::new_with_payer(&instructions, Some(&self.payer.pubkey()));
+        ensure!(
+            get_missing_signers(&transaction).contains(&self.authority),
+            "Authority {} doesn't sign the transaction",
+            self.authority
+        );
+
+        let signers = [&[self.payer], signers].concat();
+        transaction.try_partial_sign(&signers, recent_blockhash)?;
+
+        print_transaction_signing_status(&transaction)
+    }
+}
+
+/// 从已初始化的持久随机数账户数据中读取区块哈希及随机数权限
+/// Reads the blockhash and the nonce authority from the data of an initialized durable nonce account
+pub fn get_durable_nonce(data: &[u8]) -> Result<(Hash, Pubkey)> {
+    let versions: NonceVersions =
+        bincode::deserialize(data).context("Account is not a nonce account")?;
+    match versions.state() {
+        NonceState::Initialized(data) => Ok((data.blockhash(), data.authority)),
+        NonceState::Uninitialized => Err(anyhow!("Nonce account is not initialized")),
+    }
+}
+
+/// 返回交易中尚未签名的签名者
+/// Returns the signers that didn't sign the transaction yet
+pub fn get_missing_signers(transaction: &Transaction) -> Vec<Pubkey> {
+    let num_required_signatures = usize::from(transaction.message.header.num_required_signatures);
+
+    transaction
+        .message
+        .account_keys
+        .iter()
+        .zip(transaction.signatures.iter())
+        .take(num_required_signatures)
+        .filter(|(_, signature)| **signature == Signature::default())
+        .map(|(signer, _)| *signer)
+        .collect()
+}
+
+/// 解码base58编码的序列化交易
+/// Decode a base58 encoded serialized transaction
+pub fn decode_transaction(encoded: &str) -> Result<Transaction> {
+    let bytes = bs58::decode(encoded.trim())
+        .into_vec()
+        .context("Transaction is not base58 encoded")?;
+    bincode::deserialize(&bytes).context("Invalid serialized transaction")
+}
+
+/// 以JSON打印base58编码的序列化交易、区块哈希及尚未签名的签名者
+/// Print the base58 encoded serialized transaction, its blockhash and the signers that didn't sign it yet as JSON
+pub fn print_transaction_signing_status(transaction: &Transaction) -> Result<()> {
+    let missing_signers = get_missing_signers(transaction)
+        .iter()
+        .map(|signer| signer.to_string())
+        .collect::<Vec<_>>();
+
+    let status = serde_json::json!({
+        "transaction": bs58::encode(bincode::serialize(transaction)?).into_string(),
+        "recent_blockhash": transaction.message.recent_blockhash.to_string(),
+        "missing_signers": missing_signers,
+    });
+    println!("{}", serde_json::to_string_pretty(&status)?);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_client::client_error::ClientError as SolanaClientError;
+    use solana_client::rpc_request::RpcRequest;
+    use solana_client::rpc_response::{Response, RpcResponseContext};
+    use solana_sdk::instruction::InstructionError;
+
+    #[test]
+    fn test_is_retriable() {
+        let blockhash_not_found = SolanaClientError::from(TransactionError::BlockhashNotFound);
+        assert!(is_retriable(&anyhow::Error::from(blockhash_not_found)));
+
+        let io_error = SolanaClientError::from(std::io::Error::from(std::io::ErrorKind::TimedOut));
+        assert!(is_retriable(
+            &anyhow::Error::from(io_error).context("Failed to send transaction")
+        ));
+
+        let program_error = SolanaClientError::from(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(6000),
+        ));
+        assert!(!is_retriable(&anyhow::Error::from(program_error)));
+
+        assert!(!is_retriable(&anyhow!("Invalid position range")));
+    }
+
+    #[test]
+    fn test_is_retriable_client_error() {
+        for transaction_error in [
+            TransactionError::BlockhashNotFound,
+            TransactionError::WouldExceedMaxBlockCostLimit,
+            TransactionError::WouldExceedAccountDataBlockLimit,
+            TransactionError::AccountInUse,
+        ] {
+            assert!(is_retriable_client_error(&SolanaClientError::from(
+                transaction_error
+            )));
+        }
+
+        // 确定性错误，例如交易锁定的账户超过上限，每次重试都会同样失败
+        // Deterministic errors, such as a transaction locking more accounts than allowed, fail the same way on
+        // every retry
+        for transaction_error in [
+            TransactionError::TooManyAccountLocks,
+            TransactionError::AccountLoadedTwice,
+            TransactionError::InsufficientFundsForFee,
+            TransactionError::InstructionError(0, InstructionError::Custom(6000)),
+        ] {
+            assert!(!is_retriable_client_error(&SolanaClientError::from(
+                transaction_error
+            )));
+        }
+    }
+
+    #[test]
+    fn test_is_rate_limited_error() {
+        let reqwest_error = |status: u16| {
+            let response = http::Response::builder().status(status).body("").unwrap();
+            SolanaClientError::from(
+                reqwest::Response::from(response)
+                    .error_for_status()
+                    .unwrap_err(),
+            )
+        };
+
+        assert!(is_rate_limited_error(&reqwest_error(429)));
+        assert!(is_retriable(&anyhow::Error::from(reqwest_error(429))));
+        assert!(!is_rate_limited_error(&reqwest_error(500)));
+
+        // 只检查HTTP状态码，不匹配错误信息中的文字
+        // Only the HTTP status code is checked, not the text of the error message
+        let custom_error = SolanaClientError::from(ClientErrorKind::Custom(
+            "Too Many Requests for slot 429".to_string(),
+        ));
+        assert!(!is_rate_limited_error(&custom_error));
+    }
+
+    fn dummy_instruction(account_count: usize, data_len: usize) -> Instruction {
+        Instruction {
+            program_id: dlmm::ID,
+            accounts: (0..account_count)
+                .map(|_| AccountMeta::new(Pubkey::new_unique(), false))
+                .collect(),
+            data: vec![0u8; data_len],
+        }
+    }
+
+    #[test]
+    fn test_pack_instructions_sizes_compute_unit_limit_per_transaction() {
+        let payer = Pubkey::new_unique();
+        let instructions = (0..7).map(|_| dummy_instruction(2, 8)).collect::<Vec<_>>();
+
+        // 每条指令40万计算单元时，一笔交易最多容纳3条指令
+        // With 400k compute units per instruction, a transaction holds at most 3 instructions
+        let transactions = pack_instructions(
+            instructions.clone(),
+            &payer,
+            MAX_ACCOUNTS_PER_TRANSACTION,
+            ComputeUnitPrice::MicroLamports(1_000),
+            400_000,
+        )
+        .unwrap();
+
+        assert_eq!(
+            transactions.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![5, 5, 3]
+        );
+
+        for (ixs, instruction_count) in transactions.iter().zip([3u32, 3, 1]) {
+            assert_eq!(
+                ixs[0],
+                ComputeBudgetInstruction::set_compute_unit_limit(400_000 * instruction_count)
+            );
+            assert_eq!(
+                ixs[1],
+                ComputeBudgetInstruction::set_compute_unit_price(1_000)
+            );
+        }
+
+        let packed = transactions
+            .into_iter()
+            .flat_map(|ixs| ixs.into_iter().skip(2))
+            .collect::<Vec<_>>();
+        assert_eq!(packed, instructions);
+    }
+
+    #[test]
+    fn test_pack_instructions_splits_by_size_and_accounts() {
+        let payer = Pubkey::new_unique();
+        let instructions = (0..10).map(|_| dummy_instruction(8, 64)).collect::<Vec<_>>();
+
+        let transactions =
+            pack_instructions(
+            instructions.clone(),
+            &payer,
+            20,
+            ComputeUnitPrice::default(),
+            10_000,
+        ).unwrap();
+        assert!(transactions.len() > 1);
+
+        for ixs in transactions.iter() {
+            assert!(Message::new(ixs, Some(&payer)).account_keys.len() <= 20);
+            assert!(fits_in_single_transaction(ixs, &payer));
+            assert_eq!(
+                ixs[0],
+                ComputeBudgetInstruction::set_compute_unit_limit(10_000 * (ixs.len() as u32 - 1))
+            );
+        }
+
+        let packed = transactions
+            .into_iter()
+            .flat_map(|ixs| ixs.into_iter().skip(1))
+            .collect::<Vec<_>>();
+        assert_eq!(packed, instructions);
+    }
+
+    #[test]
+    fn test_pack_instructions_rejects_oversized_instruction() {
+        let payer = Pubkey::new_unique();
+
+        assert!(pack_instructions(
+            vec![dummy_instruction(2, PACKET_DATA_SIZE)],
+            &payer,
+            MAX_ACCOUNTS_PER_TRANSACTION,
+            ComputeUnitPrice::default(),
+            200_000,
+        )
+        .is_err());
+
+        assert!(pack_instructions(
+            vec![dummy_instruction(2, 8)],
+            &payer,
+            MAX_ACCOUNTS_PER_TRANSACTION,
+            ComputeUnitPrice::default(),
+            MAX_COMPUTE_UNIT_LIMIT + 1,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_pack_instruction_groups_sizes_compute_unit_limit_per_transaction() {
+        let payer = Pubkey::new_unique();
+        let groups = (0..5)
+            .map(|_| vec![dummy_instruction(2, 8), dummy_instruction(2, 8)])
+            .collect::<Vec<_>>();
+
+        // 每组70万计算单元时，即使允许更多组，一笔交易最多容纳2组
+        // With 700k compute units per group, a transaction holds at most 2 groups even when more are allowed
+        // 由优先费用总额推导价格时，每笔交易的价格由其计算单元上限推导
+        // When the price is derived from the total priority fee, the price of each transaction is derived from its
+        // compute unit limit
+        let compute_unit_price = ComputeUnitPrice::MaxTotalFee {
+            max_priority_fee_lamports: 10_000,
+            compute_unit_limit: MAX_COMPUTE_UNIT_LIMIT,
+        };
+        let transactions =
+            pack_instruction_groups(groups.clone(), &payer, 4, compute_unit_price, 700_000)
+                .unwrap();
+        assert_eq!(
+            transactions.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![6, 6, 4]
+        );
+        for (ixs, group_count) in transactions.iter().zip([2u32, 2, 1]) {
+            assert_eq!(
+                ixs[0],
+                ComputeBudgetInstruction::set_compute_unit_limit(700_000 * group_count)
+            );
+            assert_eq!(
+                ixs[1],
+                ComputeBudgetInstruction::set_compute_unit_price(
+                    10_000_000_000 / u64::from(700_000 * group_count)
+                )
+            );
+        }
+
+        let transactions =
+            pack_instruction_groups(groups, &payer, 1, ComputeUnitPrice::default(), 100_000)
+                .unwrap();
+        assert_eq!(transactions.len(), 5);
+        for ixs in transactions.iter() {
+            assert_eq!(ixs[0], ComputeBudgetInstruction::set_compute_unit_limit(100_000));
+        }
+    }
+
+    /// 返回当前slot为 `slot` 的模拟RPC客户端，交易对账户为全零的 `LbPair`。模拟响应只生效一次，之后的请求返回
+    /// 默认响应（slot为0）
+    /// Mock RPC client whose current slot is `slot`, with a zeroed `LbPair` as the pair account. The mocked
+    /// responses are used once, later requests get the default responses (slot 0)
+    fn mock_rpc_client_at_slot(slot: u64) -> RpcClient {
+        let lb_pair_state: LbPair = bytemuck::Zeroable::zeroed();
+        let lb_pair_account = Account {
+            data: [LbPair::DISCRIMINATOR, bytemuck::bytes_of(&lb_pair_state)].concat(),
+            owner: dlmm::ID,
+            ..Default::default()
+        };
+
+        let mocks = HashMap::from([
+            (RpcRequest::GetSlot, serde_json::json!(slot)),
+            (
+                RpcRequest::GetAccountInfo,
+                serde_json::json!(Response {
+                    context: RpcResponseContext::new(slot),
+                    value: Some(encode_ui_account(
+                        &Pubkey::new_unique(),
+                        &lb_pair_account,
+                        UiAccountEncoding::Base64,
+                        None,
+                        None,
+                    )),
+                }),
+            ),
+        ]);
+
+        RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks)
+    }
+
+    #[tokio::test]
+    async fn test_is_quote_stale() {
+        let is_stale = |max_quote_age_slots: Option<u64>, commitment: Option<CommitmentConfig>| async move {
+            is_quote_stale(
+                &mock_rpc_client_at_slot(120),
+                100,
+                commitment,
+                max_quote_age_slots,
+            )
+            .await
+            .unwrap()
+        };
+
+        assert!(is_stale(Some(10), None).await);
+        assert!(is_stale(Some(10), Some(CommitmentConfig::processed())).await);
+        assert!(!is_stale(Some(20), None).await);
+        assert!(!is_stale(Some(30), Some(CommitmentConfig::finalized())).await);
+        // 未指定阈值时从不过期 / Never stale without a threshold
+        assert!(!is_stale(None, None).await);
+    }
+
+    #[tokio::test]
+    async fn test_quote_with_requotes() {
+        let lb_pair_state: LbPair = bytemuck::Zeroable::zeroed();
+
+        // 报价未过期时只报价一次 / A fresh quote is quoted once
+        let mut quotes = 0;
+        let context_slot = quote_with_requotes(
+            &mock_rpc_client_at_slot(120),
+            Pubkey::new_unique(),
+            lb_pair_state,
+            None,
+            Some(10),
+            |context_slot: &u64| *context_slot,
+            |_| {
+                quotes += 1;
+                async { Ok(115u64) }
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!((context_slot, quotes), (115, 1));
+
+        // 第一次报价在slot 120时已过期，重新读取交易对后再次报价，此时当前slot为默认的0，报价未过期
+        // The first quote is stale at slot 120, the pair is re-read and quoted again. The current slot is then the
+        // default 0 and the quote is fresh
+        let mut quotes = 0;
+        let context_slot = quote_with_requotes(
+            &mock_rpc_client_at_slot(120),
+            Pubkey::new_unique(),
+            lb_pair_state,
+            None,
+            Some(10),
+            |context_slot: &u64| *context_slot,
+            |_| {
+                quotes += 1;
+                let context_slot = 99 + quotes;
+                async move { Ok(context_slot) }
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!((context_slot, quotes), (101, 2));
+    }
+
+    #[test]
+    fn test_load_bin_liquidity_distribution_file() {
+        let write_file = |name: &str, content: &str| {
+            let path = std::env::temp_dir().join(format!("dlmm-distribution-test-{}", name));
+            std::fs::write(&path, content).unwrap();
+            path.to_string_lossy().to_string()
+        };
+        let expected = vec![(5554, 0.0, 0.25), (5555, 0.75, 0.75), (5556, 0.25, 0.0)];
+
+        let path = write_file(
+            "valid.json",
+            "[[5554, 0.0, 0.25], [5555, 0.75, 0.75], [5556, 0.25, 0]]",
+        );
+        assert_eq!(load_bin_liquidity_distribution_file(&path).unwrap(), expected);
+
+        let path = write_file(
+            "valid.csv",
+            "bin_id,dist_x,dist_y\n# comment\n5554, 0.0, 0.25\n\n5555,0.75,0.75\n5556,0.25,0\n",
+        );
+        assert_eq!(load_bin_liquidity_distribution_file(&path).unwrap(), expected);
+
+        // JSON与CSV使用相同的行校验 / JSON and CSV rows go through the same validation
+        for (name, content) in [
+            ("share-above-one.json", "[[5555, 1.5, 0.0]]"),
+            ("missing-share.json", "[[5555, 0.5]]"),
+            ("fractional-bin.json", "[[5555.5, 0.5, 0.5]]"),
+            ("string-share.json", r#"[[5555, "0.5", 0.5]]"#),
+            ("share-above-one.csv", "5555,1.5,0.0"),
+            ("extra-field.csv", "1,5555,0.5,0.5"),
+            ("late-header.csv", "5555,0.5,0.5\nbin_id,dist_x,dist_y"),
+            ("unknown.txt", "5555,0.5,0.5"),
+        ] {
+            let path = write_file(name, content);
+            assert!(
+                load_bin_liquidity_distribution_file(&path).is_err(),
+                "{} must be rejected",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_swap_for_y() {
+        let mut lb_pair_state: LbPair = bytemuck::Zeroable::zeroed();
+        let token_x_mint = Pubkey::new_unique();
+        let token_y_mint = Pubkey::new_unique();
+        lb_pair_state.token_x_mint = token_x_mint;
+        lb_pair_state.token_y_mint = token_y_mint;
+
+        // 未提供代币铸造地址时使用 `swap_for_y` / `swap_for_y` is used when no mint is provided
+        assert!(resolve_swap_for_y(&lb_pair_state, true, None, None).unwrap());
+        assert!(!resolve_swap_for_y(&lb_pair_state, false, None, None).unwrap());
+
+        // 代币铸造地址优先于 `swap_for_y` / The mints take precedence over `swap_for_y`
+        assert!(resolve_swap_for_y(&lb_pair_state, false, Some(token_x_mint), None).unwrap());
+        assert!(!resolve_swap_for_y(&lb_pair_state, true, Some(token_y_mint), None).unwrap());
+        assert!(resolve_swap_for_y(&lb_pair_state, false, None, Some(token_y_mint)).unwrap());
+        assert!(!resolve_swap_for_y(&lb_pair_state, true, None, Some(token_x_mint)).unwrap());
+        assert!(resolve_swap_for_y(
+            &lb_pair_state,
+            false,
+            Some(token_x_mint),
+            Some(token_y_mint)
+        )
+        .unwrap());
+
+        // 不属于交易对的代币，或输入输出为同一代币 / Mints outside the pair, or the same mint as input and output
+        let other_mint = Pubkey::new_unique();
+        assert!(resolve_swap_for_y(&lb_pair_state, true, Some(other_mint), None).is_err());
+        assert!(resolve_swap_for_y(&lb_pair_state, true, None, Some(other_mint)).is_err());
+        assert!(resolve_swap_for_y(
+            &lb_pair_state,
+            true,
+            Some(token_x_mint),
+            Some(token_x_mint)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_get_durable_nonce() {
+        use solana_sdk::nonce::state::{Data, DurableNonce};
+
+        let authority = Pubkey::new_unique();
+        let durable_nonce = DurableNonce::from_blockhash(&Hash::new_unique());
+        let versions = NonceVersions::new(NonceState::Initialized(Data::new(
+            authority,
+            durable_nonce,
+            5_000,
+        )));
+        let data = bincode::serialize(&versions).unwrap();
+        assert_eq!(
+            get_durable_nonce(&data).unwrap(),
+            (*durable_nonce.as_hash(), authority)
+        );
+
+        let uninitialized =
+            bincode::serialize(&NonceVersions::new(NonceState::Uninitialized)).unwrap();
+        assert!(get_durable_nonce(&uninitialized).is_err());
+        assert!(get_durable_nonce(&[]).is_err());
+    }
+
+    #[test]
+    fn test_validate_customizable_params() {
+        let clock = Clock {
+            slot: 1_000,
+            unix_timestamp: 1_700_000_000,
+            ..Default::default()
+        };
+        let params = CustomizableParams {
+            active_id: 0,
+            bin_step: 10,
+            // 0.025%
+            base_factor: 2_500,
+            activation_type: 0,
+            has_alpha_vault: false,
+            activation_point: None,
+            creator_pool_on_off_control: false,
+            base_fee_power_factor: 0,
+            padding: [0u8; 62],
+        };
+        assert!(validate_customizable_params(&params, &clock).is_ok());
+
+        // 基础手续费低于 0.01% / Base fee below 0.01%
+        let low_fee = CustomizableParams {
+            base_factor: 9,
+            ..params
+        };
+        assert!(validate_customizable_params(&low_fee, &clock).is_err());
+
+        let alpha_vault_without_activation = CustomizableParams {
+            has_alpha_vault: true,
+            ..params
+        };
+        assert!(validate_customizable_params(&alpha_vault_without_activation, &clock).is_err());
+
+        let activation_in_past = CustomizableParams {
+            activation_point: Some(999),
+            ..params
+        };
+        assert!(validate_customizable_params(&activation_in_past, &clock).is_err());
+
+        let activation_too_late = CustomizableParams {
+            activation_point: Some(1_000 + MAX_ACTIVATION_SLOT_DURATION + 1),
+            ..params
+        };
+        assert!(validate_customizable_params(&activation_too_late, &clock).is_err());
+
+        let timestamp_activation = CustomizableParams {
+            activation_type: 1,
+            has_alpha_vault: true,
+            activation_point: Some(1_700_003_600),
+            ..params
+        };
+        assert!(validate_customizable_params(&timestamp_activation, &clock).is_ok());
+
+        let invalid_activation_type = CustomizableParams {
+            activation_type: 2,
+            ..params
+        };
+        assert!(validate_customizable_params(&invalid_activation_type, &clock).is_err());
+    }
+
+    #[test]
+    fn test_partially_signed_transaction_roundtrip() {
+        let payer = Keypair::new();
+        let authority = Keypair::new();
+        let instruction = Instruction {
+            program_id: dlmm::ID,
+            accounts: vec![AccountMeta::new_readonly(authority.pubkey(), true)],
+            data: vec![],
+        };
+
+        let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+        assert_eq!(
+            get_missing_signers(&transaction),
+            vec![payer.pubkey(), authority.pubkey()]
+        );
+
+        let recent_blockhash = solana_sdk::hash::Hash::new_unique();
+        transaction
+            .try_partial_sign(&[&payer], recent_blockhash)
+            .unwrap();
+
+        let encoded = bs58::encode(bincode::serialize(&transaction).unwrap()).into_string();
+        let mut transaction = decode_transaction(&encoded).unwrap();
+        assert_eq!(get_missing_signers(&transaction), vec![authority.pubkey()]);
+
+        transaction
+            .try_partial_sign(&[&authority], recent_blockhash)
+            .unwrap();
+        assert!(get_missing_signers(&transaction).is_empty());
+        assert!(transaction.verify().is_ok());
+    }
+}
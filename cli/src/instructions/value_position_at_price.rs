@@ -0,0 +1,194 @@
+use anchor_lang::AccountDeserialize;
+use anchor_spl::token_interface::Mint;
+use rust_decimal::prelude::*;
+
+use crate::*;
+
+/// 估算仓位在假设价格下代币组成的参数结构体
+/// Parameters for valuing a position at a hypothetical price
+#[derive(Debug, Parser)]
+pub struct ValuePositionAtPriceParams {
+    /// 仓位地址
+    /// Position address
+    pub position: Pubkey,
+    /// 假设的价格（以Y代币计价的X代币价格）
+    /// Hypothetical price (token X priced in token Y)
+    pub price: f64,
+}
+
+/// 执行估算仓位在假设价格下的代币组成
+/// Executes valuing the position at a hypothetical price
+///
+/// # 功能说明 / Functionality
+/// 按仓位的流动性份额计算当前持有的X/Y代币数量，并假设活跃bin移动到目标价格所在的bin：
+/// 目标bin以下的bin全部转换为Y代币，以上的bin全部转换为X代币，目标bin保持当前组成。不计算手续费
+///
+/// Computes the token X / Y held by the position from its liquidity shares, then assumes the active bin moved
+/// to the bin of the target price: bins below the target bin are fully converted to token Y, bins above to
+/// token X, and the target bin keeps its current composition. Fees are not included
+pub async fn execute_value_position_at_price<C: Deref<Target = impl Signer> + Clone>(
+    params: ValuePositionAtPriceParams,
+    program: &Program<C>,
+) -> Result<()> {
+    let ValuePositionAtPriceParams { position, price } = params;
+
+    let rpc_client = program.rpc();
+
+    let position_state: PositionV2 = rpc_client
+        .get_account_and_deserialize(&position, |account| {
+            deserialize_position_v2(&account.data)
+        })
+        .await?;
+
+    let lb_pair_state: LbPair = rpc_client
+        .get_account_and_deserialize(&position_state.lb_pair, |account| {
+            Ok(bytemuck::pod_read_unaligned(&account.data[8..]))
+        })
+        .await?;
+
+    let bin_array_keys = position_state.get_bin_array_keys_coverage()?;
+
+    let accounts = rpc_client
+        .get_multiple_accounts(
+            &[
+                vec![lb_pair_state.token_x_mint, lb_pair_state.token_y_mint],
+                bin_array_keys,
+            ]
+            .concat(),
+        )
+        .await?;
+
+    let token_x_mint_account = accounts[0].as_ref().context("token_x_mint not found")?;
+    let token_y_mint_account = accounts[1].as_ref().context("token_y_mint not found")?;
+
+    let token_x_mint = Mint::try_deserialize(&mut token_x_mint_account.data.as_ref())?;
+    let token_y_mint = Mint::try_deserialize(&mut token_y_mint_account.data.as_ref())?;
+
+    let bin_arrays = accounts[2..]
+        .iter()
+        .map(|account| {
+            let account = account.as_ref().context("Bin array not found")?;
+            Ok(bytemuck::pod_read_unaligned::<BinArray>(&account.data[8..]))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let price_per_lamport =
+        price_per_token_to_per_lamport(price, token_x_mint.decimals, token_y_mint.decimals)
+            .context("price_per_token_to_per_lamport overflow")?;
+
+    let target_bin_id =
+        get_id_from_price(lb_pair_state.bin_step, &price_per_lamport, Rounding::Down)
+            .context("get_id_from_price overflow")?;
+
+    let mut current_amount_x = 0u64;
+    let mut current_amount_y = 0u64;
+    let mut target_amount_x = 0u64;
+    let mut target_amount_y = 0u64;
+
+    for (i, bin_id) in (position_state.lower_bin_id..=position_state.upper_bin_id).enumerate() {
+        let share = position_state.liquidity_shares[i];
+        if share == 0 {
+            continue;
+        }
+
+        let bin = bin_arrays
+            .iter()
+            .find(|bin_array| bin_array.is_bin_id_within_range(bin_id).unwrap_or(false))
+            .context("Bin array not found")?
+            .get_bin(bin_id)?;
+
+        if bin.liquidity_supply == 0 {
+            continue;
+        }
+
+        // 按流动性份额计算仓位在该bin中的代币数量
+        // Token amounts of the position in the bin by its liquidity share
+        let amount_x: u64 = safe_mul_div_cast(
+            bin.amount_x.into(),
+            share,
+            bin.liquidity_supply,
+            Rounding::Down,
+        )?;
+        let amount_y: u64 = safe_mul_div_cast(
+            bin.amount_y.into(),
+            share,
+            bin.liquidity_supply,
+            Rounding::Down,
+        )?;
+
+        current_amount_x = current_amount_x.checked_add(amount_x).context("overflow")?;
+        current_amount_y = current_amount_y.checked_add(amount_y).context("overflow")?;
+
+        let bin_price = get_price_from_id(bin_id, lb_pair_state.bin_step)?;
+
+        let (amount_x, amount_y) = match bin_id.cmp(&target_bin_id) {
+            // 价格上涨越过该bin，X代币全部换为Y代币
+            // Price moved above the bin, all token X is swapped to token Y
+            std::cmp::Ordering::Less => {
+                let swapped_y = Bin::get_amount_out(amount_x, bin_price, true)?;
+                (0, amount_y.checked_add(swapped_y).context("overflow")?)
+            }
+            // 价格下跌越过该bin，Y代币全部换为X代币
+            // Price moved below the bin, all token Y is swapped to token X
+            std::cmp::Ordering::Greater => {
+                let swapped_x = Bin::get_amount_out(amount_y, bin_price, false)?;
+                (amount_x.checked_add(swapped_x).context("overflow")?, 0)
+            }
+            std::cmp::Ordering::Equal => (amount_x, amount_y),
+        };
+
+        target_amount_x = target_amount_x.checked_add(amount_x).context("overflow")?;
+        target_amount_y = target_amount_y.checked_add(amount_y).context("overflow")?;
+    }
+
+    let to_ui_x = |amount: u64| amount_to_ui_amount(amount, token_x_mint.decimals);
+    let to_ui_y = |amount: u64| amount_to_ui_amount(amount, token_y_mint.decimals);
+
+    let current_price = get_ui_price_from_id(
+        lb_pair_state.bin_step,
+        lb_pair_state.active_id,
+        token_x_mint.decimals,
+        token_y_mint.decimals,
+    )
+    .context("Current price overflow")?;
+
+    // 以目标价格计算的Y代币价值，比较持有当前代币与提供流动性的差异
+    // Value in token Y at the target price, comparing holding the current tokens against providing liquidity
+    let value_in_y = |amount_x: u64, amount_y: u64| -> Option<Decimal> {
+        to_ui_x(amount_x)?
+            .checked_mul(Decimal::from_f64(price)?)?
+            .checked_add(to_ui_y(amount_y)?)
+    };
+
+    let current_value = value_in_y(current_amount_x, current_amount_y).context("overflow")?;
+    let target_value = value_in_y(target_amount_x, target_amount_y).context("overflow")?;
+
+    println!("Position: {}", position);
+    println!(
+        "Current: active bin {} price {} amount_x {} ({}) amount_y {} ({})",
+        lb_pair_state.active_id,
+        current_price,
+        current_amount_x,
+        to_ui_x(current_amount_x).context("overflow")?,
+        current_amount_y,
+        to_ui_y(current_amount_y).context("overflow")?
+    );
+    println!(
+        "Hypothetical: active bin {} price {} amount_x {} ({}) amount_y {} ({})",
+        target_bin_id,
+        price,
+        target_amount_x,
+        to_ui_x(target_amount_x).context("overflow")?,
+        target_amount_y,
+        to_ui_y(target_amount_y).context("overflow")?
+    );
+    println!(
+        "Value in token Y at {}: hold {} position {} difference {}",
+        price,
+        current_value,
+        target_value,
+        target_value - current_value
+    );
+
+    Ok(())
+}
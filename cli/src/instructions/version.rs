@@ -0,0 +1,86 @@
+use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+
+use crate::*;
+
+/// 生成程序绑定所用的IDL
+/// IDL the program bindings are generated from
+const DLMM_IDL: &str = include_str!("../../../idls/dlmm.json");
+
+/// 显示版本信息的参数结构体
+/// Parameters for showing the version information
+#[derive(Debug, Clone, Parser)]
+pub struct VersionParams {
+    /// 同时读取链上程序的升级权限及最近部署的slot
+    /// Also read the upgrade authority and the last deployed slot of the on-chain program
+    #[clap(long)]
+    pub on_chain: bool,
+}
+
+/// 执行显示CLI构建所针对的程序ID、IDL版本及CLI版本，用于确认连接的程序部署及在问题报告中注明所用构建
+/// Executes showing the program id the CLI is built against, the IDL version and the CLI version, to confirm the
+/// program deployment being used and to identify the build in bug reports
+///
+/// # 功能说明 / Functionality
+/// 默认离线执行。指定 `--on-chain` 时读取程序账户及其程序数据账户，显示升级权限（不可升级时为none）及最近部署的slot
+/// Runs offline by default. With `--on-chain`, reads the program account and its program data account and shows the
+/// upgrade authority (none when the program is immutable) and the last deployed slot
+pub async fn execute_version(params: VersionParams, rpc_client: &RpcClient) -> Result<()> {
+    let VersionParams { on_chain } = params;
+
+    let idl: serde_json::Value = serde_json::from_str(DLMM_IDL)?;
+    let idl_version = idl["metadata"]["version"]
+        .as_str()
+        .context("IDL version not found")?;
+
+    println!("Program ID: {}", dlmm::ID);
+    println!("IDL version: {}", idl_version);
+    println!("CLI version: {}", env!("CARGO_PKG_VERSION"));
+
+    if !on_chain {
+        return Ok(());
+    }
+
+    println!("RPC: {}", rpc_client.url());
+
+    let program_account = rpc_client
+        .get_account(&dlmm::ID)
+        .await
+        .with_context(|| format!("Program {} not found", dlmm::ID))?;
+    ensure!(
+        program_account.owner == bpf_loader_upgradeable::ID,
+        "Program {} is owned by loader {}, not the upgradeable loader",
+        dlmm::ID,
+        program_account.owner
+    );
+
+    let UpgradeableLoaderState::Program {
+        programdata_address,
+    } = bincode::deserialize(&program_account.data)?
+    else {
+        bail!("Invalid program account {}", dlmm::ID);
+    };
+
+    let programdata_account = rpc_client.get_account(&programdata_address).await?;
+    let metadata_size = UpgradeableLoaderState::size_of_programdata_metadata();
+    let UpgradeableLoaderState::ProgramData {
+        slot,
+        upgrade_authority_address,
+    } = bincode::deserialize(
+        programdata_account
+            .data
+            .get(..metadata_size)
+            .context("Invalid program data account")?,
+    )?
+    else {
+        bail!("Invalid program data account {}", programdata_address);
+    };
+
+    match upgrade_authority_address {
+        Some(upgrade_authority) => println!("Upgrade authority: {}", upgrade_authority),
+        None => println!("Upgrade authority: none (immutable)"),
+    }
+    println!("Last deployed slot: {}", slot);
+
+    Ok(())
+}
@@ -0,0 +1,208 @@
+//! DLMM SDK：命令行工具使用的指令实现及面向库使用者的 `DlmmClient`
+//! DLMM SDK: the instruction implementations used by the command line tool and `DlmmClient` for library consumers
+
+// 导入必要的依赖
+use anchor_client::solana_sdk::compute_budget::ComputeBudgetInstruction;
+use anchor_client::solana_sdk::instruction::Instruction;
+use anchor_client::*;
+use anchor_client::{
+    solana_client::rpc_config::RpcSendTransactionConfig,
+    solana_sdk::pubkey::Pubkey,
+    solana_sdk::{
+        commitment_config::CommitmentConfig,
+        signer::{keypair::*, Signer},
+    },
+};
+use anchor_lang::prelude::AccountMeta;
+use anchor_lang::AccountDeserialize;
+use anchor_lang::InstructionData;
+use anchor_lang::ToAccountMetas;
+use anyhow::*;
+use clap::*;
+use commons::*;
+use dlmm::accounts::*;
+use dlmm::types::*;
+use solana_account_decoder::*;
+use std::ops::Deref;
+use tracing::{debug, error, info, warn};
+
+// 模块声明
+pub mod args;         // 命令行参数定义
+pub mod client;       // 面向库使用者的DLMM客户端
+pub mod config;       // 配置文件
+#[cfg(test)]
+mod fixtures;         // 测试夹具
+pub mod instructions; // 指令实现
+pub mod math;        // 数学计算工具
+
+use args::*;
+use client::*;
+use commons::rpc_client_extension::*;
+use instructions::*;
+use math::*;
+
+/// 获取设置计算单元价格的指令
+/// 用于设置交易的优先费用，提高交易被打包的概率
+/// 
+/// # 参数
+/// * `micro_lamports` - 每个计算单元的价格（以micro lamports为单位）
+/// 
+/// # 返回
+/// * 如果价格大于0，返回设置计算单元价格的指令
+/// * 如果价格为0，返回None（不设置优先费用）
+pub fn get_set_compute_unit_price_ix(micro_lamports: u64) -> Option<Instruction> {
+    if micro_lamports > 0 {
+        Some(ComputeBudgetInstruction::set_compute_unit_price(
+            micro_lamports,
+        ))
+    } else {
+        None
+    }
+}
+
+/// 单笔交易允许的最大计算单元数量
+/// Maximum compute unit limit of a single transaction
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// 由愿意支付的优先费用总额及计算单元上限推导每个计算单元的价格（micro lamports），向下取整保证总额不超过最大值
+/// Derive the compute unit price (micro lamports) from the total priority fee willing to pay and the compute
+/// unit limit. Rounded down so the total never exceeds the maximum
+pub fn compute_unit_price_from_total_fee(
+    max_priority_fee_lamports: u64,
+    compute_unit_limit: u32,
+) -> Result<u64> {
+    ensure!(
+        compute_unit_limit > 0,
+        "compute_unit_limit must be greater than 0"
+    );
+
+    let micro_lamports = u128::from(max_priority_fee_lamports)
+        .checked_mul(1_000_000)
+        .context("overflow")?
+        / u128::from(compute_unit_limit);
+
+    u64::try_from(micro_lamports).context("overflow")
+}
+
+/// 交易的计算单元价格（优先费用）设置
+/// Compute unit price (priority fee) setting of the transactions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComputeUnitPrice {
+    /// 固定的计算单元价格（micro lamports），为0时不设置优先费用
+    /// Fixed compute unit price (micro lamports). No priority fee is set when 0
+    MicroLamports(u64),
+    /// 每笔交易愿意支付的最大优先费用总额，计算单元价格由交易的计算单元上限推导。未自行设置上限的交易使用
+    /// `compute_unit_limit`
+    /// Maximum total priority fee willing to pay per transaction. The compute unit price is derived from the compute
+    /// unit limit of the transaction. Transactions which don't set their own limit use `compute_unit_limit`
+    MaxTotalFee {
+        max_priority_fee_lamports: u64,
+        compute_unit_limit: u32,
+    },
+}
+
+impl Default for ComputeUnitPrice {
+    fn default() -> Self {
+        Self::MicroLamports(0)
+    }
+}
+
+impl ComputeUnitPrice {
+    /// 未自行设置计算单元上限的交易的计算预算指令
+    /// Compute budget instructions of a transaction which doesn't set its own compute unit limit
+    pub fn instructions(&self) -> Result<Vec<Instruction>> {
+        self.instructions_with_limit(None)
+    }
+
+    /// 计算预算指令，`compute_unit_limit` 为交易自行设置的计算单元上限。由优先费用总额推导价格时始终设置上限，
+    /// 使优先费用总额不超过最大值
+    /// Compute budget instructions, `compute_unit_limit` being the compute unit limit set by the transaction itself.
+    /// When the price is derived from the total priority fee, the limit is always set so the total priority fee
+    /// doesn't exceed the maximum
+    pub fn instructions_with_limit(
+        &self,
+        compute_unit_limit: Option<u32>,
+    ) -> Result<Vec<Instruction>> {
+        let (compute_unit_limit, micro_lamports) = match *self {
+            Self::MicroLamports(micro_lamports) => (compute_unit_limit, micro_lamports),
+            Self::MaxTotalFee {
+                max_priority_fee_lamports,
+                compute_unit_limit: default_compute_unit_limit,
+            } => {
+                let compute_unit_limit = compute_unit_limit.unwrap_or(default_compute_unit_limit);
+                let micro_lamports =
+                    compute_unit_price_from_total_fee(max_priority_fee_lamports, compute_unit_limit)?;
+                (Some(compute_unit_limit), micro_lamports)
+            }
+        };
+
+        Ok(compute_unit_limit
+            .map(ComputeBudgetInstruction::set_compute_unit_limit)
+            .into_iter()
+            .chain(get_set_compute_unit_price_ix(micro_lamports))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_unit_price_from_total_fee() {
+        // 0.001 SOL 分摊到 140万 计算单元
+        // 0.001 SOL spread over 1.4M compute units
+        assert_eq!(
+            compute_unit_price_from_total_fee(1_000_000, MAX_COMPUTE_UNIT_LIMIT).unwrap(),
+            714_285
+        );
+        // 向下取整，总额不超过最大值
+        // Rounded down, the total never exceeds the maximum
+        assert_eq!(compute_unit_price_from_total_fee(1, 3).unwrap(), 333_333);
+        assert_eq!(compute_unit_price_from_total_fee(0, 200_000).unwrap(), 0);
+
+        assert!(compute_unit_price_from_total_fee(1_000, 0).is_err());
+        assert!(compute_unit_price_from_total_fee(u64::MAX, 1).is_err());
+    }
+
+    #[test]
+    fn test_compute_unit_price_instructions() {
+        let fixed = ComputeUnitPrice::MicroLamports(1_000);
+        assert_eq!(
+            fixed.instructions().unwrap(),
+            vec![ComputeBudgetInstruction::set_compute_unit_price(1_000)]
+        );
+        assert_eq!(
+            fixed.instructions_with_limit(Some(200_000)).unwrap(),
+            vec![
+                ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+                ComputeBudgetInstruction::set_compute_unit_price(1_000),
+            ]
+        );
+        assert!(ComputeUnitPrice::default().instructions().unwrap().is_empty());
+
+        // 未自行设置上限的交易使用配置的上限，设置了上限的交易由其上限推导价格
+        // Transactions without their own limit use the configured limit, the price of transactions with their own
+        // limit is derived from it
+        let max_total_fee = ComputeUnitPrice::MaxTotalFee {
+            max_priority_fee_lamports: 1_000,
+            compute_unit_limit: 400_000,
+        };
+        assert_eq!(
+            max_total_fee.instructions().unwrap(),
+            vec![
+                ComputeBudgetInstruction::set_compute_unit_limit(400_000),
+                ComputeBudgetInstruction::set_compute_unit_price(2_500),
+            ]
+        );
+        assert_eq!(
+            max_total_fee
+                .instructions_with_limit(Some(MAX_COMPUTE_UNIT_LIMIT))
+                .unwrap(),
+            vec![
+                ComputeBudgetInstruction::set_compute_unit_limit(MAX_COMPUTE_UNIT_LIMIT),
+                ComputeBudgetInstruction::set_compute_unit_price(714),
+            ]
+        );
+    }
+}
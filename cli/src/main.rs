@@ -1,80 +1,69 @@
 // 导入必要的依赖
-use anchor_client::solana_sdk::compute_budget::ComputeBudgetInstruction;
-use anchor_client::solana_sdk::instruction::Instruction;
-use anchor_client::*;
-use anchor_client::{
-    solana_client::rpc_config::RpcSendTransactionConfig,
-    solana_sdk::pubkey::Pubkey,
-    solana_sdk::{
-        commitment_config::CommitmentConfig,
-        signer::{keypair::*, Signer},
-    },
-};
-use anchor_lang::prelude::AccountMeta;
-use anchor_lang::AccountDeserialize;
-use anchor_lang::InstructionData;
-use anchor_lang::ToAccountMetas;
+use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+use anchor_client::solana_client::rpc_config::RpcSendTransactionConfig;
+use anchor_client::solana_sdk::signer::{keypair::*, Signer};
+use anchor_client::Client;
 use anyhow::*;
-use clap::*;
-use commons::*;
-use dlmm::accounts::*;
-use dlmm::types::*;
-use instructions::set_pair_status_permissionless::execute_set_pair_status_permissionless;
-use solana_account_decoder::*;
-use std::ops::Deref;
+use cli::args::*;
+use cli::client::DlmmClient;
+use cli::config::*;
+use cli::instructions::set_pair_status_permissionless::execute_set_pair_status_permissionless;
+use cli::instructions::*;
+use cli::*;
+use commons::dlmm;
 use std::rc::Rc;
 use std::time::Duration;
-
-// 模块声明
-mod args;         // 命令行参数定义
-mod instructions; // 指令实现
-mod math;        // 数学计算工具
-
-use args::*;
-use commons::rpc_client_extension::*;
-use instructions::*;
-use math::*;
-
-/// 获取设置计算单元价格的指令
-/// 用于设置交易的优先费用，提高交易被打包的概率
-/// 
-/// # 参数
-/// * `micro_lamports` - 每个计算单元的价格（以micro lamports为单位）
-/// 
-/// # 返回
-/// * 如果价格大于0，返回设置计算单元价格的指令
-/// * 如果价格为0，返回None（不设置优先费用）
-fn get_set_compute_unit_price_ix(micro_lamports: u64) -> Option<Instruction> {
-    if micro_lamports > 0 {
-        Some(ComputeBudgetInstruction::set_compute_unit_price(
-            micro_lamports,
-        ))
-    } else {
-        None
-    }
-}
+use tracing::{debug, error, info, warn};
 
 /// 主函数入口
 /// 使用tokio异步运行时处理所有命令
 #[tokio::main]
 async fn main() -> Result<()> {
     // 解析命令行参数
-    let cli = Cli::parse();
+    let cli = parse_cli()?;
+
+    // 初始化日志订阅者，日志输出到stderr，命令结果输出到stdout
+    // Initialize the log subscriber. Logs go to stderr, command results go to stdout
+    tracing_subscriber::fmt()
+        .with_max_level(cli.config_override.log_level)
+        .with_writer(std::io::stderr)
+        .init();
+
+    // 离线命令不需要钱包及RPC连接
+    // Offline commands need neither the wallet nor an RPC connection
+    match &cli.command {
+        DLMMCommand::ComputeBinId(params) => return execute_compute_bin_id(params.clone()),
+        DLMMCommand::ComputePrice(params) => return execute_compute_price(params.clone()),
+        // 版本信息不需要钱包，只在指定 `--on-chain` 时连接RPC
+        // The version information doesn't need the wallet, RPC is only accessed with `--on-chain`
+        DLMMCommand::Version(params) => {
+            let rpc_client = RpcClient::new_with_commitment(
+                cli.config_override.resolve_cluster().url().to_string(),
+                cli.config_override.resolve_commitment(&cli.command),
+            );
+            return execute_version(params.clone(), &rpc_client).await;
+        }
+        _ => {}
+    }
 
     // 读取钱包密钥对文件
-    let payer =
-        read_keypair_file(cli.config_override.wallet).expect("Wallet keypair file not found");
+    // 钱包路径可能来自环境变量或配置文件，同样展开 `~`
+    // The wallet path may come from an env var or the config file, so `~` is expanded as well
+    let wallet = shellexpand::tilde(&cli.config_override.wallet).to_string();
+    let payer = read_keypair_file(wallet).expect("Wallet keypair file not found");
 
     // 打印钱包公钥
-    println!("Wallet {:#?}", payer.pubkey());
+    info!(wallet = %payer.pubkey(), "Wallet");
 
-    // 设置确认级别为confirmed
-    // confirmed表示交易已被集群中大多数节点确认
-    let commitment_config = CommitmentConfig::confirmed();
+    // 确认级别：优先使用 `--commitment`，只读命令默认为processed，其他命令默认为confirmed（交易已被集群中大多数节点确认）
+    // Commitment: `--commitment` takes precedence, read-only commands default to processed and the other commands
+    // to confirmed (the transaction was voted on by a supermajority of the cluster)
+    let commitment_config = cli.config_override.resolve_commitment(&cli.command);
+    debug!(commitment = ?commitment_config.commitment, "Commitment");
 
     // 创建Anchor客户端，用于与Solana区块链交互
     let client = Client::new_with_options(
-        cli.config_override.cluster,
+        cli.config_override.resolve_cluster(),
         Rc::new(Keypair::from_bytes(&payer.to_bytes())?),
         commitment_config,
     );
@@ -91,93 +80,263 @@ async fn main() -> Result<()> {
         min_context_slot: None,       // 不设置最小上下文槽位
     };
 
-    // 根据用户设置创建计算单元价格指令（优先费用）
-    let compute_unit_price_ix = get_set_compute_unit_price_ix(cli.config_override.priority_fee);
+    // 根据用户设置确定计算单元价格（优先费用）。提供优先费用总额时，由每笔交易的计算单元上限推导计算单元价格，
+    // 未自行设置上限的交易使用 `--compute-unit-limit`
+    // Resolve the compute unit price (priority fee). When the total priority fee is provided, the compute unit
+    // price is derived from the compute unit limit of each transaction, `--compute-unit-limit` being used by the
+    // transactions which don't set their own
+    let compute_unit_price = match cli.config_override.max_priority_fee_lamports {
+        Some(max_priority_fee_lamports) => {
+            let compute_unit_limit = cli.config_override.compute_unit_limit;
+            ensure!(
+                compute_unit_limit <= MAX_COMPUTE_UNIT_LIMIT,
+                "--compute-unit-limit must not exceed {}",
+                MAX_COMPUTE_UNIT_LIMIT
+            );
+            info!(
+                max_priority_fee_lamports,
+                compute_unit_limit,
+                compute_unit_price =
+                    compute_unit_price_from_total_fee(max_priority_fee_lamports, compute_unit_limit)?,
+                "Derived compute unit price"
+            );
+            ComputeUnitPrice::MaxTotalFee {
+                max_priority_fee_lamports,
+                compute_unit_limit,
+            }
+        }
+        None => ComputeUnitPrice::MicroLamports(cli.config_override.priority_fee),
+    };
+
+    // 管理员不是钱包时，管理员命令只部分签名交易
+    // When the admin isn't the wallet, admin commands only partially sign the transaction
+    let external_authority = cli
+        .config_override
+        .authority
+        .filter(|authority| *authority != payer.pubkey())
+        .map(|authority| ExternalAuthority {
+            authority,
+            payer: &payer,
+            nonce_account: cli.config_override.nonce,
+        });
+    ensure!(
+        external_authority.is_none() || cli.command.supports_external_authority(),
+        "--authority is not supported by this command"
+    );
+
+    // 基于DLMM客户端实现的命令共享同一份交易配置及优先费用
+    // Commands implemented on top of the DLMM client share the same transaction configuration and priority fee
+    let dlmm_client = DlmmClient::new(client.program(dlmm::ID)?, transaction_config)
+        .with_compute_unit_price(compute_unit_price.clone());
 
     // 根据用户输入的命令执行相应的操作
     match cli.command {
         // 初始化流动性对（版本2）
         DLMMCommand::InitializePair2(params) => {
-            execute_initialize_lb_pair2(params, &program, transaction_config).await?;
+            execute_initialize_lb_pair2(
+                params,
+                &program,
+                transaction_config,
+                compute_unit_price,
+            )
+            .await?;
         }
         // 初始化流动性对（版本1）
         DLMMCommand::InitializePair(params) => {
-            execute_initialize_lb_pair(params, &program, transaction_config).await?;
+            execute_initialize_lb_pair(params, &program, transaction_config, compute_unit_price)
+                .await?;
         }
         DLMMCommand::InitializeBinArray(params) => {
-            execute_initialize_bin_array(params, &program, transaction_config).await?;
+            execute_initialize_bin_array(
+                params,
+                &program,
+                transaction_config,
+                compute_unit_price,
+            )
+            .await?;
         }
         DLMMCommand::InitializeBinArrayWithPriceRange(params) => {
-            execute_initialize_bin_array_with_price_range(params, &program, transaction_config)
-                .await?;
+            execute_initialize_bin_array_with_price_range(
+                params,
+                &program,
+                transaction_config,
+                compute_unit_price,
+            )
+            .await?;
         }
         DLMMCommand::InitializeBinArrayWithBinRange(params) => {
-            execute_initialize_bin_array_with_bin_range(params, &program, transaction_config)
-                .await?;
+            execute_initialize_bin_array_with_bin_range(
+                params,
+                &program,
+                transaction_config,
+                compute_unit_price,
+            )
+            .await?;
         }
         DLMMCommand::InitializePositionWithPriceRange(params) => {
-            execute_initialize_position_with_price_range(params, &program, transaction_config)
-                .await?;
+            execute_initialize_position_with_price_range(
+                params,
+                &program,
+                transaction_config,
+                compute_unit_price,
+            )
+            .await?;
         }
         DLMMCommand::InitializePosition(params) => {
-            execute_initialize_position(params, &program, transaction_config).await?;
+            execute_initialize_position(
+                params,
+                &program,
+                transaction_config,
+                compute_unit_price,
+            )
+            .await?;
         }
         DLMMCommand::AddLiquidity(params) => {
-            execute_add_liquidity(params, &program, transaction_config, compute_unit_price_ix)
-                .await?;
+            execute_add_liquidity(params, &dlmm_client, &payer).await?;
+        }
+        DLMMCommand::InitializePositionAndAddLiquidity(params) => {
+            execute_initialize_position_and_add_liquidity(
+                params,
+                &program,
+                transaction_config,
+                compute_unit_price,
+            )
+            .await?;
         }
         DLMMCommand::RemoveLiquidity(params) => {
-            execute_remove_liquidity(params, &program, transaction_config, compute_unit_price_ix)
-                .await?;
+            execute_remove_liquidity(
+                params,
+                &program,
+                transaction_config,
+                compute_unit_price,
+                &payer,
+            )
+            .await?;
         }
         DLMMCommand::SwapExactIn(params) => {
-            execute_swap(params, &program, transaction_config).await?;
+            execute_swap(params, &dlmm_client).await?;
         }
 
         DLMMCommand::ShowPair(params) => {
             execute_show_pair(params, &program).await?;
         }
+        DLMMCommand::GetBinReserves(params) => {
+            execute_get_bin_reserves(params, &program).await?;
+        }
+        DLMMCommand::SnapshotPair(params) => {
+            execute_snapshot_pair(params, &program).await?;
+        }
+        DLMMCommand::ShowBitmapExtension(params) => {
+            execute_show_bitmap_extension(params, &program).await?;
+        }
+        DLMMCommand::GetProtocolFees(params) => {
+            execute_get_protocol_fees(params, &program).await?;
+        }
+        DLMMCommand::PairHealth(params) => {
+            execute_pair_health(params, &program).await?;
+        }
+        DLMMCommand::ShowOracle(params) => {
+            execute_show_oracle(params, &program).await?;
+        }
+        DLMMCommand::GetTwap(params) => {
+            execute_get_twap(params, &program).await?;
+        }
+        DLMMCommand::ComputeBinId(_) | DLMMCommand::ComputePrice(_) | DLMMCommand::Version(_) => {
+            unreachable!("offline commands are handled before connecting")
+        }
+        DLMMCommand::ListRewards(params) => {
+            execute_list_rewards(params, &program).await?;
+        }
         DLMMCommand::ShowPosition(params) => {
             execute_show_position(params, &program).await?;
         }
+        DLMMCommand::ValuePositionAtPrice(params) => {
+            execute_value_position_at_price(params, &program).await?;
+        }
         DLMMCommand::ClaimReward(params) => {
-            execute_claim_reward(params, &program, transaction_config, compute_unit_price_ix)
+            execute_claim_reward(params, &program, transaction_config, compute_unit_price)
                 .await?;
         }
         DLMMCommand::UpdateRewardDuration(params) => {
-            execute_update_reward_duration(params, &program, transaction_config).await?;
+            execute_update_reward_duration(
+                params,
+                &program,
+                transaction_config,
+                compute_unit_price,
+            )
+            .await?;
         }
         DLMMCommand::UpdateRewardFunder(params) => {
-            execute_update_reward_funder(params, &program, transaction_config).await?;
+            execute_update_reward_funder(
+                params,
+                &program,
+                transaction_config,
+                compute_unit_price,
+            )
+            .await?;
         }
         DLMMCommand::ClosePosition(params) => {
-            execute_close_position(params, &program, transaction_config).await?;
+            execute_close_position(params, &program, transaction_config, compute_unit_price)
+                .await?;
+        }
+        DLMMCommand::MigratePosition(params) => {
+            execute_migrate_position(params, &program, transaction_config, compute_unit_price)
+                .await?;
         }
         DLMMCommand::ClaimFee(params) => {
-            execute_claim_fee(params, &program, transaction_config, compute_unit_price_ix).await?;
+            execute_claim_fee(params, &program, transaction_config, compute_unit_price).await?;
+        }
+        DLMMCommand::ClaimFeeBatch(params) => {
+            execute_claim_fee_batch(params, &program, transaction_config, compute_unit_price)
+                .await?;
+        }
+        DLMMCommand::ClaimFeeAndRewards(params) => {
+            execute_claim_fee_and_rewards(
+                params,
+                &program,
+                transaction_config,
+                compute_unit_price,
+            )
+            .await?;
+        }
+        DLMMCommand::HarvestAll(params) => {
+            execute_harvest_all(params, &program, transaction_config, compute_unit_price)
+                .await?;
         }
         DLMMCommand::IncreaseOracleLength(params) => {
-            execute_increase_oracle_length(params, &program, transaction_config).await?;
+            execute_increase_oracle_length(
+                params,
+                &program,
+                transaction_config,
+                compute_unit_price,
+            )
+            .await?;
         }
         DLMMCommand::ShowPresetParameter(params) => {
             execute_show_preset_parameters(params, &program).await?;
         }
 
-        DLMMCommand::ListAllBinStep => {
-            execute_list_all_bin_step(&program).await?;
+        DLMMCommand::ListAllBinStep(params) => {
+            execute_list_all_bin_step(params, &program).await?;
         }
         DLMMCommand::SwapExactOut(params) => {
-            execute_swap_exact_out(params, &program, transaction_config).await?;
+            execute_swap_exact_out(params, &dlmm_client).await?;
         }
         DLMMCommand::SwapWithPriceImpact(params) => {
-            execute_swap_with_price_impact(params, &program, transaction_config).await?;
+            execute_swap_with_price_impact(params, &dlmm_client).await?;
+        }
+        DLMMCommand::QuoteExactInBatch(params) => {
+            execute_quote_exact_in_batch(params, &dlmm_client).await?;
+        }
+        DLMMCommand::ShowDepth(params) => {
+            execute_show_depth(params, &dlmm_client).await?;
         }
         DLMMCommand::InitializeCustomizablePermissionlessLbPair2(params) => {
             execute_initialize_customizable_permissionless_lb_pair2(
                 params,
                 &program,
                 transaction_config,
-                compute_unit_price_ix,
+                compute_unit_price,
             )
             .await?;
         }
@@ -186,12 +345,12 @@ async fn main() -> Result<()> {
                 params,
                 &program,
                 transaction_config,
-                compute_unit_price_ix,
+                compute_unit_price,
             )
             .await?;
         }
         // 由操作员播种流动性
-        // 包含重试机制，用于处理网络错误或交易失败
+        // 包含重试机制，用于处理网络错误等暂时性错误，确定性错误立即返回
         DLMMCommand::SeedLiquidityByOperator(params) => {
             let mut retry_count = 0;
             // 循环重试直到成功或达到最大重试次数
@@ -199,14 +358,18 @@ async fn main() -> Result<()> {
                 params.clone(),
                 &program,
                 transaction_config,
-                compute_unit_price_ix.clone(),
+                compute_unit_price.clone(),
             )
             .await
             {
-                println!("Error: {}", err);
+                if !is_retriable(&err) {
+                    error!(error = %err, "Seed liquidity by operator failed with a non retriable error");
+                    return Err(err);
+                }
+                warn!(error = %err, retry_count, "Seed liquidity by operator failed");
                 retry_count += 1;
                 if retry_count >= params.max_retries {
-                    println!("Exceeded max retries {}", params.max_retries);
+                    error!(max_retries = params.max_retries, "Exceeded max retries");
                     break;
                 }
                 // 等待16秒后重试（约一个区块时间）
@@ -218,76 +381,185 @@ async fn main() -> Result<()> {
                 params,
                 &program,
                 transaction_config,
-                compute_unit_price_ix,
+                compute_unit_price,
             )
             .await?;
         }
+        DLMMCommand::PreviewSeed(params) => {
+            execute_preview_seed(params, &program).await?;
+        }
         DLMMCommand::GetAllPositionsForAnOwner(params) => {
-            execute_get_all_positions(&program, params).await?;
+            execute_get_all_positions(&dlmm_client, params).await?;
         }
         DLMMCommand::SetPairStatusPermissionless(params) => {
-            execute_set_pair_status_permissionless(params, &program, transaction_config).await?;
+            execute_set_pair_status_permissionless(
+                params,
+                &program,
+                transaction_config,
+                compute_unit_price,
+            )
+            .await?;
         }
         DLMMCommand::SyncPrice(params) => {
-            execute_sync_price(params, &program, transaction_config, compute_unit_price_ix).await?;
+            execute_sync_price(params, &program, transaction_config, compute_unit_price).await?;
+        }
+        DLMMCommand::CreateLookupTable(params) => {
+            execute_create_lookup_table(
+                params,
+                &program,
+                transaction_config,
+                compute_unit_price,
+            )
+            .await?;
+        }
+        DLMMCommand::SignTransaction(params) => {
+            execute_sign_transaction(params, &payer)?;
+        }
+        DLMMCommand::SendTransaction(params) => {
+            execute_send_transaction(params, &program, transaction_config).await?;
         }
         // 管理员命令处理
         DLMMCommand::Admin(command) => match command {
             // 初始化需要权限的流动性对
             AdminCommand::InitializePermissionPair(params) => {
-                execute_initialize_permission_lb_pair(params, &program, transaction_config).await?;
+                execute_initialize_permission_lb_pair(
+                    params,
+                    &program,
+                    transaction_config,
+                    compute_unit_price,
+                    external_authority,
+                )
+                .await?;
             }
             AdminCommand::SetPairStatus(params) => {
-                execute_set_pair_status(params, &program, transaction_config).await?;
+                execute_set_pair_status(
+                    params,
+                    &program,
+                    transaction_config,
+                    compute_unit_price,
+                    external_authority,
+                )
+                .await?;
             }
             AdminCommand::RemoveLiquidityByPriceRange(params) => {
                 execute_remove_liquidity_by_price_range(
                     params,
                     &program,
                     transaction_config,
-                    compute_unit_price_ix,
+                    compute_unit_price,
                 )
                 .await?;
             }
             AdminCommand::SetActivationPoint(params) => {
-                execute_set_activation_point(params, &program, transaction_config).await?;
+                execute_set_activation_point(
+                    params,
+                    &program,
+                    transaction_config,
+                    compute_unit_price,
+                    external_authority,
+                )
+                .await?;
             }
             AdminCommand::ClosePresetParameter(params) => {
-                execute_close_preset_parameter(params, &program, transaction_config).await?;
+                execute_close_preset_parameter(
+                    params,
+                    &program,
+                    transaction_config,
+                    compute_unit_price,
+                    external_authority,
+                )
+                .await?;
             }
             AdminCommand::InitializePresetParameter(params) => {
-                execute_initialize_preset_parameter(params, &program, transaction_config).await?;
+                execute_initialize_preset_parameter(
+                    params,
+                    &program,
+                    transaction_config,
+                    compute_unit_price,
+                    external_authority,
+                )
+                .await?;
             }
             AdminCommand::WithdrawProtocolFee(params) => {
-                execute_withdraw_protocol_fee(params, &program, transaction_config).await?;
+                execute_withdraw_protocol_fee(
+                    params,
+                    &program,
+                    transaction_config,
+                    compute_unit_price,
+                )
+                .await?;
             }
             AdminCommand::FundReward(params) => {
-                execute_fund_reward(params, &program, transaction_config, compute_unit_price_ix)
+                execute_fund_reward(params, &program, transaction_config, compute_unit_price)
                     .await?;
             }
             AdminCommand::InitializeReward(params) => {
-                execute_initialize_reward(params, &program, transaction_config).await?;
+                execute_initialize_reward(
+                    params,
+                    &program,
+                    transaction_config,
+                    compute_unit_price,
+                    external_authority,
+                )
+                .await?;
             }
             AdminCommand::SetPreActivationSwapAddress(params) => {
-                execute_set_pre_activation_swap_address(params, &program, transaction_config)
-                    .await?;
+                execute_set_pre_activation_swap_address(
+                    params,
+                    &program,
+                    transaction_config,
+                    compute_unit_price,
+                )
+                .await?;
             }
             AdminCommand::SetPreActivationDuration(params) => {
-                execute_set_pre_activation_duration(params, &program, transaction_config).await?;
+                execute_set_pre_activation_duration(
+                    params,
+                    &program,
+                    transaction_config,
+                    compute_unit_price,
+                )
+                .await?;
             }
             AdminCommand::InitializeTokenBadge(params) => {
-                execute_initialize_token_badge(params, &program, transaction_config).await?;
+                execute_initialize_token_badge(
+                    params,
+                    &program,
+                    transaction_config,
+                    compute_unit_price,
+                    external_authority,
+                )
+                .await?;
             }
             AdminCommand::CreateClaimProtocolFeeOperator(params) => {
-                execute_create_claim_protocol_fee_operator(params, &program, transaction_config)
-                    .await?;
+                execute_create_claim_protocol_fee_operator(
+                    params,
+                    &program,
+                    transaction_config,
+                    compute_unit_price,
+                    external_authority,
+                )
+                .await?;
             }
             AdminCommand::CloseClaimProtocolFeeOperator(params) => {
-                execute_close_claim_protocol_fee_operator(params, &program, transaction_config)
-                    .await?;
+                execute_close_claim_protocol_fee_operator(
+                    params,
+                    &program,
+                    transaction_config,
+                    compute_unit_price,
+                    external_authority,
+                )
+                .await?;
             }
             AdminCommand::UpdateBaseFee(params) => {
-                execute_update_base_fee(params, &program, transaction_config).await?;
+                execute_update_base_fee(
+                    params,
+                    &program,
+                    transaction_config,
+                    compute_unit_price,
+                    external_authority,
+                )
+                .await?;
             }
         },
     };
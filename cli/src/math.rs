@@ -1,9 +1,9 @@
 // DLMM数学计算工具模块
 // 提供价格、bin ID、费率等相关的数学计算功能
 
-use anyhow::{anyhow, Result};
-use commons::dlmm::types::Rounding;
-use commons::{BASIS_POINT_MAX, SCALE_OFFSET};
+use anyhow::{anyhow, ensure, Context, Result};
+use commons::dlmm::types::{BinLiquidityDistribution, BinLiquidityDistributionByWeight, Rounding};
+use commons::{get_price_from_id, BASIS_POINT_MAX, FEE_PRECISION, MAX_BIN_ID, SCALE_OFFSET};
 use rust_decimal::MathematicalOps;
 use rust_decimal::{
     prelude::{FromPrimitive, ToPrimitive},
@@ -139,10 +139,72 @@ pub fn get_id_from_price(bin_step: u16, price: &Decimal, rounding: Rounding) ->
 /// # 说明
 /// Q64xQ64格式使用64位表示整数部分，64位表示小数部分
 pub fn q64x64_price_to_decimal(q64x64_price: u128) -> Option<Decimal> {
-    let q_price = Decimal::from_u128(q64x64_price)?;
+    // 整数部分和小数部分分别转换，大于Decimal尾数范围的Q64xQ64价格（例如bin ID绝对值较大时）也不会溢出
+    let integer = Decimal::from_u128(q64x64_price >> SCALE_OFFSET)?;
+    let fraction = Decimal::from_u128(q64x64_price & u128::from(u64::MAX))?;
     // SCALE_OFFSET通常是64，表示需要除以2^64来获得实际价格
     let scale_off = Decimal::TWO.powu(SCALE_OFFSET.into());
-    q_price.checked_div(scale_off)
+    integer.checked_add(fraction.checked_div(scale_off)?)
+}
+
+/// 从bin ID计算UI价格（以报价代币计价的基础代币价格）
+/// 使用与链上一致的Q64xQ64价格并以Decimal计算，避免f64幂运算在bin ID绝对值较大时的精度损失
+///
+/// # 参数
+/// * `bin_step` - bin步长（以基点为单位）
+/// * `bin_id` - bin ID
+/// * `base_token_decimal` - 基础代币的小数位数
+/// * `quote_token_decimal` - 报价代币的小数位数
+///
+/// # 返回
+/// * 每个代币的价格（UI价格），溢出时返回None
+pub fn get_ui_price_from_id(
+    bin_step: u16,
+    bin_id: i32,
+    base_token_decimal: u8,
+    quote_token_decimal: u8,
+) -> Option<Decimal> {
+    let q64x64_price = get_price_from_id(bin_id, bin_step).ok()?;
+    let price_per_lamport = q64x64_price_to_decimal(q64x64_price)?;
+
+    price_per_lamport
+        .checked_mul(Decimal::TEN.checked_powu(base_token_decimal.into())?)?
+        .checked_div(Decimal::TEN.checked_powu(quote_token_decimal.into())?)
+}
+
+/// 从非整数bin ID（例如时间加权平均bin ID）计算UI价格，整数部分使用链上价格，小数部分按bin步长插值
+/// Computes the UI price of a fractional bin id, e.g. a time weighted average bin id. The integer part uses the
+/// on-chain price, the fractional part is interpolated with the bin step
+pub fn get_ui_price_from_fractional_id(
+    bin_step: u16,
+    bin_id: f64,
+    base_token_decimal: u8,
+    quote_token_decimal: u8,
+) -> Option<Decimal> {
+    let floor_bin_id = bin_id.floor();
+    let floor_price = get_ui_price_from_id(
+        bin_step,
+        i32::from_f64(floor_bin_id)?,
+        base_token_decimal,
+        quote_token_decimal,
+    )?;
+    let fraction_factor =
+        (1.0 + f64::from(bin_step) / BASIS_POINT_MAX as f64).powf(bin_id - floor_bin_id);
+
+    floor_price.checked_mul(Decimal::from_f64(fraction_factor)?)
+}
+
+/// 可表示价格范围的比例（基点），bin ID超出时几乎总是代币小数位数或价格输入错误
+/// Fraction of the representable price range, in basis points, beyond which a bin id almost always comes from wrong
+/// token decimals or a wrong price
+pub const BIN_ID_NEAR_LIMIT_BPS: i64 = 7_500;
+
+/// bin ID是否接近可表示的极限。价格约为 (1 + bin_step / 10000)^bin_id，|bin_id| * bin_step 的上限约为 MAX_BIN_ID
+/// Whether the bin id is near the representable limit. The price is about (1 + bin_step / 10000)^bin_id, so
+/// |bin_id| * bin_step is bounded by about MAX_BIN_ID whatever the bin step
+pub fn is_bin_id_near_limit(bin_id: i32, bin_step: u16) -> bool {
+    i64::from(bin_id).abs() * i64::from(bin_step) * i64::from(BASIS_POINT_MAX)
+        > i64::from(MAX_BIN_ID) * BIN_ID_NEAR_LIMIT_BPS
 }
 
 /// 将每个代币的价格转换为每个lamport的价格
@@ -206,3 +268,368 @@ pub fn price_per_lamport_to_price_per_token(
         .checked_mul(price_per_lamport)?
         .checked_div(one_ui_quote_token_amount)
 }
+
+/// 可转换为百分比的最大手续费率，即100%。超过时为异常值
+/// Maximum fee rate convertible to a percentage, i.e. 100%. Anything above is an absurd value
+pub const MAX_CONVERTIBLE_FEE_RATE: u128 = FEE_PRECISION as u128;
+
+/// 将手续费率转换为手续费百分比，手续费率超过100%时返回错误
+/// Converts fee rate to fee percentage. Returns an error when the fee rate exceeds 100%
+pub fn fee_rate_to_fee_pct(fee_rate: u128) -> Result<Decimal> {
+    ensure!(
+        fee_rate <= MAX_CONVERTIBLE_FEE_RATE,
+        "Fee rate {} exceeds 100% ({})",
+        fee_rate,
+        MAX_CONVERTIBLE_FEE_RATE
+    );
+
+    let fee_rate = Decimal::from_u128(fee_rate)
+        .and_then(|fee_rate| fee_rate.checked_div(Decimal::from(FEE_PRECISION)))
+        .context("fee rate convert to decimal overflow")?;
+    fee_rate
+        .checked_mul(Decimal::ONE_HUNDRED)
+        .context("fee rate convert to percentage overflow")
+}
+
+/// 当报价代币为X代币时翻转价格
+/// 流动性对的价格始终是以Y代币计价的X代币价格。若报价代币注册为X代币，用户使用的价格是以X代币计价的Y代币价格，两者互为倒数
+///
+/// Flip the price when the quote token is token X.
+/// The pair price is always token X priced in token Y. When the quote token was registered as token X,
+/// the user facing price is token Y priced in token X, which is the reciprocal.
+pub fn flip_price_if_quote_is_x(price: f64, quote_is_x: bool) -> f64 {
+    if quote_is_x {
+        1.0 / price
+    } else {
+        price
+    }
+}
+
+/// 当报价代币为X代币时翻转价格范围，翻转后最小和最大价格互换
+/// Flip the price range when the quote token is token X. The min and max price swap places after flipping
+pub fn flip_price_range_if_quote_is_x(
+    min_price: f64,
+    max_price: f64,
+    quote_is_x: bool,
+) -> (f64, f64) {
+    if quote_is_x {
+        (
+            flip_price_if_quote_is_x(max_price, quote_is_x),
+            flip_price_if_quote_is_x(min_price, quote_is_x),
+        )
+    } else {
+        (min_price, max_price)
+    }
+}
+
+/// 将UI代币数量转换为最小单位数量，超出精度的部分向下取整
+/// Converts an UI token amount to the amount in smallest unit, truncating digits beyond the mint precision
+pub fn ui_amount_to_amount(ui_amount: f64, decimals: u8) -> Option<u64> {
+    let ui_amount = Decimal::from_f64(ui_amount)?;
+    let one_ui_token_amount = Decimal::TEN.checked_powu(decimals.into())?;
+    ui_amount
+        .checked_mul(one_ui_token_amount)?
+        .floor()
+        .to_u64()
+}
+
+/// 将最小单位的代币数量转换为UI代币数量
+/// Converts a token amount in smallest unit to the UI token amount
+pub fn amount_to_ui_amount(amount: u64, decimals: u8) -> Option<Decimal> {
+    Decimal::from(amount).checked_div(Decimal::TEN.checked_powu(decimals.into())?)
+}
+
+/// 计算实际价格相对请求价格的偏差（基点，取绝对值），请求价格为0时返回None
+/// Computes the absolute deviation of the actual price from the requested price in bps. Returns None when the
+/// requested price is zero
+pub fn price_deviation_bps(requested_price: Decimal, actual_price: Decimal) -> Option<Decimal> {
+    (actual_price - requested_price)
+        .abs()
+        .checked_mul(Decimal::from(BASIS_POINT_MAX))?
+        .checked_div(requested_price)
+}
+
+/// 计算交换的实际成交价格，即以Y代币计价的X代币UI价格，包含手续费
+/// Computes the effective execution price of a swap, i.e. the UI price of token X in token Y, fees included
+pub fn get_execution_price(
+    amount_in: u64,
+    amount_out: u64,
+    swap_for_y: bool,
+    decimals_x: u8,
+    decimals_y: u8,
+) -> Option<Decimal> {
+    let (amount_x, amount_y) = if swap_for_y {
+        (amount_in, amount_out)
+    } else {
+        (amount_out, amount_in)
+    };
+
+    amount_to_ui_amount(amount_y, decimals_y)?
+        .checked_div(amount_to_ui_amount(amount_x, decimals_x)?)
+}
+
+/// 校验成交价格不劣于限价。卖出X代币时成交价格不能低于限价，买入X代币时不能高于限价
+/// Checks the execution price is not worse than the price limit. When selling token X the execution price must not
+/// be below the limit, when buying token X it must not be above it
+pub fn ensure_price_within_limit(
+    execution_price: Decimal,
+    price_limit: f64,
+    swap_for_y: bool,
+) -> Result<()> {
+    let price_limit = Decimal::from_f64(price_limit)
+        .with_context(|| format!("Invalid price limit {}", price_limit))?;
+
+    if swap_for_y {
+        ensure!(
+            execution_price >= price_limit,
+            "Execution price {} is below the price limit {}",
+            execution_price,
+            price_limit
+        );
+    } else {
+        ensure!(
+            execution_price <= price_limit,
+            "Execution price {} is above the price limit {}",
+            execution_price,
+            price_limit
+        );
+    }
+
+    Ok(())
+}
+
+/// 将Q64.64格式的奖励速率转换为每秒代币数量
+/// Converts a Q64.64 reward rate to token amount per second
+pub fn reward_rate_per_second(reward_rate: u128) -> Option<Decimal> {
+    Decimal::from_u128(reward_rate)?.checked_div(Decimal::TWO.powu(SCALE_OFFSET.into()))
+}
+
+/// 将基点分配转换为按权重存入的分配。每个bin的权重与其分得代币以Y代币计价的价值成正比，与TS客户端一致
+/// Converts a basis point distribution to a by weight distribution. The weight of each bin is proportional to the
+/// value of its share of the amounts in token Y, as in the TS client
+pub fn to_weight_distribution(
+    amount_x: u64,
+    amount_y: u64,
+    bin_liquidity_distribution: &[BinLiquidityDistribution],
+    bin_step: u16,
+) -> Result<Vec<BinLiquidityDistributionByWeight>> {
+    let bin_values = bin_liquidity_distribution
+        .iter()
+        .map(|bin| {
+            let price =
+                get_price_from_id(bin.bin_id, bin_step)? as f64 / 2.0f64.powi(SCALE_OFFSET.into());
+            let value_x =
+                amount_x as f64 * f64::from(bin.distribution_x) / BASIS_POINT_MAX as f64 * price;
+            let value_y = amount_y as f64 * f64::from(bin.distribution_y) / BASIS_POINT_MAX as f64;
+            Ok((bin.bin_id, value_x + value_y))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let total_value: f64 = bin_values.iter().map(|(_, value)| value).sum();
+    ensure!(total_value > 0.0, "Liquidity distribution deposits nothing");
+
+    Ok(bin_values
+        .into_iter()
+        .map(|(bin_id, value)| BinLiquidityDistributionByWeight {
+            bin_id,
+            weight: (value / total_value * f64::from(u16::MAX)).floor() as u16,
+        })
+        .filter(|bin| bin.weight > 0)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 旧的f64实现，仅用于比较误差
+    /// The previous f64 implementation, only used to compare the error
+    fn get_ui_price_from_id_f64(
+        bin_step: u16,
+        bin_id: i32,
+        base_token_decimal: u8,
+        quote_token_decimal: u8,
+    ) -> f64 {
+        (1.0 + bin_step as f64 / 10_000.0).powi(bin_id)
+            * 10.0f64.powi(base_token_decimal as i32 - quote_token_decimal as i32)
+    }
+
+    fn relative_error(
+        bin_step: u16,
+        bin_id: i32,
+        base_token_decimal: u8,
+        quote_token_decimal: u8,
+    ) -> f64 {
+        let price = get_ui_price_from_id(bin_step, bin_id, base_token_decimal, quote_token_decimal)
+            .unwrap()
+            .to_f64()
+            .unwrap();
+        let price_f64 =
+            get_ui_price_from_id_f64(bin_step, bin_id, base_token_decimal, quote_token_decimal);
+
+        (price - price_f64).abs() / price_f64
+    }
+
+    #[test]
+    fn test_flip_price_if_quote_is_x() {
+        assert_eq!(flip_price_if_quote_is_x(4.0, false), 4.0);
+        assert_eq!(flip_price_if_quote_is_x(4.0, true), 0.25);
+        assert_eq!(flip_price_if_quote_is_x(0.5, true), 2.0);
+    }
+
+    #[test]
+    fn test_flip_price_range_if_quote_is_x() {
+        assert_eq!(flip_price_range_if_quote_is_x(2.0, 4.0, false), (2.0, 4.0));
+        assert_eq!(flip_price_range_if_quote_is_x(2.0, 4.0, true), (0.25, 0.5));
+    }
+
+    #[test]
+    fn test_get_ui_price_from_id_decimals() {
+        assert_eq!(get_ui_price_from_id(10, 0, 9, 6), Some(Decimal::from(1000)));
+        assert_eq!(get_ui_price_from_id(10, 0, 6, 6), Some(Decimal::ONE));
+        assert_eq!(get_ui_price_from_id(10, 0, 6, 9), Some(Decimal::new(1, 3)));
+    }
+
+    #[test]
+    fn test_get_ui_price_from_fractional_id() {
+        assert_eq!(
+            get_ui_price_from_fractional_id(10, 5.0, 9, 6),
+            get_ui_price_from_id(10, 5, 9, 6)
+        );
+
+        let lower = get_ui_price_from_id(10, -3, 6, 6).unwrap();
+        let upper = get_ui_price_from_id(10, -2, 6, 6).unwrap();
+        let price = get_ui_price_from_fractional_id(10, -2.5, 6, 6).unwrap();
+        assert!(lower < price && price < upper);
+    }
+
+    #[test]
+    fn test_is_bin_id_near_limit() {
+        // 100000 USDC/BTC，8位及6位小数
+        // 100000 USDC per BTC with 8 and 6 decimals
+        let price = price_per_token_to_per_lamport(100_000.0, 8, 6).unwrap();
+        let bin_id = get_id_from_price(10, &price, Rounding::Up).unwrap();
+        assert!(!is_bin_id_near_limit(bin_id, 10));
+
+        // 小数位数错误使每lamport价格偏离 10^18 倍
+        // Wrong decimals moving the price per lamport by 10^18
+        let price = price_per_token_to_per_lamport(100_000.0, 0, 18).unwrap();
+        let bin_id = get_id_from_price(10, &price, Rounding::Up).unwrap();
+        assert!(is_bin_id_near_limit(bin_id, 10));
+
+        assert!(is_bin_id_near_limit(MAX_BIN_ID, 1));
+        assert!(is_bin_id_near_limit(-MAX_BIN_ID / 80, 100));
+        assert!(!is_bin_id_near_limit(-MAX_BIN_ID / 200, 100));
+    }
+
+    #[test]
+    fn test_fee_rate_to_fee_pct() {
+        // 1% = 10_000_000 / FEE_PRECISION
+        assert_eq!(fee_rate_to_fee_pct(10_000_000).unwrap(), Decimal::ONE);
+        assert_eq!(
+            fee_rate_to_fee_pct(MAX_CONVERTIBLE_FEE_RATE).unwrap(),
+            Decimal::ONE_HUNDRED
+        );
+        assert!(fee_rate_to_fee_pct(MAX_CONVERTIBLE_FEE_RATE + 1).is_err());
+        assert!(fee_rate_to_fee_pct(u128::MAX).is_err());
+    }
+
+    #[test]
+    fn test_price_deviation_bps() {
+        assert_eq!(
+            price_deviation_bps(Decimal::from(100), Decimal::new(1001, 1)),
+            Some(Decimal::from(10))
+        );
+        assert_eq!(
+            price_deviation_bps(Decimal::from(100), Decimal::new(999, 1)),
+            Some(Decimal::from(10))
+        );
+        assert_eq!(price_deviation_bps(Decimal::ZERO, Decimal::ONE), None);
+    }
+
+    #[test]
+    fn test_execution_price_against_limit() {
+        // 卖出 2 X（9位小数）获得 30 Y（6位小数），成交价格为 15
+        // Selling 2 X (9 decimals) for 30 Y (6 decimals) executes at 15
+        let price = get_execution_price(2_000_000_000, 30_000_000, true, 9, 6).unwrap();
+        assert_eq!(price, Decimal::from(15));
+        assert!(ensure_price_within_limit(price, 15.0, true).is_ok());
+        assert!(ensure_price_within_limit(price, 15.5, true).is_err());
+
+        // 支付 30 Y 买入 2 X，成交价格同样为 15
+        // Paying 30 Y for 2 X also executes at 15
+        let price = get_execution_price(30_000_000, 2_000_000_000, false, 9, 6).unwrap();
+        assert_eq!(price, Decimal::from(15));
+        assert!(ensure_price_within_limit(price, 15.5, false).is_ok());
+        assert!(ensure_price_within_limit(price, 14.5, false).is_err());
+
+        assert!(get_execution_price(0, 30_000_000, true, 9, 6).is_none());
+    }
+
+    #[test]
+    fn test_q64x64_price_to_decimal_beyond_decimal_mantissa() {
+        // 2^100 超过Decimal的96位尾数
+        // 2^100 exceeds the 96 bit mantissa of Decimal
+        let price = q64x64_price_to_decimal(1u128 << 100).unwrap();
+        assert_eq!(price, Decimal::from(1u64 << 36));
+    }
+
+    #[test]
+    fn test_get_ui_price_from_id_matches_f64_for_moderate_bin_ids() {
+        for bin_id in [-100_000, -10_000, -1, 1, 10_000, 100_000] {
+            assert!(relative_error(1, bin_id, 9, 6) < 1e-10, "bin_id {}", bin_id);
+        }
+    }
+
+    #[test]
+    fn test_get_ui_price_from_id_error_bound_near_400000() {
+        // Q64xQ64价格在bin ID接近 ±400000 时只剩下约7位有效二进制位，与f64结果的差异为链上价格本身的舍入
+        // Near bin id ±400000 the Q64xQ64 price only has about 7 significant bits left, so the difference to the
+        // f64 result is the rounding of the on-chain price itself
+        for bin_id in [-400_000, -399_999, 399_999, 400_000] {
+            assert!(relative_error(1, bin_id, 6, 6) < 1e-2, "bin_id {}", bin_id);
+        }
+    }
+
+    #[test]
+    fn test_to_weight_distribution() {
+        let distribution = [
+            BinLiquidityDistribution {
+                bin_id: -1,
+                distribution_x: 0,
+                distribution_y: 5000,
+            },
+            BinLiquidityDistribution {
+                bin_id: 0,
+                distribution_x: 5000,
+                distribution_y: 5000,
+            },
+            BinLiquidityDistribution {
+                bin_id: 1,
+                distribution_x: 5000,
+                distribution_y: 0,
+            },
+            BinLiquidityDistribution {
+                bin_id: 2,
+                distribution_x: 0,
+                distribution_y: 0,
+            },
+        ];
+
+        // 价格为1附近时，X与Y各一半的价值平分到两侧，活跃bin得到两者各一半
+        // Around a price of 1 the halves of X and Y split evenly to both sides, the active bin gets half of each
+        let weights = to_weight_distribution(1_000, 1_000, &distribution, 1).unwrap();
+        let weights = weights
+            .iter()
+            .map(|bin| (bin.bin_id, bin.weight))
+            .collect::<Vec<_>>();
+        assert_eq!(weights.len(), 3);
+        assert_eq!(weights[0], (-1, 16383));
+        assert_eq!(weights[1].0, 0);
+        assert!(weights[1].1.abs_diff(32767) <= 2);
+        assert_eq!(weights[2].0, 1);
+        assert!(weights[2].1.abs_diff(16383) <= 2);
+
+        assert!(to_weight_distribution(0, 0, &distribution, 1).is_err());
+    }
+}
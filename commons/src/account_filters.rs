@@ -1,7 +1,12 @@
 use anchor_client::solana_client::rpc_filter::{Memcmp, RpcFilterType};
 use solana_sdk::pubkey::Pubkey;
 
+use crate::dlmm::accounts::{BinArray, PositionV2};
+
 pub fn position_filter_by_wallet_and_pair(wallet: Pubkey, pair: Pubkey) -> Vec<RpcFilterType> {
+    let position_size_filter =
+        RpcFilterType::DataSize((8 + std::mem::size_of::<PositionV2>()) as u64);
+
     let position_pair_filter =
         RpcFilterType::Memcmp(Memcmp::new_base58_encoded(8, &pair.to_bytes()));
 
@@ -10,5 +15,43 @@ pub fn position_filter_by_wallet_and_pair(wallet: Pubkey, pair: Pubkey) -> Vec<R
         &wallet.to_bytes(),
     ));
 
-    vec![position_pair_filter, position_owner_filter]
+    vec![
+        position_size_filter,
+        position_pair_filter,
+        position_owner_filter,
+    ]
+}
+
+pub fn position_filter_by_wallet(wallet: Pubkey) -> Vec<RpcFilterType> {
+    let position_size_filter =
+        RpcFilterType::DataSize((8 + std::mem::size_of::<PositionV2>()) as u64);
+
+    let position_owner_filter = RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+        8 + std::mem::size_of::<Pubkey>(),
+        &wallet.to_bytes(),
+    ));
+
+    vec![position_size_filter, position_owner_filter]
+}
+
+pub fn position_filter_by_pair(pair: Pubkey) -> Vec<RpcFilterType> {
+    let position_size_filter =
+        RpcFilterType::DataSize((8 + std::mem::size_of::<PositionV2>()) as u64);
+
+    let position_pair_filter =
+        RpcFilterType::Memcmp(Memcmp::new_base58_encoded(8, &pair.to_bytes()));
+
+    vec![position_size_filter, position_pair_filter]
+}
+
+pub fn bin_array_filter_by_pair(pair: Pubkey) -> Vec<RpcFilterType> {
+    let bin_array_size_filter =
+        RpcFilterType::DataSize((8 + std::mem::size_of::<BinArray>()) as u64);
+
+    let bin_array_pair_filter = RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+        8 + std::mem::offset_of!(BinArray, lb_pair),
+        &pair.to_bytes(),
+    ));
+
+    vec![bin_array_size_filter, bin_array_pair_filter]
 }
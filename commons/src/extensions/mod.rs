@@ -12,3 +12,6 @@ pub use bin_array_bitmap::*;
 
 pub mod position;
 pub use position::*;
+
+pub mod oracle;
+pub use oracle::*;
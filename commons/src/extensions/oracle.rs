@@ -0,0 +1,186 @@
+use crate::*;
+use bytemuck::{Pod, Zeroable};
+
+/// Oracle observation sample. The samples are stored after the `Oracle` header in the oracle account, the IDL
+/// doesn't describe them
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Pod, Zeroable)]
+pub struct Observation {
+    /// Cumulative active bin id
+    pub cumulative_active_bin_id: i128,
+    /// Timestamp the sample was created at
+    pub created_at: i64,
+    /// Timestamp the sample was last updated at
+    pub last_updated_at: i64,
+}
+
+/// Oracle account with its observation samples
+#[derive(Debug, Clone)]
+pub struct DynamicOracle {
+    pub metadata: Oracle,
+    pub observations: Vec<Observation>,
+}
+
+impl DynamicOracle {
+    /// Deserialize the oracle header and its `length` observations from the oracle account data
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        let header_end = 8 + std::mem::size_of::<Oracle>();
+        let metadata: Oracle = bytemuck::pod_read_unaligned(
+            data.get(8..header_end)
+                .context("Invalid oracle account data")?,
+        );
+
+        let observation_size = std::mem::size_of::<Observation>();
+        let length = usize::try_from(metadata.length)?;
+        let observations_end = header_end + length * observation_size;
+        let observations = data
+            .get(header_end..observations_end)
+            .context("Oracle account data is shorter than its length")?
+            .chunks_exact(observation_size)
+            .map(bytemuck::pod_read_unaligned)
+            .collect();
+
+        Ok(Self {
+            metadata,
+            observations,
+        })
+    }
+
+    /// Initialized samples ordered from the oldest to the latest. The observations are a ring buffer where `idx` is
+    /// the latest sample and `active_size` the number of initialized samples
+    pub fn samples(&self) -> Vec<Observation> {
+        let length = self.observations.len();
+        let active_size = (self.metadata.active_size as usize).min(length);
+        if active_size == 0 {
+            return vec![];
+        }
+
+        let oldest = (self.metadata.idx as usize + 1 + length - active_size) % length;
+        (0..active_size)
+            .map(|offset| self.observations[(oldest + offset) % length])
+            .collect()
+    }
+
+    /// Time weighted average active bin id from the latest sample updated at or before `current_timestamp - window`
+    /// up to `current_timestamp`, using `active_id` since the latest update. Returns the average and the observed
+    /// window in seconds, which is at least `window`. Returns None when the samples don't reach back far enough
+    pub fn get_time_weighted_average_bin_id(
+        &self,
+        active_id: i32,
+        current_timestamp: i64,
+        window: i64,
+    ) -> Option<(f64, i64)> {
+        let samples = self.samples();
+        let latest = samples.last()?;
+        let target_timestamp = current_timestamp.checked_sub(window)?;
+        let start = samples
+            .iter()
+            .rev()
+            .find(|sample| sample.last_updated_at <= target_timestamp)?;
+
+        let observed_window = current_timestamp.checked_sub(start.last_updated_at)?;
+        if observed_window <= 0 {
+            return None;
+        }
+
+        let elapsed_since_latest = current_timestamp.checked_sub(latest.last_updated_at)?;
+        let cumulative_active_bin_id = latest
+            .cumulative_active_bin_id
+            .checked_add(i128::from(active_id).checked_mul(elapsed_since_latest.into())?)?;
+        let cumulative_change =
+            cumulative_active_bin_id.checked_sub(start.cumulative_active_bin_id)?;
+        let average_bin_id = cumulative_change as f64 / observed_window as f64;
+
+        Some((average_bin_id, observed_window))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observation(created_at: i64) -> Observation {
+        Observation {
+            cumulative_active_bin_id: created_at as i128 * 10,
+            created_at,
+            last_updated_at: created_at,
+        }
+    }
+
+    fn oracle_data(idx: u64, active_size: u64, observations: &[Observation]) -> Vec<u8> {
+        let metadata = Oracle {
+            idx,
+            active_size,
+            length: observations.len() as u64,
+        };
+        let mut data = vec![0u8; 8];
+        data.extend_from_slice(bytemuck::bytes_of(&metadata));
+        for observation in observations {
+            data.extend_from_slice(bytemuck::bytes_of(observation));
+        }
+        data
+    }
+
+    #[test]
+    fn test_samples_partially_filled() {
+        let observations = [observation(1), observation(2), Observation::default()];
+        let oracle = DynamicOracle::from_account_data(&oracle_data(1, 2, &observations)).unwrap();
+
+        assert_eq!(oracle.samples(), vec![observation(1), observation(2)]);
+    }
+
+    #[test]
+    fn test_samples_wrapped() {
+        let observations = [observation(4), observation(2), observation(3)];
+        let oracle = DynamicOracle::from_account_data(&oracle_data(0, 3, &observations)).unwrap();
+
+        assert_eq!(
+            oracle.samples(),
+            vec![observation(2), observation(3), observation(4)]
+        );
+    }
+
+    #[test]
+    fn test_time_weighted_average_bin_id() {
+        // 时间 100 至 200 活跃bin为 10，200 至 300 为 20，之后为 30
+        // Active bin 10 from timestamp 100 to 200, 20 from 200 to 300 and 30 afterwards
+        let observations = [
+            Observation {
+                cumulative_active_bin_id: 0,
+                created_at: 100,
+                last_updated_at: 100,
+            },
+            Observation {
+                cumulative_active_bin_id: 1_000,
+                created_at: 200,
+                last_updated_at: 200,
+            },
+            Observation {
+                cumulative_active_bin_id: 3_000,
+                created_at: 300,
+                last_updated_at: 300,
+            },
+        ];
+        let oracle = DynamicOracle::from_account_data(&oracle_data(2, 3, &observations)).unwrap();
+
+        assert_eq!(
+            oracle.get_time_weighted_average_bin_id(30, 400, 200),
+            Some((25.0, 200))
+        );
+        // 窗口起点之前最近的样本在 100，观测窗口扩大到 300
+        // The latest sample before the window start is at 100, the observed window widens to 300
+        assert_eq!(
+            oracle.get_time_weighted_average_bin_id(30, 400, 250),
+            Some((20.0, 300))
+        );
+        assert_eq!(oracle.get_time_weighted_average_bin_id(30, 400, 301), None);
+    }
+
+    #[test]
+    fn test_from_account_data_too_short() {
+        let mut data = oracle_data(0, 1, &[observation(1)]);
+        data.truncate(data.len() - 1);
+
+        assert!(DynamicOracle::from_account_data(&data).is_err());
+    }
+}
@@ -25,6 +25,67 @@ pub trait PositionExtension {
     ) -> Result<Vec<AccountMeta>>;
 
     fn is_empty(&self) -> bool;
+
+    fn get_claimable_fees_and_rewards(
+        &self,
+        lb_pair: &LbPair,
+        bin_arrays: &[BinArray],
+        current_time: u64,
+    ) -> Result<PositionClaimable>;
+
+    fn get_fee_breakdown_by_bin(&self, bin_arrays: &[BinArray]) -> Result<Vec<BinFeeBreakdown>>;
+}
+
+/// Fees and rewards claimable by a position. Fees are accrued up to the last update of the bins, while rewards also
+/// include the emission of the active bin since the last reward update of the pair, which the program adds on claim
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PositionClaimable {
+    pub fee_x: u64,
+    pub fee_y: u64,
+    pub rewards: [u64; NUM_REWARDS],
+}
+
+impl PositionClaimable {
+    pub fn is_zero(&self) -> bool {
+        self.fee_x == 0 && self.fee_y == 0 && self.rewards.iter().all(|&reward| reward == 0)
+    }
+}
+
+/// Pending fee of a position in a single bin, from the fee growth checkpoints of the position against the fee
+/// growth of the bin. The fees of every bin add up to the fees of `PositionClaimable`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BinFeeBreakdown {
+    pub bin_id: i32,
+    /// Liquidity share of the position in the bin, without the Q64.64 fractional part
+    pub liquidity_share: u128,
+    /// Fee growth per token X of the bin since the position checkpoint, Q64.64
+    pub fee_x_per_token_delta: u128,
+    /// Fee growth per token Y of the bin since the position checkpoint, Q64.64
+    pub fee_y_per_token_delta: u128,
+    /// Fee X already credited to the position at its last update
+    pub fee_x_pending: u64,
+    /// Fee Y already credited to the position at its last update
+    pub fee_y_pending: u64,
+    /// Fee X accrued since the checkpoint, not yet credited to the position
+    pub new_fee_x: u64,
+    /// Fee Y accrued since the checkpoint, not yet credited to the position
+    pub new_fee_y: u64,
+}
+
+impl BinFeeBreakdown {
+    /// Total claimable fee X in the bin
+    pub fn fee_x(&self) -> Result<u64> {
+        self.fee_x_pending
+            .checked_add(self.new_fee_x)
+            .context("overflow")
+    }
+
+    /// Total claimable fee Y in the bin
+    pub fn fee_y(&self) -> Result<u64> {
+        self.fee_y_pending
+            .checked_add(self.new_fee_y)
+            .context("overflow")
+    }
 }
 
 impl PositionExtension for PositionV2 {
@@ -97,4 +158,362 @@ impl PositionExtension for PositionV2 {
 
         true
     }
+
+    fn get_claimable_fees_and_rewards(
+        &self,
+        lb_pair: &LbPair,
+        bin_arrays: &[BinArray],
+        current_time: u64,
+    ) -> Result<PositionClaimable> {
+        let mut claimable = PositionClaimable::default();
+
+        for (i, bin_id) in (self.lower_bin_id..=self.upper_bin_id).enumerate() {
+            let fee_info = &self.fee_infos[i];
+            let reward_info = &self.reward_infos[i];
+
+            claimable.fee_x = claimable
+                .fee_x
+                .checked_add(fee_info.fee_x_pending)
+                .context("overflow")?;
+            claimable.fee_y = claimable
+                .fee_y
+                .checked_add(fee_info.fee_y_pending)
+                .context("overflow")?;
+
+            for (reward, pending) in claimable
+                .rewards
+                .iter_mut()
+                .zip(reward_info.reward_pendings)
+            {
+                *reward = reward.checked_add(pending).context("overflow")?;
+            }
+
+            let liquidity_share = self.liquidity_shares[i] >> SCALE_OFFSET;
+            if liquidity_share == 0 {
+                continue;
+            }
+
+            let bin = bin_arrays
+                .iter()
+                .find(|bin_array| bin_array.is_bin_id_within_range(bin_id).unwrap_or(false))
+                .context("Bin array not found")?
+                .get_bin(bin_id)?;
+
+            let new_fee_x: u64 = safe_mul_shr_cast(
+                liquidity_share,
+                bin.fee_amount_x_per_token_stored
+                    .checked_sub(fee_info.fee_x_per_token_complete)
+                    .context("overflow")?,
+                SCALE_OFFSET,
+                Rounding::Down,
+            )?;
+            let new_fee_y: u64 = safe_mul_shr_cast(
+                liquidity_share,
+                bin.fee_amount_y_per_token_stored
+                    .checked_sub(fee_info.fee_y_per_token_complete)
+                    .context("overflow")?,
+                SCALE_OFFSET,
+                Rounding::Down,
+            )?;
+
+            claimable.fee_x = claimable.fee_x.checked_add(new_fee_x).context("overflow")?;
+            claimable.fee_y = claimable.fee_y.checked_add(new_fee_y).context("overflow")?;
+
+            for (j, reward) in claimable.rewards.iter_mut().enumerate() {
+                let mut reward_per_token_stored = bin.reward_per_token_stored[j];
+
+                // The emission since the last reward update goes to the active bin, and is only added to the bin when
+                // the program next updates the rewards
+                if bin_id == lb_pair.active_id {
+                    reward_per_token_stored = reward_per_token_stored
+                        .checked_add(pending_reward_per_token(
+                            &lb_pair.reward_infos[j],
+                            bin.liquidity_supply,
+                            current_time,
+                        )?)
+                        .context("overflow")?;
+                }
+
+                let new_reward: u64 = safe_mul_shr_cast(
+                    liquidity_share,
+                    reward_per_token_stored
+                        .checked_sub(reward_info.reward_per_token_completes[j])
+                        .context("overflow")?,
+                    SCALE_OFFSET,
+                    Rounding::Down,
+                )?;
+                *reward = reward.checked_add(new_reward).context("overflow")?;
+            }
+        }
+
+        Ok(claimable)
+    }
+
+    fn get_fee_breakdown_by_bin(&self, bin_arrays: &[BinArray]) -> Result<Vec<BinFeeBreakdown>> {
+        let mut breakdown = vec![];
+
+        for (i, bin_id) in (self.lower_bin_id..=self.upper_bin_id).enumerate() {
+            let fee_info = &self.fee_infos[i];
+            let mut bin_fee = BinFeeBreakdown {
+                bin_id,
+                liquidity_share: self.liquidity_shares[i] >> SCALE_OFFSET,
+                fee_x_pending: fee_info.fee_x_pending,
+                fee_y_pending: fee_info.fee_y_pending,
+                ..Default::default()
+            };
+
+            // Without liquidity no fee accrues since the checkpoint, the bin array is not needed
+            if bin_fee.liquidity_share > 0 {
+                let bin = bin_arrays
+                    .iter()
+                    .find(|bin_array| bin_array.is_bin_id_within_range(bin_id).unwrap_or(false))
+                    .context("Bin array not found")?
+                    .get_bin(bin_id)?;
+
+                bin_fee.fee_x_per_token_delta = bin
+                    .fee_amount_x_per_token_stored
+                    .checked_sub(fee_info.fee_x_per_token_complete)
+                    .context("overflow")?;
+                bin_fee.fee_y_per_token_delta = bin
+                    .fee_amount_y_per_token_stored
+                    .checked_sub(fee_info.fee_y_per_token_complete)
+                    .context("overflow")?;
+                bin_fee.new_fee_x = safe_mul_shr_cast(
+                    bin_fee.liquidity_share,
+                    bin_fee.fee_x_per_token_delta,
+                    SCALE_OFFSET,
+                    Rounding::Down,
+                )?;
+                bin_fee.new_fee_y = safe_mul_shr_cast(
+                    bin_fee.liquidity_share,
+                    bin_fee.fee_y_per_token_delta,
+                    SCALE_OFFSET,
+                    Rounding::Down,
+                )?;
+            }
+
+            breakdown.push(bin_fee);
+        }
+
+        Ok(breakdown)
+    }
+}
+
+/// Reward per token emitted to the active bin since the last reward update of the pair, as the program computes it on
+/// the next reward update
+fn pending_reward_per_token(
+    reward_info: &RewardInfo,
+    liquidity_supply: u128,
+    current_time: u64,
+) -> Result<u128> {
+    let liquidity_supply = liquidity_supply >> SCALE_OFFSET;
+
+    if reward_info.mint == Pubkey::default() || liquidity_supply == 0 {
+        return Ok(0);
+    }
+
+    let elapsed = current_time
+        .min(reward_info.reward_duration_end)
+        .saturating_sub(reward_info.last_update_time);
+
+    safe_mul_div_cast(
+        elapsed.into(),
+        reward_info.reward_rate,
+        liquidity_supply,
+        Rounding::Down,
+    )
+}
+
+/// Validate the position width is within [1, DEFAULT_BIN_PER_POSITION] and the position bin range
+/// stays within [MIN_BIN_ID, MAX_BIN_ID].
+pub fn validate_position_width(lower_bin_id: i32, width: i32) -> Result<()> {
+    ensure!(
+        width >= 1 && width <= DEFAULT_BIN_PER_POSITION as i32,
+        "Width {} must be between 1 and {}",
+        width,
+        DEFAULT_BIN_PER_POSITION
+    );
+
+    let upper_bin_id = lower_bin_id
+        .checked_add(width)
+        .and_then(|bin_id| bin_id.checked_sub(1))
+        .context("overflow")?;
+
+    ensure!(
+        lower_bin_id >= MIN_BIN_ID && upper_bin_id <= MAX_BIN_ID,
+        "Position bin range [{}, {}] is outside of the valid bin id range [{}, {}]",
+        lower_bin_id,
+        upper_bin_id,
+        MIN_BIN_ID,
+        MAX_BIN_ID
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_claimable_fees_and_rewards() {
+        let mut position: PositionV2 = bytemuck::Zeroable::zeroed();
+        position.lower_bin_id = 0;
+        position.upper_bin_id = 1;
+        position.liquidity_shares[0] = 10u128 << SCALE_OFFSET;
+        position.fee_infos[0].fee_x_pending = 5;
+        position.fee_infos[1].fee_y_pending = 7;
+        position.reward_infos[0].reward_per_token_completes[0] = 1u128 << SCALE_OFFSET;
+
+        let mut bin_array: BinArray = bytemuck::Zeroable::zeroed();
+        bin_array.bins[0].fee_amount_x_per_token_stored = 2u128 << SCALE_OFFSET;
+        bin_array.bins[0].fee_amount_y_per_token_stored = 3u128 << SCALE_OFFSET;
+        bin_array.bins[0].reward_per_token_stored[0] = 4u128 << SCALE_OFFSET;
+
+        let lb_pair: LbPair = bytemuck::Zeroable::zeroed();
+        let claimable = position
+            .get_claimable_fees_and_rewards(&lb_pair, &[bin_array], 0)
+            .unwrap();
+
+        assert_eq!(
+            claimable,
+            PositionClaimable {
+                fee_x: 25,
+                fee_y: 37,
+                rewards: [30, 0],
+            }
+        );
+        assert!(!claimable.is_zero());
+    }
+
+    #[test]
+    fn test_get_fee_breakdown_by_bin() {
+        let mut position: PositionV2 = bytemuck::Zeroable::zeroed();
+        position.lower_bin_id = 0;
+        position.upper_bin_id = 1;
+        position.liquidity_shares[0] = 10u128 << SCALE_OFFSET;
+        position.fee_infos[0].fee_x_pending = 5;
+        position.fee_infos[0].fee_x_per_token_complete = 1u128 << SCALE_OFFSET;
+        position.fee_infos[1].fee_y_pending = 7;
+
+        let mut bin_array: BinArray = bytemuck::Zeroable::zeroed();
+        bin_array.bins[0].fee_amount_x_per_token_stored = 3u128 << SCALE_OFFSET;
+        bin_array.bins[0].fee_amount_y_per_token_stored = 3u128 << SCALE_OFFSET;
+
+        let breakdown = position.get_fee_breakdown_by_bin(&[bin_array]).unwrap();
+
+        assert_eq!(
+            breakdown,
+            vec![
+                BinFeeBreakdown {
+                    bin_id: 0,
+                    liquidity_share: 10,
+                    fee_x_per_token_delta: 2u128 << SCALE_OFFSET,
+                    fee_y_per_token_delta: 3u128 << SCALE_OFFSET,
+                    fee_x_pending: 5,
+                    fee_y_pending: 0,
+                    new_fee_x: 20,
+                    new_fee_y: 30,
+                },
+                BinFeeBreakdown {
+                    bin_id: 1,
+                    fee_y_pending: 7,
+                    ..Default::default()
+                },
+            ]
+        );
+
+        // The fees of every bin add up to the claimable fees of the position
+        let lb_pair: LbPair = bytemuck::Zeroable::zeroed();
+        let claimable = position
+            .get_claimable_fees_and_rewards(&lb_pair, &[bin_array], 0)
+            .unwrap();
+        let fee_x = breakdown
+            .iter()
+            .map(|bin| bin.fee_x().unwrap())
+            .sum::<u64>();
+        let fee_y = breakdown
+            .iter()
+            .map(|bin| bin.fee_y().unwrap())
+            .sum::<u64>();
+        assert_eq!((fee_x, fee_y), (claimable.fee_x, claimable.fee_y));
+    }
+
+    #[test]
+    fn test_get_claimable_fees_and_rewards_missing_bin_array() {
+        let mut position: PositionV2 = bytemuck::Zeroable::zeroed();
+        position.lower_bin_id = 0;
+        position.upper_bin_id = 0;
+        position.liquidity_shares[0] = 1u128 << SCALE_OFFSET;
+
+        let lb_pair: LbPair = bytemuck::Zeroable::zeroed();
+        assert!(position
+            .get_claimable_fees_and_rewards(&lb_pair, &[], 0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_get_claimable_rewards_pending_emission() {
+        let mut position: PositionV2 = bytemuck::Zeroable::zeroed();
+        position.lower_bin_id = 0;
+        position.upper_bin_id = 1;
+        position.liquidity_shares[0] = 10u128 << SCALE_OFFSET;
+        position.liquidity_shares[1] = 10u128 << SCALE_OFFSET;
+
+        let mut bin_array: BinArray = bytemuck::Zeroable::zeroed();
+        bin_array.bins[0].liquidity_supply = 20u128 << SCALE_OFFSET;
+        bin_array.bins[0].reward_per_token_stored[0] = 1u128 << SCALE_OFFSET;
+        bin_array.bins[1].liquidity_supply = 20u128 << SCALE_OFFSET;
+
+        // 2 tokens per second emitted since the stale last update at 100
+        let mut lb_pair: LbPair = bytemuck::Zeroable::zeroed();
+        lb_pair.active_id = 0;
+        lb_pair.reward_infos[0].mint = Pubkey::new_unique();
+        lb_pair.reward_infos[0].reward_rate = 2u128 << SCALE_OFFSET;
+        lb_pair.reward_infos[0].last_update_time = 100;
+        lb_pair.reward_infos[0].reward_duration_end = 1000;
+
+        // Stored 10, plus half of the 50 seconds of emission to the active bin
+        let claimable = position
+            .get_claimable_fees_and_rewards(&lb_pair, &[bin_array], 150)
+            .unwrap();
+        assert_eq!(claimable.rewards, [60, 0]);
+
+        // The emission stops at the end of the reward duration
+        let claimable = position
+            .get_claimable_fees_and_rewards(&lb_pair, &[bin_array], 5000)
+            .unwrap();
+        assert_eq!(claimable.rewards, [910, 0]);
+
+        // Without the active bin in the position range, only the stored rewards are claimable
+        lb_pair.active_id = 2;
+        let claimable = position
+            .get_claimable_fees_and_rewards(&lb_pair, &[bin_array], 150)
+            .unwrap();
+        assert_eq!(claimable.rewards, [10, 0]);
+    }
+
+    #[test]
+    fn test_validate_position_width() {
+        assert!(validate_position_width(0, 1).is_ok());
+        assert!(validate_position_width(0, DEFAULT_BIN_PER_POSITION as i32).is_ok());
+
+        assert!(validate_position_width(0, 0).is_err());
+        assert!(validate_position_width(0, -1).is_err());
+        assert!(validate_position_width(0, DEFAULT_BIN_PER_POSITION as i32 + 1).is_err());
+    }
+
+    #[test]
+    fn test_validate_position_width_at_bin_id_bounds() {
+        let width = DEFAULT_BIN_PER_POSITION as i32;
+
+        assert!(validate_position_width(MIN_BIN_ID, width).is_ok());
+        assert!(validate_position_width(MIN_BIN_ID - 1, width).is_err());
+
+        assert!(validate_position_width(MAX_BIN_ID - width + 1, width).is_ok());
+        assert!(validate_position_width(MAX_BIN_ID - width + 2, width).is_err());
+        assert!(validate_position_width(MAX_BIN_ID, 1).is_ok());
+
+        assert!(validate_position_width(i32::MAX, width).is_err());
+    }
 }
@@ -301,6 +301,36 @@ pub fn get_bin_array_pubkeys_for_swap(
     Ok(bin_array_pubkeys)
 }
 
+/// Minimum amount out after applying `slippage_bps` to the quoted amount out.
+pub fn get_min_amount_out_with_slippage(amount_out: u64, slippage_bps: u16) -> Result<u64> {
+    let slippage_bps = u128::from(slippage_bps);
+    ensure!(
+        slippage_bps <= BASIS_POINT_MAX as u128,
+        "Slippage {} bps exceeds {} bps",
+        slippage_bps,
+        BASIS_POINT_MAX
+    );
+
+    let min_amount_out = u128::from(amount_out)
+        .checked_mul(BASIS_POINT_MAX as u128 - slippage_bps)
+        .context("min amount out overflow")?
+        .checked_div(BASIS_POINT_MAX as u128)
+        .context("min amount out overflow")?;
+
+    min_amount_out.try_into().context("min amount out exceeds u64")
+}
+
+/// Maximum amount in after applying `slippage_bps` to the quoted amount in (including fee).
+pub fn get_max_amount_in_with_slippage(amount_in: u64, slippage_bps: u16) -> Result<u64> {
+    let max_amount_in = u128::from(amount_in)
+        .checked_mul(BASIS_POINT_MAX as u128 + u128::from(slippage_bps))
+        .context("max amount in overflow")?
+        .checked_div(BASIS_POINT_MAX as u128)
+        .context("max amount in overflow")?;
+
+    max_amount_in.try_into().context("max amount in exceeds u64")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -551,4 +581,36 @@ mod tests {
             quote_result.amount_out as f64 / 1_000_000_000.0
         );
     }
+
+    #[test]
+    fn test_min_amount_out_with_slippage() {
+        assert_eq!(get_min_amount_out_with_slippage(10_000, 100).unwrap(), 9_900);
+        assert_eq!(get_min_amount_out_with_slippage(10_000, 0).unwrap(), 10_000);
+
+        // No wraparound near u64::MAX
+        let amount_out = u64::MAX / 9900 * 9900;
+        assert_eq!(
+            get_min_amount_out_with_slippage(amount_out, 100).unwrap(),
+            (u128::from(amount_out) * 9900 / 10_000) as u64
+        );
+        assert!(get_min_amount_out_with_slippage(u64::MAX, 100).unwrap() < u64::MAX);
+
+        assert!(get_min_amount_out_with_slippage(10_000, 10_001).is_err());
+    }
+
+    #[test]
+    fn test_max_amount_in_with_slippage() {
+        assert_eq!(get_max_amount_in_with_slippage(10_000, 100).unwrap(), 10_100);
+
+        let amount_in = u64::MAX / 10_100;
+        assert_eq!(
+            get_max_amount_in_with_slippage(amount_in, 100).unwrap(),
+            (u128::from(amount_in) * 10_100 / 10_000) as u64
+        );
+
+        // Result no longer fits in u64, must error instead of wrapping around
+        assert!(get_max_amount_in_with_slippage(u64::MAX / 10_100 * 10_000, 100).is_ok());
+        assert!(get_max_amount_in_with_slippage(u64::MAX / 10_000 * 9_999, 100).is_err());
+        assert!(get_max_amount_in_with_slippage(u64::MAX, 100).is_err());
+    }
 }
@@ -3,6 +3,9 @@ use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
 use async_trait::async_trait;
 use solana_sdk::{account::Account, pubkey::Pubkey};
 
+/// Maximum number of accounts the RPC accepts in a single getMultipleAccounts request.
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
 #[async_trait]
 pub trait RpcClientExtension {
     async fn get_account_and_deserialize<T>(
@@ -10,6 +13,10 @@ pub trait RpcClientExtension {
         pubkey: &Pubkey,
         deserialize_fn: fn(Account) -> Result<T>,
     ) -> Result<T>;
+
+    /// Fetch any number of accounts by splitting the keys into getMultipleAccounts requests of at most
+    /// `MAX_MULTIPLE_ACCOUNTS` keys. The accounts are returned in the order of the keys.
+    async fn get_multiple_accounts_chunked(&self, keys: &[Pubkey]) -> Result<Vec<Option<Account>>>;
 }
 
 #[async_trait]
@@ -23,4 +30,14 @@ impl RpcClientExtension for RpcClient {
         let data = deserialize_fn(account)?;
         Ok(data)
     }
+
+    async fn get_multiple_accounts_chunked(&self, keys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        let mut accounts = Vec::with_capacity(keys.len());
+
+        for chunk in keys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            accounts.extend(self.get_multiple_accounts(chunk).await?);
+        }
+
+        Ok(accounts)
+    }
 }
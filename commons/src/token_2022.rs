@@ -65,6 +65,16 @@ pub async fn get_extra_account_metas_for_transfer_hook(
     rpc_client: RpcClient,
 ) -> Result<Vec<AccountMeta>> {
     let mint_account = rpc_client.get_account(&mint).await?;
+    get_extra_account_metas_for_transfer_hook_with_mint_account(mint, &mint_account, rpc_client)
+        .await
+}
+
+/// Same as `get_extra_account_metas_for_transfer_hook`, using an already fetched mint account
+pub async fn get_extra_account_metas_for_transfer_hook_with_mint_account(
+    mint: Pubkey,
+    mint_account: &Account,
+    rpc_client: RpcClient,
+) -> Result<Vec<AccountMeta>> {
     if mint_account.owner.eq(&spl_token::ID) {
         return Ok(vec![]);
     }